@@ -0,0 +1,186 @@
+//! Test AsyncFromRequest extractors resolving providers through ModuleRef
+
+use serial_test::serial;
+use std::time::Duration;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    http_helpers::{HttpException, HttpResponse, IntoResponse},
+    injectable,
+    injector::ModuleRef,
+    module, provider_value, AsyncFromRequest, Body as ToniBody, FromRequest, HttpAdapter,
+    HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+#[injectable(pub struct TokenService {})]
+impl TokenService {
+    fn user_id(&self, token: &str) -> Option<u64> {
+        token.strip_prefix("token-")?.parse().ok()
+    }
+}
+
+#[module(
+    providers: [TokenService],
+    exports: [TokenService],
+)]
+impl AuthModule {}
+
+#[injectable(pub struct UserRepository {})]
+impl UserRepository {
+    async fn find(&self, id: u64) -> Option<String> {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        (id == 1).then(|| "alice".to_string())
+    }
+}
+
+/// Looks the caller up from the bearer token
+struct CurrentUser(String);
+
+#[async_trait]
+impl AsyncFromRequest for CurrentUser {
+    type Error = HttpException;
+
+    async fn extract(req: &HttpRequest, module: &ModuleRef) -> Result<Self, Self::Error> {
+        let missing = || HttpException::internal_server_error("Missing provider");
+        let tokens = module.get::<TokenService>(req).await.ok_or_else(missing)?;
+        let users = module
+            .get::<UserRepository>(req)
+            .await
+            .ok_or_else(missing)?;
+
+        let token = req.headers.get_str("authorization").unwrap_or_default();
+        let id = tokens
+            .user_id(token)
+            .ok_or_else(|| HttpException::unauthorized("Invalid token"))?;
+        let name = users
+            .find(id)
+            .await
+            .ok_or_else(|| HttpException::not_found(format!("User {} not found", id)))?;
+        Ok(CurrentUser(name))
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// Reads a value provider by token
+struct Greeting(String);
+
+#[async_trait]
+impl AsyncFromRequest for Greeting {
+    type Error = String;
+
+    async fn extract(req: &HttpRequest, module: &ModuleRef) -> Result<Self, Self::Error> {
+        module
+            .get_by_token::<String>("GREETING", req)
+            .await
+            .map(Greeting)
+            .ok_or_else(|| "GREETING is not provided".to_string())
+    }
+}
+
+/// A sync extractor, still usable on its own
+struct ApiVersion(u32);
+
+impl FromRequest for ApiVersion {
+    type Error = String;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        req.headers
+            .get_str("x-api-version")
+            .and_then(|version| version.parse().ok())
+            .map(ApiVersion)
+            .ok_or_else(|| "Missing or invalid X-Api-Version".to_string())
+    }
+}
+
+#[controller_struct(
+    pub struct ProfileController;
+)]
+#[controller("/profile")]
+impl ProfileController {
+    #[get("/")]
+    async fn me(&self, greeting: Greeting, user: CurrentUser) -> ToniBody {
+        ToniBody::Text(format!("{}, {}", greeting.0, user.0))
+    }
+
+    #[get("/version")]
+    fn version(&self, version: ApiVersion) -> ToniBody {
+        ToniBody::Text(format!("v{}", version.0))
+    }
+}
+
+#[module(
+    imports: [AuthModule],
+    controllers: [ProfileController],
+    providers: [
+        UserRepository,
+        provider_value!("GREETING", "Hello".to_string()),
+    ],
+)]
+impl AppModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_async_extractors() {
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29430;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(AppModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/profile", port);
+            let me = |token: &str| {
+                client
+                    .get(format!("{}/", base))
+                    .header("authorization", token)
+                    .send()
+            };
+
+            // Test 1: providers of the module and of its imports are resolved
+            let response = me("token-1").await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "Hello, alice");
+
+            // Test 2: the extractor's own rejection is sent
+            let response = me("nope").await.unwrap();
+            assert_eq!(response.status(), 401);
+
+            let response = me("token-7").await.unwrap();
+            assert_eq!(response.status(), 404);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["message"], "User 7 not found");
+
+            // Test 3: sync extractors work as the only argument
+            let response = client
+                .get(format!("{}/version", base))
+                .header("x-api-version", "3")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap(), "v3");
+
+            let response = client
+                .get(format!("{}/version", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 400);
+        })
+        .await;
+}
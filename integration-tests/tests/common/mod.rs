@@ -0,0 +1,101 @@
+//! Shared fixture for tests that serve an app and check it over the network
+//!
+//! ```rust,ignore
+//! mod common;
+//!
+//! #[actix_rt::test]
+//! #[serial]
+//! async fn test_route_syntax() {
+//!     common::run_on_both_adapters(CatalogModule::module_definition, 29460, check_route_syntax)
+//!         .await;
+//! }
+//! ```
+
+// Each test binary only uses part of the fixture
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::net::TcpStream;
+use toni::module_helpers::module_enum::ModuleDefinition;
+use toni::{HttpAdapter, ToniFactory};
+use toni_actix::ActixAdapter;
+use toni_axum::AxumAdapter;
+
+/// How long a server gets to start listening
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serves `module` with the axum adapter on `port`, then with the actix
+/// adapter on `port + 1`, running `check` against each once it listens.
+///
+/// Needs the actix runtime, i.e. `#[actix_rt::test]`.
+pub async fn run_on_both_adapters<C, F>(module: fn() -> ModuleDefinition, port: u16, check: C)
+where
+    C: Fn(u16) -> F,
+    F: Future<Output = ()>,
+{
+    run_on_both_adapters_with(module, |_| {}, port, check).await;
+}
+
+/// [`run_on_both_adapters`] with a factory prepared by `setup`, e.g. to add
+/// global middleware
+pub async fn run_on_both_adapters_with<S, C, F>(
+    module: fn() -> ModuleDefinition,
+    setup: S,
+    port: u16,
+    check: C,
+) where
+    S: Fn(&mut ToniFactory),
+    C: Fn(u16) -> F,
+    F: Future<Output = ()>,
+{
+    let factory = || {
+        let mut factory = ToniFactory::new();
+        setup(&mut factory);
+        factory
+    };
+    run_on(AxumAdapter::new(), factory(), module(), port, check(port)).await;
+
+    let port = port + 1;
+    run_on(ActixAdapter::new(), factory(), module(), port, check(port)).await;
+}
+
+/// Serves `module` with `adapter` on `port` and runs `check` once it listens.
+/// The server stops when `check` returns.
+pub async fn run_on<A, F>(
+    adapter: A,
+    factory: ToniFactory,
+    module: ModuleDefinition,
+    port: u16,
+    check: F,
+) where
+    A: HttpAdapter + 'static,
+    F: Future<Output = ()>,
+{
+    let local = tokio::task::LocalSet::new();
+    local.spawn_local(async move {
+        let app = factory.create(module, adapter).await;
+        app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            wait_until_listening(port).await;
+            check.await;
+        })
+        .await;
+}
+
+async fn wait_until_listening(port: u16) {
+    let started = Instant::now();
+    while TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+        assert!(
+            started.elapsed() < STARTUP_TIMEOUT,
+            "Nothing is listening on port {} after {:?}",
+            port,
+            STARTUP_TIMEOUT
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
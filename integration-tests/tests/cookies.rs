@@ -0,0 +1,160 @@
+//! Test the Cookies extractor, CookieJar responses and signed/encrypted cookies
+
+use serial_test::serial;
+use toni::{
+    controller, controller_struct,
+    extractors::Cookies,
+    get,
+    http_helpers::{Cookie, CookieJar, CookieKey, SameSite},
+    module, post, Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+const SECRET: &str = "integration-test-secret-with-32-bytes!!";
+
+#[controller_struct(
+    pub struct CookieController;
+)]
+#[controller("/cookies")]
+impl CookieController {
+    #[get("/theme")]
+    fn theme(&self, cookies: Cookies) -> ToniBody {
+        ToniBody::Text(cookies.get("theme").unwrap_or("light").to_string())
+    }
+
+    #[post("/login")]
+    fn login(&self, cookies: Cookies) -> (CookieJar, ToniBody) {
+        let mut jar = cookies.jar();
+        jar.add(
+            Cookie::new("theme", "dark")
+                .path("/")
+                .same_site(SameSite::Lax),
+        )
+        .add_signed(Cookie::new("session", "user-42").path("/").http_only(true))
+        .add_private(Cookie::new("token", "s3cr3t").path("/").max_age(3600));
+        (jar, ToniBody::Text("logged in".to_string()))
+    }
+
+    #[get("/me")]
+    fn me(&self, cookies: Cookies) -> ToniBody {
+        ToniBody::Json(serde_json::json!({
+            "session": cookies.get_signed("session"),
+            "token": cookies.get_private("token"),
+        }))
+    }
+
+    #[post("/logout")]
+    fn logout(&self, _req: HttpRequest) -> CookieJar {
+        let mut jar = CookieJar::new();
+        jar.remove("session", "/");
+        jar
+    }
+}
+
+#[module(
+    controllers: [CookieController],
+    providers: [],
+)]
+impl CookieModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_cookies_and_cookie_jar() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29320;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let mut factory = ToniFactory::new();
+        factory.use_cookie_key(CookieKey::from_secret(SECRET));
+        let app = factory
+            .create(CookieModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/cookies", port);
+
+            // Test 1: plain cookie extraction
+            let response = client
+                .get(format!("{}/theme", base))
+                .header("Cookie", "lang=en; theme=dark")
+                .send()
+                .await
+                .expect("Failed to read theme");
+            assert_eq!(response.text().await.unwrap(), "dark");
+
+            // Test 2: one Set-Cookie header per cookie, with attributes
+            let response = client
+                .post(format!("{}/login", base))
+                .send()
+                .await
+                .expect("Failed to login");
+            assert_eq!(response.status(), 200);
+
+            let set_cookies: Vec<String> = response
+                .headers()
+                .get_all("set-cookie")
+                .iter()
+                .map(|v| v.to_str().unwrap().to_string())
+                .collect();
+            assert_eq!(set_cookies.len(), 3);
+            assert_eq!(set_cookies[0], "theme=dark; Path=/; SameSite=Lax");
+            assert!(set_cookies[1].starts_with("session=user-42."));
+            assert!(set_cookies[1].ends_with("; Path=/; HttpOnly"));
+            assert!(set_cookies[2].starts_with("token="));
+            assert!(!set_cookies[2].contains("s3cr3t"));
+            assert!(set_cookies[2].contains("Max-Age=3600"));
+
+            // Send the cookies back as a browser would
+            let cookie_header = set_cookies
+                .iter()
+                .map(|c| c.split(';').next().unwrap())
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            // Test 3: signed and encrypted cookies are verified
+            let response = client
+                .get(format!("{}/me", base))
+                .header("Cookie", &cookie_header)
+                .send()
+                .await
+                .expect("Failed to read me");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["session"], "user-42");
+            assert_eq!(body["token"], "s3cr3t");
+
+            // Test 4: tampered values are rejected
+            let tampered = cookie_header.replace("user-42", "user-1");
+            let response = client
+                .get(format!("{}/me", base))
+                .header("Cookie", &tampered)
+                .send()
+                .await
+                .expect("Failed to read me");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert!(body["session"].is_null());
+            assert_eq!(body["token"], "s3cr3t");
+
+            // Test 5: removal expires the cookie
+            let response = client
+                .post(format!("{}/logout", base))
+                .send()
+                .await
+                .expect("Failed to logout");
+            let set_cookie = response.headers()["set-cookie"].to_str().unwrap();
+            assert_eq!(
+                set_cookie,
+                "session=; Path=/; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT"
+            );
+        })
+        .await;
+}
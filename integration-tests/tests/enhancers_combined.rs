@@ -19,6 +19,7 @@ use toni::{
 };
 use toni_axum::AxumAdapter;
 
+use toni::http_helpers::HttpException;
 use toni::injector::Context;
 use toni::traits_helpers::middleware::{Middleware, MiddlewareResult, Next};
 use toni::traits_helpers::{Guard, Interceptor, InterceptorNext, MiddlewareConsumer, Pipe};
@@ -89,16 +90,16 @@ impl Middleware for OrderTrackerMiddleware {
 
         // Add custom header to track middleware execution (using the new headers_mut method!)
         req.headers_mut()
-            .push(("X-Middleware-Order".to_string(), self.name.clone()));
+            .append("X-Middleware-Order", self.name.clone());
 
         // Call next in chain and modify the response
         let mut response = next.run(req).await?;
 
         // Since we now return concrete HttpResponse, we can easily modify it!
-        response.headers.push((
-            "X-Middleware-Modified".to_string(),
+        response.headers.append(
+            "X-Middleware-Modified",
             format!("processed-by-{}", self.name),
-        ));
+        );
 
         Ok(response)
     }
@@ -154,16 +155,24 @@ impl AdminGuard {
     }
 }
 
+impl Default for AdminGuard {
+    fn default() -> Self {
+        Self::new(get_global_tracker())
+    }
+}
+
+#[async_trait]
 impl Guard for AdminGuard {
-    fn can_activate(&self, context: &Context) -> bool {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
         track(&self.tracker, "guard:admin");
 
         let req = context.take_request();
 
         // Check for X-Admin-Token header (using the new header method!)
-        req.header("X-Admin-Token")
+        Ok(req
+            .header("X-Admin-Token")
             .map(|value| value == "secret123")
-            .unwrap_or(false)
+            .unwrap_or(false))
     }
 }
 
@@ -178,14 +187,21 @@ impl AuthGuard {
     }
 }
 
+impl Default for AuthGuard {
+    fn default() -> Self {
+        Self::new(get_global_tracker())
+    }
+}
+
+#[async_trait]
 impl Guard for AuthGuard {
-    fn can_activate(&self, context: &Context) -> bool {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
         track(&self.tracker, "guard:auth");
 
         let req = context.take_request();
 
         // Check for Authorization header (using the new has_header method!)
-        req.has_header("Authorization")
+        Ok(req.has_header("Authorization"))
     }
 }
 
@@ -237,8 +253,9 @@ impl ValidationPipe {
     }
 }
 
+#[async_trait]
 impl Pipe for ValidationPipe {
-    fn process(&self, context: &mut Context) {
+    async fn process(&self, context: &mut Context) {
         track(&self.tracker, "pipe:validation");
 
         let req = context.take_request();
@@ -271,8 +288,9 @@ impl TransformPipe {
     }
 }
 
+#[async_trait]
 impl Pipe for TransformPipe {
-    fn process(&self, _context: &mut Context) {
+    async fn process(&self, _context: &mut Context) {
         track(&self.tracker, "pipe:transform");
         // In a real scenario, this would transform DTO data
         // For this test, we just track execution
@@ -372,12 +390,12 @@ impl EnhancerModule {
 
         // Config 1: Order tracker middleware for all /api/* routes
         consumer
-            .apply(OrderTrackerMiddleware::new("first", tracker.clone()))
+            .apply_instance(OrderTrackerMiddleware::new("first", tracker.clone()))
             .for_routes(vec!["/api/*"]);
 
         // Config 2: Header check middleware for specific routes
         consumer
-            .apply(HeaderCheckMiddleware::new("X-Request-ID", tracker))
+            .apply_instance(HeaderCheckMiddleware::new("X-Request-ID", tracker))
             .for_routes(vec!["/api/validate"]);
     }
 }
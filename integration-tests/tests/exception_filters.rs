@@ -0,0 +1,276 @@
+//! Test HttpException, #[use_filters] at method/controller level and global filters
+
+use serial_test::serial;
+use std::sync::Arc;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct,
+    extractors::Json,
+    get,
+    http_helpers::{Exception, HttpException, IntoResponse, StatusCode},
+    module,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        ExceptionFilter,
+    },
+    use_filters, Body as ToniBody, HttpAdapter, HttpRequest, HttpResponse,
+};
+use toni_axum::AxumAdapter;
+
+// ============================================================================
+// DOMAIN ERROR
+// ============================================================================
+
+#[derive(Debug)]
+enum UserError {
+    Database(String),
+}
+
+impl std::fmt::Display for UserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserError::Database(reason) => write!(f, "database error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
+
+impl IntoResponse for UserError {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        HttpException::internal_server_error(self.to_string()).to_response()
+    }
+}
+
+// ============================================================================
+// FILTERS
+// ============================================================================
+
+/// Only handles `UserError`, hiding the details
+struct DatabaseErrorFilter;
+
+impl ExceptionFilter for DatabaseErrorFilter {
+    fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+        let UserError::Database(_) = exception.downcast_ref::<UserError>()?;
+        Some(
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ToniBody::Json(serde_json::json!({ "error": "Try again later" })),
+            )
+                .to_response(),
+        )
+    }
+}
+
+/// Tags every `HttpException` it sees, except auth failures left to the global filter
+struct ControllerFilter;
+
+impl ExceptionFilter for ControllerFilter {
+    fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+        let error = exception.downcast_ref::<HttpException>()?;
+        if error.status() == StatusCode::UNAUTHORIZED {
+            return None;
+        }
+        let mut response = exception.response().clone();
+        response.headers.insert("X-Filter", "controller");
+        Some(response)
+    }
+}
+
+/// Method-level filter, tried before the controller-level one
+struct ConflictFilter;
+
+impl ExceptionFilter for ConflictFilter {
+    fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+        let error = exception.downcast_ref::<HttpException>()?;
+        if error.status() != StatusCode::CONFLICT {
+            return None;
+        }
+        let mut response = exception.response().clone();
+        response.headers.insert("X-Filter", "conflict");
+        Some(response)
+    }
+}
+
+/// Global filter: adds a challenge to 401s raised anywhere, including middleware
+struct UnauthorizedFilter;
+
+impl ExceptionFilter for UnauthorizedFilter {
+    fn catch(&self, exception: &Exception, req: &HttpRequest) -> Option<HttpResponse> {
+        let error = exception.downcast_ref::<HttpException>()?;
+        if error.status() != StatusCode::UNAUTHORIZED {
+            return None;
+        }
+        let mut response = exception.response().clone();
+        response.headers.insert("WWW-Authenticate", "Bearer");
+        response.headers.insert("X-Path", req.uri.clone());
+        Some(response)
+    }
+}
+
+// ============================================================================
+// MIDDLEWARE
+// ============================================================================
+
+#[derive(Debug)]
+struct BrokenPipe;
+
+impl std::fmt::Display for BrokenPipe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "secret internal detail")
+    }
+}
+
+impl std::error::Error for BrokenPipe {}
+
+struct ApiKeyMiddleware;
+
+#[async_trait]
+impl Middleware for ApiKeyMiddleware {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        match req.header("X-Api-Key") {
+            Some("valid") => next.run(req).await,
+            Some("boom") => Err(BrokenPipe.into()),
+            _ => Err(HttpException::unauthorized("Missing API key").into()),
+        }
+    }
+}
+
+// ============================================================================
+// CONTROLLER
+// ============================================================================
+
+#[controller_struct(
+    pub struct UserController;
+)]
+#[use_filters(ControllerFilter)]
+#[controller("/users")]
+impl UserController {
+    #[get("/missing")]
+    fn missing(&self, _req: HttpRequest) -> Result<Json<serde_json::Value>, HttpException> {
+        Err(HttpException::not_found("User 7 not found"))
+    }
+
+    #[use_filters(DatabaseErrorFilter)]
+    #[get("/db")]
+    fn db(&self, _req: HttpRequest) -> Result<Json<serde_json::Value>, UserError> {
+        Err(UserError::Database("connection reset".to_string()))
+    }
+
+    #[get("/db-unfiltered")]
+    fn db_unfiltered(&self, _req: HttpRequest) -> Result<Json<serde_json::Value>, UserError> {
+        Err(UserError::Database("connection reset".to_string()))
+    }
+
+    #[use_filters(ConflictFilter)]
+    #[get("/conflict")]
+    fn conflict(&self, _req: HttpRequest) -> HttpException {
+        HttpException::conflict("Email already taken")
+            .with_details(serde_json::json!({ "field": "email" }))
+    }
+
+    #[get("/ok")]
+    fn ok(&self, _req: HttpRequest) -> Result<ToniBody, HttpException> {
+        Ok(ToniBody::Text("fine".to_string()))
+    }
+}
+
+#[module(
+    controllers: [UserController],
+    providers: [],
+)]
+impl UserModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_exception_filters() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29350;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let mut factory = ToniFactory::new();
+        factory
+            .use_global_middleware(Arc::new(ApiKeyMiddleware))
+            .use_global_filters(Arc::new(UnauthorizedFilter));
+
+        let adapter = AxumAdapter::new();
+        let app = factory
+            .create(UserModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/users", port);
+            let get = |path: &str| {
+                client
+                    .get(format!("{}{}", base, path))
+                    .header("X-Api-Key", "valid")
+            };
+
+            // Test 1: HttpException renders as JSON, tagged by the controller filter
+            let response = get("/missing").send().await.unwrap();
+            assert_eq!(response.status(), 404);
+            assert_eq!(response.headers()["x-filter"], "controller");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(
+                body,
+                serde_json::json!({
+                    "statusCode": 404,
+                    "error": "Not Found",
+                    "message": "User 7 not found",
+                })
+            );
+
+            // Test 2: method filter matched by error type
+            let response = get("/db").send().await.unwrap();
+            assert_eq!(response.status(), 503);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "Try again later");
+
+            // Test 3: no filter matches a UserError elsewhere, its own response is used
+            let response = get("/db-unfiltered").send().await.unwrap();
+            assert_eq!(response.status(), 500);
+            assert!(response.headers().get("x-filter").is_none());
+
+            // Test 4: method filter wins over the controller filter
+            let response = get("/conflict").send().await.unwrap();
+            assert_eq!(response.status(), 409);
+            assert_eq!(response.headers()["x-filter"], "conflict");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["details"]["field"], "email");
+
+            // Test 5: Ok values never reach filters
+            let response = get("/ok").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert!(response.headers().get("x-filter").is_none());
+            assert_eq!(response.text().await.unwrap(), "fine");
+
+            // Test 6: HttpException from middleware goes through the global filter
+            let response = client.get(format!("{}/ok", base)).send().await.unwrap();
+            assert_eq!(response.status(), 401);
+            assert_eq!(response.headers()["www-authenticate"], "Bearer");
+            assert_eq!(response.headers()["x-path"], "/users/ok");
+
+            // Test 7: other middleware errors are a 500 without the error message
+            let response = client
+                .get(format!("{}/ok", base))
+                .header("X-Api-Key", "boom")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 500);
+            let body = response.text().await.unwrap();
+            assert!(!body.contains("secret internal detail"));
+        })
+        .await;
+}
@@ -5,6 +5,7 @@
 //! 2. Request-scoped providers using from_request to access that data
 //! 3. Controllers using request context without manual extraction
 
+use toni::http_helpers::HeaderMap;
 use toni::{
     controller, controller_struct, get, injectable, module, toni_factory::ToniFactory,
     Body as ToniBody, HttpAdapter, HttpRequest,
@@ -135,7 +136,7 @@ mod tests {
         // Test that we can construct RequestContext manually for testing
         let mut req = HttpRequest {
             body: ToniBody::Text("".to_string()),
-            headers: vec![],
+            headers: HeaderMap::new(),
             method: "GET".to_string(),
             uri: "/test".to_string(),
             query_params: std::collections::HashMap::new(),
@@ -161,7 +162,7 @@ mod tests {
         // Test with no extensions (anonymous user)
         let req = HttpRequest {
             body: ToniBody::Text("".to_string()),
-            headers: vec![],
+            headers: HeaderMap::new(),
             method: "GET".to_string(),
             uri: "/test".to_string(),
             query_params: std::collections::HashMap::new(),
@@ -192,7 +192,7 @@ async fn test_json_extractor() {
                 .await
                 .expect("Failed to create user");
 
-            assert_eq!(response.status(), 400);
+            assert_eq!(response.status(), 422);
         })
         .await;
 }
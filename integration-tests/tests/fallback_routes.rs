@@ -0,0 +1,186 @@
+//! Test the 404 and 405 responses Toni sends for requests no route matches
+
+use serde_json::Value;
+use std::sync::Arc;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct,
+    extractors::Path,
+    get, module, post,
+    toni_factory::ToniFactory,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        FallbackHandler,
+    },
+    Body as ToniBody, HttpAdapter, HttpMethod, HttpRequest, HttpResponse,
+};
+use toni_axum::AxumAdapter;
+
+mod common;
+
+/// Global middleware, expected on fallback responses too
+struct ServedBy;
+
+#[async_trait]
+impl Middleware for ServedBy {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let mut response = next.run(req).await?;
+        response.headers.insert("X-Served-By", "toni");
+        Ok(response)
+    }
+}
+
+/// Plain text responses instead of JSON
+struct PlainFallback;
+
+impl FallbackHandler for PlainFallback {
+    fn not_found(&self, req: &HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.status = 404;
+        response.body = Some(ToniBody::Text(format!("Nothing at {}", req.path())));
+        response
+    }
+
+    fn method_not_allowed(&self, _req: &HttpRequest, allow: &[HttpMethod]) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.status = 405;
+        response.body = Some(ToniBody::Text(format!("Try one of {}", allow.len())));
+        response
+    }
+}
+
+#[controller_struct(
+    pub struct ThingController;
+)]
+#[controller("/things")]
+impl ThingController {
+    #[get("/")]
+    fn list(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("things".to_string())
+    }
+
+    #[post("/")]
+    fn create(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("created".to_string())
+    }
+
+    #[get("/:id(int)")]
+    fn find(&self, Path(id): Path<u32>) -> ToniBody {
+        ToniBody::Text(format!("thing {}", id))
+    }
+}
+
+#[module(
+    controllers: [ThingController],
+    providers: [],
+)]
+impl ThingModule {}
+
+fn use_served_by(factory: &mut ToniFactory) {
+    factory.use_global_middleware(Arc::new(ServedBy));
+}
+
+async fn check_fallbacks(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}", port);
+
+    // Test 1: unknown paths get a JSON 404, after the global middleware
+    let response = client
+        .get(format!("{}/nothing?page=2", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.headers()["x-served-by"], "toni");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["statusCode"], 404);
+    assert_eq!(body["error"], "Not Found");
+    assert_eq!(body["message"], "Cannot GET /nothing");
+
+    // Test 2: known paths with another method get a 405 listing the methods
+    let response = client
+        .delete(format!("{}/things", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.headers()["allow"], "GET, POST, HEAD, OPTIONS");
+    assert_eq!(response.headers()["x-served-by"], "toni");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["message"], "Cannot DELETE /things");
+
+    let response = client
+        .put(format!("{}/things/7/", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.headers()["allow"], "GET, HEAD, OPTIONS");
+
+    // Test 3: failed constraints answer like an unknown path
+    let response = client
+        .get(format!("{}/things/seven", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.headers()["x-served-by"], "toni");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["message"], "Cannot GET /things/seven");
+
+    let response = client
+        .delete(format!("{}/things/seven", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_fallbacks() {
+    common::run_on_both_adapters_with(
+        ThingModule::module_definition,
+        use_served_by,
+        29470,
+        check_fallbacks,
+    )
+    .await;
+}
+
+#[actix_rt::test]
+async fn test_custom_fallback_handler() {
+    let port = 29472;
+    let mut factory = ToniFactory::new();
+    use_served_by(&mut factory);
+    factory.use_fallback_handler(Arc::new(PlainFallback));
+
+    common::run_on(
+        AxumAdapter::new(),
+        factory,
+        ThingModule::module_definition(),
+        port,
+        async move {
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}", port);
+
+            let response = client
+                .get(format!("{}/nothing", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 404);
+            assert_eq!(response.text().await.unwrap(), "Nothing at /nothing");
+
+            // The Allow header is still added
+            let response = client
+                .delete(format!("{}/things", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 405);
+            assert_eq!(response.headers()["allow"], "GET, POST, HEAD, OPTIONS");
+            assert_eq!(response.text().await.unwrap(), "Try one of 4");
+        },
+    )
+    .await;
+}
@@ -16,6 +16,7 @@ use toni::{
 };
 use toni_axum::AxumAdapter;
 
+use toni::http_helpers::HttpException;
 use toni::injector::Context;
 use toni::traits_helpers::{Guard, Interceptor, InterceptorNext, Pipe};
 
@@ -69,6 +70,7 @@ fn get_tracker() -> ExecutionOrder {
 // GUARD IMPLEMENTATIONS
 // ============================================================================
 
+#[derive(Default)]
 pub struct GlobalGuard;
 
 impl GlobalGuard {
@@ -77,13 +79,15 @@ impl GlobalGuard {
     }
 }
 
+#[async_trait]
 impl Guard for GlobalGuard {
-    fn can_activate(&self, _context: &Context) -> bool {
+    async fn can_activate(&self, _context: &mut Context) -> Result<bool, HttpException> {
         get_tracker().track("guard:global");
-        true
+        Ok(true)
     }
 }
 
+#[derive(Default)]
 pub struct ControllerGuard;
 
 impl ControllerGuard {
@@ -92,13 +96,15 @@ impl ControllerGuard {
     }
 }
 
+#[async_trait]
 impl Guard for ControllerGuard {
-    fn can_activate(&self, _context: &Context) -> bool {
+    async fn can_activate(&self, _context: &mut Context) -> Result<bool, HttpException> {
         get_tracker().track("guard:controller");
-        true
+        Ok(true)
     }
 }
 
+#[derive(Default)]
 pub struct MethodGuard;
 
 impl MethodGuard {
@@ -107,10 +113,11 @@ impl MethodGuard {
     }
 }
 
+#[async_trait]
 impl Guard for MethodGuard {
-    fn can_activate(&self, _context: &Context) -> bool {
+    async fn can_activate(&self, _context: &mut Context) -> Result<bool, HttpException> {
         get_tracker().track("guard:method");
-        true
+        Ok(true)
     }
 }
 
@@ -181,8 +188,9 @@ impl GlobalPipe {
     }
 }
 
+#[async_trait]
 impl Pipe for GlobalPipe {
-    fn process(&self, _context: &mut Context) {
+    async fn process(&self, _context: &mut Context) {
         get_tracker().track("pipe:global");
     }
 }
@@ -195,8 +203,9 @@ impl ControllerPipe {
     }
 }
 
+#[async_trait]
 impl Pipe for ControllerPipe {
-    fn process(&self, _context: &mut Context) {
+    async fn process(&self, _context: &mut Context) {
         get_tracker().track("pipe:controller");
     }
 }
@@ -209,8 +218,9 @@ impl MethodPipe {
     }
 }
 
+#[async_trait]
 impl Pipe for MethodPipe {
-    fn process(&self, _context: &mut Context) {
+    async fn process(&self, _context: &mut Context) {
         get_tracker().track("pipe:method");
     }
 }
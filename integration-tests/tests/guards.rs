@@ -0,0 +1,167 @@
+//! Test async guards: HttpException denials, request extensions and DI guards
+
+use serial_test::serial;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get, http_helpers::HttpException, injectable, injector::Context,
+    module, traits_helpers::Guard, use_guards, Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+// ============================================================================
+// SERVICES
+// ============================================================================
+
+#[derive(Clone)]
+struct CurrentUser {
+    name: String,
+    admin: bool,
+}
+
+#[injectable(pub struct TokenVerifier {})]
+impl TokenVerifier {
+    fn verify(&self, token: &str) -> Option<CurrentUser> {
+        match token {
+            "alice-token" => Some(CurrentUser {
+                name: "alice".to_string(),
+                admin: true,
+            }),
+            "bob-token" => Some(CurrentUser {
+                name: "bob".to_string(),
+                admin: false,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// GUARDS
+// ============================================================================
+
+/// Built by DI, stores the authenticated user for the handler
+#[injectable(pub struct AuthGuard {
+    #[inject]
+    verifier: TokenVerifier,
+})]
+impl AuthGuard {}
+
+#[async_trait]
+impl Guard for AuthGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        let token = context
+            .take_request()
+            .header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| HttpException::unauthorized("Missing bearer token"))?;
+        let user = self
+            .verifier
+            .verify(token)
+            .ok_or_else(|| HttpException::unauthorized("Invalid token"))?;
+
+        context.take_request_mut().extensions.insert(user);
+        Ok(true)
+    }
+}
+
+/// Built with `Default`, relies on `AuthGuard` running first
+#[derive(Default)]
+struct AdminGuard;
+
+#[async_trait]
+impl Guard for AdminGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        Ok(context
+            .take_request()
+            .extensions
+            .get::<CurrentUser>()
+            .is_some_and(|user| user.admin))
+    }
+}
+
+// ============================================================================
+// CONTROLLER
+// ============================================================================
+
+#[controller_struct(
+    pub struct AccountController;
+)]
+#[use_guards(AuthGuard)]
+#[controller("/account")]
+impl AccountController {
+    #[get("/me")]
+    fn me(&self, req: HttpRequest) -> ToniBody {
+        let user = req.extensions.get::<CurrentUser>().unwrap();
+        ToniBody::Text(user.name.clone())
+    }
+
+    #[use_guards(AdminGuard)]
+    #[get("/admin")]
+    fn admin(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("admin area".to_string())
+    }
+}
+
+#[module(
+    controllers: [AccountController],
+    providers: [TokenVerifier, AuthGuard],
+)]
+impl AccountModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_guards() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29370;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(AccountModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/account", port);
+            let get = |path: &str, token: &str| {
+                client
+                    .get(format!("{}{}", base, path))
+                    .header("Authorization", format!("Bearer {}", token))
+            };
+
+            // Test 1: DI guard hands the user to the handler through extensions
+            let response = get("/me", "alice-token").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "alice");
+
+            // Test 2: Err from a guard becomes its HttpException response
+            let response = client.get(format!("{}/me", base)).send().await.unwrap();
+            assert_eq!(response.status(), 401);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["message"], "Missing bearer token");
+
+            let response = get("/me", "forged").send().await.unwrap();
+            assert_eq!(response.status(), 401);
+
+            // Test 3: Ok(false) denies with 403
+            let response = get("/admin", "bob-token").send().await.unwrap();
+            assert_eq!(response.status(), 403);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "Forbidden");
+
+            // Test 4: controller guard runs before the method guard
+            let response = get("/admin", "alice-token").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "admin area");
+        })
+        .await;
+}
@@ -0,0 +1,162 @@
+//! Test controllers restricted to a host, with host parameters read like path
+//! parameters
+
+use toni::async_trait;
+use toni::{
+    controller, controller_struct,
+    extractors::Path,
+    get,
+    middleware::RoutePattern,
+    module, post,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        MiddlewareConsumer,
+    },
+    Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+/// Marks responses served on the admin host
+struct AdminArea;
+
+#[async_trait]
+impl Middleware for AdminArea {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let mut response = next.run(req).await?;
+        response.headers.insert("X-Area", "admin");
+        Ok(response)
+    }
+}
+
+#[controller_struct(
+    pub struct ApiController;
+)]
+#[controller("/", host = "api.example.com")]
+impl ApiController {
+    #[get("/status")]
+    fn status(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("api".to_string())
+    }
+}
+
+#[controller_struct(
+    pub struct TenantController;
+)]
+#[controller("/", host = ":tenant(alpha).example.com")]
+impl TenantController {
+    #[get("/status")]
+    fn status(&self, Path(tenant): Path<String>) -> ToniBody {
+        ToniBody::Text(format!("tenant {}", tenant))
+    }
+
+    #[get("/projects/:id(int)")]
+    fn project(&self, Path((tenant, id)): Path<(String, u32)>) -> ToniBody {
+        ToniBody::Text(format!("{} project {}", tenant, id))
+    }
+}
+
+#[controller_struct(
+    pub struct PublicController;
+)]
+#[controller("/")]
+impl PublicController {
+    #[get("/status")]
+    fn status(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("public".to_string())
+    }
+
+    #[post("/status")]
+    fn report(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("reported".to_string())
+    }
+}
+
+#[module(
+    controllers: [ApiController, TenantController, PublicController],
+    providers: [],
+)]
+impl HostModule {
+    fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
+        consumer.apply_instance(AdminArea).for_routes(vec![
+            RoutePattern::all_methods("/*").on_host("admin.example.org")
+        ]);
+    }
+}
+
+async fn check_host_routing(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}", port);
+    let send = |method: reqwest::Method, host: &str, path: &str| {
+        let request = client.request(method, format!("{}{}", base, path));
+        let request = if host.is_empty() {
+            request
+        } else {
+            request.header("Host", format!("{}:{}", host, port))
+        };
+        request.send()
+    };
+    let get = |host: &'static str, path: &'static str| {
+        let response = send(reqwest::Method::GET, host, path);
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), 200, "GET {}{}", host, path);
+            response.text().await.unwrap()
+        }
+    };
+
+    // Test 1: the most specific host serves the route
+    assert_eq!(get("api.example.com", "/status").await, "api");
+    assert_eq!(get("API.Example.com", "/status").await, "api");
+    assert_eq!(get("acme.example.com", "/status").await, "tenant acme");
+    assert_eq!(get("42.example.com", "/status").await, "public");
+    assert_eq!(get("", "/status").await, "public");
+
+    // Test 2: host parameters come first in tuples
+    assert_eq!(
+        get("acme.example.com", "/projects/7").await,
+        "acme project 7"
+    );
+    let response = send(reqwest::Method::GET, "", "/projects/7").await.unwrap();
+    assert_eq!(response.status(), 404);
+
+    // Test 3: 405 only lists the methods served on the host
+    let response = send(reqwest::Method::POST, "api.example.com", "/status")
+        .await
+        .unwrap();
+    assert_eq!(response.text().await.unwrap(), "reported");
+
+    let response = send(reqwest::Method::DELETE, "api.example.com", "/status")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.headers()["allow"], "GET, POST, HEAD, OPTIONS");
+
+    let response = send(reqwest::Method::DELETE, "acme.example.com", "/projects/7")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 405);
+    assert_eq!(response.headers()["allow"], "GET, HEAD, OPTIONS");
+
+    let response = send(reqwest::Method::DELETE, "", "/projects/7")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    // Test 4: middleware patterns match the request's host
+    let response = send(reqwest::Method::GET, "admin.example.org", "/status")
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-area"], "admin");
+    assert_eq!(response.text().await.unwrap(), "public");
+
+    let response = send(reqwest::Method::GET, "api.example.com", "/status")
+        .await
+        .unwrap();
+    assert!(response.headers().get("x-area").is_none());
+}
+
+#[actix_rt::test]
+async fn test_host_routing() {
+    common::run_on_both_adapters(HostModule::module_definition, 29480, check_host_routing).await;
+}
@@ -0,0 +1,147 @@
+//! Test `#[middleware]` structs built by dependency injection and applied with
+//! `apply::<M>()`
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    http_helpers::{HttpException, IntoResponse},
+    injectable, middleware, module,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        MiddlewareConsumer,
+    },
+    Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+#[injectable(pub struct TokenVerifier {})]
+impl TokenVerifier {
+    fn user(&self, token: &str) -> Option<&'static str> {
+        match token {
+            "alice-token" => Some("alice"),
+            _ => None,
+        }
+    }
+}
+
+/// Shared by every clone of the provider
+#[injectable(pub struct RequestStats {
+    count: Arc<AtomicUsize>,
+})]
+impl RequestStats {}
+
+#[middleware(pub struct AuthMiddleware {
+    #[inject]
+    verifier: TokenVerifier,
+})]
+impl AuthMiddleware {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let user = req
+            .header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.verifier.user(token));
+        let Some(user) = user else {
+            return Ok(HttpException::unauthorized("Invalid token").to_response());
+        };
+
+        let mut response = next.run(req).await?;
+        response.headers.insert("X-User", user);
+        Ok(response)
+    }
+}
+
+#[middleware]
+pub struct CountingMiddleware {
+    #[inject]
+    stats: RequestStats,
+}
+
+#[async_trait]
+impl Middleware for CountingMiddleware {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let count = self.stats.count.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut response = next.run(req).await?;
+        response.headers.insert("X-Count", count.to_string());
+        Ok(response)
+    }
+}
+
+#[controller_struct(
+    pub struct AccountController;
+)]
+#[controller("/")]
+impl AccountController {
+    #[get("/account")]
+    fn account(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("account".to_string())
+    }
+
+    #[get("/public")]
+    fn public(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("public".to_string())
+    }
+}
+
+#[module(
+    controllers: [AccountController],
+    providers: [TokenVerifier, RequestStats, AuthMiddleware, CountingMiddleware],
+)]
+impl AccountModule {
+    fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
+        consumer
+            .apply::<CountingMiddleware>()
+            .apply_also::<AuthMiddleware>()
+            .for_routes(vec!["/account"]);
+        consumer
+            .apply::<CountingMiddleware>()
+            .for_routes(vec!["/public"]);
+    }
+}
+
+async fn check_middleware_injection(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}", port);
+
+    // Test 1: injected dependencies reach the middleware
+    let response = client
+        .get(format!("{}/account", base))
+        .bearer_auth("alice-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-user"], "alice");
+    assert_eq!(response.headers()["x-count"], "1");
+
+    let response = client
+        .get(format!("{}/account", base))
+        .bearer_auth("mallory-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.headers()["x-count"], "2");
+
+    // Test 2: every configuration shares the singleton's dependencies
+    let response = client.get(format!("{}/public", base)).send().await.unwrap();
+    assert_eq!(response.text().await.unwrap(), "public");
+
+    let response = client.get(format!("{}/public", base)).send().await.unwrap();
+    assert!(response.headers().get("x-user").is_none());
+    assert_eq!(response.headers()["x-count"], "4");
+}
+
+#[actix_rt::test]
+async fn test_middleware_injection() {
+    common::run_on_both_adapters(
+        AccountModule::module_definition,
+        29510,
+        check_middleware_injection,
+    )
+    .await;
+}
@@ -0,0 +1,132 @@
+//! Test middleware applied by glob, regex and controller, matched against the
+//! request path
+
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    middleware::RoutePattern,
+    module, post,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        MiddlewareConsumer,
+    },
+    Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+/// Adds a header naming the middleware to responses
+struct Tag(&'static str);
+
+#[async_trait]
+impl Middleware for Tag {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let mut response = next.run(req).await?;
+        response.headers.insert(format!("X-{}", self.0), "1");
+        Ok(response)
+    }
+}
+
+#[controller_struct(
+    pub struct UserController;
+)]
+#[controller("/users")]
+impl UserController {
+    #[get("/:id")]
+    fn find(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("user".to_string())
+    }
+
+    #[get("/:id/:section")]
+    fn section(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("section".to_string())
+    }
+
+    #[post("/login")]
+    fn login(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("login".to_string())
+    }
+}
+
+#[controller_struct(
+    pub struct TeamController;
+)]
+#[controller("/teams")]
+impl TeamController {
+    #[get("/:id/:section")]
+    fn section(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("team section".to_string())
+    }
+
+    #[get("/reports/:file")]
+    fn report(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("report".to_string())
+    }
+}
+
+#[module(
+    controllers: [UserController, TeamController],
+    providers: [],
+)]
+impl PatternModule {
+    fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
+        consumer
+            .apply_instance(Tag("Users"))
+            .for_controller::<UserController>()
+            .exclude_route(("/users/login", "POST"))
+            .done();
+        consumer
+            .apply_instance(Tag("Detail"))
+            .for_routes(vec!["/{users,teams}/:id(int)/*"]);
+        consumer
+            .apply_instance(Tag("Export"))
+            .for_routes(vec!["/**/*.{csv,pdf}"]);
+        consumer
+            .apply_instance(Tag("Admin"))
+            .for_routes(vec![RoutePattern::regex("^/teams/[0-9]+/admin$")]);
+    }
+}
+
+async fn check_middleware_patterns(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}", port);
+    let tags = |method: reqwest::Method, path: &'static str| {
+        let response = client.request(method, format!("{}{}", base, path)).send();
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), 200, "{}", path);
+            let mut tags: Vec<&str> = ["users", "detail", "export", "admin"]
+                .into_iter()
+                .filter(|tag| response.headers().contains_key(format!("x-{}", tag)))
+                .collect();
+            tags.sort();
+            tags
+        }
+    };
+    let get = |path| tags(reqwest::Method::GET, path);
+
+    // Test 1: controllers, wherever their routes are mounted
+    assert_eq!(get("/users/me").await, ["users"]);
+    assert!(tags(reqwest::Method::POST, "/users/login").await.is_empty());
+
+    // Test 2: globs with parameters are matched against the request path
+    assert_eq!(get("/users/7/posts").await, ["detail", "users"]);
+    assert_eq!(get("/users/me/posts").await, ["users"]);
+    assert_eq!(get("/teams/7/posts").await, ["detail"]);
+    assert_eq!(get("/teams/reports/q1.csv").await, ["export"]);
+    assert!(get("/teams/reports/q1.txt").await.is_empty());
+
+    // Test 3: regex patterns
+    assert_eq!(get("/teams/7/admin").await, ["admin", "detail"]);
+    assert!(get("/teams/seven/admin").await.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_middleware_patterns() {
+    common::run_on_both_adapters(
+        PatternModule::module_definition,
+        29500,
+        check_middleware_patterns,
+    )
+    .await;
+}
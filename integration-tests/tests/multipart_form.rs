@@ -0,0 +1,310 @@
+//! Test Form<T> and Multipart extractors with file uploads and per-route limits,
+//! including the body limit the adapters enforce while reading
+
+use serde::Deserialize;
+use serial_test::serial;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct,
+    extractors::{Form, Multipart, MultipartLimits, UploadedFile},
+    http_helpers::HttpException,
+    injector::Context,
+    module, post,
+    traits_helpers::Guard,
+    upload_limits, use_guards, Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+mod common;
+
+#[derive(Debug, Deserialize)]
+struct LoginForm {
+    username: String,
+    remember: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AvatarUpload {
+    user_id: u64,
+    avatar: UploadedFile,
+}
+
+#[derive(Debug, Deserialize)]
+struct GalleryUpload {
+    photos: Vec<UploadedFile>,
+}
+
+#[controller_struct(
+    pub struct UploadController;
+)]
+#[controller("/uploads")]
+impl UploadController {
+    #[post("/login")]
+    fn login(&self, Form(form): Form<LoginForm>) -> ToniBody {
+        ToniBody::Text(format!("{}:{}", form.username, form.remember))
+    }
+
+    #[post("/avatar")]
+    fn avatar(&self, Form(dto): Form<AvatarUpload>) -> ToniBody {
+        let bytes = dto.avatar.bytes().unwrap();
+        ToniBody::Json(serde_json::json!({
+            "user_id": dto.user_id,
+            "filename": dto.avatar.filename,
+            "content_type": dto.avatar.content_type,
+            "size": dto.avatar.size,
+            "bytes": bytes.to_vec(),
+        }))
+    }
+
+    #[post("/gallery")]
+    #[upload_limits(max_files = 2, max_file_size = 8)]
+    fn gallery(&self, Form(dto): Form<GalleryUpload>) -> ToniBody {
+        ToniBody::Text(format!("{} photos", dto.photos.len()))
+    }
+
+    #[post("/fields")]
+    async fn fields(&self, mut multipart: Multipart) -> ToniBody {
+        let mut names = Vec::new();
+        while let Ok(Some(field)) = multipart.next_field().await {
+            names.push(field.name().to_string());
+        }
+        ToniBody::Text(names.join(","))
+    }
+}
+
+/// Lets requests through when the route's body limit is `X-Expected-Limit`
+#[derive(Default)]
+struct BodyLimitGuard;
+
+#[async_trait]
+impl Guard for BodyLimitGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        let limit = context.handler().and_then(|handler| handler.body_limit());
+        let limit = limit.map_or("none".to_string(), |limit| limit.to_string());
+        Ok(context.take_request().header("X-Expected-Limit") == Some(limit.as_str()))
+    }
+}
+
+#[controller_struct(
+    pub struct ArchiveController;
+)]
+#[use_guards(BodyLimitGuard)]
+#[controller("/archives")]
+impl ArchiveController {
+    #[post("/")]
+    #[upload_limits(max_files = 1, max_file_size = 1_048_576)]
+    fn upload(&self, Form(dto): Form<AvatarUpload>) -> ToniBody {
+        ToniBody::Text(format!("{} bytes", dto.avatar.size))
+    }
+
+    #[post("/drafts")]
+    fn drafts(&self, _multipart: Multipart) -> ToniBody {
+        ToniBody::Text("drafts".to_string())
+    }
+
+    #[post("/ping")]
+    fn ping(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("pong".to_string())
+    }
+
+    #[post("/notes")]
+    #[upload_limits(max_body_size = 1024)]
+    async fn notes(&self, mut multipart: Multipart) -> ToniBody {
+        let mut count = 0;
+        while let Ok(Some(_)) = multipart.next_field().await {
+            count += 1;
+        }
+        ToniBody::Text(format!("{} notes", count))
+    }
+}
+
+#[module(
+    controllers: [UploadController],
+    providers: [],
+)]
+impl UploadModule {}
+
+#[module(
+    controllers: [ArchiveController],
+    providers: [],
+)]
+impl ArchiveModule {}
+
+async fn check_body_limits(port: u16) {
+    use reqwest::multipart::{Form as MultipartForm, Part};
+
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}/archives", port);
+    let post = |path: &str, limit: usize| {
+        client
+            .post(format!("{}{}", base, path))
+            .header("X-Expected-Limit", limit.to_string())
+    };
+
+    // Test 1: bodies within the limits the route derives are read whole
+    let form = MultipartForm::new().text("user_id", "7").part(
+        "avatar",
+        Part::bytes(vec![7u8; 512 * 1024]).file_name("backup.tar"),
+    );
+    let limit = MultipartLimits {
+        max_files: 1,
+        max_file_size: 1_048_576,
+        ..Default::default()
+    };
+    let response = post("/", limit.body_limit())
+        .multipart(form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "524288 bytes");
+
+    let form = MultipartForm::new().text("note", "short");
+    let response = post("/notes", 1024).multipart(form).send().await.unwrap();
+    assert_eq!(response.text().await.unwrap(), "1 notes");
+
+    // Test 2: the adapter stops reading past `max_body_size`
+    let form = MultipartForm::new().text("note", "x".repeat(4096));
+    let response = post("/notes", 1024).multipart(form).send().await.unwrap();
+    assert_eq!(response.status(), 413);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["detail"], "Request body exceeds the 1024 byte limit");
+
+    // Test 3: handlers reading forms get the default limits, others none
+    let default_limit = MultipartLimits::default().body_limit();
+    let form = MultipartForm::new().text("note", "draft");
+    let response = post("/drafts", default_limit)
+        .multipart(form)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.text().await.unwrap(), "drafts");
+
+    let response = client
+        .post(format!("{}/ping", base))
+        .header("X-Expected-Limit", "none")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.text().await.unwrap(), "pong");
+}
+
+#[actix_rt::test]
+async fn test_body_limits() {
+    common::run_on_both_adapters(ArchiveModule::module_definition, 29311, check_body_limits).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_form_and_multipart_extractors() {
+    use reqwest::multipart::{Form as MultipartForm, Part};
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29310;
+    let module = UploadModule::module_definition();
+
+    common::run_on(
+        AxumAdapter::new(),
+        ToniFactory::new(),
+        module,
+        port,
+        async move {
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/uploads", port);
+
+            // Test 1: urlencoded form
+            let response = client
+                .post(format!("{}/login", base))
+                .form(&[("username", "alice"), ("remember", "true")])
+                .send()
+                .await
+                .expect("Failed to login");
+
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "alice:true");
+
+            // Test 2: multipart upload keeps binary bytes intact
+            let binary = vec![0u8, 159, 146, 150, 255, 13, 10];
+            let form = MultipartForm::new().text("user_id", "42").part(
+                "avatar",
+                Part::bytes(binary.clone())
+                    .file_name("avatar.png")
+                    .mime_str("image/png")
+                    .unwrap(),
+            );
+            let response = client
+                .post(format!("{}/avatar", base))
+                .multipart(form)
+                .send()
+                .await
+                .expect("Failed to upload avatar");
+
+            assert_eq!(response.status(), 200);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["user_id"], 42);
+            assert_eq!(body["filename"], "avatar.png");
+            assert_eq!(body["content_type"], "image/png");
+            assert_eq!(body["size"], binary.len());
+            assert_eq!(body["bytes"], serde_json::json!(binary));
+
+            // Test 3: per-route limits accept uploads within bounds
+            let form = MultipartForm::new()
+                .part("photos", Part::bytes(b"one".to_vec()).file_name("1.jpg"))
+                .part("photos", Part::bytes(b"two".to_vec()).file_name("2.jpg"));
+            let response = client
+                .post(format!("{}/gallery", base))
+                .multipart(form)
+                .send()
+                .await
+                .expect("Failed to upload gallery");
+
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "2 photos");
+
+            // Test 4: too many files are rejected
+            let form = MultipartForm::new()
+                .part("photos", Part::bytes(b"1".to_vec()).file_name("1.jpg"))
+                .part("photos", Part::bytes(b"2".to_vec()).file_name("2.jpg"))
+                .part("photos", Part::bytes(b"3".to_vec()).file_name("3.jpg"));
+            let response = client
+                .post(format!("{}/gallery", base))
+                .multipart(form)
+                .send()
+                .await
+                .expect("Failed to upload gallery");
+
+            assert_eq!(response.status(), 400);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert!(body["detail"].as_str().unwrap().contains("Too many files"));
+
+            // Test 5: oversized files are rejected
+            let form = MultipartForm::new().part(
+                "photos",
+                Part::bytes(b"0123456789".to_vec()).file_name("big.jpg"),
+            );
+            let response = client
+                .post(format!("{}/gallery", base))
+                .multipart(form)
+                .send()
+                .await
+                .expect("Failed to upload gallery");
+
+            assert_eq!(response.status(), 400);
+
+            // Test 6: Multipart extractor read part by part
+            let form = MultipartForm::new()
+                .text("first", "a")
+                .part("second", Part::bytes(b"b".to_vec()).file_name("b.txt"));
+            let response = client
+                .post(format!("{}/fields", base))
+                .multipart(form)
+                .send()
+                .await
+                .expect("Failed to post fields");
+
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "first,second");
+        },
+    )
+    .await;
+}
@@ -0,0 +1,227 @@
+//! Test that panics in handlers and guards become 500 responses routed through
+//! filters, and that invalid response headers do not drop the connection
+
+use serial_test::serial;
+use std::sync::Arc;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    http_helpers::{Exception, HttpException, StatusCode},
+    injector::Context,
+    module,
+    traits_helpers::{ExceptionFilter, Guard, Interceptor, InterceptorNext},
+    Body as ToniBody, HttpAdapter, HttpRequest, HttpResponse,
+};
+use toni_axum::AxumAdapter;
+
+mod common;
+
+struct ExplodingGuard;
+
+#[async_trait]
+impl Guard for ExplodingGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        if context.take_request().has_header("X-Explode") {
+            panic!("guard exploded");
+        }
+        Ok(true)
+    }
+}
+
+/// Neither calls `next` nor sets a response
+struct SilentInterceptor;
+
+#[async_trait]
+impl Interceptor for SilentInterceptor {
+    async fn intercept(&self, _context: &mut Context, _next: Box<dyn InterceptorNext>) {}
+}
+
+/// Marks 500s so the test can tell the filter ran
+struct ServerErrorFilter;
+
+impl ExceptionFilter for ServerErrorFilter {
+    fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+        let error = exception.downcast_ref::<HttpException>()?;
+        if error.status() != StatusCode::INTERNAL_SERVER_ERROR {
+            return None;
+        }
+        let mut response = exception.response().clone();
+        response.headers.insert("X-Filtered", "true");
+        Some(response)
+    }
+}
+
+#[controller_struct(
+    pub struct PanicController;
+)]
+#[controller("/panic")]
+impl PanicController {
+    #[get("/handler")]
+    fn handler(&self, _req: HttpRequest) -> ToniBody {
+        let items: Vec<u32> = Vec::new();
+        ToniBody::Text(items[3].to_string())
+    }
+
+    #[get("/async")]
+    async fn async_handler(&self, _req: HttpRequest) -> ToniBody {
+        tokio::task::yield_now().await;
+        panic!("async handler failed");
+    }
+
+    #[get("/fine")]
+    fn fine(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("still alive".to_string())
+    }
+}
+
+#[module(
+    controllers: [PanicController],
+    providers: [],
+)]
+impl PanicModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_panics_become_500() {
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29360;
+    let mut factory = ToniFactory::new();
+    factory
+        .use_global_guards(Arc::new(ExplodingGuard))
+        .use_global_filters(Arc::new(ServerErrorFilter));
+
+    common::run_on(
+        AxumAdapter::new(),
+        factory,
+        PanicModule::module_definition(),
+        port,
+        async move {
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/panic", port);
+
+            // Test 1: sync handler panic
+            let response = client
+                .get(format!("{}/handler", base))
+                .send()
+                .await
+                .expect("Connection dropped on handler panic");
+            assert_eq!(response.status(), 500);
+            assert_eq!(response.headers()["x-filtered"], "true");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "Internal Server Error");
+            assert!(!body.to_string().contains("index out of bounds"));
+
+            // Test 2: panic after an await point
+            let response = client
+                .get(format!("{}/async", base))
+                .send()
+                .await
+                .expect("Connection dropped on async panic");
+            assert_eq!(response.status(), 500);
+
+            // Test 3: guard panic
+            let response = client
+                .get(format!("{}/fine", base))
+                .header("X-Explode", "1")
+                .send()
+                .await
+                .expect("Connection dropped on guard panic");
+            assert_eq!(response.status(), 500);
+
+            // Test 4: the server keeps serving
+            let response = client.get(format!("{}/fine", base)).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "still alive");
+        },
+    )
+    .await;
+}
+
+#[controller_struct(
+    pub struct SilentController;
+)]
+#[controller("/silent")]
+impl SilentController {
+    #[get("/")]
+    fn silent(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("unreachable".to_string())
+    }
+}
+
+#[module(
+    controllers: [SilentController],
+    providers: [],
+)]
+impl SilentModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_missing_response_becomes_500() {
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29363;
+    let mut factory = ToniFactory::new();
+    factory
+        .use_global_interceptors(Arc::new(SilentInterceptor))
+        .use_global_filters(Arc::new(ServerErrorFilter));
+
+    common::run_on(
+        AxumAdapter::new(),
+        factory,
+        SilentModule::module_definition(),
+        port,
+        async move {
+            // No panic is raised, so the filters don't see one
+            let url = format!("http://127.0.0.1:{}/silent", port);
+            let response = reqwest::get(url).await.unwrap();
+            assert_eq!(response.status(), 500);
+            assert!(response.headers().get("x-filtered").is_none());
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["message"], "No response was produced");
+        },
+    )
+    .await;
+}
+
+#[controller_struct(
+    pub struct HeaderController;
+)]
+#[controller("/headers")]
+impl HeaderController {
+    #[get("/invalid")]
+    fn invalid(&self, _req: HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.headers.insert("Bad Name", "1");
+        response.headers.insert("X-Split", "a\r\nb");
+        response.headers.insert("X-Kept", "1");
+        response.body = Some(ToniBody::Text("sent".to_string()));
+        response
+    }
+}
+
+#[module(
+    controllers: [HeaderController],
+    providers: [],
+)]
+impl HeaderModule {}
+
+async fn check_invalid_headers(port: u16) {
+    let response = reqwest::get(format!("http://127.0.0.1:{}/headers/invalid", port))
+        .await
+        .expect("Connection dropped on an invalid header");
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get("x-split").is_none());
+    assert_eq!(response.headers()["x-kept"], "1");
+    assert_eq!(response.text().await.unwrap(), "sent");
+}
+
+#[actix_rt::test]
+async fn test_invalid_headers_are_dropped() {
+    common::run_on_both_adapters(
+        HeaderModule::module_definition,
+        29361,
+        check_invalid_headers,
+    )
+    .await;
+}
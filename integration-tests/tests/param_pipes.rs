@@ -0,0 +1,170 @@
+//! Test #[pipe] on Path and Query arguments: built-in parse pipes and DI pipes
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serial_test::serial;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct,
+    extractors::{Path, Query},
+    get,
+    http_helpers::HttpException,
+    injectable, module,
+    pipes::{DefaultValuePipe, ParseEnumPipe, ParseIntPipe, ParseUuidPipe},
+    traits_helpers::{ArgumentMetadata, PipeTransform},
+    Body as ToniBody, HttpAdapter,
+};
+use toni_axum::AxumAdapter;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Active,
+    Archived,
+}
+
+#[injectable(pub struct UserRepository {})]
+impl UserRepository {
+    fn find(&self, id: u64) -> Option<User> {
+        (id == 1).then(|| User {
+            id,
+            name: "alice".to_string(),
+        })
+    }
+}
+
+/// Built by DI, turns an id into the user it names
+#[injectable(pub struct LoadUserPipe {
+    #[inject]
+    users: UserRepository,
+})]
+impl LoadUserPipe {}
+
+#[async_trait]
+impl PipeTransform for LoadUserPipe {
+    async fn transform(
+        &self,
+        value: Value,
+        _metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException> {
+        let id = value.as_u64().unwrap_or_default();
+        let user = self
+            .users
+            .find(id)
+            .ok_or_else(|| HttpException::not_found(format!("User {} not found", id)))?;
+        Ok(serde_json::to_value(user).unwrap())
+    }
+}
+
+#[controller_struct(
+    pub struct UserController;
+)]
+#[controller("/users")]
+impl UserController {
+    #[get("/{id}")]
+    async fn find_one(
+        &self,
+        #[pipe("id", ParseIntPipe, LoadUserPipe)] Path(user): Path<User>,
+    ) -> ToniBody {
+        ToniBody::Text(format!("{} {}", user.id, user.name))
+    }
+
+    #[get("/by-uuid/{uuid}")]
+    fn find_by_uuid(&self, #[pipe(ParseUuidPipe)] Path(uuid): Path<String>) -> ToniBody {
+        ToniBody::Text(uuid)
+    }
+
+    #[get("/")]
+    fn list(
+        &self,
+        #[pipe(DefaultValuePipe::new(1), ParseIntPipe)] Query(page): Query<u32>,
+        #[pipe(DefaultValuePipe::new("active"), ParseEnumPipe::<Status>)] Query(status): Query<
+            Status,
+        >,
+    ) -> ToniBody {
+        ToniBody::Text(format!("page={} status={:?}", page, status))
+    }
+}
+
+#[module(
+    controllers: [UserController],
+    providers: [UserRepository, LoadUserPipe],
+)]
+impl UserModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_param_pipes() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29400;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(UserModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/users", port);
+            let get = |path: &str| client.get(format!("{}{}", base, path)).send();
+            let message = |response: reqwest::Response| async move {
+                assert_eq!(response.status(), 400);
+                let body: Value = response.json().await.unwrap();
+                body["message"].as_str().unwrap().to_string()
+            };
+
+            // Test 1: parse pipe then DI pipe loading the entity
+            let response = get("/1").await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "1 alice");
+
+            let response = get("/abc").await.unwrap();
+            assert_eq!(message(response).await, "Parameter 'id' must be an integer");
+
+            let response = get("/7").await.unwrap();
+            assert_eq!(response.status(), 404);
+
+            // Test 2: UUID validation
+            let uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+            let response = get(&format!("/by-uuid/{}", uuid)).await.unwrap();
+            assert_eq!(response.text().await.unwrap(), uuid);
+
+            let response = get("/by-uuid/42").await.unwrap();
+            assert_eq!(message(response).await, "Parameter 'uuid' must be a UUID");
+
+            // Test 3: query defaults, integers and enums
+            let response = get("/").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "page=1 status=Active");
+
+            let response = get("/?page=3&status=archived").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "page=3 status=Archived");
+
+            let response = get("/?page=-1").await.unwrap();
+            assert!(message(response)
+                .await
+                .starts_with("Parameter 'page' is invalid"));
+
+            let response = get("/?status=deleted").await.unwrap();
+            assert_eq!(
+                message(response).await,
+                "Parameter 'status' must be one of: active, archived"
+            );
+        })
+        .await;
+}
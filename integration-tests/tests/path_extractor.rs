@@ -0,0 +1,124 @@
+//! Test Path<T> extraction of single values, tuples and structs
+
+use serde::Deserialize;
+use serde_json::Value;
+use serial_test::serial;
+use toni::{
+    controller, controller_struct, extractors::Path, get, module, Body as ToniBody, HttpAdapter,
+};
+use toni_axum::AxumAdapter;
+
+#[derive(Deserialize)]
+struct IssueParams {
+    org: String,
+    repo: u64,
+    number: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Tab {
+    Code,
+    Issues,
+}
+
+#[controller_struct(
+    pub struct RepoController;
+)]
+#[controller("/orgs")]
+impl RepoController {
+    #[get("/{org}")]
+    fn org(&self, Path(org): Path<String>) -> ToniBody {
+        ToniBody::Text(org)
+    }
+
+    #[get("/{org}/repos/{repo}")]
+    fn repo(&self, Path((org, repo)): Path<(String, u64)>) -> ToniBody {
+        ToniBody::Text(format!("{}#{}", org, repo))
+    }
+
+    #[get("/{org}/repos/{repo}/issues/{number}")]
+    async fn issue(&self, Path(IssueParams { org, repo, number }): Path<IssueParams>) -> ToniBody {
+        ToniBody::Text(format!("{}#{}/{}", org, repo, number))
+    }
+
+    #[get("/{org}/repos/{repo}/tabs/{tab}")]
+    fn tab(&self, Path((_, _, tab)): Path<(String, u64, Tab)>) -> ToniBody {
+        ToniBody::Text(
+            match tab {
+                Tab::Code => "code",
+                Tab::Issues => "issues",
+            }
+            .to_string(),
+        )
+    }
+}
+
+#[module(
+    controllers: [RepoController],
+    providers: [],
+)]
+impl RepoModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_path_extraction() {
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29440;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(RepoModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/orgs", port);
+            let get = |path: &str| client.get(format!("{}{}", base, path)).send();
+
+            // Test 1: a single parameter
+            let response = get("/toni").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "toni");
+
+            // Test 2: tuples follow the route order
+            let response = get("/toni/repos/42").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "toni#42");
+
+            let response = get("/toni/repos/42/tabs/issues").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "issues");
+
+            // Test 3: structs are read by name
+            let response = get("/toni/repos/42/issues/7").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "toni#42/7");
+
+            // Test 4: rejections name the failing segment
+            let response = get("/toni/repos/latest").await.unwrap();
+            assert_eq!(response.status(), 400);
+            let body: Value = response.json().await.unwrap();
+            assert_eq!(body["param"], "repo");
+            assert!(body["detail"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid value 'latest' for path parameter 'repo'"));
+
+            let response = get("/toni/repos/42/issues/first").await.unwrap();
+            assert_eq!(response.status(), 400);
+            let body: Value = response.json().await.unwrap();
+            assert_eq!(body["param"], "number");
+
+            let response = get("/toni/repos/42/tabs/wiki").await.unwrap();
+            assert_eq!(response.status(), 400);
+            let body: Value = response.json().await.unwrap();
+            assert_eq!(body["param"], "tab");
+        })
+        .await;
+}
@@ -0,0 +1,150 @@
+//! Test #[set_metadata] / #[roles] read through the Reflector from guards and interceptors
+
+use serial_test::serial;
+use std::sync::Arc;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    http_helpers::HttpException,
+    injector::{Context, Reflector},
+    module, roles, set_metadata,
+    traits_helpers::{Guard, Interceptor, InterceptorNext},
+    use_guards, Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+/// Lets the request through when its `X-Role` is one of the handler's roles
+#[derive(Default)]
+struct RolesGuard {
+    reflector: Reflector,
+}
+
+#[async_trait]
+impl Guard for RolesGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        if self.reflector.get::<bool>("public", context) == Some(true) {
+            return Ok(true);
+        }
+        let Some(roles) = self.reflector.get::<Vec<String>>("roles", context) else {
+            return Ok(true);
+        };
+        let role = context.take_request().header("X-Role").unwrap_or_default();
+        Ok(roles.iter().any(|allowed| allowed == role))
+    }
+}
+
+/// Reports the handler identity and merged scopes in response headers
+struct HandlerHeadersInterceptor;
+
+#[async_trait]
+impl Interceptor for HandlerHeadersInterceptor {
+    async fn intercept(&self, context: &mut Context, next: Box<dyn InterceptorNext>) {
+        let handler = context.handler().unwrap();
+        let name = format!("{}::{}", handler.controller(), handler.handler());
+        let scopes = Reflector.get_all_and_merge::<String>("scopes", context);
+
+        next.run(context).await;
+
+        if let Some(response) = context.take_response() {
+            let mut response = response.to_response();
+            response.headers.insert("X-Handler", name);
+            response.headers.insert("X-Scopes", scopes.join(","));
+            context.set_response(Box::new(response));
+        }
+    }
+}
+
+#[controller_struct(
+    pub struct ReportController;
+)]
+#[roles("admin", "auditor")]
+#[set_metadata("scopes", ["reports:read"])]
+#[use_guards(RolesGuard)]
+#[controller("/reports")]
+impl ReportController {
+    #[get("/")]
+    fn list(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("reports".to_string())
+    }
+
+    #[roles("admin")]
+    #[set_metadata("scopes", ["reports:delete"])]
+    #[get("/purge")]
+    fn purge(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("purged".to_string())
+    }
+
+    #[set_metadata("public", true)]
+    #[get("/summary")]
+    fn summary(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("summary".to_string())
+    }
+}
+
+#[module(
+    controllers: [ReportController],
+    providers: [],
+)]
+impl ReportModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_reflector_metadata() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29380;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let mut factory = ToniFactory::new();
+        factory.use_global_interceptors(Arc::new(HandlerHeadersInterceptor));
+
+        let adapter = AxumAdapter::new();
+        let app = factory
+            .create(ReportModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/reports", port);
+            let get = |path: &str, role: &str| {
+                client
+                    .get(format!("{}{}", base, path))
+                    .header("X-Role", role)
+            };
+
+            // Test 1: controller-level roles apply to every method
+            let response = get("/", "auditor").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["x-handler"], "ReportController::list");
+            assert_eq!(response.headers()["x-scopes"], "reports:read");
+
+            let response = get("/", "guest").send().await.unwrap();
+            assert_eq!(response.status(), 403);
+
+            // Test 2: method-level roles override the controller's
+            let response = get("/purge", "auditor").send().await.unwrap();
+            assert_eq!(response.status(), 403);
+
+            let response = get("/purge", "admin").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["x-handler"], "ReportController::purge");
+            // Merged: controller scopes first, then method scopes
+            assert_eq!(
+                response.headers()["x-scopes"],
+                "reports:read,reports:delete"
+            );
+
+            // Test 3: arbitrary metadata values
+            let response = get("/summary", "guest").send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "summary");
+        })
+        .await;
+}
@@ -0,0 +1,266 @@
+//! Test problem+json rejections of the built-in extractors and restyling them
+//! with a global exception filter
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::Duration;
+use toni::{
+    controller, controller_struct,
+    extractors::{Body, Header, Json, JsonError, Query},
+    get,
+    http_helpers::{Authorization, Exception, HttpRequest, HttpResponse, IntoResponse, StatusCode},
+    module, post,
+    traits_helpers::ExceptionFilter,
+    Body as ToniBody, FromRequest, HttpAdapter,
+};
+use toni_axum::AxumAdapter;
+
+#[derive(Debug, Deserialize)]
+struct CreateUserDto {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    page: u32,
+}
+
+/// Custom extractor whose error is only `Display`
+struct ApiVersion(u32);
+
+impl FromRequest for ApiVersion {
+    type Error = String;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        req.headers
+            .get_str("x-api-version")
+            .and_then(|version| version.parse().ok())
+            .map(ApiVersion)
+            .ok_or_else(|| "Missing or invalid X-Api-Version".to_string())
+    }
+}
+
+#[controller_struct(
+    pub struct UserController;
+)]
+#[controller("/users")]
+impl UserController {
+    #[post("/")]
+    fn create(&self, Json(dto): Json<CreateUserDto>) -> ToniBody {
+        ToniBody::Text(dto.name)
+    }
+
+    #[post("/any")]
+    fn create_any(&self, Body(dto): Body<CreateUserDto>) -> ToniBody {
+        ToniBody::Text(dto.name)
+    }
+
+    #[get("/")]
+    fn list(&self, Query(pagination): Query<Pagination>) -> ToniBody {
+        ToniBody::Text(format!("page {}", pagination.page))
+    }
+
+    #[get("/me")]
+    fn me(&self, Header(auth): Header<Authorization>) -> ToniBody {
+        ToniBody::Text(auth.bearer().unwrap_or_default().to_string())
+    }
+
+    #[get("/version")]
+    fn version(&self, version: ApiVersion) -> ToniBody {
+        ToniBody::Text(format!("v{}", version.0))
+    }
+}
+
+#[module(
+    controllers: [UserController],
+    providers: [],
+)]
+impl UserModule {}
+
+/// Restyles every rejection, pointing `instance` at the request path
+struct ProblemInstanceFilter;
+
+impl ExceptionFilter for ProblemInstanceFilter {
+    fn catch(&self, exception: &Exception, req: &HttpRequest) -> Option<HttpResponse> {
+        let problem = exception.problem()?;
+        Some(problem.with_instance(req.uri.clone()).to_response())
+    }
+}
+
+/// Handles JSON rejections only, with a body of its own
+struct JsonRejectionFilter;
+
+impl ExceptionFilter for JsonRejectionFilter {
+    fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+        let error = exception.downcast_ref::<JsonError>()?;
+        Some(
+            (
+                StatusCode::BAD_REQUEST,
+                ToniBody::Json(json!({ "invalidJson": error.to_string() })),
+            )
+                .to_response(),
+        )
+    }
+}
+
+fn spawn_server(local: &tokio::task::LocalSet, port: u16, filters: bool) {
+    use toni::toni_factory::ToniFactory;
+
+    local.spawn_local(async move {
+        let mut factory = ToniFactory::new();
+        if filters {
+            factory.use_global_filters(Arc::new(ProblemInstanceFilter));
+            factory.use_global_filters(Arc::new(JsonRejectionFilter));
+        }
+        let adapter = AxumAdapter::new();
+        let app = factory
+            .create(UserModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+}
+
+async fn problem(response: reqwest::Response, status: u16) -> Value {
+    assert_eq!(response.status(), status);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["type"], "about:blank");
+    assert_eq!(body["status"], status);
+    body
+}
+
+#[tokio::test]
+#[serial]
+async fn test_problem_details() {
+    let port = 29420;
+    let local = tokio::task::LocalSet::new();
+    spawn_server(&local, port, false);
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/users", port);
+
+            // Test 1: a body that is not JSON is a 415
+            let response = client
+                .post(format!("{}/", base))
+                .header("content-type", "text/plain")
+                .body("alice")
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 415).await;
+            assert_eq!(body["title"], "Unsupported Media Type");
+            assert_eq!(body["detail"], "Request body is not JSON");
+
+            // Test 2: JSON that does not fit the DTO is a 422
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({ "nickname": "alice" }))
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 422).await;
+            assert_eq!(body["title"], "Unprocessable Entity");
+            assert!(body["detail"]
+                .as_str()
+                .unwrap()
+                .contains("missing field `name`"));
+
+            // Test 3: Body rejects unknown content types with a 415
+            let response = client
+                .post(format!("{}/any", base))
+                .header("content-type", "application/xml")
+                .body("<name>alice</name>")
+                .send()
+                .await
+                .unwrap();
+            problem(response, 415).await;
+
+            // Test 4: bad query strings and missing headers are 400s
+            let response = client
+                .get(format!("{}/?page=first", base))
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 400).await;
+            assert_eq!(body["title"], "Bad Request");
+
+            let response = client.get(format!("{}/me", base)).send().await.unwrap();
+            let body = problem(response, 400).await;
+            assert_eq!(body["detail"], "Missing header 'authorization'");
+
+            // Test 5: custom extractors default to a 400 problem
+            let response = client
+                .get(format!("{}/version", base))
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 400).await;
+            assert_eq!(body["detail"], "Missing or invalid X-Api-Version");
+
+            let response = client
+                .get(format!("{}/version", base))
+                .header("x-api-version", "2")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap(), "v2");
+        })
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rejection_filters() {
+    let port = 29421;
+    let local = tokio::task::LocalSet::new();
+    spawn_server(&local, port, true);
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/users", port);
+
+            // Test 1: the filter registered last handles JSON rejections
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({}))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 400);
+            let body: Value = response.json().await.unwrap();
+            assert!(body["invalidJson"]
+                .as_str()
+                .unwrap()
+                .contains("missing field `name`"));
+
+            // Test 2: every other rejection is restyled through its problem
+            let response = client
+                .get(format!("{}/?page=first", base))
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 400).await;
+            assert_eq!(body["instance"], "/users/?page=first");
+
+            let response = client
+                .get(format!("{}/version", base))
+                .send()
+                .await
+                .unwrap();
+            let body = problem(response, 400).await;
+            assert_eq!(body["instance"], "/users/version");
+        })
+        .await;
+}
@@ -0,0 +1,176 @@
+//! Test that the Axum and Actix adapters decode query strings and bodies the same way
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use toni::{
+    controller, controller_struct,
+    extractors::{Form, Json, Query},
+    get, module, post, Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+#[derive(Serialize, Deserialize)]
+struct Filter {
+    status: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Search {
+    q: String,
+    tag: Vec<String>,
+    page: Option<u32>,
+    filter: Filter,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Signup {
+    name: String,
+    roles: Vec<String>,
+}
+
+#[controller_struct(
+    pub struct DecodingController;
+)]
+#[controller("/decoding")]
+impl DecodingController {
+    #[get("/search")]
+    fn search(&self, Query(search): Query<Search>) -> ToniBody {
+        ToniBody::Json(serde_json::to_value(search).unwrap())
+    }
+
+    #[get("/raw")]
+    fn raw(&self, req: HttpRequest) -> ToniBody {
+        ToniBody::Json(json!({
+            "query": req.query_string(),
+            "tags": req.query_all("tag"),
+            "last": req.query_params.get("tag"),
+        }))
+    }
+
+    #[post("/body")]
+    fn body(&self, req: HttpRequest) -> ToniBody {
+        let kind = match req.body {
+            ToniBody::Json(_) => "json",
+            ToniBody::Text(_) => "text",
+            ToniBody::Binary(_) => "binary",
+        };
+        ToniBody::Text(kind.to_string())
+    }
+
+    #[post("/json")]
+    fn json(&self, Json(value): Json<Value>) -> ToniBody {
+        ToniBody::Json(value)
+    }
+
+    #[post("/signup")]
+    fn signup(&self, Form(signup): Form<Signup>) -> ToniBody {
+        ToniBody::Json(serde_json::to_value(signup).unwrap())
+    }
+}
+
+#[module(
+    controllers: [DecodingController],
+    providers: [],
+)]
+impl DecodingModule {}
+
+async fn check_decoding(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}/decoding", port);
+
+    // Test 1: percent-decoded, repeated and nested query parameters
+    let response = client
+        .get(format!(
+            "{}/search?q=caf%C3%A9+au+lait&tag=a&tag=b%26c&page=2&filter[status]=open",
+            base
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "q": "café au lait",
+            "tag": ["a", "b&c"],
+            "page": 2,
+            "filter": { "status": "open" },
+        })
+    );
+
+    // Test 2: a name sent once still fills a list
+    let response = client
+        .get(format!(
+            "{}/search?q=x&tag=solo&filter%5Bstatus%5D=done",
+            base
+        ))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["tag"], json!(["solo"]));
+    assert_eq!(body["page"], Value::Null);
+
+    // Test 3: the raw query string and every value stay available
+    let response = client
+        .get(format!("{}/raw?tag=a&tag=b%20c", base))
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!({ "query": "tag=a&tag=b%20c", "tags": ["a", "b c"], "last": "b c" })
+    );
+
+    // Test 4: only bodies declared as JSON (or untyped) are parsed as JSON
+    for (content_type, kind) in [
+        (Some("application/json"), "json"),
+        (Some("application/vnd.api+json"), "json"),
+        (Some("text/plain"), "text"),
+        (None, "json"),
+    ] {
+        let mut request = client.post(format!("{}/body", base)).body(r#"{"a":1}"#);
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+        let response = request.send().await.unwrap();
+        assert_eq!(response.text().await.unwrap(), kind, "{:?}", content_type);
+    }
+
+    // Test 5: malformed JSON is a 400, not "not JSON"
+    let response = client
+        .post(format!("{}/json", base))
+        .header("content-type", "application/json")
+        .body(r#"{"a":"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["detail"]
+        .as_str()
+        .unwrap()
+        .starts_with("Malformed JSON body"));
+
+    // Test 6: urlencoded forms decode like query strings
+    let response = client
+        .post(format!("{}/signup", base))
+        .header("content-type", "application/x-www-form-urlencoded")
+        .body("name=Ana+Lima&roles=admin&roles=dev")
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!({ "name": "Ana Lima", "roles": ["admin", "dev"] })
+    );
+}
+
+#[actix_rt::test]
+async fn test_request_decoding() {
+    common::run_on_both_adapters(DecodingModule::module_definition, 29450, check_decoding).await;
+}
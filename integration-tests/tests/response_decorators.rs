@@ -0,0 +1,118 @@
+//! Test `#[http_code]`, `#[header]` and `#[redirect]` on handler methods
+
+use toni::{
+    controller, controller_struct, get, header, http_code,
+    http_helpers::{HttpException, IntoResponse, Redirect},
+    module, post, redirect, Body as ToniBody, HttpRequest, HttpResponse,
+};
+
+mod common;
+
+#[controller_struct(
+    pub struct DocsController;
+)]
+#[controller("/docs")]
+impl DocsController {
+    #[post("/")]
+    #[http_code(201)]
+    #[header("Cache-Control", "no-store")]
+    #[header("X-Docs-Version", "2")]
+    async fn create(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("created".to_string())
+    }
+
+    #[get("/:id")]
+    #[header("Cache-Control", "max-age=60")]
+    fn find(&self, req: HttpRequest) -> Result<String, HttpException> {
+        match req.path_params.get("id").map(String::as_str) {
+            Some("intro") => Ok("intro".to_string()),
+            _ => Err(HttpException::not_found("No such page")),
+        }
+    }
+
+    #[get("/old/guide")]
+    #[redirect("/docs/guide", 301)]
+    fn old_guide(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("unused".to_string())
+    }
+
+    // The handler's own redirect wins over the attribute
+    #[get("/latest/page")]
+    #[redirect("/docs/intro")]
+    fn latest(&self, req: HttpRequest) -> HttpResponse {
+        match req.query_params.get("version") {
+            Some(version) => Redirect::to(format!("/docs/v{}", version)).to_response(),
+            None => HttpResponse::new(),
+        }
+    }
+}
+
+#[module(
+    controllers: [DocsController],
+    providers: [],
+)]
+impl DocsModule {}
+
+async fn check_response_decorators(port: u16) {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let base = format!("http://127.0.0.1:{}/docs", port);
+
+    // Test 1: status and headers on an async handler
+    let response = client.post(&base).send().await.unwrap();
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.headers()["cache-control"], "no-store");
+    assert_eq!(response.headers()["x-docs-version"], "2");
+    assert_eq!(response.text().await.unwrap(), "created");
+
+    // Test 2: errors are left alone
+    let response = client.get(format!("{}/intro", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["cache-control"], "max-age=60");
+
+    let response = client
+        .get(format!("{}/missing", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert!(response.headers().get("cache-control").is_none());
+
+    // Test 3: redirects
+    let response = client
+        .get(format!("{}/old/guide", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 301);
+    assert_eq!(response.headers()["location"], "/docs/guide");
+    assert_eq!(response.text().await.unwrap(), "");
+
+    let response = client
+        .get(format!("{}/latest/page", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 302);
+    assert_eq!(response.headers()["location"], "/docs/intro");
+
+    let response = client
+        .get(format!("{}/latest/page?version=3", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 303);
+    assert_eq!(response.headers()["location"], "/docs/v3");
+}
+
+#[actix_rt::test]
+async fn test_response_decorators() {
+    common::run_on_both_adapters(
+        DocsModule::module_definition,
+        29490,
+        check_response_decorators,
+    )
+    .await;
+}
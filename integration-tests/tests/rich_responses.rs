@@ -0,0 +1,184 @@
+//! Test IntoResponse for Json<T>, Result, Option, status/header tuples, Redirect and NoContent
+
+use serde::{Deserialize, Serialize};
+use serial_test::serial;
+use toni::{
+    controller, controller_struct, delete,
+    extractors::Json,
+    get,
+    http_helpers::{HeaderMap, IntoResponse, NoContent, Redirect, StatusCode},
+    module, post, Body as ToniBody, HttpAdapter, HttpRequest, HttpResponse,
+};
+use toni_axum::AxumAdapter;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug)]
+enum ApiError {
+    InvalidId(String),
+}
+
+impl From<std::num::ParseIntError> for ApiError {
+    fn from(e: std::num::ParseIntError) -> Self {
+        ApiError::InvalidId(e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        match self {
+            ApiError::InvalidId(reason) => (
+                StatusCode::BAD_REQUEST,
+                ToniBody::Json(serde_json::json!({ "error": reason })),
+            )
+                .to_response(),
+        }
+    }
+}
+
+fn find_user(id: u32) -> Option<User> {
+    (id == 1).then(|| User {
+        id,
+        name: "Ada".to_string(),
+    })
+}
+
+#[controller_struct(
+    pub struct UserController;
+)]
+#[controller("/users")]
+impl UserController {
+    #[get("/find/{id}")]
+    fn find(&self, req: HttpRequest) -> Option<Json<User>> {
+        let id = req.path_params.get("id")?.parse().ok()?;
+        find_user(id).map(Json)
+    }
+
+    #[get("/parse/{raw}")]
+    fn parse(&self, req: HttpRequest) -> Result<Json<User>, ApiError> {
+        let id: u32 = req.path_params["raw"].parse()?;
+        Ok(Json(User {
+            id,
+            name: "Parsed".to_string(),
+        }))
+    }
+
+    #[post("/create")]
+    fn create(&self, Json(user): Json<User>) -> (StatusCode, HeaderMap, Json<User>) {
+        let mut headers = HeaderMap::new();
+        headers.insert("Location", format!("/users/find/{}", user.id));
+        (StatusCode::CREATED, headers, Json(user))
+    }
+
+    #[get("/old")]
+    fn old(&self, _req: HttpRequest) -> Redirect {
+        Redirect::permanent("/users/find/1")
+    }
+
+    #[delete("/remove/{id}")]
+    fn remove(&self, _req: HttpRequest) -> NoContent {
+        NoContent
+    }
+}
+
+#[module(
+    controllers: [UserController],
+    providers: [],
+)]
+impl UserModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_rich_responses() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29340;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(UserModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap();
+            let base = format!("http://127.0.0.1:{}/users", port);
+
+            // Test 1: Some(Json) is 200 with a JSON body
+            let response = client.get(format!("{}/find/1", base)).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["content-type"], "application/json");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body, serde_json::json!({ "id": 1, "name": "Ada" }));
+
+            // Test 2: None is a 404
+            let response = client.get(format!("{}/find/2", base)).send().await.unwrap();
+            assert_eq!(response.status(), 404);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "Not Found");
+            assert_eq!(body["statusCode"], 404);
+
+            // Test 3: Ok and Err through `?`
+            let response = client
+                .get(format!("{}/parse/7", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["id"], 7);
+
+            let response = client
+                .get(format!("{}/parse/seven", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 400);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["error"], "invalid digit found in string");
+
+            // Test 4: (StatusCode, HeaderMap, Json)
+            let response = client
+                .post(format!("{}/create", base))
+                .json(&serde_json::json!({ "id": 5, "name": "Grace" }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 201);
+            assert_eq!(response.headers()["location"], "/users/find/5");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["name"], "Grace");
+
+            // Test 5: Redirect
+            let response = client.get(format!("{}/old", base)).send().await.unwrap();
+            assert_eq!(response.status(), 308);
+            assert_eq!(response.headers()["location"], "/users/find/1");
+
+            // Test 6: NoContent
+            let response = client
+                .delete(format!("{}/remove/1", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 204);
+            assert_eq!(response.text().await.unwrap(), "");
+        })
+        .await;
+}
@@ -0,0 +1,116 @@
+//! Test the route and handler information exposed on Context
+
+use serial_test::serial;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get,
+    injector::Context,
+    module, post,
+    traits_helpers::{Interceptor, InterceptorNext},
+    Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+/// One line per request, keyed on the route template rather than the URI
+#[derive(Clone, Default)]
+struct Metrics {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+struct MetricsInterceptor {
+    metrics: Metrics,
+}
+
+#[async_trait]
+impl Interceptor for MetricsInterceptor {
+    async fn intercept(&self, context: &mut Context, next: Box<dyn InterceptorNext>) {
+        next.run(context).await;
+
+        let handler = context.handler().unwrap();
+        assert!(context.elapsed() >= Duration::from_millis(20));
+        self.metrics.lines.lock().unwrap().push(format!(
+            "{} {} {}::{} ({})",
+            handler.method().as_str(),
+            handler.route(),
+            handler.controller(),
+            handler.handler(),
+            handler.module(),
+        ));
+    }
+}
+
+#[controller_struct(
+    pub struct ItemController;
+)]
+#[controller("/items")]
+impl ItemController {
+    #[get("/{id}")]
+    async fn find_one(&self, req: HttpRequest) -> ToniBody {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        ToniBody::Text(req.path_params["id"].clone())
+    }
+
+    #[post("/")]
+    async fn create(&self, _req: HttpRequest) -> ToniBody {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        ToniBody::Text("created".to_string())
+    }
+}
+
+#[module(
+    controllers: [ItemController],
+    providers: [],
+)]
+impl ItemModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_route_context() {
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29390;
+    let metrics = Metrics::default();
+    let local = tokio::task::LocalSet::new();
+
+    let server_metrics = metrics.clone();
+    local.spawn_local(async move {
+        let mut factory = ToniFactory::new();
+        factory.use_global_interceptors(Arc::new(MetricsInterceptor {
+            metrics: server_metrics,
+        }));
+
+        let adapter = AxumAdapter::new();
+        let app = factory
+            .create(ItemModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/items", port);
+
+            for id in ["1", "2"] {
+                let response = client.get(format!("{}/{}", base, id)).send().await.unwrap();
+                assert_eq!(response.text().await.unwrap(), id);
+            }
+            let response = client.post(format!("{}/", base)).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+
+            let lines = metrics.lines.lock().unwrap().clone();
+            assert_eq!(
+                lines,
+                [
+                    "GET /items/{id} ItemController::find_one (ItemModule)",
+                    "GET /items/{id} ItemController::find_one (ItemModule)",
+                    "POST /items/ ItemController::create (ItemModule)",
+                ]
+            );
+        })
+        .await;
+}
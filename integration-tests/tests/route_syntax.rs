@@ -0,0 +1,134 @@
+//! Test that one route syntax behaves the same on the Axum and Actix adapters
+
+use toni::{
+    controller, controller_struct, extractors::Path, get, module, Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+#[controller_struct(
+    pub struct CatalogController;
+)]
+#[controller("/catalog")]
+impl CatalogController {
+    #[get("/")]
+    fn index(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("index".to_string())
+    }
+
+    #[get("/items/:id(int)")]
+    fn item(&self, Path(id): Path<i64>) -> ToniBody {
+        ToniBody::Text(format!("item {}", id))
+    }
+
+    #[get("/users/{id(uuid)}")]
+    fn user(&self, Path(id): Path<String>) -> ToniBody {
+        ToniBody::Text(format!("user {}", id))
+    }
+
+    #[get("/archive/:year(int)?/:month?")]
+    fn archive(&self, req: HttpRequest) -> ToniBody {
+        let param = |name: &str| req.path_params.get(name).cloned().unwrap_or("-".into());
+        ToniBody::Text(format!("{}/{}", param("year"), param("month")))
+    }
+
+    #[get("/tags/:name")]
+    fn tag(&self, Path(name): Path<String>) -> ToniBody {
+        ToniBody::Text(format!("tag {}", name))
+    }
+
+    // Matched before `/tags/:name` whatever the registration order
+    #[get("/tags/popular")]
+    fn popular_tags(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("popular tags".to_string())
+    }
+
+    #[get("/products/:id(int)")]
+    fn product(&self, Path(id): Path<i64>) -> ToniBody {
+        ToniBody::Text(format!("product {}", id))
+    }
+
+    // Shares the adapter's path with `/products/:id(int)`, nothing matches both
+    #[get("/products/:slug(alpha)")]
+    fn product_by_slug(&self, req: HttpRequest) -> ToniBody {
+        ToniBody::Text(format!("product '{}'", req.path_params["slug"]))
+    }
+
+    #[get("/files/*path")]
+    fn file(&self, Path(path): Path<String>) -> ToniBody {
+        ToniBody::Text(path)
+    }
+}
+
+#[module(
+    controllers: [CatalogController],
+    providers: [],
+)]
+impl CatalogModule {}
+
+async fn check_route_syntax(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}/catalog", port);
+    let get = |path: &str| client.get(format!("{}{}", base, path)).send();
+    let text = |path: &'static str| {
+        let response = get(path);
+        async move {
+            let response = response.await.unwrap();
+            assert_eq!(response.status(), 200, "GET {}", path);
+            response.text().await.unwrap()
+        }
+    };
+    let status = |path: &'static str| {
+        let response = get(path);
+        async move { response.await.unwrap().status().as_u16() }
+    };
+
+    // Test 1: trailing slashes reach the same route
+    assert_eq!(text("").await, "index");
+    assert_eq!(text("/").await, "index");
+    assert_eq!(text("/items/42").await, "item 42");
+    assert_eq!(text("/items/42/").await, "item 42");
+
+    // Test 2: constraints turn mismatches into 404
+    assert_eq!(text("/items/-7").await, "item -7");
+    assert_eq!(status("/items/abc").await, 404);
+    assert_eq!(
+        text("/users/67e55044-10b1-426f-9247-bb680e5fe0c8").await,
+        "user 67e55044-10b1-426f-9247-bb680e5fe0c8"
+    );
+    assert_eq!(status("/users/42").await, 404);
+
+    // Test 3: optional segments
+    assert_eq!(text("/archive").await, "-/-");
+    assert_eq!(text("/archive/2024").await, "2024/-");
+    assert_eq!(text("/archive/2024/05/").await, "2024/05");
+    assert_eq!(status("/archive/latest").await, 404);
+
+    // Test 4: wildcards capture the rest of the path
+    assert_eq!(text("/files/docs/guide.md").await, "docs/guide.md");
+    assert_eq!(status("/files/").await, 404);
+
+    // Test 5: static segments win over parameters on every adapter
+    assert_eq!(text("/tags/popular").await, "popular tags");
+    assert_eq!(text("/tags/rust").await, "tag rust");
+
+    // Test 6: implicit OPTIONS follows the constraints too
+    let options = |path: &str| {
+        client
+            .request(reqwest::Method::OPTIONS, format!("{}{}", base, path))
+            .send()
+    };
+    assert_eq!(options("/items/42").await.unwrap().status(), 204);
+    assert_eq!(options("/items/abc").await.unwrap().status(), 404);
+
+    // Test 7: disjoint constraints split a path between handlers
+    assert_eq!(text("/products/42").await, "product 42");
+    assert_eq!(text("/products/lamp/").await, "product 'lamp'");
+    assert_eq!(status("/products/lamp-42").await, 404);
+    assert_eq!(options("/products/lamp").await.unwrap().status(), 204);
+}
+
+#[actix_rt::test]
+async fn test_route_syntax() {
+    common::run_on_both_adapters(CatalogModule::module_definition, 29460, check_route_syntax).await;
+}
@@ -0,0 +1,186 @@
+//! Test the HeaderMap on requests/responses and the typed Header<T> extractor
+
+use serial_test::serial;
+use toni::{
+    controller, controller_struct,
+    extractors::Header,
+    get,
+    http_helpers::{AcceptLanguage, Authorization, HttpResponse, IfNoneMatch},
+    module, Body as ToniBody, HttpAdapter, HttpRequest,
+};
+use toni_axum::AxumAdapter;
+
+const CURRENT_ETAG: &str = "\"v2\"";
+
+#[controller_struct(
+    pub struct HeaderController;
+)]
+#[controller("/headers")]
+impl HeaderController {
+    #[get("/echo")]
+    fn echo(&self, req: HttpRequest) -> ToniBody {
+        let forwarded: Vec<_> = req
+            .headers
+            .get_all("x-forwarded-for")
+            .filter_map(|v| v.to_str())
+            .collect();
+        ToniBody::Json(serde_json::json!({
+            "trace": req.header("X-TRACE-ID"),
+            "forwarded": forwarded,
+        }))
+    }
+
+    #[get("/multi")]
+    fn multi(&self, _req: HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.headers.append("Vary", "Accept");
+        response.headers.append("Vary", "Accept-Language");
+        response.headers.insert("Content-Type", "text/csv");
+        response.body = Some(ToniBody::Text("a,b".to_string()));
+        response
+    }
+
+    #[get("/token")]
+    fn token(&self, Header(auth): Header<Authorization>) -> ToniBody {
+        ToniBody::Text(auth.bearer().unwrap_or("not bearer").to_string())
+    }
+
+    #[get("/resource")]
+    fn resource(&self, if_none_match: Option<Header<IfNoneMatch>>) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.headers.insert("ETag", CURRENT_ETAG);
+        match if_none_match {
+            Some(Header(tags)) if tags.matches(CURRENT_ETAG) => response.status = 304,
+            _ => response.body = Some(ToniBody::Text("fresh".to_string())),
+        }
+        response
+    }
+
+    #[get("/greeting")]
+    fn greeting(&self, Header(langs): Header<AcceptLanguage>) -> ToniBody {
+        let text = match langs.negotiate(&["en", "fr", "de"]) {
+            Some("fr") => "Bonjour",
+            Some("de") => "Hallo",
+            _ => "Hello",
+        };
+        ToniBody::Text(text.to_string())
+    }
+}
+
+#[module(
+    controllers: [HeaderController],
+    providers: [],
+)]
+impl HeaderModule {}
+
+#[tokio::test]
+#[serial]
+async fn test_header_map_and_typed_headers() {
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 29330;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = AxumAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(HeaderModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/headers", port);
+
+            // Test 1: case-insensitive lookup and repeated request headers
+            let response = client
+                .get(format!("{}/echo", base))
+                .header("x-trace-id", "abc")
+                .header("X-Forwarded-For", "10.0.0.1")
+                .header("X-Forwarded-For", "10.0.0.2")
+                .send()
+                .await
+                .expect("Failed to echo");
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(body["trace"], "abc");
+            assert_eq!(
+                body["forwarded"],
+                serde_json::json!(["10.0.0.1", "10.0.0.2"])
+            );
+
+            // Test 2: repeated response headers and overridden Content-Type
+            let response = client
+                .get(format!("{}/multi", base))
+                .send()
+                .await
+                .expect("Failed to get multi");
+            let vary: Vec<_> = response
+                .headers()
+                .get_all("vary")
+                .iter()
+                .map(|v| v.to_str().unwrap().to_string())
+                .collect();
+            assert_eq!(vary, vec!["Accept", "Accept-Language"]);
+            assert_eq!(response.headers()["content-type"], "text/csv");
+
+            // Test 3: typed Authorization
+            let response = client
+                .get(format!("{}/token", base))
+                .header("Authorization", "Bearer abc.def")
+                .send()
+                .await
+                .expect("Failed to get token");
+            assert_eq!(response.text().await.unwrap(), "abc.def");
+
+            // Test 4: missing required header is a 400
+            let response = client
+                .get(format!("{}/token", base))
+                .send()
+                .await
+                .expect("Failed to get token");
+            assert_eq!(response.status(), 400);
+            let body: serde_json::Value = response.json().await.unwrap();
+            assert!(body["detail"].as_str().unwrap().contains("authorization"));
+
+            // Test 5: optional If-None-Match
+            let response = client
+                .get(format!("{}/resource", base))
+                .send()
+                .await
+                .expect("Failed to get resource");
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.headers()["etag"], CURRENT_ETAG);
+
+            let response = client
+                .get(format!("{}/resource", base))
+                .header("If-None-Match", "\"v1\", W/\"v2\"")
+                .send()
+                .await
+                .expect("Failed to get resource");
+            assert_eq!(response.status(), 304);
+
+            let response = client
+                .get(format!("{}/resource", base))
+                .header("If-None-Match", "not-quoted")
+                .send()
+                .await
+                .expect("Failed to get resource");
+            assert_eq!(response.status(), 400);
+
+            // Test 6: Accept-Language negotiation
+            let response = client
+                .get(format!("{}/greeting", base))
+                .header("Accept-Language", "es, de;q=0.7, fr;q=0.9")
+                .send()
+                .await
+                .expect("Failed to get greeting");
+            assert_eq!(response.text().await.unwrap(), "Bonjour");
+        })
+        .await;
+}
@@ -0,0 +1,146 @@
+//! Test `#[use_middleware]` on controllers and handler methods, run after the
+//! module middleware
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use toni::async_trait;
+use toni::{
+    controller, controller_struct, get, injectable, middleware, module,
+    traits_helpers::{
+        middleware::{Middleware, MiddlewareResult, Next},
+        MiddlewareConsumer,
+    },
+    use_middleware, Body as ToniBody, HttpRequest,
+};
+
+mod common;
+
+/// Appends `name` to the request's `X-Trace` header
+fn trace(req: &mut HttpRequest, name: &str) {
+    let trace = match req.header("X-Trace") {
+        Some(trace) => format!("{},{}", trace, name),
+        None => name.to_string(),
+    };
+    req.headers.insert("X-Trace", trace);
+}
+
+fn traced(req: &HttpRequest) -> ToniBody {
+    ToniBody::Text(req.header("X-Trace").unwrap_or_default().to_string())
+}
+
+struct ModuleTrace;
+
+#[async_trait]
+impl Middleware for ModuleTrace {
+    async fn handle(&self, mut req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        trace(&mut req, "module");
+        next.run(req).await
+    }
+}
+
+#[derive(Default)]
+pub struct RequestLogger;
+
+#[async_trait]
+impl Middleware for RequestLogger {
+    async fn handle(&self, mut req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        trace(&mut req, "logger");
+        next.run(req).await
+    }
+}
+
+/// Shared by every clone of the provider
+#[injectable(pub struct AuditLog {
+    count: Arc<AtomicUsize>,
+})]
+impl AuditLog {}
+
+#[middleware(pub struct AuditMiddleware {
+    #[inject]
+    log: AuditLog,
+})]
+impl AuditMiddleware {
+    async fn handle(&self, mut req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        trace(&mut req, "audit");
+        let count = self.log.count.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut response = next.run(req).await?;
+        response.headers.insert("X-Audit", count.to_string());
+        Ok(response)
+    }
+}
+
+#[controller_struct(
+    pub struct OrderController;
+)]
+#[use_middleware(RequestLogger)]
+#[controller("/orders")]
+impl OrderController {
+    #[get("/")]
+    fn find_all(&self, req: HttpRequest) -> ToniBody {
+        traced(&req)
+    }
+
+    #[get("/:id")]
+    #[use_middleware(AuditMiddleware)]
+    fn find(&self, req: HttpRequest) -> ToniBody {
+        traced(&req)
+    }
+}
+
+#[controller_struct(
+    pub struct StatusController;
+)]
+#[controller("/status")]
+impl StatusController {
+    #[get("/")]
+    fn status(&self, req: HttpRequest) -> ToniBody {
+        traced(&req)
+    }
+}
+
+#[module(
+    controllers: [OrderController, StatusController],
+    providers: [AuditLog, AuditMiddleware],
+)]
+impl OrderModule {
+    fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
+        consumer.apply_instance(ModuleTrace).for_routes(vec!["/**"]);
+    }
+}
+
+async fn check_use_middleware(port: u16) {
+    let client = reqwest::Client::new();
+    let base = format!("http://127.0.0.1:{}", port);
+
+    // Test 1: controller-level middleware runs after the module's
+    let response = client.get(format!("{}/orders", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "module,logger");
+
+    // Test 2: method-level middleware runs last and can be injected
+    let response = client
+        .get(format!("{}/orders/7", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-audit"], "1");
+    assert_eq!(response.text().await.unwrap(), "module,logger,audit");
+
+    let response = client
+        .get(format!("{}/orders/8", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-audit"], "2");
+
+    // Test 3: other controllers are left alone
+    let response = client.get(format!("{}/status", base)).send().await.unwrap();
+    assert_eq!(response.text().await.unwrap(), "module");
+}
+
+#[actix_rt::test]
+async fn test_use_middleware() {
+    common::run_on_both_adapters(OrderModule::module_definition, 29520, check_use_middleware).await;
+}
@@ -0,0 +1,299 @@
+//! Test ValidationPipe: field-keyed errors for Validated<Json>, Validated<Query>
+//! and Validated<Body>, and the global pipe options
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::Duration;
+use toni::{
+    controller, controller_struct,
+    extractors::{Body, Json, Query, Validated},
+    get, module,
+    pipes::ValidationPipe,
+    post, Body as ToniBody, HttpAdapter,
+};
+use toni_axum::AxumAdapter;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+struct AddressDto {
+    #[validate(length(equal = 5, message = "Zip must have 5 digits"))]
+    zip: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct ItemDto {
+    #[validate(range(min = 1))]
+    quantity: u32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct OrderDto {
+    #[validate(email)]
+    email: String,
+    #[validate(nested)]
+    address: AddressDto,
+    #[validate(nested)]
+    items: Vec<ItemDto>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct SearchDto {
+    #[validate(range(max = 100))]
+    limit: u32,
+    archived: bool,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct LoginDto {
+    #[validate(length(min = 8))]
+    password: String,
+}
+
+#[controller_struct(
+    pub struct OrderController;
+)]
+#[controller("/orders")]
+impl OrderController {
+    #[post("/")]
+    fn create(&self, Validated(Json(order)): Validated<Json<OrderDto>>) -> ToniBody {
+        ToniBody::Text(format!("{} item(s) for {}", order.items.len(), order.email))
+    }
+
+    #[get("/")]
+    fn search(&self, Validated(Query(search)): Validated<Query<SearchDto>>) -> ToniBody {
+        ToniBody::Text(format!(
+            "limit={} archived={}",
+            search.limit, search.archived
+        ))
+    }
+
+    #[post("/login")]
+    fn login(&self, Validated(Body(login)): Validated<Body<LoginDto>>) -> ToniBody {
+        ToniBody::Text(format!("{} chars", login.password.len()))
+    }
+}
+
+#[module(
+    controllers: [OrderController],
+    providers: [],
+)]
+impl OrderModule {}
+
+fn spawn_server(local: &tokio::task::LocalSet, port: u16, pipe: Option<ValidationPipe>) {
+    use toni::toni_factory::ToniFactory;
+
+    local.spawn_local(async move {
+        let mut factory = ToniFactory::new();
+        if let Some(pipe) = pipe {
+            factory.use_global_pipes(Arc::new(pipe));
+        }
+        let adapter = AxumAdapter::new();
+        let app = factory
+            .create(OrderModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+}
+
+async fn field_errors(response: reqwest::Response) -> Value {
+    assert_eq!(response.status(), 400);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["detail"], "Validation failed");
+    body["errors"].clone()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_field_errors() {
+    let port = 29410;
+    let local = tokio::task::LocalSet::new();
+    spawn_server(&local, port, None);
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/orders", port);
+
+            // Test 1: valid order, unknown fields are dropped
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "a@example.com",
+                    "address": { "zip": "12345" },
+                    "items": [{ "quantity": 2 }],
+                    "coupon": "FREE"
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(
+                response.text().await.unwrap(),
+                "1 item(s) for a@example.com"
+            );
+
+            // Test 2: nested and list errors are keyed by path
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "not-an-email",
+                    "address": { "zip": "1" },
+                    "items": [{ "quantity": 2 }, { "quantity": 0 }]
+                }))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["email"][0]["code"], "email");
+            assert_eq!(
+                details["address.zip"][0]["message"],
+                "Zip must have 5 digits"
+            );
+            assert_eq!(details["items[1].quantity"][0]["code"], "range");
+            assert_eq!(details["items[1].quantity"][0]["params"]["min"], 1);
+            assert_eq!(details["items[1].quantity"][0]["params"]["value"], 0);
+
+            // Test 3: type errors and missing fields
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "a@example.com",
+                    "items": [{ "quantity": "two" }]
+                }))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["items[0].quantity"][0]["code"], "type");
+
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({ "email": "a@example.com", "items": [] }))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["address"][0]["code"], "required");
+
+            // Test 4: query strings are coerced to the field types
+            let response = client
+                .get(format!("{}/?limit=20&archived=true", base))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap(), "limit=20 archived=true");
+
+            let response = client
+                .get(format!("{}/?limit=500&archived=false", base))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["limit"][0]["code"], "range");
+
+            let response = client
+                .get(format!("{}/?limit=ten&archived=false", base))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["limit"][0]["code"], "type");
+
+            // Test 5: form bodies go through the same validation
+            let response = client
+                .post(format!("{}/login", base))
+                .form(&[("password", "short")])
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["password"][0]["code"], "length");
+
+            let response = client
+                .post(format!("{}/login", base))
+                .json(&json!({ "password": "long enough" }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap(), "11 chars");
+        })
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_global_validation_pipe() {
+    let port = 29411;
+    let local = tokio::task::LocalSet::new();
+    spawn_server(
+        &local,
+        port,
+        Some(
+            ValidationPipe::new()
+                .forbid_unknown_fields(true)
+                .transform(true)
+                .stop_at_first_error(true),
+        ),
+    );
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let base = format!("http://127.0.0.1:{}/orders", port);
+
+            // Test 1: transform converts JSON strings to numbers
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "a@example.com",
+                    "address": { "zip": "12345" },
+                    "items": [{ "quantity": "3" }]
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(
+                response.text().await.unwrap(),
+                "1 item(s) for a@example.com"
+            );
+
+            // Test 2: unknown fields are rejected
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "a@example.com",
+                    "address": { "zip": "12345", "city": "Paris" },
+                    "items": []
+                }))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details["address.city"][0]["code"], "unknown_field");
+
+            // Test 3: only the first error is reported
+            let response = client
+                .post(format!("{}/", base))
+                .json(&json!({
+                    "email": "not-an-email",
+                    "address": { "zip": "1" },
+                    "items": [{ "quantity": 0 }]
+                }))
+                .send()
+                .await
+                .unwrap();
+            let details = field_errors(response).await;
+            assert_eq!(details.as_object().unwrap().len(), 1);
+        })
+        .await;
+}
@@ -0,0 +1,222 @@
+//! Test for WebSocket gateways
+//!
+//! This test verifies that:
+//! 1. Gateways are built through DI, can inject the shared WsServer and be
+//!    served on a path with parameters
+//! 2. Guards on the gateway run against the handshake request and can be
+//!    providers with injected services
+//! 3. Connection hooks run and can emit to the new client
+//! 4. #[subscribe_message] handlers receive typed payloads and reply
+//! 5. Rooms and broadcasts reach the right clients
+//! 6. A panicking handler answers with an error event and keeps the socket open
+//! 7. Disconnect hooks run when a client goes away
+//!
+//! The same scenario runs against the axum and the actix adapter.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serial_test::serial;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use toni::async_trait;
+use toni::http_helpers::HttpException;
+use toni::injector::Context;
+use toni::traits_helpers::Guard;
+use toni::{
+    gateway_struct, injectable, module, on_connection, on_disconnect, subscribe_message,
+    use_guards, websocket_gateway, WsClient, WsServer,
+};
+
+mod common;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[injectable(pub struct TokenStore {})]
+impl TokenStore {
+    pub fn is_valid(&self, token: &str) -> bool {
+        token == "secret"
+    }
+}
+
+/// Built by DI, checks the handshake token against the store
+#[injectable(pub struct TokenGuard {
+    #[inject]
+    store: TokenStore,
+})]
+impl TokenGuard {}
+
+#[async_trait]
+impl Guard for TokenGuard {
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException> {
+        Ok(context
+            .take_request()
+            .header("x-token")
+            .is_some_and(|token| self.store.is_valid(token)))
+    }
+}
+
+#[injectable(pub struct GreetingService {})]
+impl GreetingService {
+    pub fn greet(&self, id: &str) -> String {
+        format!("welcome {}", id)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ChatMessage {
+    room: String,
+    text: String,
+}
+
+#[derive(Serialize)]
+pub struct Ack {
+    delivered: usize,
+}
+
+#[gateway_struct(pub struct ChatGateway {
+    #[inject]
+    server: WsServer,
+    #[inject]
+    greeting: GreetingService,
+})]
+#[use_guards(TokenGuard)]
+#[websocket_gateway("/ws/:channel")]
+impl ChatGateway {
+    #[on_connection]
+    fn connected(&self, client: WsClient) {
+        client.emit("welcome", self.greeting.greet(client.id()));
+    }
+
+    #[subscribe_message("room.join")]
+    fn join(&self, client: WsClient, room: String) -> bool {
+        self.server.join(client.id(), &room)
+    }
+
+    #[subscribe_message("chat.send")]
+    async fn send(&self, message: ChatMessage) -> Ack {
+        let delivered = self
+            .server
+            .emit_to_room(&message.room, "chat.message", &message.text);
+        Ack { delivered }
+    }
+
+    #[subscribe_message("chat.crash")]
+    fn crash(&self) -> bool {
+        panic!("handler crashed")
+    }
+
+    #[on_disconnect]
+    fn left(&self, client: WsClient) {
+        self.server.emit_to_room("lobby", "left", client.id());
+    }
+}
+
+#[module(
+    providers: [GreetingService, TokenStore, TokenGuard],
+    gateways: [ChatGateway]
+)]
+impl ChatModule {}
+
+async fn connect(port: u16) -> Socket {
+    let mut request = format!("ws://127.0.0.1:{}/ws/general", port)
+        .into_client_request()
+        .unwrap();
+    request
+        .headers_mut()
+        .insert("x-token", "secret".parse().unwrap());
+    let (socket, _) = connect_async(request).await.expect("Failed to connect");
+    socket
+}
+
+async fn send(socket: &mut Socket, event: &str, data: Value) {
+    let frame = json!({ "event": event, "data": data }).to_string();
+    socket.send(Message::Text(frame.into())).await.unwrap();
+}
+
+async fn next_event(socket: &mut Socket) -> Value {
+    loop {
+        match socket.next().await.expect("Socket closed").unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+async fn check_gateway(port: u16) {
+    // Handshake without the token is rejected by the guard
+    let rejected = connect_async(format!("ws://127.0.0.1:{}/ws/general", port)).await;
+    match rejected {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), 403);
+        }
+        other => panic!("Expected handshake rejection, got {:?}", other.is_ok()),
+    }
+
+    let mut alice = connect(port).await;
+    let mut bob = connect(port).await;
+
+    // Connection hook greets each client with its id
+    let welcome = next_event(&mut alice).await;
+    assert_eq!(welcome["event"], "welcome");
+    assert!(welcome["data"].as_str().unwrap().starts_with("welcome ws-"));
+    next_event(&mut bob).await;
+
+    // Only bob joins the room
+    send(&mut bob, "room.join", json!("lobby")).await;
+    let joined = next_event(&mut bob).await;
+    assert_eq!(joined, json!({ "event": "room.join", "data": true }));
+
+    // Alice sends to the room; bob receives it, alice gets the ack
+    send(
+        &mut alice,
+        "chat.send",
+        json!({ "room": "lobby", "text": "hello" }),
+    )
+    .await;
+    let ack = next_event(&mut alice).await;
+    assert_eq!(
+        ack,
+        json!({ "event": "chat.send", "data": { "delivered": 1 } })
+    );
+
+    let message = next_event(&mut bob).await;
+    assert_eq!(message, json!({ "event": "chat.message", "data": "hello" }));
+
+    // Bad payloads and unknown events come back as error events
+    send(&mut alice, "chat.send", json!({ "room": 1 })).await;
+    let error = next_event(&mut alice).await;
+    assert_eq!(error["event"], "error");
+    assert_eq!(error["data"]["event"], "chat.send");
+
+    send(&mut alice, "nope", Value::Null).await;
+    let error = next_event(&mut alice).await;
+    assert_eq!(error["data"]["message"], "Unknown event 'nope'");
+
+    // A panicking handler is reported to the client, the socket stays open
+    let mut carol = connect(port).await;
+    let welcome = next_event(&mut carol).await;
+    let carol_id = welcome["data"].as_str().unwrap()["welcome ".len()..].to_string();
+    send(&mut carol, "chat.crash", Value::Null).await;
+    let error = next_event(&mut carol).await;
+    assert_eq!(error["event"], "error");
+    assert_eq!(error["data"]["event"], "chat.crash");
+
+    // Closing the socket still runs the disconnect hook, which tells the room
+    send(&mut carol, "room.join", json!("lobby")).await;
+    next_event(&mut carol).await;
+    carol.close(None).await.unwrap();
+
+    let left = next_event(&mut bob).await;
+    assert_eq!(left, json!({ "event": "left", "data": carol_id }));
+}
+
+#[actix_rt::test]
+#[serial]
+async fn test_websocket_gateway() {
+    common::run_on_both_adapters(ChatModule::module_definition, 29300, check_gateway).await;
+}
@@ -2,13 +2,15 @@ use anyhow::{Context, Result};
 use std::sync::Arc;
 
 use actix_web::{dev::Server, web, App, HttpServer};
-use toni::{HttpAdapter, HttpMethod, InstanceWrapper, RouteAdapter};
+use toni::{GatewayWrapper, HttpAdapter, HttpMethod, InstanceWrapper, RouteAdapter, RoutePath};
 
-use super::ActixRouteAdapter;
+use super::{ActixRouteAdapter, ActixWebSocket};
 
 #[derive(Clone)]
 pub struct ActixAdapter {
     routes: Arc<std::sync::Mutex<Vec<RouteConfig>>>,
+    gateways: Arc<std::sync::Mutex<Vec<GatewayConfig>>>,
+    fallback: Arc<std::sync::Mutex<Option<Arc<InstanceWrapper>>>>,
 }
 
 struct RouteConfig {
@@ -17,13 +19,28 @@ struct RouteConfig {
     handler: Arc<InstanceWrapper>,
 }
 
+struct GatewayConfig {
+    path: String,
+    gateway: Arc<GatewayWrapper>,
+}
+
 impl HttpAdapter for ActixAdapter {
     fn new() -> Self {
         Self {
             routes: Arc::new(std::sync::Mutex::new(Vec::new())),
+            gateways: Arc::new(std::sync::Mutex::new(Vec::new())),
+            fallback: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
+    /// Actix spells a wildcard as a regex segment
+    fn native_path(route: &RoutePath) -> String {
+        route.render(
+            |name| format!("{{{}}}", name),
+            |name| format!("{{{}:.+}}", name),
+        )
+    }
+
     fn add_route(&mut self, path: &str, method: HttpMethod, handler: Arc<InstanceWrapper>) {
         println!("Adding route: {} {:?}", path, method);
 
@@ -35,9 +52,25 @@ impl HttpAdapter for ActixAdapter {
         });
     }
 
+    fn set_fallback(&mut self, handler: Arc<InstanceWrapper>) {
+        *self.fallback.lock().unwrap() = Some(handler);
+    }
+
+    fn add_websocket_route(&mut self, path: &str, gateway: Arc<GatewayWrapper>) {
+        println!("Adding websocket route: {}", path);
+
+        let mut gateways = self.gateways.lock().unwrap();
+        gateways.push(GatewayConfig {
+            path: path.to_string(),
+            gateway,
+        });
+    }
+
     async fn listen(self, port: u16, hostname: &str) -> Result<()> {
         let addr = format!("{}:{}", hostname, port);
         let routes = self.routes.clone();
+        let gateways = self.gateways.clone();
+        let fallback = self.fallback.clone();
 
         println!("Listening on {}", addr);
 
@@ -53,52 +86,54 @@ impl HttpAdapter for ActixAdapter {
                     HttpMethod::GET => {
                         app = app.route(
                             &path,
-                            web::get().to(move |req: actix_web::HttpRequest, body: web::Bytes| {
-                                let handler = handler.clone();
-                                async move {
-                                    ActixRouteAdapter::handle_request((req, body), handler)
-                                        .await
-                                        .unwrap()
-                                }
-                            }),
+                            web::get().to(
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
+                                    let handler = handler.clone();
+                                    async move {
+                                        ActixRouteAdapter::handle_request((req, body), handler)
+                                            .await
+                                    }
+                                },
+                            ),
                         );
                     }
                     HttpMethod::POST => {
                         app = app.route(
                             &path,
-                            web::post().to(move |req: actix_web::HttpRequest, body: web::Bytes| {
-                                let handler = handler.clone();
-                                async move {
-                                    ActixRouteAdapter::handle_request((req, body), handler)
-                                        .await
-                                        .unwrap()
-                                }
-                            }),
+                            web::post().to(
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
+                                    let handler = handler.clone();
+                                    async move {
+                                        ActixRouteAdapter::handle_request((req, body), handler)
+                                            .await
+                                    }
+                                },
+                            ),
                         );
                     }
                     HttpMethod::PUT => {
                         app = app.route(
                             &path,
-                            web::put().to(move |req: actix_web::HttpRequest, body: web::Bytes| {
-                                let handler = handler.clone();
-                                async move {
-                                    ActixRouteAdapter::handle_request((req, body), handler)
-                                        .await
-                                        .unwrap()
-                                }
-                            }),
+                            web::put().to(
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
+                                    let handler = handler.clone();
+                                    async move {
+                                        ActixRouteAdapter::handle_request((req, body), handler)
+                                            .await
+                                    }
+                                },
+                            ),
                         );
                     }
                     HttpMethod::DELETE => {
                         app = app.route(
                             &path,
                             web::delete().to(
-                                move |req: actix_web::HttpRequest, body: web::Bytes| {
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
                                     let handler = handler.clone();
                                     async move {
                                         ActixRouteAdapter::handle_request((req, body), handler)
                                             .await
-                                            .unwrap()
                                     }
                                 },
                             ),
@@ -108,12 +143,11 @@ impl HttpAdapter for ActixAdapter {
                         app = app.route(
                             &path,
                             web::patch().to(
-                                move |req: actix_web::HttpRequest, body: web::Bytes| {
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
                                     let handler = handler.clone();
                                     async move {
                                         ActixRouteAdapter::handle_request((req, body), handler)
                                             .await
-                                            .unwrap()
                                     }
                                 },
                             ),
@@ -122,26 +156,26 @@ impl HttpAdapter for ActixAdapter {
                     HttpMethod::HEAD => {
                         app = app.route(
                             &path,
-                            web::head().to(move |req: actix_web::HttpRequest, body: web::Bytes| {
-                                let handler = handler.clone();
-                                async move {
-                                    ActixRouteAdapter::handle_request((req, body), handler)
-                                        .await
-                                        .unwrap()
-                                }
-                            }),
+                            web::head().to(
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
+                                    let handler = handler.clone();
+                                    async move {
+                                        ActixRouteAdapter::handle_request((req, body), handler)
+                                            .await
+                                    }
+                                },
+                            ),
                         );
                     }
                     HttpMethod::OPTIONS => {
                         app = app.route(
                             &path,
                             web::route().method(actix_web::http::Method::OPTIONS).to(
-                                move |req: actix_web::HttpRequest, body: web::Bytes| {
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
                                     let handler = handler.clone();
                                     async move {
                                         ActixRouteAdapter::handle_request((req, body), handler)
                                             .await
-                                            .unwrap()
                                     }
                                 },
                             ),
@@ -151,12 +185,11 @@ impl HttpAdapter for ActixAdapter {
                         app = app.route(
                             &path,
                             web::route().method(actix_web::http::Method::TRACE).to(
-                                move |req: actix_web::HttpRequest, body: web::Bytes| {
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
                                     let handler = handler.clone();
                                     async move {
                                         ActixRouteAdapter::handle_request((req, body), handler)
                                             .await
-                                            .unwrap()
                                     }
                                 },
                             ),
@@ -166,12 +199,11 @@ impl HttpAdapter for ActixAdapter {
                         app = app.route(
                             &path,
                             web::route().method(actix_web::http::Method::CONNECT).to(
-                                move |req: actix_web::HttpRequest, body: web::Bytes| {
+                                move |req: actix_web::HttpRequest, body: web::Payload| {
                                     let handler = handler.clone();
                                     async move {
                                         ActixRouteAdapter::handle_request((req, body), handler)
                                             .await
-                                            .unwrap()
                                     }
                                 },
                             ),
@@ -180,6 +212,29 @@ impl HttpAdapter for ActixAdapter {
                 }
             }
 
+            let gateways_guard = gateways.lock().unwrap();
+
+            for gateway_config in gateways_guard.iter() {
+                let gateway = gateway_config.gateway.clone();
+                app = app.route(
+                    &gateway_config.path,
+                    web::get().to(move |req: actix_web::HttpRequest, body: web::Payload| {
+                        let gateway = gateway.clone();
+                        async move { ActixWebSocket::upgrade(req, body, gateway).await }
+                    }),
+                );
+            }
+
+            // Unknown paths and unknown methods both end up here
+            if let Some(handler) = fallback.lock().unwrap().clone() {
+                app = app.default_service(web::to(
+                    move |req: actix_web::HttpRequest, body: web::Payload| {
+                        let handler = handler.clone();
+                        async move { ActixRouteAdapter::handle_request((req, body), handler).await }
+                    },
+                ));
+            }
+
             app
         })
         .bind(&addr)
@@ -1,26 +1,24 @@
 use std::collections::HashMap;
 
-use actix_web::{web::Bytes, HttpRequest as ActixHttpRequest, HttpResponse as ActixHttpResponse};
+use actix_web::{
+    http::header::{HeaderName, HeaderValue},
+    web::{Bytes, Payload},
+    HttpRequest as ActixHttpRequest, HttpResponse as ActixHttpResponse,
+};
 use anyhow::{anyhow, Result};
-use serde_json::Value;
 
-use toni::{http_helpers::Extensions, Body, HttpRequest, HttpResponse, IntoResponse, RouteAdapter};
+use futures_util::StreamExt;
+use toni::{
+    Body, BodyStream, HttpRequest, HttpResponse, IntoResponse, PayloadTooLarge, RouteAdapter,
+};
 
 pub struct ActixRouteAdapter;
 
 impl ActixRouteAdapter {
-    async fn adapt_actix_request(req: ActixHttpRequest, body: Bytes) -> Result<HttpRequest> {
-        // Parse body
-        let body = if let Ok(body_str) = String::from_utf8(body.to_vec()) {
-            if let Ok(json) = serde_json::from_str::<Value>(&body_str) {
-                Body::Json(json)
-            } else {
-                Body::Text(body_str)
-            }
-        } else {
-            Body::Text(String::from_utf8_lossy(&body).to_string())
-        };
-
+    pub(crate) async fn adapt_actix_request(
+        req: ActixHttpRequest,
+        body: Bytes,
+    ) -> Result<HttpRequest> {
         // Extract path parameters
         let path_params: HashMap<String, String> = req
             .match_info()
@@ -28,34 +26,20 @@ impl ActixRouteAdapter {
             .map(|(k, v)| (k.to_string(), v.to_string()))
             .collect();
 
-        // Extract query parameters
-        let query_params: HashMap<String, String> = req
-            .query_string()
-            .split('&')
-            .filter_map(|pair| {
-                let mut parts = pair.split('=');
-                let key = parts.next()?;
-                let value = parts.next().unwrap_or("");
-                Some((key.to_string(), value.to_string()))
-            })
-            .collect();
-
         // Extract headers
-        let headers: Vec<(String, String)> = req
+        let headers = req
             .headers()
             .iter()
-            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+            .map(|(name, value)| (name.as_str(), value.as_bytes().to_vec()))
             .collect();
 
-        Ok(HttpRequest {
-            body,
+        Ok(HttpRequest::from_parts(
+            req.method().as_str(),
+            req.uri().to_string(),
             headers,
-            method: req.method().to_string(),
-            uri: req.uri().to_string(),
-            query_params,
             path_params,
-            extensions: Extensions::new(),
-        })
+            body.to_vec(),
+        ))
     }
 
     fn adapt_actix_response(
@@ -78,18 +62,34 @@ impl ActixRouteAdapter {
                     .content_type("application/json")
                     .body(json_str)
             }
+            Some(Body::Binary(bytes)) => actix_response
+                .content_type("application/octet-stream")
+                .body(bytes),
             None => actix_response.finish(),
         };
 
-        // Set headers
+        // Set headers: the first value replaces defaults, repeated names (e.g. Set-Cookie) are appended
         let mut actix_response = actix_response;
+        let mut seen = Vec::new();
         for (key, value) in response.headers {
-            actix_response.headers_mut().insert(
-                actix_web::http::header::HeaderName::from_bytes(key.as_bytes())
-                    .map_err(|e| anyhow!("Failed to parse header name: {}", e))?,
-                actix_web::http::header::HeaderValue::from_str(&value)
-                    .map_err(|e| anyhow!("Failed to parse header value: {}", e))?,
-            );
+            let header = HeaderName::from_bytes(key.as_bytes()).map_err(|e| e.to_string());
+            let header = header.and_then(|name| {
+                let value = HeaderValue::from_bytes(value.as_bytes()).map_err(|e| e.to_string())?;
+                Ok((name, value))
+            });
+            let (name, value) = match header {
+                Ok(header) => header,
+                Err(e) => {
+                    eprintln!("❌ Dropping invalid response header '{}': {}", key, e);
+                    continue;
+                }
+            };
+            if seen.contains(&name) {
+                actix_response.headers_mut().append(name, value);
+            } else {
+                seen.push(name.clone());
+                actix_response.headers_mut().insert(name, value);
+            }
         }
 
         Ok(actix_response)
@@ -97,11 +97,42 @@ impl ActixRouteAdapter {
 }
 
 impl RouteAdapter for ActixRouteAdapter {
-    type Request = (ActixHttpRequest, Bytes);
+    type Request = (ActixHttpRequest, Payload);
     type Response = ActixHttpResponse;
 
-    async fn adapt_request(request: Self::Request) -> Result<HttpRequest> {
-        Self::adapt_actix_request(request.0, request.1).await
+    async fn adapt_request(
+        request: Self::Request,
+        body_limit: Option<usize>,
+        stream_body: bool,
+    ) -> Result<HttpRequest> {
+        let (req, mut payload) = request;
+        if stream_body {
+            // The payload can't leave the worker thread, so it is read on a local task
+            let (mut sender, stream) = BodyStream::channel(body_limit);
+            actix_web::rt::spawn(async move {
+                while let Some(chunk) = payload.next().await {
+                    match chunk {
+                        Ok(chunk) => {
+                            if !sender.send(chunk.to_vec()).await {
+                                return;
+                            }
+                        }
+                        Err(e) => return sender.fail(e.to_string()).await,
+                    }
+                }
+            });
+            let mut request = Self::adapt_actix_request(req, Bytes::new()).await?;
+            request.extensions.insert(stream);
+            return Ok(request);
+        }
+
+        let limit = body_limit.unwrap_or(usize::MAX);
+        let body = payload
+            .to_bytes_limited(limit)
+            .await
+            .map_err(|_| PayloadTooLarge { limit })?
+            .map_err(|e| anyhow!("Failed to read request body: {}", e))?;
+        Self::adapt_actix_request(req, body).await
     }
 
     fn adapt_response(
@@ -109,4 +140,8 @@ impl RouteAdapter for ActixRouteAdapter {
     ) -> Result<Self::Response> {
         Self::adapt_actix_response(response)
     }
+
+    fn server_error() -> Self::Response {
+        ActixHttpResponse::InternalServerError().finish()
+    }
 }
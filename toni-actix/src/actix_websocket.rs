@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use actix_web::{
+    error::{ErrorBadRequest, ErrorInternalServerError},
+    web::{Bytes, Payload},
+    Error, HttpRequest as ActixHttpRequest, HttpResponse as ActixHttpResponse,
+};
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, Session};
+use toni::{GatewayWrapper, HttpRequest, RouteAdapter, WsMessage};
+
+use super::ActixRouteAdapter;
+
+pub struct ActixWebSocket;
+
+impl ActixWebSocket {
+    /// Runs the gateway guards on the handshake and upgrades if they pass
+    pub async fn upgrade(
+        req: ActixHttpRequest,
+        body: Payload,
+        gateway: Arc<GatewayWrapper>,
+    ) -> Result<ActixHttpResponse, Error> {
+        let http_request = ActixRouteAdapter::adapt_actix_request(req.clone(), Bytes::new())
+            .await
+            .map_err(ErrorBadRequest)?;
+
+        if let Err(rejection) = gateway.authorize(&http_request).await {
+            return ActixRouteAdapter::adapt_response(Box::new(rejection))
+                .map_err(ErrorInternalServerError);
+        }
+
+        let (response, session, stream) = actix_ws::handle(&req, body)?;
+        actix_web::rt::spawn(Self::serve(
+            session,
+            stream.aggregate_continuations(),
+            gateway,
+            http_request,
+        ));
+
+        Ok(response)
+    }
+
+    async fn serve(
+        mut session: Session,
+        mut stream: AggregatedMessageStream,
+        gateway: Arc<GatewayWrapper>,
+        req: HttpRequest,
+    ) {
+        let (client, mut outgoing) = gateway.connect(&req).await;
+
+        loop {
+            tokio::select! {
+                incoming = stream.recv() => {
+                    let message = match incoming {
+                        Some(Ok(AggregatedMessage::Text(text))) => WsMessage::Text(text.to_string()),
+                        Some(Ok(AggregatedMessage::Binary(bytes))) => WsMessage::Binary(bytes.to_vec()),
+                        Some(Ok(AggregatedMessage::Ping(bytes))) => {
+                            if session.pong(&bytes).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                        Some(Ok(AggregatedMessage::Pong(_))) => continue,
+                        Some(Ok(AggregatedMessage::Close(_))) | Some(Err(_)) | None => break,
+                    };
+                    gateway.handle_message(&client, message).await;
+                }
+                queued = outgoing.recv() => {
+                    let sent = match queued {
+                        Some(WsMessage::Text(text)) => session.text(text).await,
+                        Some(WsMessage::Binary(bytes)) => session.binary(bytes).await,
+                        Some(WsMessage::Close) | None => break,
+                    };
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        gateway.disconnect(&client).await;
+        let _ = session.close(None).await;
+    }
+}
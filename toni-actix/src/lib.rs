@@ -22,9 +22,11 @@
 
 mod actix_adapter;
 mod actix_route_adapter;
+mod actix_websocket;
 
 pub use actix_adapter::ActixAdapter;
 pub use actix_route_adapter::ActixRouteAdapter;
+pub use actix_websocket::ActixWebSocket;
 
 // Re-export commonly used types from toni
 pub use toni::{HttpAdapter, RouteAdapter};
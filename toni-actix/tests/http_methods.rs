@@ -0,0 +1,145 @@
+use toni::{
+    all, controller, controller_struct, get, head, module, options, patch, post, route,
+    Body as ToniBody, HttpAdapter, HttpRequest, HttpResponse,
+};
+use toni_actix::ActixAdapter;
+
+#[controller_struct(
+    pub struct ItemController;
+)]
+#[controller("/items")]
+impl ItemController {
+    #[get("/list")]
+    fn list(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("items".to_string())
+    }
+
+    #[patch("/list")]
+    fn patch_list(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("patched".to_string())
+    }
+
+    #[get("/probe")]
+    fn probe(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("probe body".to_string())
+    }
+
+    #[head("/probe")]
+    fn probe_head(&self, _req: HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::new();
+        response.headers.insert("X-Probe", "explicit");
+        response
+    }
+
+    #[post("/custom")]
+    fn custom(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("created".to_string())
+    }
+
+    #[options("/custom")]
+    fn custom_options(&self, _req: HttpRequest) -> ToniBody {
+        ToniBody::Text("custom options".to_string())
+    }
+
+    #[all("/any")]
+    fn any(&self, req: HttpRequest) -> ToniBody {
+        ToniBody::Text(req.method)
+    }
+
+    #[route("/multi", methods = [PUT, DELETE])]
+    fn multi(&self, req: HttpRequest) -> ToniBody {
+        ToniBody::Text(req.method)
+    }
+}
+
+#[module(
+    controllers: [ItemController],
+    providers: [],
+)]
+impl ItemModule {}
+
+#[actix_rt::test]
+async fn test_actix_http_methods() {
+    use reqwest::Method;
+    use std::time::Duration;
+    use toni::toni_factory::ToniFactory;
+
+    let port = 18083;
+    let local = tokio::task::LocalSet::new();
+
+    local.spawn_local(async move {
+        let adapter = ActixAdapter::new();
+        let factory = ToniFactory::new();
+        let app = factory
+            .create(ItemModule::module_definition(), adapter)
+            .await;
+        let _ = app.listen(port, "127.0.0.1").await;
+    });
+
+    local
+        .run_until(async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            let client = reqwest::Client::new();
+            let url = |path: &str| format!("http://127.0.0.1:{}/items{}", port, path);
+
+            // PATCH
+            let response = client.patch(url("/list")).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "patched");
+
+            // HEAD derived from GET: same status, no body
+            let response = client.head(url("/list")).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "");
+
+            // Explicit HEAD wins over the derived one
+            let response = client.head(url("/probe")).send().await.unwrap();
+            assert_eq!(response.headers()["x-probe"], "explicit");
+
+            // OPTIONS answered with Allow
+            let response = client
+                .request(Method::OPTIONS, url("/list"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 204);
+            assert_eq!(response.headers()["allow"], "GET, HEAD, PATCH, OPTIONS");
+
+            // Explicit OPTIONS wins over the derived one
+            let response = client
+                .request(Method::OPTIONS, url("/custom"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            assert_eq!(response.text().await.unwrap(), "custom options");
+
+            // #[all]
+            for method in [Method::GET, Method::POST, Method::DELETE, Method::OPTIONS] {
+                let response = client
+                    .request(method.clone(), url("/any"))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.text().await.unwrap(), method.as_str());
+            }
+
+            // #[route(methods = [...])]
+            for method in [Method::PUT, Method::DELETE] {
+                let response = client
+                    .request(method.clone(), url("/multi"))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.text().await.unwrap(), method.as_str());
+            }
+            let response = client
+                .request(Method::OPTIONS, url("/multi"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.headers()["allow"], "PUT, DELETE, OPTIONS");
+        })
+        .await;
+}
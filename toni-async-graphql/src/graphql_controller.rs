@@ -4,7 +4,9 @@ use async_graphql::{ObjectType, SubscriptionType};
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::sync::Arc;
-use toni::traits_helpers::{Controller, ControllerTrait, Guard, Interceptor, Pipe, ProviderTrait};
+use toni::traits_helpers::{
+    Controller, ControllerTrait, GuardSource, Interceptor, Pipe, ProviderTrait,
+};
 use toni::{Body, FxHashMap, HttpMethod, HttpRequest, HttpResponse, IntoResponse};
 
 /// GraphQL request payload
@@ -154,11 +156,12 @@ where
                                 "message": format!("Invalid GraphQL request: {}", e)
                             }]
                         }))),
-                        headers: vec![("content-type".to_string(), "application/json".to_string())],
+                        headers: vec![("content-type".to_string(), "application/json".to_string())]
+                            .into(),
                     });
                 }
             },
-            Body::Text(text) => match serde_json::from_str(text) {
+            body => match serde_json::from_slice(&body.as_bytes()) {
                 Ok(req) => req,
                 Err(e) => {
                     return Box::new(HttpResponse {
@@ -168,7 +171,8 @@ where
                                 "message": format!("Invalid GraphQL request: {}", e)
                             }]
                         }))),
-                        headers: vec![("content-type".to_string(), "application/json".to_string())],
+                        headers: vec![("content-type".to_string(), "application/json".to_string())]
+                            .into(),
                     });
                 }
             },
@@ -180,7 +184,7 @@ where
                             "message": "Expected JSON body"
                         }]
                     }))),
-                    headers: vec![("content-type".to_string(), "application/json".to_string())],
+                    headers: vec![("content-type".to_string(), "application/json".to_string())].into(),
                 });
             }*/
         };
@@ -208,7 +212,7 @@ where
         Box::new(HttpResponse {
             status: 200,
             body: Some(Body::Json(response_json)),
-            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            headers: vec![("content-type".to_string(), "application/json".to_string())].into(),
         })
     }
 
@@ -220,7 +224,7 @@ where
         HttpMethod::POST
     }
 
-    fn get_guards(&self) -> Vec<Arc<dyn Guard>> {
+    fn get_guards(&self) -> Vec<GuardSource> {
         vec![]
     }
 
@@ -235,8 +239,11 @@ where
     fn get_body_dto(
         &self,
         _req: &HttpRequest,
-    ) -> Option<Box<dyn toni::traits_helpers::validate::Validatable>> {
-        None // GraphQL doesn't use DTO validation (uses GraphQL schema validation)
+    ) -> Result<
+        Option<Box<dyn toni::traits_helpers::validate::Validatable>>,
+        toni::extractors::ValidationError,
+    > {
+        Ok(None) // GraphQL doesn't use DTO validation (uses GraphQL schema validation)
     }
 }
 
@@ -259,7 +266,7 @@ impl ControllerTrait for GraphQLPlaygroundController {
         Box::new(HttpResponse {
             status: 200,
             body: Some(Body::Text(self.playground_html.clone())),
-            headers: vec![("content-type".to_string(), "text/html".to_string())],
+            headers: vec![("content-type".to_string(), "text/html".to_string())].into(),
         })
     }
 
@@ -271,7 +278,7 @@ impl ControllerTrait for GraphQLPlaygroundController {
         HttpMethod::GET
     }
 
-    fn get_guards(&self) -> Vec<Arc<dyn Guard>> {
+    fn get_guards(&self) -> Vec<GuardSource> {
         vec![]
     }
 
@@ -286,7 +293,10 @@ impl ControllerTrait for GraphQLPlaygroundController {
     fn get_body_dto(
         &self,
         _req: &HttpRequest,
-    ) -> Option<Box<dyn toni::traits_helpers::validate::Validatable>> {
-        None // Playground doesn't use DTO validation
+    ) -> Result<
+        Option<Box<dyn toni::traits_helpers::validate::Validatable>>,
+        toni::extractors::ValidationError,
+    > {
+        Ok(None) // Playground doesn't use DTO validation
     }
 }
@@ -4,13 +4,14 @@ use tokio::net::TcpListener;
 
 use axum::{
     body::Body,
+    extract::ws::WebSocketUpgrade,
     http::Request,
     routing::{connect, delete, get, head, options, patch, post, put, trace},
     Router,
 };
-use toni::{HttpAdapter, HttpMethod, InstanceWrapper};
+use toni::{GatewayWrapper, HttpAdapter, HttpMethod, InstanceWrapper};
 
-use super::AxumRouteAdapter;
+use super::{AxumRouteAdapter, AxumWebSocket};
 use toni::RouteAdapter;
 
 #[derive(Clone)]
@@ -28,11 +29,7 @@ impl HttpAdapter for AxumAdapter {
     fn add_route(&mut self, path: &str, method: HttpMethod, handler: Arc<InstanceWrapper>) {
         let route_handler = move |req: Request<Body>| {
             let handler: Arc<InstanceWrapper> = handler.clone();
-            Box::pin(async move {
-                AxumRouteAdapter::handle_request(req, handler)
-                    .await
-                    .unwrap()
-            })
+            Box::pin(async move { AxumRouteAdapter::handle_request(req, handler).await })
         };
         println!("Adding route: {} {:?}", path, method);
 
@@ -49,6 +46,30 @@ impl HttpAdapter for AxumAdapter {
         };
     }
 
+    fn set_fallback(&mut self, handler: Arc<InstanceWrapper>) {
+        let fallback = move |req: Request<Body>| {
+            let handler: Arc<InstanceWrapper> = handler.clone();
+            Box::pin(async move { AxumRouteAdapter::handle_request(req, handler).await })
+        };
+
+        // Axum tells unknown paths from unknown methods, Toni answers both
+        self.instance = self
+            .instance
+            .clone()
+            .fallback(fallback.clone())
+            .method_not_allowed_fallback(fallback);
+    }
+
+    fn add_websocket_route(&mut self, path: &str, gateway: Arc<GatewayWrapper>) {
+        let ws_handler = move |ws: WebSocketUpgrade, req: Request<Body>| {
+            let gateway = gateway.clone();
+            async move { AxumWebSocket::upgrade(ws, req, gateway).await }
+        };
+        println!("Adding websocket route: {}", path);
+
+        self.instance = self.instance.clone().route(path, get(ws_handler));
+    }
+
     async fn listen(self, port: u16, hostname: &str) -> Result<()> {
         let addr = format!("{}:{}", hostname, port);
         let listener: TcpListener = TcpListener::bind(&addr).await?;
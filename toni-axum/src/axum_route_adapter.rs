@@ -2,15 +2,17 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
 use axum::{
-    body::to_bytes,
-    extract::{Path, Query},
+    extract::Path,
     http::{HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse as _,
     RequestPartsExt,
 };
-use serde_json::Value;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
 use std::str::FromStr;
 
-use toni::{http_helpers::Extensions, Body, HttpRequest, HttpResponse, IntoResponse, RouteAdapter};
+use toni::{
+    Body, BodyStream, HttpRequest, HttpResponse, IntoResponse, PayloadTooLarge, RouteAdapter,
+};
 
 pub struct AxumRouteAdapter;
 
@@ -18,19 +20,40 @@ impl RouteAdapter for AxumRouteAdapter {
     type Request = Request<axum::body::Body>;
     type Response = Response<axum::body::Body>;
 
-    async fn adapt_request(request: Self::Request) -> Result<HttpRequest> {
-        let (mut parts, body) = request.into_parts();
-        let body_bytes = to_bytes(body, usize::MAX).await?;
-        let bytes = body_bytes.to_vec();
-
-        let body = if let Ok(body_str) = String::from_utf8(bytes) {
-            if let Ok(json) = serde_json::from_str::<Value>(&body_str) {
-                Body::Json(json)
-            } else {
-                Body::Text(body_str)
-            }
+    async fn adapt_request(
+        request: Self::Request,
+        body_limit: Option<usize>,
+        stream_body: bool,
+    ) -> Result<HttpRequest> {
+        let (mut parts, mut body) = request.into_parts();
+        let mut stream = None;
+        let body_bytes = if stream_body {
+            let (mut sender, body_stream) = BodyStream::channel(body_limit);
+            tokio::spawn(async move {
+                while let Some(frame) = body.frame().await {
+                    match frame {
+                        Ok(frame) => {
+                            if let Ok(data) = frame.into_data() {
+                                if !sender.send(data.to_vec()).await {
+                                    return;
+                                }
+                            }
+                        }
+                        Err(e) => return sender.fail(e.to_string()).await,
+                    }
+                }
+            });
+            stream = Some(body_stream);
+            Default::default()
         } else {
-            Body::Text(String::from_utf8_lossy(&body_bytes).to_string())
+            let limit = body_limit.unwrap_or(usize::MAX);
+            match Limited::new(body, limit).collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(e) if e.is::<LengthLimitError>() => {
+                    return Err(PayloadTooLarge { limit }.into())
+                }
+                Err(e) => return Err(anyhow!("Failed to read request body: {}", e)),
+            }
         };
 
         let Path(path_params) = parts
@@ -38,26 +61,23 @@ impl RouteAdapter for AxumRouteAdapter {
             .await
             .map_err(|e| anyhow!("Failed to extract path parameters: {:?}", e))?;
 
-        let Query(query_params) = parts
-            .extract::<Query<HashMap<String, String>>>()
-            .await
-            .map_err(|e| anyhow!("Failed to extract query parameters: {:?}", e))?;
-
         let headers = parts
             .headers
             .iter()
-            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+            .map(|(name, value)| (name.as_str(), value.as_bytes().to_vec()))
             .collect();
 
-        Ok(HttpRequest {
-            body,
+        let mut request = HttpRequest::from_parts(
+            parts.method.as_str(),
+            parts.uri.to_string(),
             headers,
-            method: parts.method.to_string(),
-            uri: parts.uri.to_string(),
-            query_params,
             path_params,
-            extensions: Extensions::new(),
-        })
+            body_bytes.to_vec(),
+        );
+        if let Some(stream) = stream {
+            request.extensions.insert(stream);
+        }
+        Ok(request)
     }
 
     fn adapt_response(
@@ -68,37 +88,52 @@ impl RouteAdapter for AxumRouteAdapter {
         let status =
             StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
 
-        let mut body_is_json = false;
+        let mut content_type = "text/plain";
 
         let body = match response.body {
             Some(Body::Text(text)) => axum::body::Body::from(text),
             Some(Body::Json(json)) => {
-                body_is_json = true;
+                content_type = "application/json";
                 let vec = serde_json::to_vec(&json)
                     .map_err(|e| anyhow::anyhow!("Failed to serialize JSON: {}", e))?;
                 axum::body::Body::from(vec)
             }
-            _ => axum::body::Body::empty(),
+            Some(Body::Binary(bytes)) => {
+                content_type = "application/octet-stream";
+                axum::body::Body::from(bytes)
+            }
+            None => axum::body::Body::empty(),
         };
 
         let mut headers = HeaderMap::new();
 
-        let content_type = if body_is_json {
-            "application/json"
-        } else {
-            "text/plain"
-        };
         headers.insert(
             HeaderName::from_str("Content-Type")
                 .map_err(|e| anyhow::anyhow!("Failed to parse header name Content-Type: {}", e))?,
             HeaderValue::from_static(content_type),
         );
 
+        // The first value replaces defaults such as Content-Type, repeated names
+        // (e.g. Set-Cookie) are appended
+        let mut seen = Vec::new();
         for (k, v) in &response.headers {
-            if let Ok(header_name) = HeaderName::from_bytes(k.as_bytes()) {
-                if let Ok(header_value) = HeaderValue::from_str(v) {
-                    headers.insert(header_name, header_value);
+            let header = HeaderName::from_bytes(k.as_bytes()).map_err(|e| e.to_string());
+            let header = header.and_then(|name| {
+                let value = HeaderValue::from_bytes(v.as_bytes()).map_err(|e| e.to_string())?;
+                Ok((name, value))
+            });
+            let (header_name, header_value) = match header {
+                Ok(header) => header,
+                Err(e) => {
+                    eprintln!("❌ Dropping invalid response header '{}': {}", k, e);
+                    continue;
                 }
+            };
+            if seen.contains(&header_name) {
+                headers.append(header_name, header_value);
+            } else {
+                seen.push(header_name.clone());
+                headers.insert(header_name, header_value);
             }
         }
 
@@ -111,4 +146,8 @@ impl RouteAdapter for AxumRouteAdapter {
 
         Ok(res)
     }
+
+    fn server_error() -> Self::Response {
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    }
 }
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    http::{Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use toni::{GatewayWrapper, HttpRequest, RouteAdapter, WsMessage};
+
+use super::AxumRouteAdapter;
+
+pub struct AxumWebSocket;
+
+impl AxumWebSocket {
+    /// Runs the gateway guards on the handshake and upgrades if they pass
+    pub async fn upgrade(
+        ws: WebSocketUpgrade,
+        req: Request<Body>,
+        gateway: Arc<GatewayWrapper>,
+    ) -> Response {
+        let http_request = match AxumRouteAdapter::adapt_request(req, None, false).await {
+            Ok(http_request) => http_request,
+            Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+        };
+
+        if let Err(rejection) = gateway.authorize(&http_request).await {
+            return AxumRouteAdapter::adapt_response(Box::new(rejection))
+                .unwrap_or_else(|_| StatusCode::FORBIDDEN.into_response());
+        }
+
+        ws.on_upgrade(move |socket| Self::serve(socket, gateway, http_request))
+    }
+
+    async fn serve(mut socket: WebSocket, gateway: Arc<GatewayWrapper>, req: HttpRequest) {
+        let (client, mut outgoing) = gateway.connect(&req).await;
+
+        loop {
+            tokio::select! {
+                incoming = socket.recv() => {
+                    let message = match incoming {
+                        Some(Ok(Message::Text(text))) => WsMessage::Text(text.to_string()),
+                        Some(Ok(Message::Binary(bytes))) => WsMessage::Binary(bytes.to_vec()),
+                        Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => continue,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    };
+                    gateway.handle_message(&client, message).await;
+                }
+                queued = outgoing.recv() => {
+                    let message = match queued {
+                        Some(WsMessage::Text(text)) => Message::Text(text.into()),
+                        Some(WsMessage::Binary(bytes)) => Message::Binary(bytes.into()),
+                        Some(WsMessage::Close) | None => {
+                            let _ = socket.send(Message::Close(None)).await;
+                            break;
+                        }
+                    };
+                    if socket.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        gateway.disconnect(&client).await;
+    }
+}
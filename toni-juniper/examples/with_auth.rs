@@ -40,12 +40,9 @@ impl User {
 impl _AuthService {
     fn verify_token(&self, req: &HttpRequest) -> Option<User> {
         // In a real app, verify JWT token from headers
-        let auth_header = req
-            .headers
-            .iter()
-            .find(|(k, _)| k.to_lowercase() == "authorization")?;
+        let auth_header = req.header("authorization")?;
 
-        if auth_header.1.starts_with("Bearer valid-token") {
+        if auth_header.starts_with("Bearer valid-token") {
             Some(User {
                 id: 1,
                 username: "john_doe".to_string(),
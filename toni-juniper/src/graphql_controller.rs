@@ -6,7 +6,9 @@ use juniper::{
 };
 use serde::Deserialize;
 use std::sync::Arc;
-use toni::traits_helpers::{Controller, ControllerTrait, Guard, Interceptor, Pipe, ProviderTrait};
+use toni::traits_helpers::{
+    Controller, ControllerTrait, GuardSource, Interceptor, Pipe, ProviderTrait,
+};
 use toni::{Body, FxHashMap, HttpMethod, HttpRequest, HttpResponse, IntoResponse};
 
 /// GraphQL request payload
@@ -241,11 +243,12 @@ where
                                 "message": format!("Invalid GraphQL request: {}", e)
                             }]
                         }))),
-                        headers: vec![("content-type".to_string(), "application/json".to_string())],
+                        headers: vec![("content-type".to_string(), "application/json".to_string())]
+                            .into(),
                     });
                 }
             },
-            Body::Text(text) => match serde_json::from_str(text) {
+            body => match serde_json::from_slice(&body.as_bytes()) {
                 Ok(req) => req,
                 Err(e) => {
                     return Box::new(HttpResponse {
@@ -255,7 +258,8 @@ where
                                 "message": format!("Invalid GraphQL request: {}", e)
                             }]
                         }))),
-                        headers: vec![("content-type".to_string(), "application/json".to_string())],
+                        headers: vec![("content-type".to_string(), "application/json".to_string())]
+                            .into(),
                     });
                 }
             },
@@ -281,7 +285,7 @@ where
         Box::new(HttpResponse {
             status: 200,
             body: Some(Body::Json(response_json)),
-            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            headers: vec![("content-type".to_string(), "application/json".to_string())].into(),
         })
     }
 
@@ -293,7 +297,7 @@ where
         HttpMethod::POST
     }
 
-    fn get_guards(&self) -> Vec<Arc<dyn Guard>> {
+    fn get_guards(&self) -> Vec<GuardSource> {
         vec![]
     }
 
@@ -308,8 +312,11 @@ where
     fn get_body_dto(
         &self,
         _req: &HttpRequest,
-    ) -> Option<Box<dyn toni::traits_helpers::validate::Validatable>> {
-        None // GraphQL doesn't use DTO validation (uses GraphQL schema validation)
+    ) -> Result<
+        Option<Box<dyn toni::traits_helpers::validate::Validatable>>,
+        toni::extractors::ValidationError,
+    > {
+        Ok(None) // GraphQL doesn't use DTO validation (uses GraphQL schema validation)
     }
 }
 
@@ -332,7 +339,7 @@ impl ControllerTrait for GraphQLPlaygroundController {
         Box::new(HttpResponse {
             status: 200,
             body: Some(Body::Text(self.playground_html.clone())),
-            headers: vec![("content-type".to_string(), "text/html".to_string())],
+            headers: vec![("content-type".to_string(), "text/html".to_string())].into(),
         })
     }
 
@@ -344,7 +351,7 @@ impl ControllerTrait for GraphQLPlaygroundController {
         HttpMethod::GET
     }
 
-    fn get_guards(&self) -> Vec<Arc<dyn Guard>> {
+    fn get_guards(&self) -> Vec<GuardSource> {
         vec![]
     }
 
@@ -359,7 +366,10 @@ impl ControllerTrait for GraphQLPlaygroundController {
     fn get_body_dto(
         &self,
         _req: &HttpRequest,
-    ) -> Option<Box<dyn toni::traits_helpers::validate::Validatable>> {
-        None
+    ) -> Result<
+        Option<Box<dyn toni::traits_helpers::validate::Validatable>>,
+        toni::extractors::ValidationError,
+    > {
+        Ok(None)
     }
 }
@@ -1,11 +1,21 @@
 //! Extractor parameter detection and code generation
 //!
-//! Detects extractor types like Path<T>, Query<T>, Json<T>, Validated<T>
-//! and generates FromRequest extraction code.
+//! Detects extractor types like Path<T>, Query<T>, Json<T>, Validated<T>,
+//! Form<T> and Multipart and generates FromRequest extraction code. Form<T>
+//! and other argument types are extracted through `AsyncFromRequest`.
+//! `Path` and `Query` arguments marked `#[pipe(..)]` are read through their
+//! parameter pipes instead.
 
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{FnArg, Ident, ImplItemFn, Result, Type};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Error, Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, MetaNameValue,
+    Result, Token, Type,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+use crate::enhancer::enhancer::enhancer_source;
 
 /// Check if a method has a `self` receiver (i.e., is an instance method)
 pub fn has_self_receiver(method: &ImplItemFn) -> bool {
@@ -25,6 +35,52 @@ pub struct ExtractorParam {
     pub param_type: Type,
     /// The extractor kind
     pub kind: ExtractorKind,
+    /// `#[pipe(..)]` on the parameter
+    pub pipes: Option<ParamPipes>,
+}
+
+/// `#[pipe("name", ParseIntPipe, LoadUserPipe)]`; the optional leading string
+/// names the path or query parameter when it differs from the binding.
+#[derive(Clone)]
+pub struct ParamPipes {
+    pub name: Option<LitStr>,
+    pub pipes: Vec<Expr>,
+}
+
+impl Parse for ParamPipes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = if input.peek(LitStr) {
+            let name = input.parse()?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+            Some(name)
+        } else {
+            None
+        };
+        let pipes = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+        Ok(Self {
+            name,
+            pipes: pipes.into_iter().collect(),
+        })
+    }
+}
+
+fn is_pipe_attribute(attr: &Attribute) -> bool {
+    attr.path().is_ident("pipe")
+}
+
+/// Removes `#[pipe]` from the handler parameters, which are emitted as written
+pub fn remove_pipe_attributes(impl_block: &mut ItemImpl) {
+    for item in &mut impl_block.items {
+        if let ImplItem::Fn(method) = item {
+            for input in &mut method.sig.inputs {
+                if let FnArg::Typed(pat_type) = input {
+                    pat_type.attrs.retain(|attr| !is_pipe_attribute(attr));
+                }
+            }
+        }
+    }
 }
 
 /// The kind of extractor
@@ -40,9 +96,17 @@ pub enum ExtractorKind {
     Body,
     /// Validated<T> extractor
     Validated,
+    /// Form<T> extractor (urlencoded or multipart)
+    Form,
+    /// Multipart extractor
+    Multipart,
+    /// Cookies extractor
+    Cookies,
+    /// Header<T> typed header extractor
+    Header,
     /// HttpRequest (not an extractor, just passed through)
     HttpRequest,
-    /// Unknown type - will be passed as-is
+    /// Any other type, extracted through `AsyncFromRequest`
     Unknown,
 }
 
@@ -67,14 +131,13 @@ fn extract_param_name(pat: &syn::Pat) -> Option<Ident> {
 pub fn get_extractor_params(method: &ImplItemFn) -> Result<Vec<ExtractorParam>> {
     let mut params = Vec::new();
 
-    for input in method.sig.inputs.iter() {
+    for (index, input) in method.sig.inputs.iter().enumerate() {
         if let FnArg::Typed(pat_type) = input {
-            // Get parameter name - recursively extract from nested patterns
-            let param_name = extract_param_name(&pat_type.pat);
-            let param_name = match param_name {
-                Some(name) => name,
-                None => continue,
-            };
+            // Get parameter name - recursively extract from nested patterns.
+            // Tuple and struct patterns such as `Path((org, repo))` bind no
+            // single name, their value is held in a generated one.
+            let param_name = extract_param_name(&pat_type.pat)
+                .unwrap_or_else(|| format_ident!("__arg{}", index));
 
             // Skip `self` parameter
             if param_name == "self" {
@@ -84,10 +147,24 @@ pub fn get_extractor_params(method: &ImplItemFn) -> Result<Vec<ExtractorParam>>
             let param_type = (*pat_type.ty).clone();
             let kind = detect_extractor_kind(&param_type);
 
+            let pipes = match pat_type.attrs.iter().find(|attr| is_pipe_attribute(attr)) {
+                Some(attr) if matches!(kind, ExtractorKind::Path | ExtractorKind::Query) => {
+                    Some(attr.parse_args::<ParamPipes>()?)
+                }
+                Some(attr) => {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "#[pipe] is only supported on Path<T> and Query<T> parameters",
+                    ));
+                }
+                None => None,
+            };
+
             params.push(ExtractorParam {
                 param_name,
                 param_type,
                 kind,
+                pipes,
             });
         }
     }
@@ -95,6 +172,33 @@ pub fn get_extractor_params(method: &ImplItemFn) -> Result<Vec<ExtractorParam>>
     Ok(params)
 }
 
+impl ExtractorParam {
+    /// Whether the extractor may read a multipart or urlencoded form, so the
+    /// route needs a body limit
+    pub fn reads_form(&self) -> bool {
+        match self.kind {
+            ExtractorKind::Form | ExtractorKind::Multipart | ExtractorKind::Body => true,
+            // `Validated<Form<T>>` or `Validated<Body<T>>`
+            ExtractorKind::Validated => match &self.param_type {
+                Type::Path(type_path) => type_path
+                    .path
+                    .segments
+                    .last()
+                    .and_then(option_inner_type)
+                    .map(detect_extractor_kind)
+                    .is_some_and(|kind| matches!(kind, ExtractorKind::Form | ExtractorKind::Body)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the extractor reads the body as the adapter streams it
+    pub fn streams_body(&self) -> bool {
+        matches!(self.kind, ExtractorKind::Form | ExtractorKind::Multipart)
+    }
+}
+
 /// Detect what kind of extractor a type is
 fn detect_extractor_kind(ty: &Type) -> ExtractorKind {
     if let Type::Path(type_path) = ty {
@@ -106,7 +210,16 @@ fn detect_extractor_kind(ty: &Type) -> ExtractorKind {
                 "Json" => ExtractorKind::Json,
                 "Body" => ExtractorKind::Body,
                 "Validated" => ExtractorKind::Validated,
+                "Form" => ExtractorKind::Form,
+                "Multipart" => ExtractorKind::Multipart,
+                "Cookies" => ExtractorKind::Cookies,
+                "Header" => ExtractorKind::Header,
                 "HttpRequest" => ExtractorKind::HttpRequest,
+                // Optional extractors such as `Option<Header<T>>`
+                "Option" => match option_inner_type(segment) {
+                    Some(inner) => detect_extractor_kind(inner),
+                    None => ExtractorKind::Unknown,
+                },
                 _ => ExtractorKind::Unknown,
             };
         }
@@ -114,17 +227,98 @@ fn detect_extractor_kind(ty: &Type) -> ExtractorKind {
     ExtractorKind::Unknown
 }
 
+/// The `T` of an `Option<T>` path segment
+fn option_inner_type(segment: &syn::PathSegment) -> Option<&Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The `PipeSource` of a pipe listed in `#[pipe]`.
+///
+/// A bare type is built with `Default` or resolved from the module's providers
+/// at startup, like guards; any other expression is used as the instance.
+fn pipe_source(pipe: &Expr) -> TokenStream {
+    match pipe {
+        Expr::Path(path) if path.qself.is_none() => enhancer_source(quote! { #path }),
+        _ => quote! {
+            ::toni::traits_helpers::PipeSource::Instance(::std::sync::Arc::new(#pipe))
+        },
+    }
+}
+
+/// The `T` of `Path<T>` or `Query<T>`
+fn extractor_inner_type(ty: &Type) -> Result<&Type> {
+    let inner = match ty {
+        Type::Path(type_path) => type_path.path.segments.last().and_then(option_inner_type),
+        _ => None,
+    };
+    inner.ok_or_else(|| Error::new_spanned(ty, "Expected Path<T> or Query<T>"))
+}
+
+/// Extraction of a `#[pipe]` argument; its pipes are `pipe_index..` in the
+/// handler's parameter pipes.
+fn generate_piped_extraction(
+    param: &ExtractorParam,
+    pipes: &ParamPipes,
+    pipe_index: usize,
+) -> Result<TokenStream> {
+    let param_name = &param.param_name;
+    let inner_type = extractor_inner_type(&param.param_type)?;
+    let name = match &pipes.name {
+        Some(name) => name.value(),
+        None => param_name.to_string(),
+    };
+    let (source, wrapper) = match param.kind {
+        ExtractorKind::Query => (quote! { Query }, quote! { ::toni::extractors::Query }),
+        _ => (quote! { Path }, quote! { ::toni::extractors::Path }),
+    };
+    let pipe_end = pipe_index + pipes.pipes.len();
+
+    Ok(quote! {
+        let #param_name = match ::toni::traits_helpers::apply_param_pipes::<#inner_type>(
+            &req,
+            #pipe_index..#pipe_end,
+            ::toni::traits_helpers::ArgumentMetadata::new(
+                #name,
+                ::toni::traits_helpers::ParamSource::#source,
+            ),
+        )
+        .await
+        {
+            Ok(value) => #wrapper(value),
+            Err(exception) => return Box::new(exception),
+        };
+    })
+}
+
 /// Generate extraction code for extractor parameters
+///
+/// Returns the extractions, the handler call arguments and the `PipeSource`s
+/// of every `#[pipe]` argument.
 pub fn generate_extractor_extractions(
     params: &[ExtractorParam],
-) -> Result<(Vec<TokenStream>, Vec<TokenStream>)> {
+) -> Result<(Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>)> {
     let mut extractions = Vec::new();
     let mut call_args = Vec::new();
+    let mut param_pipes = Vec::new();
+    let mut module_ref_read = false;
 
     for param in params {
         let param_name = &param.param_name;
         let param_type = &param.param_type;
 
+        if let Some(pipes) = &param.pipes {
+            extractions.push(generate_piped_extraction(param, pipes, param_pipes.len())?);
+            param_pipes.extend(pipes.pipes.iter().map(pipe_source));
+            call_args.push(quote! { #param_name });
+            continue;
+        }
+
         match param.kind {
             ExtractorKind::HttpRequest => {
                 // Just pass req directly
@@ -134,43 +328,36 @@ pub fn generate_extractor_extractions(
             | ExtractorKind::Query
             | ExtractorKind::Json
             | ExtractorKind::Body
-            | ExtractorKind::Validated => {
+            | ExtractorKind::Validated
+            | ExtractorKind::Multipart
+            | ExtractorKind::Cookies
+            | ExtractorKind::Header => {
                 // Generate extraction code
                 let extraction = quote! {
                     let #param_name = match <#param_type as ::toni::FromRequest>::from_request(&req) {
                         Ok(value) => value,
-                        Err(e) => {
-                            let error_body = ::serde_json::json!({
-                                "error": "Extraction failed",
-                                "details": e.to_string()
-                            });
-                            return Box::new(::toni::http_helpers::HttpResponse {
-                                body: Some(::toni::http_helpers::Body::Json(error_body)),
-                                status: 400,
-                                headers: vec![],
-                            });
-                        }
+                        Err(e) => return <#param_type as ::toni::FromRequest>::rejection(e),
                     };
                 };
                 extractions.push(extraction);
                 call_args.push(quote! { #param_name });
             }
-            ExtractorKind::Unknown => {
-                // Unknown type - try to extract it anyway
+            ExtractorKind::Form | ExtractorKind::Unknown => {
+                // `Form` awaits its streamed body, custom extractors may resolve providers
+                if !module_ref_read {
+                    extractions.push(quote! {
+                        let __module_ref = req
+                            .extensions
+                            .get::<::toni::injector::ModuleRef>()
+                            .cloned()
+                            .unwrap_or_default();
+                    });
+                    module_ref_read = true;
+                }
                 let extraction = quote! {
-                    let #param_name = match <#param_type as ::toni::FromRequest>::from_request(&req) {
+                    let #param_name = match <#param_type as ::toni::AsyncFromRequest>::extract(&req, &__module_ref).await {
                         Ok(value) => value,
-                        Err(e) => {
-                            let error_body = ::serde_json::json!({
-                                "error": "Extraction failed",
-                                "details": e.to_string()
-                            });
-                            return Box::new(::toni::http_helpers::HttpResponse {
-                                body: Some(::toni::http_helpers::Body::Json(error_body)),
-                                status: 400,
-                                headers: vec![],
-                            });
-                        }
+                        Err(e) => return <#param_type as ::toni::AsyncFromRequest>::rejection(e),
                     };
                 };
                 extractions.push(extraction);
@@ -179,7 +366,52 @@ pub fn generate_extractor_extractions(
         }
     }
 
-    Ok((extractions, call_args))
+    Ok((extractions, call_args, param_pipes))
+}
+
+/// Generate the `MultipartLimits` of `#[upload_limits(...)]`
+///
+/// Accepted keys: `max_files`, `max_file_size`, `max_fields`, `max_field_size`,
+/// `disk_threshold` and `max_body_size`. Unset keys keep the `MultipartLimits`
+/// defaults.
+pub fn generate_upload_limits(method: &ImplItemFn) -> Result<Option<TokenStream>> {
+    let Some(attr) = method
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("upload_limits"))
+    else {
+        return Ok(None);
+    };
+
+    let args = attr.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)?;
+    let mut fields = Vec::new();
+
+    for arg in args {
+        let key = arg
+            .path
+            .get_ident()
+            .ok_or_else(|| Error::new_spanned(&arg.path, "Expected a limit name"))?;
+        let value = &arg.value;
+        match key.to_string().as_str() {
+            "max_files" | "max_file_size" | "max_fields" | "max_field_size" => {
+                fields.push(quote! { #key: #value });
+            }
+            "disk_threshold" | "max_body_size" => fields.push(quote! { #key: Some(#value) }),
+            _ => {
+                return Err(Error::new_spanned(
+                    key,
+                    "Unknown upload limit, expected one of: max_files, max_file_size, max_fields, max_field_size, disk_threshold, max_body_size",
+                ));
+            }
+        }
+    }
+
+    Ok(Some(quote! {
+        ::toni::extractors::MultipartLimits {
+            #(#fields,)*
+            ..::std::default::Default::default()
+        }
+    }))
 }
 
 /// Generate the method call with extracted parameters
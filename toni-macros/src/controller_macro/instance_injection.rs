@@ -51,16 +51,16 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::collections::HashMap;
-use syn::{
-    Attribute, Error, Ident, ImplItemFn, ItemImpl, ItemStruct, LitStr, Result, spanned::Spanned,
-};
+use syn::{Attribute, Ident, ImplItemFn, ItemImpl, ItemStruct, Result};
 
 use crate::{
     controller_macro::extractor_params::{
-        generate_extractor_extractions, generate_extractor_method_call,
-        generate_extractor_static_method_call, get_extractor_params, has_self_receiver,
-        ExtractorKind,
+        ExtractorKind, generate_extractor_extractions, generate_extractor_method_call,
+        generate_extractor_static_method_call, generate_upload_limits, get_extractor_params,
+        has_self_receiver, remove_pipe_attributes,
     },
+    controller_macro::metadata::generate_metadata,
+    controller_macro::response_decorators::generate_response_decorators,
     enhancer::enhancer::create_enhancers_token_stream,
     markers_params::{
         extracts_marker_params::{
@@ -69,7 +69,8 @@ use crate::{
         get_marker_params::MarkerParam,
     },
     shared::{dependency_info::DependencyInfo, metadata_info::MetadataInfo},
-    utils::controller_utils::{attr_to_string, create_extract_body_dto_token_stream},
+    utils::controller_utils::{create_extract_body_dto_token_stream, parse_route_attribute},
+    utils::extracts::extract_controller_host,
 };
 
 pub fn generate_instance_controller_system(
@@ -84,7 +85,8 @@ pub fn generate_instance_controller_system(
 
     // Add Clone derive to struct (required for creating instances)
     let struct_with_clone = add_clone_derive(struct_attrs);
-    let impl_def = impl_block.clone();
+    let mut impl_def = impl_block.clone();
+    remove_pipe_attributes(&mut impl_def);
 
     // OPTIMIZATION: Conditionally generate wrappers based on scope and dependencies
     // Goal: Only generate wrappers that could actually be used
@@ -199,28 +201,46 @@ fn generate_controller_wrappers(
 
     // Extract controller-level enhancers from impl block attributes
     let controller_enhancers_attr = get_enhancers_attr(&impl_block.attrs)?;
+    let controller_metadata = generate_metadata(&impl_block.attrs)?;
+    let controller_host = extract_controller_host(impl_block)?;
 
     for item in &impl_block.items {
         if let syn::ImplItem::Fn(method) = item {
             if let Some(http_method_attr) = find_http_method_attr(&method.attrs) {
-                let method_enhancers_attr = get_enhancers_attr(&method.attrs)?;
+                let (route_path, http_methods) = parse_route_attribute(http_method_attr)?;
 
-                let marker_params = get_marker_params(method)?;
+                // Multi-method routes get one wrapper per method, named after it
+                let is_multi_method = http_methods.len() > 1;
+                for http_method in &http_methods {
+                    let method_enhancers_attr = get_enhancers_attr(&method.attrs)?;
 
-                let (wrapper, metadata) = generate_controller_wrapper(
-                    method,
-                    struct_name,
-                    dependencies,
-                    route_prefix,
-                    http_method_attr,
-                    controller_enhancers_attr.clone(),
-                    method_enhancers_attr,
-                    marker_params,
-                    scope,
-                )?;
+                    let marker_params = get_marker_params(method)?;
+
+                    let name_suffix = if is_multi_method {
+                        capitalize_first(http_method.to_lowercase())
+                    } else {
+                        String::new()
+                    };
 
-                wrappers.push(wrapper);
-                metadata_list.push(metadata);
+                    let (wrapper, metadata) = generate_controller_wrapper(
+                        method,
+                        struct_name,
+                        dependencies,
+                        route_prefix,
+                        &route_path,
+                        http_method,
+                        &name_suffix,
+                        controller_enhancers_attr.clone(),
+                        method_enhancers_attr,
+                        &controller_metadata,
+                        controller_host.as_deref(),
+                        marker_params,
+                        scope,
+                    )?;
+
+                    wrappers.push(wrapper);
+                    metadata_list.push(metadata);
+                }
             }
         }
     }
@@ -237,6 +257,8 @@ fn find_http_method_attr(attrs: &[Attribute]) -> Option<&Attribute> {
             || attr.path().is_ident("patch")
             || attr.path().is_ident("head")
             || attr.path().is_ident("options")
+            || attr.path().is_ident("all")
+            || attr.path().is_ident("route")
     })
 }
 
@@ -255,20 +277,16 @@ fn generate_controller_wrapper(
     struct_name: &Ident,
     dependencies: &DependencyInfo,
     route_prefix: &str,
-    http_method_attr: &Attribute,
+    route_path: &str,
+    http_method: &str,
+    name_suffix: &str,
     controller_enhancers_attr: HashMap<&Ident, &Attribute>,
     method_enhancers_attr: HashMap<&Ident, &Attribute>,
+    controller_metadata: &TokenStream,
+    controller_host: Option<&str>,
     marker_params: Vec<MarkerParam>,
     scope: crate::shared::scope_parser::ControllerScope,
 ) -> Result<(TokenStream, MetadataInfo)> {
-    let http_method = attr_to_string(http_method_attr)
-        .map_err(|_| Error::new(http_method_attr.span(), "Invalid attribute format"))?;
-
-    let route_path = http_method_attr
-        .parse_args::<LitStr>()
-        .map_err(|_| Error::new(http_method_attr.span(), "Invalid attribute format"))?
-        .value();
-
     let full_route_path = format!("{}{}", route_prefix, route_path);
 
     let method_name = &method.sig.ident;
@@ -280,9 +298,10 @@ fn generate_controller_wrapper(
     };
     let controller_name = Ident::new(
         &format!(
-            "{}{}Controller{}",
+            "{}{}{}Controller{}",
             struct_name,
             capitalize_first(method_name.to_string()),
+            name_suffix,
             scope_suffix
         ),
         method_name.span(),
@@ -332,18 +351,57 @@ fn generate_controller_wrapper(
         (resolutions, names, instantiation)
     };
 
-    let enhancers =
+    let mut enhancers =
         create_enhancers_token_stream(controller_enhancers_attr, method_enhancers_attr)?;
 
-    // Check if we're using extractors or marker params
+    let struct_name_str = struct_name.to_string();
+    let method_name_str = method_name.to_string();
+    let method_metadata = generate_metadata(&method.attrs)?;
+    let with_host = controller_host.map(|host| quote! { .with_host(#host) });
+    let upload_limits = generate_upload_limits(method)?;
     let extractor_params = get_extractor_params(method)?;
-    let has_extractors = extractor_params
+    // Forms read without `#[upload_limits]` are capped by the default limits
+    let body_limits = upload_limits.clone().or_else(|| {
+        extractor_params
+            .iter()
+            .any(|p| p.reads_form())
+            .then(|| quote! { ::toni::extractors::MultipartLimits::default() })
+    });
+    let with_body_limit = body_limits
+        .as_ref()
+        .map(|limits| quote! { .with_body_limit(#limits.body_limit()) });
+    let with_streamed_body = extractor_params
         .iter()
-        .any(|p| p.kind != ExtractorKind::HttpRequest && p.kind != ExtractorKind::Unknown);
+        .any(|p| p.streams_body())
+        .then(|| quote! { .with_streamed_body() });
+    let handler_info = quote! {
+        ::toni::injector::HandlerInfo::new(#struct_name_str, #method_name_str)
+            .with_controller_type::<#struct_name>()
+            .with_metadata(#controller_metadata, #method_metadata)
+            #with_host
+            #with_body_limit
+            #with_streamed_body
+    };
+
+    // Check if we're using extractors or marker params
+    // Arguments other than `HttpRequest` and legacy `#[body]`-style markers
+    let has_extractors = extractor_params.iter().any(|p| {
+        p.kind != ExtractorKind::HttpRequest
+            && !marker_params.iter().any(|m| m.param_name == p.param_name)
+    });
 
     let (method_call, marker_params_extraction, body_dto_token_stream) = if has_extractors {
         // Use extractor-based approach
-        let (extractions, call_args) = generate_extractor_extractions(&extractor_params)?;
+        let (mut extractions, call_args, param_pipes) =
+            generate_extractor_extractions(&extractor_params)?;
+        enhancers.insert("param_pipes".to_string(), param_pipes);
+        if let Some(limits) = &upload_limits {
+            let store_limits = quote! {
+                let mut req = req;
+                req.extensions.insert(#limits);
+            };
+            extractions.insert(0, store_limits);
+        }
         let method_call = if is_static_method {
             generate_extractor_static_method_call(method, struct_name, &call_args)?
         } else {
@@ -352,20 +410,28 @@ fn generate_controller_wrapper(
         (method_call, extractions, None)
     } else {
         // Use legacy marker-based approach
-        let method_call = generate_method_call(method, &marker_params, struct_name, is_static_method)?;
+        let method_call =
+            generate_method_call(method, &marker_params, struct_name, is_static_method)?;
         let (extractions, body_dto) = generate_marker_params_extraction(&marker_params)?;
         (method_call, extractions, body_dto)
     };
 
+    // `#[http_code]`, `#[header]` and `#[redirect]` post-process the returned value
+    let method_call = match generate_response_decorators(&method.attrs)? {
+        Some(decorators) => quote! { #decorators.wrap(#method_call) },
+        None => method_call,
+    };
+
     let wrapper = generate_controller_wrapper_code(
         &controller_name,
         &controller_token,
         &full_route_path,
-        &http_method,
+        http_method,
         &field_resolutions,
         &struct_instantiation,
         &method_call,
         &enhancers,
+        &handler_info,
         &marker_params_extraction,
         &body_dto_token_stream,
         scope,
@@ -500,6 +566,7 @@ fn generate_controller_wrapper_code(
     struct_instantiation: &TokenStream,
     method_call: &TokenStream,
     enhancers: &HashMap<String, Vec<TokenStream>>,
+    handler_info: &TokenStream,
     marker_params_extraction: &[TokenStream],
     body_dto_token_stream: &Option<TokenStream>,
     scope: crate::shared::scope_parser::ControllerScope,
@@ -516,6 +583,7 @@ fn generate_controller_wrapper_code(
             http_method,
             method_call,
             enhancers,
+            handler_info,
             marker_params_extraction,
             body_dto_token_stream,
             struct_name, // Pass struct name for downcast
@@ -530,6 +598,7 @@ fn generate_controller_wrapper_code(
             struct_instantiation,
             method_call,
             enhancers,
+            handler_info,
             marker_params_extraction,
             body_dto_token_stream,
             is_static_method,
@@ -545,6 +614,7 @@ fn generate_singleton_controller_wrapper(
     http_method: &str,
     method_call: &TokenStream,
     enhancers: &HashMap<String, Vec<TokenStream>>,
+    handler_info: &TokenStream,
     marker_params_extraction: &[TokenStream],
     body_dto_token_stream: &Option<TokenStream>,
     struct_name: &Ident, // Need this for downcast type
@@ -554,11 +624,14 @@ fn generate_singleton_controller_wrapper(
     let use_guards = enhancers.get("guards").unwrap_or(&binding);
     let interceptors = enhancers.get("interceptors").unwrap_or(&binding);
     let pipes = enhancers.get("pipes").unwrap_or(&binding);
+    let param_pipes = enhancers.get("param_pipes").unwrap_or(&binding);
+    let filters = enhancers.get("filters").unwrap_or(&binding);
+    let middleware = enhancers.get("middleware").unwrap_or(&binding);
 
     let body_dto_stream = if let Some(token_stream) = body_dto_token_stream {
         token_stream.clone()
     } else {
-        quote! { None }
+        quote! { Ok(None) }
     };
 
     // For static methods, we don't need to store or downcast the instance
@@ -621,7 +694,7 @@ fn generate_singleton_controller_wrapper(
                 #controller_token.to_string()
             }
 
-            fn get_guards(&self) -> Vec<::std::sync::Arc<dyn ::toni::traits_helpers::Guard>> {
+            fn get_guards(&self) -> Vec<::toni::traits_helpers::GuardSource> {
                 vec![#(#use_guards),*]
             }
 
@@ -633,7 +706,29 @@ fn generate_singleton_controller_wrapper(
                 vec![#(#pipes),*]
             }
 
-            fn get_body_dto(&self, _req: &::toni::http_helpers::HttpRequest) -> Option<Box<dyn ::toni::traits_helpers::validate::Validatable>> {
+            fn get_param_pipes(&self) -> Vec<::toni::traits_helpers::PipeSource> {
+                vec![#(#param_pipes),*]
+            }
+
+            fn get_filters(&self) -> Vec<::std::sync::Arc<dyn ::toni::traits_helpers::ExceptionFilter>> {
+                vec![#(#filters),*]
+            }
+
+            fn get_middleware(&self) -> Vec<::toni::middleware::MiddlewareSource> {
+                vec![#(#middleware),*]
+            }
+
+            fn get_handler(&self) -> ::toni::injector::HandlerInfo {
+                #handler_info
+            }
+
+            fn get_body_dto(
+                &self,
+                _req: &::toni::http_helpers::HttpRequest,
+            ) -> Result<
+                Option<Box<dyn ::toni::traits_helpers::validate::Validatable>>,
+                ::toni::extractors::ValidationError,
+            > {
                 #body_dto_stream
             }
         }
@@ -650,6 +745,7 @@ fn generate_request_controller_wrapper(
     struct_instantiation: &TokenStream,
     method_call: &TokenStream,
     enhancers: &HashMap<String, Vec<TokenStream>>,
+    handler_info: &TokenStream,
     marker_params_extraction: &[TokenStream],
     body_dto_token_stream: &Option<TokenStream>,
     is_static_method: bool,
@@ -658,11 +754,14 @@ fn generate_request_controller_wrapper(
     let use_guards = enhancers.get("guards").unwrap_or(&binding);
     let interceptors = enhancers.get("interceptors").unwrap_or(&binding);
     let pipes = enhancers.get("pipes").unwrap_or(&binding);
+    let param_pipes = enhancers.get("param_pipes").unwrap_or(&binding);
+    let filters = enhancers.get("filters").unwrap_or(&binding);
+    let middleware = enhancers.get("middleware").unwrap_or(&binding);
 
     let body_dto_stream = if let Some(token_stream) = body_dto_token_stream {
         token_stream.clone()
     } else {
-        quote! { None }
+        quote! { Ok(None) }
     };
 
     // For static methods, we don't need dependencies field
@@ -710,7 +809,7 @@ fn generate_request_controller_wrapper(
                 #controller_token.to_string()
             }
 
-            fn get_guards(&self) -> Vec<::std::sync::Arc<dyn ::toni::traits_helpers::Guard>> {
+            fn get_guards(&self) -> Vec<::toni::traits_helpers::GuardSource> {
                 vec![#(#use_guards),*]
             }
 
@@ -722,7 +821,29 @@ fn generate_request_controller_wrapper(
                 vec![#(#pipes),*]
             }
 
-            fn get_body_dto(&self, _req: &::toni::http_helpers::HttpRequest) -> Option<Box<dyn ::toni::traits_helpers::validate::Validatable>> {
+            fn get_param_pipes(&self) -> Vec<::toni::traits_helpers::PipeSource> {
+                vec![#(#param_pipes),*]
+            }
+
+            fn get_filters(&self) -> Vec<::std::sync::Arc<dyn ::toni::traits_helpers::ExceptionFilter>> {
+                vec![#(#filters),*]
+            }
+
+            fn get_middleware(&self) -> Vec<::toni::middleware::MiddlewareSource> {
+                vec![#(#middleware),*]
+            }
+
+            fn get_handler(&self) -> ::toni::injector::HandlerInfo {
+                #handler_info
+            }
+
+            fn get_body_dto(
+                &self,
+                _req: &::toni::http_helpers::HttpRequest,
+            ) -> Result<
+                Option<Box<dyn ::toni::traits_helpers::validate::Validatable>>,
+                ::toni::extractors::ValidationError,
+            > {
                 #body_dto_stream
             }
         }
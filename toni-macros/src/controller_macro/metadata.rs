@@ -0,0 +1,59 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, LitStr, Result, Token,
+    parse::{Parse, ParseStream},
+};
+
+/// `#[set_metadata("key", value)]`, where `value` uses `serde_json::json!` syntax
+struct SetMetadataArgs {
+    key: LitStr,
+    value: TokenStream,
+}
+
+impl Parse for SetMetadataArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let value = input.parse()?;
+        Ok(Self { key, value })
+    }
+}
+
+/// Builds a `Metadata` from the `#[set_metadata]` and `#[roles]` attributes;
+/// `#[roles("a", "b")]` is short for `#[set_metadata("roles", ["a", "b"])]`.
+pub fn generate_metadata(attrs: &[Attribute]) -> Result<TokenStream> {
+    let mut entries = Vec::new();
+
+    for attr in attrs {
+        let Some(segment) = attr.path().segments.last() else {
+            continue;
+        };
+        let (key, value) = match segment.ident.to_string().as_str() {
+            "set_metadata" => {
+                let args = attr.parse_args::<SetMetadataArgs>()?;
+                (args.key, args.value)
+            }
+            "roles" => {
+                let roles = &attr.meta.require_list()?.tokens;
+                (
+                    LitStr::new("roles", segment.ident.span()),
+                    quote! { [#roles] },
+                )
+            }
+            _ => continue,
+        };
+        entries.push(quote! { metadata.insert(#key, ::serde_json::json!(#value)); });
+    }
+
+    if entries.is_empty() {
+        return Ok(quote! { ::toni::injector::Metadata::new() });
+    }
+    Ok(quote! {
+        {
+            let mut metadata = ::toni::injector::Metadata::new();
+            #(#entries)*
+            metadata
+        }
+    })
+}
@@ -1,3 +1,5 @@
 pub mod controller_struct;
 pub mod extractor_params;
 pub mod instance_injection;
+pub mod metadata;
+pub mod response_decorators;
@@ -0,0 +1,194 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Error, LitInt, LitStr, Result, Token,
+    parse::{Parse, ParseStream},
+};
+
+/// `#[header("Cache-Control", "no-store")]`
+struct HeaderArgs {
+    name: LitStr,
+    value: LitStr,
+}
+
+impl Parse for HeaderArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let value = input.parse()?;
+        Ok(Self { name, value })
+    }
+}
+
+/// `#[redirect("/login")]` or `#[redirect("/login", 301)]`
+struct RedirectArgs {
+    location: LitStr,
+    status: Option<LitInt>,
+}
+
+impl Parse for RedirectArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let location = input.parse()?;
+        let status = if input.parse::<Option<Token![,]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { location, status })
+    }
+}
+
+/// A literal status code within `range`
+fn parse_status(lit: &LitInt, range: std::ops::RangeInclusive<u16>, what: &str) -> Result<u16> {
+    let status = lit.base10_parse::<u16>()?;
+    if !range.contains(&status) {
+        return Err(Error::new(
+            lit.span(),
+            format!(
+                "{} must be between {} and {}",
+                what,
+                range.start(),
+                range.end()
+            ),
+        ));
+    }
+    Ok(status)
+}
+
+/// A header name of RFC 7230 token characters
+fn check_header_name(lit: &LitStr) -> Result<()> {
+    let name = lit.value();
+    if name.is_empty() {
+        return Err(Error::new(lit.span(), "header name can't be empty"));
+    }
+    let is_tchar = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+    if let Some(c) = name.chars().find(|&c| !is_tchar(c)) {
+        return Err(Error::new(
+            lit.span(),
+            format!("header name can't contain {:?}", c),
+        ));
+    }
+    Ok(())
+}
+
+/// A header value, which a CR, LF or NUL would split or truncate
+fn check_header_value(lit: &LitStr) -> Result<()> {
+    if let Some(c) = lit
+        .value()
+        .chars()
+        .find(|c| matches!(c, '\r' | '\n' | '\0'))
+    {
+        return Err(Error::new(
+            lit.span(),
+            format!("header value can't contain {:?}", c),
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the `ResponseDecorators` of a method from its `#[http_code]`,
+/// `#[header]` and `#[redirect]` attributes, `None` without any.
+pub fn generate_response_decorators(attrs: &[Attribute]) -> Result<Option<TokenStream>> {
+    let mut calls = Vec::new();
+    let mut seen_status = false;
+    let mut seen_redirect = false;
+
+    for attr in attrs {
+        let Some(segment) = attr.path().segments.last() else {
+            continue;
+        };
+        match segment.ident.to_string().as_str() {
+            "http_code" => {
+                if std::mem::replace(&mut seen_status, true) {
+                    return Err(Error::new_spanned(attr, "duplicate `#[http_code]`"));
+                }
+                let status = parse_status(&attr.parse_args()?, 100..=599, "An HTTP status")?;
+                calls.push(quote! {
+                    .status(::toni::http_helpers::StatusCode::from_u16(#status).unwrap())
+                });
+            }
+            "header" => {
+                let HeaderArgs { name, value } = attr.parse_args()?;
+                check_header_name(&name)?;
+                check_header_value(&value)?;
+                calls.push(quote! { .header(#name, #value) });
+            }
+            "redirect" => {
+                if std::mem::replace(&mut seen_redirect, true) {
+                    return Err(Error::new_spanned(attr, "duplicate `#[redirect]`"));
+                }
+                let RedirectArgs { location, status } = attr.parse_args()?;
+                check_header_value(&location)?;
+                let status = match &status {
+                    Some(lit) => parse_status(lit, 300..=399, "A redirect status")?,
+                    None => 302,
+                };
+                calls.push(quote! {
+                    .redirect(::toni::http_helpers::StatusCode::from_u16(#status).unwrap(), #location)
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if calls.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(quote! {
+        ::toni::http_helpers::ResponseDecorators::new() #(#calls)*
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn error(attr: Attribute) -> String {
+        generate_response_decorators(&[attr])
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_accepts_valid_headers() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[header("Cache-Control", "no-store, max-age=0")]),
+            parse_quote!(#[header("X-Request_Id.v2", "a\tb")]),
+            parse_quote!(#[redirect("/login?next=%2F")]),
+        ];
+        assert!(generate_response_decorators(&attrs).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_rejects_invalid_header_names() {
+        assert_eq!(
+            error(parse_quote!(#[header("", "v")])),
+            "header name can't be empty"
+        );
+        assert_eq!(
+            error(parse_quote!(#[header("X Trace", "v")])),
+            "header name can't contain ' '"
+        );
+        assert_eq!(
+            error(parse_quote!(#[header("X-Trace:", "v")])),
+            "header name can't contain ':'"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_header_values() {
+        assert_eq!(
+            error(parse_quote!(#[header("X-Trace", "a\r\nSet-Cookie: b")])),
+            "header value can't contain '\\r'"
+        );
+        assert_eq!(
+            error(parse_quote!(#[header("X-Trace", "a\0")])),
+            "header value can't contain '\\0'"
+        );
+        assert_eq!(
+            error(parse_quote!(#[redirect("/a\nb")])),
+            "header value can't contain '\\n'"
+        );
+    }
+}
@@ -7,10 +7,62 @@ use syn::{Attribute, Error, Ident, Result, Token, punctuated::Punctuated, spanne
 fn is_enhancer(segment: &Ident) -> bool {
     matches!(
         segment.to_string().as_str(),
-        "toni_guards" | "toni_interceptors" | "toni_pipes"
+        "toni_guards"
+            | "toni_interceptors"
+            | "toni_pipes"
+            | "toni_filters"
+            | "toni_middleware"
+            | "use_guards"
+            | "use_filters"
+            | "use_middleware"
     )
 }
 
+/// Guards, filters and middleware show up either as `#[use_guards(..)]` (method attributes
+/// are not expanded yet when `#[controller]` runs) or as the
+/// `#[toni::toni_guards(..)]` marker it expands to.
+fn is_path_enhancer_attribute(attr: &Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| {
+        matches!(
+            segment.ident.to_string().as_str(),
+            "use_guards"
+                | "toni_guards"
+                | "use_filters"
+                | "toni_filters"
+                | "use_middleware"
+                | "toni_middleware"
+        )
+    })
+}
+
+/// Guards and middleware that are not `Default` are resolved from the module's providers at startup
+pub fn enhancer_instance(key: &str, ident: &Ident) -> TokenStream {
+    match key {
+        "guards" | "middleware" => enhancer_source(quote! { #ident }),
+        _ => quote! { ::std::sync::Arc::new(#ident) },
+    }
+}
+
+/// `EnhancerSource` of the enhancer type `ty`, built in place when it is
+/// `Default` and resolved from the module's providers otherwise
+pub fn enhancer_source(ty: TokenStream) -> TokenStream {
+    quote! {
+        {
+            use ::toni::traits_helpers::{DefaultEnhancerSource as _, ProviderEnhancerSource as _};
+            (&&::toni::traits_helpers::EnhancerRef::<#ty>::new()).enhancer_source()
+        }
+    }
+}
+
+/// `toni_guards` -> `guards`, `use_filters` -> `filters`
+fn enhancer_key(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.strip_prefix("toni_")
+        .or_else(|| name.strip_prefix("use_"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
 pub fn has_enhancer_attribute(attr: &Attribute) -> bool {
     attr.path()
         .segments
@@ -32,19 +84,16 @@ pub fn create_enchancers_token_stream(
             .map_err(|_| Error::new(attr.span(), "Invalid attribute format"))?;
 
         // Normalize the attribute name (remove toni_ prefix)
-        let key = ident.to_string().replace("toni_", "");
+        let key = enhancer_key(ident);
 
         // Add each identifier to the enhancers map
         for arg_ident in arg_idents {
             match enhancers.get_mut(key.as_str()) {
                 Some(enhancer_mut) => {
-                    enhancer_mut.push(quote! {::std::sync::Arc::new(#arg_ident)});
+                    enhancer_mut.push(enhancer_instance(&key, &arg_ident));
                 }
                 None => {
-                    enhancers.insert(
-                        key.clone(),
-                        vec![quote! {::std::sync::Arc::new(#arg_ident)}],
-                    );
+                    enhancers.insert(key.clone(), vec![enhancer_instance(&key, &arg_ident)]);
                 }
             };
         }
@@ -66,18 +115,15 @@ pub fn create_enhancers_token_stream(
             .parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
             .map_err(|_| Error::new(attr.span(), "Invalid attribute format"))?;
 
-        let key = ident.to_string().replace("toni_", "");
+        let key = enhancer_key(ident);
 
         for arg_ident in arg_idents {
             match enhancers.get_mut(key.as_str()) {
                 Some(enhancer_mut) => {
-                    enhancer_mut.push(quote! {::std::sync::Arc::new(#arg_ident)});
+                    enhancer_mut.push(enhancer_instance(&key, &arg_ident));
                 }
                 None => {
-                    enhancers.insert(
-                        key.clone(),
-                        vec![quote! {::std::sync::Arc::new(#arg_ident)}],
-                    );
+                    enhancers.insert(key.clone(), vec![enhancer_instance(&key, &arg_ident)]);
                 }
             };
         }
@@ -89,19 +135,16 @@ pub fn create_enhancers_token_stream(
             .parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)
             .map_err(|_| Error::new(attr.span(), "Invalid attribute format"))?;
 
-        let key = ident.to_string().replace("toni_", "");
+        let key = enhancer_key(ident);
 
         for arg_ident in arg_idents {
             match enhancers.get_mut(key.as_str()) {
                 Some(enhancer_mut) => {
                     // This APPENDS instead of replacing!
-                    enhancer_mut.push(quote! {::std::sync::Arc::new(#arg_ident)});
+                    enhancer_mut.push(enhancer_instance(&key, &arg_ident));
                 }
                 None => {
-                    enhancers.insert(
-                        key.clone(),
-                        vec![quote! {::std::sync::Arc::new(#arg_ident)}],
-                    );
+                    enhancers.insert(key.clone(), vec![enhancer_instance(&key, &arg_ident)]);
                 }
             };
         }
@@ -114,8 +157,10 @@ pub fn get_enhancers_attr(attrs: &[Attribute]) -> Result<HashMap<&Ident, &Attrib
     let mut enhancers_attr = HashMap::new();
     attrs.iter().for_each(|attr| {
         if has_enhancer_attribute(attr) {
-            let ident = match attr.meta.path().get_ident() {
+            let path = attr.meta.path();
+            let ident = match path.get_ident() {
                 Some(ident) => ident,
+                None if is_path_enhancer_attribute(attr) => &path.segments.last().unwrap().ident,
                 None => return,
             };
             enhancers_attr.insert(ident, attr);
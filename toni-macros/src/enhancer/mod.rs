@@ -1,4 +1,6 @@
 pub mod enhancer;
+pub mod use_filters;
 pub mod use_guards;
 pub mod use_interceptors;
+pub mod use_middleware;
 pub mod use_pipes;
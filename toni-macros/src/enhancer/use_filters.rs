@@ -0,0 +1,54 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Ident, Item, Token, parse_macro_input, punctuated::Punctuated};
+
+/// Attribute macro for applying exception filters to a route handler method or controller impl block
+///
+/// # Example - Method level
+/// ```ignore
+/// #[use_filters(DatabaseErrorFilter)]
+/// #[get("/users/{id}")]
+/// fn find_user(&self, req: HttpRequest) -> Result<Json<User>, UserError> {
+///     // ...
+/// }
+/// ```
+///
+/// # Example - Controller level
+/// ```ignore
+/// #[use_filters(DatabaseErrorFilter)]  // Applies to ALL methods
+/// #[controller("/api")]
+/// impl MyController {
+///     // All methods get DatabaseErrorFilter
+/// }
+/// ```
+pub fn use_filters_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // Parse the list of filter types
+    let filters = parse_macro_input!(attr with Punctuated::<Ident, Token![,]>::parse_terminated);
+    let filter_list: Vec<_> = filters.iter().collect();
+
+    // Try to parse as either a method or an impl block
+    let item_parsed: Item = parse_macro_input!(item as Item);
+
+    let output = match item_parsed {
+        Item::Fn(method) => {
+            quote! {
+                #[toni::toni_filters(#(#filter_list),*)]
+                #method
+            }
+        }
+        Item::Impl(impl_block) => {
+            quote! {
+                #[toni::toni_filters(#(#filter_list),*)]
+                #impl_block
+            }
+        }
+        _ => {
+            quote! {
+                #[toni::toni_filters(#(#filter_list),*)]
+                #item_parsed
+            }
+        }
+    };
+
+    output.into()
+}
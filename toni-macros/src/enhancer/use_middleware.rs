@@ -0,0 +1,41 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Ident, Item, Token, parse_macro_input, punctuated::Punctuated};
+
+/// Attribute macro for applying middleware to a route handler method or controller impl block
+///
+/// Runs after the global and module middleware, controller-level first. Types
+/// that are not `Default` must be `#[middleware]` providers of the module, or
+/// the application fails to start.
+///
+/// # Example - Method level
+/// ```ignore
+/// #[use_middleware(RequestLogger)]
+/// #[get("/users")]
+/// fn find_all(&self, req: HttpRequest) -> HttpResponse {
+///     // ...
+/// }
+/// ```
+///
+/// # Example - Controller level
+/// ```ignore
+/// #[use_middleware(RequestLogger, AuthMiddleware)]  // Applies to ALL methods
+/// #[controller("/api")]
+/// impl MyController {
+///     // All methods get RequestLogger and AuthMiddleware
+/// }
+/// ```
+pub fn use_middleware_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // Parse the list of middleware types
+    let middleware = parse_macro_input!(attr with Punctuated::<Ident, Token![,]>::parse_terminated);
+    let middleware_list: Vec<_> = middleware.iter().collect();
+
+    let item_parsed: Item = parse_macro_input!(item as Item);
+
+    let output = quote! {
+        #[toni::toni_middleware(#(#middleware_list),*)]
+        #item_parsed
+    };
+
+    output.into()
+}
@@ -0,0 +1,458 @@
+//! WebSocket gateway code generation
+//!
+//! ```ignore
+//! #[gateway_struct(pub struct ChatGateway {
+//!     #[inject]
+//!     server: WsServer,
+//! })]
+//! #[websocket_gateway("/ws")]
+//! impl ChatGateway {
+//!     #[on_connection]
+//!     fn connected(&self, client: WsClient) { ... }
+//!
+//!     #[subscribe_message("chat.send")]
+//!     fn send(&self, client: WsClient, message: ChatMessage) -> Ack { ... }
+//! }
+//! ```
+//!
+//! Generates a `{Struct}GatewayInstance` implementing `GatewayTrait` and a
+//! `{Struct}Manager` implementing `Gateway` that resolves dependencies once at
+//! startup. Gateways are always singletons.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Result, ReturnType, Token,
+    Type, parse2, punctuated::Punctuated, spanned::Spanned,
+};
+
+use crate::{
+    controller_macro::controller_struct::{extract_constructor_params, has_new_method},
+    enhancer::enhancer::enhancer_instance,
+    shared::{
+        dependency_info::{DependencyInfo, DependencySource},
+        scope_parser::{ControllerScope, ControllerStructArgs},
+    },
+    utils::extracts::extract_struct_dependencies,
+};
+
+enum GatewayHook {
+    Message(String),
+    Connection,
+    Disconnect,
+}
+
+fn attr_name(attr: &Attribute) -> Option<String> {
+    attr.path()
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+fn extract_gateway_path(impl_block: &ItemImpl) -> Result<String> {
+    impl_block
+        .attrs
+        .iter()
+        .find(|attr| attr_name(attr).as_deref() == Some("websocket_gateway"))
+        .map(|attr| attr.parse_args::<LitStr>().map(|lit| lit.value()))
+        .transpose()
+        .map(|opt| opt.unwrap_or_else(|| "/".to_string()))
+}
+
+/// Guards may appear either as `#[use_guards(..)]` (not yet expanded when this
+/// macro runs) or as the expanded `#[toni_guards(..)]` marker.
+fn extract_gateway_guards(impl_block: &ItemImpl) -> Result<Vec<TokenStream>> {
+    let mut guards = Vec::new();
+    for attr in &impl_block.attrs {
+        if matches!(
+            attr_name(attr).as_deref(),
+            Some("use_guards") | Some("toni_guards")
+        ) {
+            let idents = attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+            guards.extend(
+                idents
+                    .into_iter()
+                    .map(|ident| enhancer_instance("guards", &ident)),
+            );
+        }
+    }
+    Ok(guards)
+}
+
+fn find_hook(method: &ImplItemFn) -> Result<Option<GatewayHook>> {
+    for attr in &method.attrs {
+        match attr_name(attr).as_deref() {
+            Some("subscribe_message") => {
+                let event = attr.parse_args::<LitStr>()?.value();
+                return Ok(Some(GatewayHook::Message(event)));
+            }
+            Some("on_connection") => return Ok(Some(GatewayHook::Connection)),
+            Some("on_disconnect") => return Ok(Some(GatewayHook::Disconnect)),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        Type::Reference(reference) => type_name(&reference.elem),
+        _ => None,
+    }
+}
+
+/// Builds the argument list for a hook call.
+///
+/// `WsClient` and (for connection hooks) `HttpRequest` are passed through;
+/// any other parameter is deserialized from the message payload.
+fn generate_hook_args(method: &ImplItemFn, allow_payload: bool) -> Result<TokenStream> {
+    let mut bindings = Vec::new();
+    let mut args = Vec::new();
+    let mut has_payload = false;
+
+    for (index, input) in method.sig.inputs.iter().enumerate() {
+        let FnArg::Typed(pat_type) = input else {
+            continue;
+        };
+        let ty = &*pat_type.ty;
+        let is_ref = matches!(ty, Type::Reference(_));
+        let arg = Ident::new(&format!("__arg{}", index), pat_type.span());
+
+        match type_name(ty).as_deref() {
+            Some("WsClient") => {
+                if is_ref {
+                    args.push(quote! { client });
+                } else {
+                    args.push(quote! { client.clone() });
+                }
+            }
+            Some("HttpRequest") if !allow_payload => {
+                if is_ref {
+                    args.push(quote! { req });
+                } else {
+                    args.push(quote! { req.clone() });
+                }
+            }
+            _ if allow_payload && !has_payload && !is_ref => {
+                has_payload = true;
+                bindings.push(quote! {
+                    let #arg: #ty = ::serde_json::from_value(data)
+                        .map_err(|e| format!("Invalid payload: {}", e))?;
+                });
+                args.push(quote! { #arg });
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    if allow_payload {
+                        "Message handlers accept `WsClient` and at most one owned payload parameter"
+                    } else {
+                        "Lifecycle hooks only accept `WsClient` and `HttpRequest` parameters"
+                    },
+                ));
+            }
+        }
+    }
+
+    let method_name = &method.sig.ident;
+    let call = if method.sig.asyncness.is_some() {
+        quote! { gateway.#method_name(#(#args),*).await }
+    } else {
+        quote! { gateway.#method_name(#(#args),*) }
+    };
+
+    Ok(quote! {
+        #(#bindings)*
+        #call
+    })
+}
+
+fn generate_message_arm(method: &ImplItemFn, event: &str) -> Result<TokenStream> {
+    let call = generate_hook_args(method, true)?;
+
+    let reply = match &method.sig.output {
+        ReturnType::Default => quote! {
+            #call;
+            Ok(None)
+        },
+        ReturnType::Type(_, _) => quote! {
+            let result = { #call };
+            let value = ::serde_json::to_value(&result)
+                .map_err(|e| format!("Failed to serialize reply: {}", e))?;
+            Ok(if value.is_null() { None } else { Some(value) })
+        },
+    };
+
+    Ok(quote! {
+        #event => {
+            #reply
+        }
+    })
+}
+
+pub fn handle_gateway_struct(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    let args = parse2::<ControllerStructArgs>(attr)?;
+    if args.was_explicit && matches!(args.scope, ControllerScope::Request) {
+        return Err(syn::Error::new(
+            args.struct_def.ident.span(),
+            "Gateways are always singletons; remove `scope = \"request\"`",
+        ));
+    }
+    let struct_def = args.struct_def;
+    let impl_block = parse2::<ItemImpl>(item)?;
+
+    let mut dependencies = extract_struct_dependencies(&struct_def)?;
+    if let Some(method_name) = args.init {
+        let params = extract_constructor_params(&impl_block, &method_name)?;
+        dependencies.init_method = Some(method_name.clone());
+        dependencies.constructor_params = params;
+        dependencies.source = DependencySource::Constructor(method_name);
+    } else if has_new_method(&impl_block) {
+        let params = extract_constructor_params(&impl_block, "new")?;
+        dependencies.init_method = Some("new".to_string());
+        dependencies.constructor_params = params;
+        dependencies.source = DependencySource::Constructor("new".to_string());
+    }
+
+    let path = extract_gateway_path(&impl_block)?;
+    let guards = extract_gateway_guards(&impl_block)?;
+
+    let mut events = Vec::new();
+    let mut message_arms = Vec::new();
+    let mut connection_calls = Vec::new();
+    let mut disconnect_calls = Vec::new();
+
+    for item in &impl_block.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        match find_hook(method)? {
+            Some(GatewayHook::Message(event)) => {
+                if events.contains(&event) {
+                    return Err(syn::Error::new(
+                        method.sig.ident.span(),
+                        format!("Duplicate handler for event '{}'", event),
+                    ));
+                }
+                message_arms.push(generate_message_arm(method, &event)?);
+                events.push(event);
+            }
+            Some(GatewayHook::Connection) => {
+                connection_calls.push(generate_hook_args(method, false)?);
+            }
+            Some(GatewayHook::Disconnect) => {
+                disconnect_calls.push(generate_hook_args(method, false)?);
+            }
+            None => {}
+        }
+    }
+
+    let struct_name = &struct_def.ident;
+    let mut struct_def = struct_def.clone();
+    struct_def
+        .attrs
+        .push(syn::parse_quote! { #[derive(::toni::Injectable)] });
+
+    let instance_name = Ident::new(
+        &format!("{}GatewayInstance", struct_name),
+        struct_name.span(),
+    );
+    let manager = generate_manager(struct_name, &instance_name, &dependencies);
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        #struct_def
+
+        #[allow(dead_code)]
+        #impl_block
+
+        pub struct #instance_name {
+            instance: ::std::sync::Arc<#struct_name>,
+        }
+
+        #[::toni::async_trait]
+        impl ::toni::traits_helpers::GatewayTrait for #instance_name {
+            fn get_token(&self) -> String {
+                stringify!(#struct_name).to_string()
+            }
+
+            fn get_path(&self) -> String {
+                #path.to_string()
+            }
+
+            fn get_guards(&self) -> Vec<::toni::traits_helpers::GuardSource> {
+                vec![#(#guards),*]
+            }
+
+            fn get_events(&self) -> Vec<String> {
+                vec![#(#events.to_string()),*]
+            }
+
+            #[allow(unused_variables)]
+            async fn on_connection(
+                &self,
+                client: &::toni::websocket::WsClient,
+                req: &::toni::http_helpers::HttpRequest,
+            ) {
+                let gateway = &*self.instance;
+                #(#connection_calls;)*
+            }
+
+            #[allow(unused_variables)]
+            async fn on_disconnect(&self, client: &::toni::websocket::WsClient) {
+                let gateway = &*self.instance;
+                #(#disconnect_calls;)*
+            }
+
+            #[allow(unused_variables, unreachable_code)]
+            async fn handle_message(
+                &self,
+                client: &::toni::websocket::WsClient,
+                event: &str,
+                data: ::serde_json::Value,
+            ) -> Result<Option<::serde_json::Value>, String> {
+                let gateway = &*self.instance;
+                match event {
+                    #(#message_arms)*
+                    _ => Err(format!("Unknown event '{}'", event)),
+                }
+            }
+        }
+
+        #manager
+    })
+}
+
+fn generate_manager(
+    struct_name: &Ident,
+    instance_name: &Ident,
+    dependencies: &DependencyInfo,
+) -> TokenStream {
+    let manager_name = Ident::new(&format!("{}Manager", struct_name), struct_name.span());
+    let struct_token = struct_name.to_string();
+
+    let deps_to_resolve = if !dependencies.constructor_params.is_empty() {
+        &dependencies.constructor_params
+    } else {
+        &dependencies.fields
+    };
+
+    let dependency_tokens: Vec<_> = deps_to_resolve
+        .iter()
+        .map(|(_, _, lookup_token_expr)| lookup_token_expr)
+        .collect();
+
+    let field_resolutions: Vec<_> = deps_to_resolve
+        .iter()
+        .map(|(field_name, full_type, lookup_token_expr)| {
+            quote! {
+                let #field_name: #full_type = {
+                    let __lookup_token = #lookup_token_expr;
+                    let provider = dependencies
+                        .get(&__lookup_token)
+                        .ok_or_else(|| format!("Missing dependency '{}'", __lookup_token))?;
+
+                    if matches!(provider.get_scope(), ::toni::ProviderScope::Request) {
+                        return Err(format!(
+                            "Gateway '{}' cannot depend on Request-scoped provider '{}'",
+                            #struct_token,
+                            __lookup_token
+                        ));
+                    }
+
+                    let any_box = provider.execute(vec![], None).await;
+
+                    *any_box.downcast::<#full_type>()
+                        .map_err(|_| format!(
+                            "Failed to downcast '{}' to {}",
+                            __lookup_token,
+                            stringify!(#full_type)
+                        ))?
+                };
+            }
+        })
+        .collect();
+
+    let field_names: Vec<_> = deps_to_resolve
+        .iter()
+        .map(|(field_name, _, _)| field_name.clone())
+        .collect();
+
+    let struct_instantiation = if let Some(init_method_name) = &dependencies.init_method {
+        let init_method = Ident::new(init_method_name, struct_name.span());
+        quote! { #struct_name::#init_method(#(#field_names),*) }
+    } else {
+        let owned_field_inits: Vec<_> = dependencies
+            .owned_fields
+            .iter()
+            .map(|(field_name, field_type, default_expr)| {
+                if let Some(expr) = default_expr {
+                    quote! { #field_name: #expr }
+                } else {
+                    quote! { #field_name: <#field_type>::default() }
+                }
+            })
+            .collect();
+
+        quote! {
+            #struct_name {
+                #(#field_names,)*
+                #(#owned_field_inits),*
+            }
+        }
+    };
+
+    quote! {
+        pub struct #manager_name;
+
+        #[::toni::async_trait]
+        impl ::toni::traits_helpers::Gateway for #manager_name {
+            async fn get_all_gateways(
+                &self,
+                dependencies: &::toni::FxHashMap<
+                    String,
+                    ::std::sync::Arc<Box<dyn ::toni::traits_helpers::ProviderTrait>>
+                >,
+            ) -> Result<
+                ::toni::FxHashMap<
+                    String,
+                    ::std::sync::Arc<Box<dyn ::toni::traits_helpers::GatewayTrait>>
+                >,
+                String
+            > {
+                let mut gateways = ::toni::FxHashMap::default();
+
+                #(#field_resolutions)*
+
+                let gateway_instance = ::std::sync::Arc::new(#struct_instantiation);
+                gateways.insert(
+                    #struct_token.to_string(),
+                    ::std::sync::Arc::new(
+                        Box::new(#instance_name {
+                            instance: gateway_instance,
+                        }) as Box<dyn ::toni::traits_helpers::GatewayTrait>
+                    )
+                );
+
+                Ok(gateways)
+            }
+
+            fn get_name(&self) -> String {
+                #struct_token.to_string()
+            }
+
+            fn get_token(&self) -> String {
+                #struct_token.to_string()
+            }
+
+            fn get_dependencies(&self) -> Vec<String> {
+                vec![#(#dependency_tokens),*]
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@ use syn::Ident;
 mod config_macro;
 mod controller_macro;
 mod enhancer;
+mod gateway_macro;
 mod markers_params;
 mod middleware_macro;
 mod module_macro;
@@ -31,6 +32,14 @@ pub fn controller_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
     proc_macro::TokenStream::from(output.unwrap_or_else(|e| e.to_compile_error()))
 }
 
+#[proc_macro_attribute]
+pub fn gateway_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let item = proc_macro2::TokenStream::from(item);
+    let output = gateway_macro::gateway_struct::handle_gateway_struct(attr, item);
+    proc_macro::TokenStream::from(output.unwrap_or_else(|e| e.to_compile_error()))
+}
+
 #[proc_macro_attribute]
 pub fn injectable(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = proc_macro2::TokenStream::from(attr);
@@ -46,6 +55,14 @@ pub fn provider_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
     injectable(attr, item)
 }
 
+#[proc_macro_attribute]
+pub fn middleware(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let item = proc_macro2::TokenStream::from(item);
+    let output = middleware_macro::middleware_struct::handle_middleware_struct(attr, item);
+    proc_macro::TokenStream::from(output.unwrap_or_else(|e| e.to_compile_error()))
+}
+
 #[proc_macro_attribute]
 pub fn controller(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
@@ -67,6 +84,71 @@ pub fn put(_attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn delete(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
+#[proc_macro_attribute]
+pub fn patch(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn head(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn options(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn all(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn route(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn set_metadata(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn roles(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn http_code(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn header(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn redirect(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn upload_limits(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn websocket_gateway(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn subscribe_message(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn on_connection(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+#[proc_macro_attribute]
+pub fn on_disconnect(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
 
 #[proc_macro_attribute]
 pub fn use_guards(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -83,6 +165,16 @@ pub fn use_pipes(attr: TokenStream, item: TokenStream) -> TokenStream {
     enhancer::use_pipes::use_pipes_impl(attr, item)
 }
 
+#[proc_macro_attribute]
+pub fn use_filters(attr: TokenStream, item: TokenStream) -> TokenStream {
+    enhancer::use_filters::use_filters_impl(attr, item)
+}
+
+#[proc_macro_attribute]
+pub fn use_middleware(attr: TokenStream, item: TokenStream) -> TokenStream {
+    enhancer::use_middleware::use_middleware_impl(attr, item)
+}
+
 // Helper derive to register #[inject] and #[default] as valid attributes
 // This allows them to be used on struct fields in injectable/controller_struct
 #[proc_macro_derive(Injectable, attributes(inject, default))]
@@ -107,6 +199,16 @@ pub fn toni_pipes(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+#[proc_macro_attribute]
+pub fn toni_filters(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_attribute]
+pub fn toni_middleware(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
 #[proc_macro_derive(Config, attributes(env, default, nested))]
 pub fn derive_config(input: TokenStream) -> TokenStream {
     config_macro::derive_config(input)
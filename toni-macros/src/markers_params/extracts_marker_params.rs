@@ -8,11 +8,10 @@ pub fn extract_body_from_param(marker_param: &MarkerParam) -> Result<TokenStream
     let param_name = &marker_param.param_name;
     let type_ident = &marker_param.type_ident;
     let extract_token_stream = quote! {
-      let body = match req.body {
-        Body::Json(json) => json.clone(),
-        _ => ::serde_json::json!({}),
+      let #param_name: #type_ident = match ::toni::extractors::body_dto(&req) {
+        Ok(dto) => dto,
+        Err(e) => return Box::new(e),
       };
-      let #param_name: #type_ident = ::serde_json::from_value(body).unwrap();
     };
     Ok(extract_token_stream)
 }
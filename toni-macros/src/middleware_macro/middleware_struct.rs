@@ -1,93 +1,89 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{Error, ItemImpl, ItemStruct, Result, parse2};
+use syn::{Error, Ident, ImplItem, ItemImpl, ItemStruct, Result, parse2};
 
-/// Handle #[middleware_struct] attribute macro
+use crate::{
+    provider_macro::provider_struct::handle_provider_struct,
+    shared::scope_parser::{ProviderScope, ProviderStructArgs},
+};
+
+/// Handle #[middleware] attribute macro
 ///
-/// This macro transforms a struct and its impl block into a proper Middleware implementation
+/// The struct is a provider built like an `#[injectable]` one, and its `handle`
+/// method becomes the `Middleware` implementation. List it in the module's
+/// `providers` and apply it with `consumer.apply::<AuthMiddleware>()`.
 ///
 /// Example input:
 /// ```ignore
-/// #[middleware_struct(
-///     pub struct MyMiddleware {
-///         config: String,
+/// #[middleware(
+///     pub struct AuthMiddleware {
+///         #[inject]
+///         verifier: TokenVerifier,
 ///     }
 /// )]
-/// impl MyMiddleware {
-///     pub fn new(config: String) -> Self {
-///         Self { config }
-///     }
-///
+/// impl AuthMiddleware {
 ///     async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
 ///         // middleware logic
 ///         next.run(req).await
 ///     }
 /// }
 /// ```
+///
+/// With `#[middleware] pub struct AuthMiddleware { .. }`, implement
+/// `Middleware` yourself.
 pub fn handle_middleware_struct(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
-    // Parse the struct definition from the attribute
-    let struct_attrs = parse2::<ItemStruct>(attr)?;
-
-    // Parse the impl block from the item
-    let impl_block = parse2::<ItemImpl>(item)?;
+    let args = parse2::<ProviderStructArgs>(attr.clone())?;
+    let struct_def = match &args.struct_def {
+        Some(struct_def) => struct_def.clone(),
+        None => parse2::<ItemStruct>(item.clone())?,
+    };
+    if args.scope == ProviderScope::Request {
+        return Err(Error::new(
+            struct_def.ident.span(),
+            "Middleware is built once at startup; remove `scope = \"request\"`",
+        ));
+    }
 
-    let struct_name = &struct_attrs.ident;
-    let struct_fields = &struct_attrs.fields;
-    let struct_vis = &struct_attrs.vis;
+    // New syntax: the struct alone, `Middleware` implemented by hand
+    if args.struct_def.is_none() {
+        return provider(attr, item);
+    }
 
-    // Find the handle method in the impl block
-    let handle_method = impl_block.items.iter()
-        .find_map(|item| {
-            if let syn::ImplItem::Fn(method) = item {
-                if method.sig.ident == "handle" {
-                    Some(method)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
+    let mut impl_block = parse2::<ItemImpl>(item)?;
+    let position = impl_block
+        .items
+        .iter()
+        .position(|item| matches!(item, ImplItem::Fn(method) if method.sig.ident == "handle"))
         .ok_or_else(|| {
             Error::new_spanned(
-                &impl_block,
-                "Middleware must have a 'handle' method with signature: async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult"
+                &impl_block.self_ty,
+                "Middleware must have a 'handle' method with signature: async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult",
             )
         })?;
+    let ImplItem::Fn(handle_method) = impl_block.items.remove(position) else {
+        unreachable!()
+    };
 
-    // Extract the method body
-    let handle_body = &handle_method.block;
-
-    // Validate the method signature (basic check)
-    if handle_method.sig.inputs.len() != 3 {
+    if handle_method.sig.asyncness.is_none() || handle_method.sig.inputs.len() != 3 {
         return Err(Error::new_spanned(
             &handle_method.sig,
-            "handle method must have exactly 3 parameters: &self, req: HttpRequest, next: Box<dyn Next>",
+            "handle method must be async with exactly 3 parameters: &self, req: HttpRequest, next: Box<dyn Next>",
         ));
     }
 
-    // Generate the complete code
-    let expanded = quote! {
-        // Keep the original struct definition
-        #[allow(dead_code)]
-        #struct_vis struct #struct_name #struct_fields
+    let struct_name = &struct_def.ident;
+    let provider = provider(attr, quote! { #impl_block })?;
 
-        // Keep the original impl block (with all other methods)
-        #[allow(dead_code)]
-        #impl_block
+    Ok(quote! {
+        #provider
 
-        // Generate the Middleware trait implementation
         #[::toni::async_trait]
         impl ::toni::traits_helpers::middleware::Middleware for #struct_name {
-            async fn handle(
-                &self,
-                req: ::toni::http_helpers::HttpRequest,
-                next: Box<dyn ::toni::traits_helpers::middleware::Next>,
-            ) -> ::toni::traits_helpers::middleware::MiddlewareResult {
-                #handle_body
-            }
+            #handle_method
         }
-    };
+    })
+}
 
-    Ok(expanded)
+fn provider(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    handle_provider_struct(attr, item, Ident::new("ProviderTrait", Span::call_site()))
 }
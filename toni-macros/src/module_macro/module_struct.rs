@@ -14,6 +14,7 @@ struct ModuleConfig {
     controllers: Vec<Ident>,
     providers: Vec<syn::Expr>,
     exports: Vec<Ident>,
+    gateways: Vec<Ident>,
     global: bool,
 }
 
@@ -22,6 +23,7 @@ struct ConfigParser {
     controllers: Vec<Ident>,
     providers: Vec<syn::Expr>,
     exports: Vec<Ident>,
+    gateways: Vec<Ident>,
     global: bool,
 }
 
@@ -32,6 +34,7 @@ impl Parse for ConfigParser {
             controllers: Vec::new(),
             providers: Vec::new(),
             exports: Vec::new(),
+            gateways: Vec::new(),
             global: false,
         };
 
@@ -112,6 +115,13 @@ impl Parse for ConfigParser {
                     let fields = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
                     config.exports = fields.into_iter().collect()
                 }
+                "gateways" => {
+                    let fields = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                    config.gateways = fields
+                        .into_iter()
+                        .map(|field| Ident::new(&format!("{}Manager", field), field.span()))
+                        .collect()
+                }
                 _ => return Err(syn::Error::new(key.span(), "Unknown field")),
             }
 
@@ -133,6 +143,7 @@ impl TryFrom<TokenStream> for ModuleConfig {
             controllers: parser.controllers,
             providers: parser.providers,
             exports: parser.exports,
+            gateways: parser.gateways,
             global: parser.global,
         })
     }
@@ -189,6 +200,7 @@ pub fn module(attr: TokenStream, item: TokenStream) -> TokenStream {
     let controllers = config.controllers;
     let providers = &config.providers;
     let exports = &config.exports;
+    let gateways = &config.gateways;
     let exports_string: Vec<String> = exports.iter().map(|e| e.to_string()).collect();
     let is_global = config.global;
 
@@ -235,6 +247,9 @@ pub fn module(attr: TokenStream, item: TokenStream) -> TokenStream {
             fn exports(&self) -> Option<Vec<String>> {
                 Some(vec![#(#exports_string.to_string()),*])
             }
+            fn gateways(&self) -> Option<Vec<Box<dyn ::toni::traits_helpers::Gateway>>> {
+                Some(vec![#(Box::new(#gateways)),*])
+            }
 
             // Include user-defined configure_middleware if present
             #configure_middleware_impl
@@ -1,10 +1,22 @@
 use quote::quote;
-use syn::{Attribute, Ident};
+use syn::{
+    Attribute, Ident, LitStr, Token, bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+};
+
+/// Methods registered by `#[all]`
+const ALL_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+const ROUTE_METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT",
+];
 
 fn is_http_method(segment: &Ident) -> bool {
     matches!(
         segment.to_string().as_str(),
-        "get" | "post" | "put" | "delete" | "patch" | "options" | "head"
+        "get" | "post" | "put" | "delete" | "patch" | "options" | "head" | "all" | "route"
     )
 }
 
@@ -22,6 +34,87 @@ pub fn find_macro_attribute(attrs: &[Attribute], type_macro: String) -> Option<&
     None
 }
 
+/// Arguments of `#[route("/path", methods = [GET, POST])]`
+struct RouteArgs {
+    path: LitStr,
+    methods: Vec<Ident>,
+}
+
+impl Parse for RouteArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let key: Ident = input.parse()?;
+        if key != "methods" {
+            return Err(syn::Error::new(key.span(), "expected `methods = [...]`"));
+        }
+        input.parse::<Token![=]>()?;
+
+        let content;
+        bracketed!(content in input);
+        let methods = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self {
+            path,
+            methods: methods.into_iter().collect(),
+        })
+    }
+}
+
+/// Parses a route attribute into its path and uppercase HTTP methods.
+///
+/// Handles the single-method attributes (`#[get("/x")]`, `#[patch("/x")]`, ...),
+/// `#[all("/x")]` and `#[route("/x", methods = [GET, POST])]`.
+pub fn parse_route_attribute(attr: &Attribute) -> syn::Result<(String, Vec<String>)> {
+    let name = attr_to_string(attr)
+        .map_err(|_| syn::Error::new(attr.span(), "Invalid attribute format"))?;
+
+    match name.as_str() {
+        "route" => {
+            let args = attr.parse_args::<RouteArgs>()?;
+            if args.methods.is_empty() {
+                return Err(syn::Error::new(
+                    args.path.span(),
+                    "`methods` must list at least one HTTP method",
+                ));
+            }
+
+            let mut methods = Vec::new();
+            for method in &args.methods {
+                let upper = method.to_string().to_uppercase();
+                if !ROUTE_METHODS.contains(&upper.as_str()) {
+                    return Err(syn::Error::new(
+                        method.span(),
+                        format!("Unknown HTTP method `{}`", method),
+                    ));
+                }
+                if methods.contains(&upper) {
+                    return Err(syn::Error::new(
+                        method.span(),
+                        format!("HTTP method `{}` is listed twice", method),
+                    ));
+                }
+                methods.push(upper);
+            }
+            Ok((args.path.value(), methods))
+        }
+        _ => {
+            let path = attr
+                .parse_args::<LitStr>()
+                .map_err(|_| syn::Error::new(attr.span(), "Invalid attribute format"))?
+                .value();
+            let methods = if name == "all" {
+                ALL_METHODS.iter().map(|m| m.to_string()).collect()
+            } else {
+                vec![name.to_uppercase()]
+            };
+            Ok((path, methods))
+        }
+    }
+}
+
 pub fn attr_to_string(attr: &Attribute) -> Result<String, ()> {
     let atribute_string = attr
         .path()
@@ -38,12 +131,9 @@ pub fn create_extract_body_dto_token_stream(
     body_dto: &Ident,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let get_body_dto_block = quote! {
-        let body = match req.body {
-            Body::Json(ref json) => json.clone(),
-            _ => ::serde_json::json!({}),
-          };
-          let body_json: #body_dto = ::serde_json::from_value(body).unwrap();
-          Some(Box::new(body_json))
+        ::toni::extractors::body_dto::<#body_dto>(_req).map(|dto| {
+            Some(Box::new(dto) as Box<dyn ::toni::traits_helpers::validate::Validatable>)
+        })
     };
     Ok(get_body_dto_block)
 }
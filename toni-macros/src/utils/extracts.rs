@@ -3,23 +3,66 @@ use std::collections::HashSet;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    Error, Expr, FnArg, Ident, ImplItemFn, ItemImpl, ItemStruct, LitStr, Pat, Result, Type,
-    TypePath, TypeReference, spanned::Spanned,
+    Error, Expr, FnArg, Ident, ImplItemFn, ItemImpl, ItemStruct, LitStr, Pat, Result, Token, Type,
+    TypePath, TypeReference,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
 };
 
 use crate::shared::TokenType;
 use crate::shared::dependency_info::{DependencyInfo, DependencySource};
 
-pub fn extract_controller_prefix(impl_block: &ItemImpl) -> Result<String> {
+/// `#[controller("/prefix", host = ":tenant.example.com")]`, both optional
+#[derive(Default)]
+struct ControllerArgs {
+    prefix: String,
+    host: Option<String>,
+}
+
+impl Parse for ControllerArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = ControllerArgs::default();
+        if input.peek(LitStr) {
+            args.prefix = input.parse::<LitStr>()?.value();
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+            match key.to_string().as_str() {
+                "host" => args.host = Some(value.value()),
+                _ => return Err(Error::new(key.span(), "expected `host`")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn extract_controller_args(impl_block: &ItemImpl) -> Result<ControllerArgs> {
     impl_block
         .attrs
         .iter()
         .find(|attr| attr.path().is_ident("controller"))
-        .map(|attr| attr.parse_args::<LitStr>().map(|lit| lit.value()))
+        .map(|attr| attr.parse_args::<ControllerArgs>())
         .transpose()
         .map(|opt| opt.unwrap_or_default())
 }
 
+pub fn extract_controller_prefix(impl_block: &ItemImpl) -> Result<String> {
+    extract_controller_args(impl_block).map(|args| args.prefix)
+}
+
+/// The host pattern the controller's routes are restricted to
+pub fn extract_controller_host(impl_block: &ItemImpl) -> Result<Option<String>> {
+    extract_controller_args(impl_block).map(|args| args.host)
+}
+
 pub fn extract_struct_dependencies(struct_attrs: &ItemStruct) -> Result<DependencyInfo> {
     let unique_types = HashSet::new();
     let mut fields = Vec::new();
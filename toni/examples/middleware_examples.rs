@@ -57,6 +57,7 @@ impl Middleware for LoggerMiddleware {
             match &req.body {
                 Body::Text(text) => println!("   Body: {}", text),
                 Body::Json(json) => println!("   Body: {}", json),
+                Body::Binary(bytes) => println!("   Body: <{} bytes>", bytes.len()),
             }
         }
 
@@ -144,27 +145,26 @@ impl Middleware for CorsMiddleware {
                 .clone();
             response
                 .headers
-                .push(("Access-Control-Allow-Origin".to_string(), origin));
-            response.headers.push((
-                "Access-Control-Allow-Methods".to_string(),
+                .insert("Access-Control-Allow-Origin", origin);
+            response.headers.insert(
+                "Access-Control-Allow-Methods",
                 self.allowed_methods.join(", "),
-            ));
-            response.headers.push((
-                "Access-Control-Allow-Headers".to_string(),
+            );
+            response.headers.insert(
+                "Access-Control-Allow-Headers",
                 self.allowed_headers.join(", "),
-            ));
+            );
 
             if self.allow_credentials {
-                response.headers.push((
-                    "Access-Control-Allow-Credentials".to_string(),
-                    "true".to_string(),
-                ));
+                response
+                    .headers
+                    .insert("Access-Control-Allow-Credentials", "true");
             }
 
             if let Some(max_age) = self.max_age {
                 response
                     .headers
-                    .push(("Access-Control-Max-Age".to_string(), max_age.to_string()));
+                    .insert("Access-Control-Max-Age", max_age.to_string());
             }
 
             return Ok(response);
@@ -183,13 +183,12 @@ impl Middleware for CorsMiddleware {
             .clone();
         response
             .headers
-            .push(("Access-Control-Allow-Origin".to_string(), origin));
+            .insert("Access-Control-Allow-Origin", origin);
 
         if self.allow_credentials {
-            response.headers.push((
-                "Access-Control-Allow-Credentials".to_string(),
-                "true".to_string(),
-            ));
+            response
+                .headers
+                .insert("Access-Control-Allow-Credentials", "true");
         }
 
         Ok(response)
@@ -228,11 +227,7 @@ impl Default for AuthMiddleware {
 impl Middleware for AuthMiddleware {
     async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
         // Check for auth header
-        let auth_header = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.eq_ignore_ascii_case(&self.header_name))
-            .map(|(_, value)| value);
+        let auth_header = req.header(&self.header_name);
 
         if let Some(header_value) = auth_header {
             if header_value.starts_with(&self.prefix) {
@@ -345,11 +340,10 @@ impl Middleware for RateLimitMiddleware {
         // Extract IP
         // TODO: Improve IP extraction, check X-Forwarded-For, etc.
         let ip = req
-            .headers
-            .iter()
-            .find(|(k, _)| k == "X-Forwarded-For" || k == "X-Real-IP")
-            .map(|(_, v)| v.clone())
-            .unwrap_or_else(|| "default_ip".to_string());
+            .header("X-Forwarded-For")
+            .or_else(|| req.header("X-Real-IP"))
+            .unwrap_or("default_ip")
+            .to_string();
 
         // Check rate limit in a separate scope to drop the lock before await
         let should_allow = {
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use super::PayloadTooLarge;
+
+/// Chunks the adapter may read ahead of the handler
+const READ_AHEAD: usize = 4;
+
+/// Failure while reading a streamed body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyStreamError {
+    /// The body grew past the route's limit
+    TooLarge(PayloadTooLarge),
+    /// The server failed to read the body
+    Read(String),
+}
+
+impl fmt::Display for BodyStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyStreamError::TooLarge(too_large) => write!(f, "{}", too_large),
+            BodyStreamError::Read(msg) => write!(f, "Failed to read request body: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BodyStreamError {}
+
+type Chunk = Result<Vec<u8>, BodyStreamError>;
+
+/// A request body the adapter reads while the handler runs
+///
+/// Routes whose handler takes `Multipart` or `Form` get one in the request
+/// extensions instead of a buffered `body`. Clones of the request share it,
+/// and the first extractor to [`take`](Self::take) it reads the body.
+#[derive(Clone)]
+pub struct BodyStream {
+    reader: Arc<Mutex<Option<BodyReader>>>,
+}
+
+impl BodyStream {
+    /// A stream and the sender the adapter feeds it through, which stops
+    /// with [`PayloadTooLarge`] past `limit`
+    pub fn channel(limit: Option<usize>) -> (BodySender, BodyStream) {
+        let (sender, receiver) = mpsc::channel(READ_AHEAD);
+        let sender = BodySender {
+            sender,
+            limit,
+            read: 0,
+        };
+        let stream = BodyStream {
+            reader: Arc::new(Mutex::new(Some(BodyReader { receiver }))),
+        };
+        (sender, stream)
+    }
+
+    /// Takes the body, `None` once it was taken
+    pub fn take(&self) -> Option<BodyReader> {
+        self.reader.lock().ok()?.take()
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream").finish_non_exhaustive()
+    }
+}
+
+/// Reads the chunks of a [`BodyStream`] as they arrive
+#[derive(Debug)]
+pub struct BodyReader {
+    receiver: mpsc::Receiver<Chunk>,
+}
+
+impl BodyReader {
+    /// The next chunk, or `None` at the end of the body
+    pub async fn next_chunk(&mut self) -> Option<Chunk> {
+        self.receiver.recv().await
+    }
+
+    /// Reads the rest of the body into memory
+    pub async fn collect(mut self) -> Result<Vec<u8>, BodyStreamError> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk().await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body)
+    }
+}
+
+/// The adapter's end of a [`BodyStream`]
+#[derive(Debug)]
+pub struct BodySender {
+    sender: mpsc::Sender<Chunk>,
+    limit: Option<usize>,
+    read: usize,
+}
+
+impl BodySender {
+    /// Passes a chunk on, waiting while the handler is behind. Returns
+    /// `false` once the adapter should stop reading: the body went past the
+    /// limit or nothing reads it anymore.
+    pub async fn send(&mut self, chunk: Vec<u8>) -> bool {
+        self.read = self.read.saturating_add(chunk.len());
+        if let Some(limit) = self.limit.filter(|limit| self.read > *limit) {
+            let too_large = BodyStreamError::TooLarge(PayloadTooLarge { limit });
+            let _ = self.sender.send(Err(too_large)).await;
+            return false;
+        }
+        self.sender.send(Ok(chunk)).await.is_ok()
+    }
+
+    /// Ends the body with a read error
+    pub async fn fail(self, message: impl Into<String>) {
+        let _ = self
+            .sender
+            .send(Err(BodyStreamError::Read(message.into())))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stops_past_the_limit() {
+        let (mut sender, stream) = BodyStream::channel(Some(4));
+        let reader = stream.take().unwrap();
+        assert!(stream.take().is_none());
+
+        tokio::spawn(async move {
+            assert!(sender.send(b"abc".to_vec()).await);
+            assert!(!sender.send(b"de".to_vec()).await);
+        });
+
+        assert_eq!(
+            reader.collect().await,
+            Err(BodyStreamError::TooLarge(PayloadTooLarge { limit: 4 }))
+        );
+    }
+}
@@ -3,10 +3,41 @@ use std::sync::Arc;
 use anyhow::Result;
 
 use crate::http_helpers::HttpMethod;
-use crate::injector::InstanceWrapper;
+use crate::injector::{GatewayWrapper, InstanceWrapper};
+use crate::router::RoutePath;
 
 pub trait HttpAdapter: Clone + Send + Sync {
     fn new() -> Self;
+    /// Translates a route into the router's syntax, `/users/{id}/{*rest}` by
+    /// default. Constraints are checked by the `InstanceWrapper`, the router
+    /// only needs the parameter names.
+    fn native_path(route: &RoutePath) -> String {
+        route.render(
+            |name| format!("{{{}}}", name),
+            |name| format!("{{*{}}}", name),
+        )
+    }
     fn add_route(&mut self, path: &str, method: HttpMethod, handler: Arc<InstanceWrapper>);
+    /// Receives every request no route matches, by path or by method; the
+    /// handler decides between `404` and `405`. Adapters without fallback
+    /// routing keep the router's own answer.
+    fn set_fallback(&mut self, handler: Arc<InstanceWrapper>) {
+        let _ = handler;
+        eprintln!(
+            "⚠️ {} doesn't support fallback routing, unmatched requests get its own response",
+            std::any::type_name::<Self>()
+        );
+    }
+    /// Registers a WebSocket endpoint; the adapter upgrades matching requests
+    /// and drives the connection through the gateway wrapper. Adapters
+    /// without WebSocket support leave the gateway unserved.
+    fn add_websocket_route(&mut self, path: &str, gateway: Arc<GatewayWrapper>) {
+        let _ = gateway;
+        eprintln!(
+            "⚠️ {} doesn't support WebSockets, the gateway at '{}' is not served",
+            std::any::type_name::<Self>(),
+            path
+        );
+    }
     fn listen(self, port: u16, hostname: &str) -> impl Future<Output = Result<()>> + Send;
 }
@@ -1,2 +1,5 @@
+mod body_stream;
+pub use body_stream::{BodyReader, BodySender, BodyStream, BodyStreamError};
+
 mod route_adapter;
-pub use route_adapter::RouteAdapter;
+pub use route_adapter::{PayloadTooLarge, RouteAdapter};
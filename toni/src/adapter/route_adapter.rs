@@ -1,28 +1,94 @@
+use std::fmt;
 use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::http_helpers::{HttpRequest, HttpResponse, IntoResponse};
+use crate::http_helpers::{
+    HttpException, HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode,
+};
 use crate::injector::InstanceWrapper;
 
+/// The request body is larger than the route accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    pub limit: usize,
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Request body exceeds the {} byte limit", self.limit)
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
 pub trait RouteAdapter {
     type Request;
     type Response;
 
-    fn adapt_request(request: Self::Request) -> impl Future<Output = Result<HttpRequest>>;
+    /// Reads the request, failing with [`PayloadTooLarge`] as soon as the body
+    /// grows past `body_limit`.
+    ///
+    /// With `stream_body` the body is not read up front: it is handed over as
+    /// a [`BodyStream`](super::BodyStream) in the request extensions, fed
+    /// while the handler reads it.
+    fn adapt_request(
+        request: Self::Request,
+        body_limit: Option<usize>,
+        stream_body: bool,
+    ) -> impl Future<Output = Result<HttpRequest>>;
 
+    /// Headers the server cannot send are logged and dropped
     fn adapt_response(
         response: Box<dyn IntoResponse<Response = HttpResponse>>,
     ) -> Result<Self::Response>;
 
+    /// A bare `500`, for when not even an error response can be adapted
+    fn server_error() -> Self::Response;
+
+    /// A body over the route's limit answers `413`, failing to adapt the
+    /// request or the response answers `500`
     fn handle_request(
         request: Self::Request,
         controller: Arc<InstanceWrapper>,
-    ) -> impl Future<Output = Result<Self::Response>> {
+    ) -> impl Future<Output = Self::Response> {
         async move {
-            let http_request = Self::adapt_request(request).await?;
-            let http_response = controller.handle_request(http_request).await;
-            Self::adapt_response(http_response)
+            let response = async {
+                let handler = controller.get_handler();
+                let body_limit = handler.body_limit();
+                let http_request =
+                    Self::adapt_request(request, body_limit, handler.streams_body()).await?;
+                // A streamed body is only read by the handler, so refuse one
+                // announced over the limit up front
+                let announced = http_request
+                    .header("content-length")
+                    .and_then(|length| length.trim().parse::<usize>().ok());
+                if let (Some(limit), Some(length)) = (body_limit, announced)
+                    && length > limit
+                {
+                    return Err(PayloadTooLarge { limit }.into());
+                }
+                let http_response = controller.handle_request(http_request).await;
+                Self::adapt_response(http_response)
+            };
+            let error = match response.await {
+                Ok(response) => return response,
+                Err(e) => e,
+            };
+            let response: Box<dyn IntoResponse<Response = HttpResponse>> =
+                match error.downcast_ref::<PayloadTooLarge>() {
+                    Some(too_large) => Box::new(
+                        ProblemDetails::new(StatusCode::PAYLOAD_TOO_LARGE)
+                            .with_detail(too_large.to_string()),
+                    ),
+                    None => {
+                        eprintln!("❌ Failed to handle request: {:#}", error);
+                        Box::new(HttpException::internal_server_error(
+                            "An error occurred while processing the request",
+                        ))
+                    }
+                };
+            Self::adapt_response(response).unwrap_or_else(|_| Self::server_error())
         }
     }
 }
@@ -6,11 +6,13 @@
 use crate::RequestManager;
 use crate::module_helpers::module_enum::ModuleDefinition;
 use crate::traits_helpers::{Controller, ModuleMetadata, Provider};
+use crate::websocket::WsServerManager;
 
 /// Built-in global module that provides core framework functionality
 ///
 /// Currently provides:
 /// - Request: HTTP request data access for handlers
+/// - WsServer: WebSocket rooms and broadcasting for gateways
 pub struct BuiltinModule;
 
 impl ModuleMetadata for BuiltinModule {
@@ -35,11 +37,11 @@ impl ModuleMetadata for BuiltinModule {
     }
 
     fn providers(&self) -> Option<Vec<Box<dyn Provider>>> {
-        Some(vec![Box::new(RequestManager)])
+        Some(vec![Box::new(RequestManager), Box::new(WsServerManager)])
     }
 
     fn exports(&self) -> Option<Vec<String>> {
-        Some(vec!["Request".to_string()])
+        Some(vec!["Request".to_string(), "WsServer".to_string()])
     }
 }
 
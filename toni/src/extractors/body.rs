@@ -1,15 +1,22 @@
 //! Generic body extractor that auto-detects content type
 
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
-use super::FromRequest;
-use crate::http_helpers::{Body as HttpBody, HttpRequest};
+use super::form_deserializer::from_multipart;
+use super::multipart::read_buffered;
+use super::{FormError, FromRequest, Multipart, Rejection, content_type, decode_urlencoded};
+use crate::http_helpers::{
+    Body as HttpBody, HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode,
+    parse_query, query_value,
+};
 
 /// Extractor for request body that auto-detects content type
 ///
 /// Supports:
 /// - `application/json` - parses as JSON
 /// - `application/x-www-form-urlencoded` - parses as form data
+/// - `multipart/form-data` - parses parts, with files as `UploadedFile`
 ///
 /// # Example
 ///
@@ -74,17 +81,22 @@ impl std::fmt::Display for BodyError {
 
 impl std::error::Error for BodyError {}
 
+/// `415` for an unsupported content type, `422` for a body that does not fit `T`
+impl Rejection for BodyError {
+    fn problem(&self) -> ProblemDetails {
+        let status = match self {
+            BodyError::UnsupportedContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            BodyError::DeserializeError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        ProblemDetails::new(status).with_detail(self.to_string())
+    }
+}
+
 impl<T: DeserializeOwned> FromRequest for Body<T> {
     type Error = BodyError;
 
     fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
-        // Get content type from headers
-        let content_type = req
-            .headers
-            .iter()
-            .find(|(name, _)| name.to_lowercase() == "content-type")
-            .map(|(_, value)| value.to_lowercase())
-            .unwrap_or_default();
+        let content_type = content_type(req).to_lowercase();
 
         if content_type.contains("application/json") {
             // Parse as JSON
@@ -94,9 +106,9 @@ impl<T: DeserializeOwned> FromRequest for Body<T> {
                         .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
                     Ok(Body(parsed))
                 }
-                HttpBody::Text(text) => {
-                    // Try to parse text as JSON
-                    let parsed: T = serde_json::from_str(text)
+                body => {
+                    // Try to parse raw bytes as JSON
+                    let parsed: T = serde_json::from_slice(&body.as_bytes())
                         .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
                     Ok(Body(parsed))
                 }
@@ -104,15 +116,21 @@ impl<T: DeserializeOwned> FromRequest for Body<T> {
         } else if content_type.contains("application/x-www-form-urlencoded") {
             // Parse as form data
             match &req.body {
-                HttpBody::Text(text) => {
-                    let parsed: T = serde_urlencoded::from_str(text)
-                        .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
-                    Ok(Body(parsed))
-                }
                 HttpBody::Json(_) => Err(BodyError::DeserializeError(
                     "Expected form data but got JSON".to_string(),
                 )),
+                _ => {
+                    let parsed: T = decode_urlencoded(req).map_err(BodyError::DeserializeError)?;
+                    Ok(Body(parsed))
+                }
             }
+        } else if content_type.starts_with("multipart/form-data") {
+            // Parse multipart parts, files included
+            let multipart = Multipart::from_buffered_request(req)
+                .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
+            let parsed: T = read_buffered(from_multipart(multipart))
+                .map_err(|e: FormError| BodyError::DeserializeError(e.to_string()))?;
+            Ok(Body(parsed))
         } else if content_type.is_empty() {
             // No content type - try JSON first, then form
             match &req.body {
@@ -121,14 +139,14 @@ impl<T: DeserializeOwned> FromRequest for Body<T> {
                         .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
                     Ok(Body(parsed))
                 }
-                HttpBody::Text(text) => {
+                body => {
+                    let bytes = body.as_bytes();
                     // Try JSON first
-                    if let Ok(parsed) = serde_json::from_str::<T>(text) {
+                    if let Ok(parsed) = serde_json::from_slice::<T>(&bytes) {
                         return Ok(Body(parsed));
                     }
                     // Fall back to form data
-                    let parsed: T = serde_urlencoded::from_str(text)
-                        .map_err(|e| BodyError::DeserializeError(e.to_string()))?;
+                    let parsed: T = decode_urlencoded(req).map_err(BodyError::DeserializeError)?;
                     Ok(Body(parsed))
                 }
             }
@@ -136,4 +154,30 @@ impl<T: DeserializeOwned> FromRequest for Body<T> {
             Err(BodyError::UnsupportedContentType(content_type))
         }
     }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// The body as JSON, and whether its scalars are all strings (forms); `None`
+/// for multipart and bodies that do not parse
+pub(super) fn body_value(req: &HttpRequest) -> Option<(Value, bool)> {
+    let content_type = content_type(req).to_lowercase();
+    let json_allowed = content_type.contains("application/json") || content_type.is_empty();
+    let form_allowed =
+        content_type.contains("application/x-www-form-urlencoded") || content_type.is_empty();
+
+    if let HttpBody::Json(value) = &req.body {
+        return json_allowed.then(|| (value.clone(), false));
+    }
+    let bytes = req.body.as_bytes();
+    if json_allowed && let Ok(value) = serde_json::from_slice(&bytes) {
+        return Some((value, false));
+    }
+    if form_allowed {
+        let fields = parse_query(&String::from_utf8_lossy(&bytes));
+        return Some((query_value(&fields), true));
+    }
+    None
 }
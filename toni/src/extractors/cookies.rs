@@ -0,0 +1,80 @@
+//! Cookie extractor
+
+use std::convert::Infallible;
+
+use super::FromRequest;
+use crate::http_helpers::{CookieJar, CookieKey, HttpRequest};
+
+/// Extractor for request cookies
+///
+/// Signed and encrypted cookies are read with the key configured through
+/// [`ToniFactory::use_cookie_key`](crate::ToniFactory::use_cookie_key).
+/// Extraction never fails; missing cookies are simply absent.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/me")]
+/// fn me(&self, cookies: Cookies) -> String {
+///     match cookies.get_signed("session") {
+///         Some(user) => format!("Hello {}", user),
+///         None => "Anonymous".to_string(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Cookies {
+    cookies: Vec<(String, String)>,
+    key: Option<CookieKey>,
+}
+
+impl Cookies {
+    /// Raw value of a cookie
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Value of a signed cookie, or `None` if it is missing, tampered with or no key is configured
+    pub fn get_signed(&self, name: &str) -> Option<String> {
+        self.key.as_ref()?.verify(name, self.get(name)?)
+    }
+
+    /// Value of an encrypted cookie, or `None` if it is missing, invalid or no key is configured
+    pub fn get_private(&self, name: &str) -> Option<String> {
+        self.key.as_ref()?.decrypt(name, self.get(name)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.cookies.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// An empty response jar sharing the configured key
+    pub fn jar(&self) -> CookieJar {
+        match &self.key {
+            Some(key) => CookieJar::with_key(key.clone()),
+            None => CookieJar::new(),
+        }
+    }
+}
+
+impl FromRequest for Cookies {
+    type Error = Infallible;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        Ok(Cookies {
+            cookies: req.cookies(),
+            key: req.extensions.get::<CookieKey>().cloned(),
+        })
+    }
+}
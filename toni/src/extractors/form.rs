@@ -0,0 +1,134 @@
+//! Form body extractor
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use super::form_deserializer::from_multipart;
+use super::{
+    AsyncFromRequest, FromRequest, Multipart, MultipartError, Rejection, content_type,
+    decode_strings, read_body,
+};
+use crate::adapter::BodyStreamError;
+use crate::http_helpers::{HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode};
+use crate::injector::ModuleRef;
+
+/// Extractor for `application/x-www-form-urlencoded` and `multipart/form-data` bodies
+///
+/// Multipart file parts deserialize into [`UploadedFile`](super::UploadedFile)
+/// fields, text parts into regular fields. Repeated fields fill `Vec`s. Like
+/// [`Multipart`], the body is streamed to the extractor, so files above the
+/// route's `disk_threshold` are written to disk as they arrive.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Deserialize)]
+/// struct AvatarUpload {
+///     user_id: u64,
+///     avatar: UploadedFile,
+///     attachments: Vec<UploadedFile>,
+/// }
+///
+/// #[post("/avatars")]
+/// fn upload(&self, Form(dto): Form<AvatarUpload>) -> String {
+///     format!("Received {} bytes for user {}", dto.avatar.size, dto.user_id)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Form<T>(pub T);
+
+impl<T> Form<T> {
+    /// Extract the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Form<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Form<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Error type for form extraction
+#[derive(Debug)]
+pub enum FormError {
+    /// Content type not supported
+    UnsupportedContentType(String),
+    /// Failed to read the multipart body
+    Multipart(MultipartError),
+    /// Failed to read the urlencoded body
+    Body(BodyStreamError),
+    /// Failed to deserialize form data
+    DeserializeError(String),
+}
+
+impl std::fmt::Display for FormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormError::UnsupportedContentType(ct) => {
+                write!(f, "Unsupported content type: {}", ct)
+            }
+            FormError::Multipart(e) => write!(f, "{}", e),
+            FormError::Body(e) => write!(f, "{}", e),
+            FormError::DeserializeError(msg) => {
+                write!(f, "Failed to deserialize form data: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormError {}
+
+impl Rejection for FormError {
+    fn problem(&self) -> ProblemDetails {
+        let status = match self {
+            FormError::UnsupportedContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            FormError::Multipart(e) => return e.problem(),
+            FormError::Body(e) => return MultipartError::Body(e.clone()).problem(),
+            FormError::DeserializeError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        ProblemDetails::new(status).with_detail(self.to_string())
+    }
+}
+
+impl From<MultipartError> for FormError {
+    fn from(e: MultipartError) -> Self {
+        FormError::Multipart(e)
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncFromRequest for Form<T> {
+    type Error = FormError;
+
+    async fn extract(req: &HttpRequest, _module: &ModuleRef) -> Result<Self, Self::Error> {
+        let content_type = content_type(req).to_lowercase();
+
+        if content_type.starts_with("multipart/form-data") {
+            let multipart = Multipart::from_request(req)?;
+            Ok(Form(from_multipart(multipart).await?))
+        } else if content_type.is_empty()
+            || content_type.starts_with("application/x-www-form-urlencoded")
+        {
+            let body = read_body(req).await.map_err(FormError::Body)?;
+            let parsed: T = decode_strings(&String::from_utf8_lossy(&body))
+                .map_err(FormError::DeserializeError)?;
+            Ok(Form(parsed))
+        } else {
+            Err(FormError::UnsupportedContentType(content_type))
+        }
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
@@ -0,0 +1,407 @@
+//! Serde deserializer over multipart parts
+//!
+//! Text parts behave like urlencoded values (numbers and booleans are parsed
+//! from their text), file parts deserialize into `UploadedFile`.
+
+use std::cell::RefCell;
+
+use serde::de::value::{Error, StringDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, Error as _, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+use serde::{Deserialize, forward_to_deserialize_any};
+
+use super::{FormError, Multipart, UploadedFile};
+
+/// Name `UploadedFile` deserializes as a newtype struct with. For it a file
+/// part is handed over through `UPLOAD` rather than as serde data, so no
+/// other input can make up an upload.
+const UPLOAD_TOKEN: &str = "$toni::UploadedFile";
+
+thread_local! {
+    static UPLOAD: RefCell<Option<UploadedFile>> = const { RefCell::new(None) };
+}
+
+enum FormValue {
+    Text(String),
+    File(UploadedFile),
+}
+
+/// Reads every part and deserializes them into `T`, grouping repeated names
+pub(crate) async fn from_multipart<T: DeserializeOwned>(
+    mut multipart: Multipart,
+) -> Result<T, FormError> {
+    let mut fields: Vec<(String, Vec<FormValue>)> = Vec::new();
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().to_string();
+        let value = if field.is_file() {
+            FormValue::File(field.into_uploaded_file().await?)
+        } else {
+            FormValue::Text(field.text().await?)
+        };
+
+        match fields.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, values)) => values.push(value),
+            None => fields.push((name, vec![value])),
+        }
+    }
+
+    T::deserialize(FormDeserializer { fields })
+        .map_err(|e| FormError::DeserializeError(e.to_string()))
+}
+
+struct FormDeserializer {
+    fields: Vec<(String, Vec<FormValue>)>,
+}
+
+impl<'de> Deserializer<'de> for FormDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(FieldsAccess {
+            fields: self.fields.into_iter(),
+            current: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FieldsAccess {
+    fields: std::vec::IntoIter<(String, Vec<FormValue>)>,
+    current: Option<(String, Vec<FormValue>)>,
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some((name, values)) => {
+                let key = seed.deserialize(StringDeserializer::<Error>::new(name.clone()))?;
+                self.current = Some((name, values));
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (name, values) = self
+            .current
+            .take()
+            .ok_or_else(|| Error::custom("value requested before key"))?;
+        seed.deserialize(ValuesDeserializer(values))
+            .map_err(|e| Error::custom(format!("field '{}': {}", name, e)))
+    }
+}
+
+/// All values sent under one field name
+struct ValuesDeserializer(Vec<FormValue>);
+
+macro_rules! forward_to_last_value {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Error> {
+            match self.0.pop() {
+                Some(value) => ValueDeserializer(value).$method(visitor),
+                None => Err(Error::custom("missing value")),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for ValuesDeserializer {
+    type Error = Error;
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(ValuesAccess(self.0.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.last() {
+            None => visitor.visit_none(),
+            Some(FormValue::Text(text)) if text.is_empty() => visitor.visit_none(),
+            Some(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name != UPLOAD_TOKEN {
+            return visitor.visit_newtype_struct(self);
+        }
+        match self.0.pop() {
+            Some(value) => ValueDeserializer(value).deserialize_newtype_struct(name, visitor),
+            None => Err(Error::custom("missing value")),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0.pop() {
+            Some(value) => ValueDeserializer(value).deserialize_struct(name, fields, visitor),
+            None => Err(Error::custom("missing value")),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0.pop() {
+            Some(value) => ValueDeserializer(value).deserialize_enum(name, variants, visitor),
+            None => Err(Error::custom("missing value")),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_last_value! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_map deserialize_identifier
+    }
+
+    forward_to_deserialize_any! {
+        unit_struct tuple_struct
+    }
+}
+
+struct ValuesAccess(std::vec::IntoIter<FormValue>);
+
+impl<'de> SeqAccess<'de> for ValuesAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.0.next() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// A single text or file part
+struct ValueDeserializer(FormValue);
+
+macro_rules! parse_text {
+    ($($method:ident => $visit:ident),*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.0 {
+                FormValue::Text(text) => match text.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(Error::custom(format!("invalid value '{}'", text))),
+                },
+                file => ValueDeserializer(file).deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            FormValue::Text(text) => visitor.visit_string(text),
+            FormValue::File(_) => Err(Error::custom("expected a text value, found a file")),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            FormValue::Text(text) => match text.trim() {
+                "true" | "on" | "1" => visitor.visit_bool(true),
+                "false" | "off" | "0" => visitor.visit_bool(false),
+                _ => Err(Error::custom(format!("invalid boolean '{}'", text))),
+            },
+            file => ValueDeserializer(file).deserialize_any(visitor),
+        }
+    }
+
+    parse_text! {
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32, deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128, deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16, deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64, deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32, deserialize_f64 => visit_f64,
+        deserialize_char => visit_char
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.0 {
+            FormValue::Text(text) if text.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            FormValue::File(file) if name == UPLOAD_TOKEN => {
+                UPLOAD.with(|upload| *upload.borrow_mut() = Some(file));
+                visitor.visit_unit()
+            }
+            FormValue::Text(_) if name == UPLOAD_TOKEN => Err(Error::custom(
+                "expected a file upload, found a text field",
+            )),
+            value => visitor.visit_newtype_struct(ValueDeserializer(value)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            FormValue::Text(text) => visitor.visit_enum(text.into_deserializer()),
+            FormValue::File(_) => Err(Error::custom("expected a text value, found a file")),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> Deserialize<'de> for UploadedFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UploadedFileVisitor;
+
+        impl<'de> Visitor<'de> for UploadedFileVisitor {
+            type Value = UploadedFile;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a file uploaded in a multipart form")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<UploadedFile, E> {
+                UPLOAD
+                    .with(|upload| upload.borrow_mut().take())
+                    .ok_or_else(|| E::invalid_type(de::Unexpected::Unit, &self))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(UPLOAD_TOKEN, UploadedFileVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::MultipartLimits;
+
+    #[derive(Deserialize)]
+    struct Upload {
+        title: String,
+        count: u32,
+        public: bool,
+        note: Option<String>,
+        tags: Vec<String>,
+        doc: UploadedFile,
+    }
+
+    #[tokio::test]
+    async fn test_deserializes_text_and_files() {
+        let mut raw = String::new();
+        for (headers, data) in [
+            ("name=\"title\"", "Report"),
+            ("name=\"count\"", "3"),
+            ("name=\"public\"", "on"),
+            ("name=\"note\"", ""),
+            ("name=\"tags\"", "a"),
+            ("name=\"tags\"", "b"),
+            (
+                "name=\"doc\"; filename=\"r.txt\"\r\nContent-Type: text/plain",
+                "hello",
+            ),
+        ] {
+            raw.push_str(&format!(
+                "--B\r\nContent-Disposition: form-data; {}\r\n\r\n{}\r\n",
+                headers, data
+            ));
+        }
+        raw.push_str("--B--\r\n");
+
+        let multipart = Multipart::new(raw.into_bytes(), "B", MultipartLimits::default());
+        let upload: Upload = from_multipart(multipart).await.unwrap();
+
+        assert_eq!(upload.title, "Report");
+        assert_eq!(upload.count, 3);
+        assert!(upload.public);
+        assert_eq!(upload.note, None);
+        assert_eq!(upload.tags, vec!["a", "b"]);
+        assert_eq!(upload.doc.filename.as_deref(), Some("r.txt"));
+        assert_eq!(upload.doc.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(upload.doc.size, 5);
+        assert_eq!(upload.doc.bytes().unwrap().as_ref(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_reports_field_name_on_error() {
+        let raw = "--B\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\nabc\r\n--B--\r\n";
+        let multipart = Multipart::new(raw.as_bytes().to_vec(), "B", MultipartLimits::default());
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Counter {
+            count: u32,
+        }
+
+        let err = from_multipart::<Counter>(multipart).await.unwrap_err();
+        assert!(err.to_string().contains("count"));
+    }
+
+    #[test]
+    fn test_uploads_only_come_from_multipart_parts() {
+        let forged = serde_json::json!({ "bytes": [1, 2], "temp_path": "/etc/hosts" });
+        assert!(serde_json::from_value::<UploadedFile>(forged).is_err());
+    }
+}
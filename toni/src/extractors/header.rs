@@ -0,0 +1,113 @@
+//! Typed header extractor
+
+use super::{FromRequest, Rejection};
+use crate::http_helpers::{
+    HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode, TypedHeader,
+};
+
+/// Extractor for a typed request header
+///
+/// Works with the built-in [`Authorization`](crate::http_helpers::Authorization),
+/// [`IfNoneMatch`](crate::http_helpers::IfNoneMatch) and
+/// [`AcceptLanguage`](crate::http_helpers::AcceptLanguage), or any type
+/// implementing [`TypedHeader`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/profile")]
+/// fn profile(&self, Header(auth): Header<Authorization>) -> String {
+///     match auth.bearer() {
+///         Some(token) => format!("Token: {}", token),
+///         None => "Unsupported scheme".to_string(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Header<T>(pub T);
+
+impl<T> Header<T> {
+    /// Extract the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Header<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Header<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Error type for typed header extraction
+#[derive(Debug)]
+pub enum HeaderError {
+    /// The header was not sent
+    Missing(&'static str),
+    /// The header could not be parsed
+    Invalid { name: &'static str, reason: String },
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeaderError::Missing(name) => write!(f, "Missing header '{}'", name),
+            HeaderError::Invalid { name, reason } => {
+                write!(f, "Invalid header '{}': {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+impl Rejection for HeaderError {
+    fn problem(&self) -> ProblemDetails {
+        ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(self.to_string())
+    }
+}
+
+impl<T: TypedHeader> FromRequest for Header<T> {
+    type Error = HeaderError;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let name = T::name();
+        let values: Vec<_> = req.headers.get_all(name).collect();
+        if values.is_empty() {
+            return Err(HeaderError::Missing(name));
+        }
+
+        T::decode(&values)
+            .map(Header)
+            .map_err(|reason| HeaderError::Invalid { name, reason })
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// Optional typed header: `None` when absent, an error only when malformed
+impl<T: TypedHeader> FromRequest for Option<Header<T>> {
+    type Error = HeaderError;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        match Header::<T>::from_request(req) {
+            Ok(header) => Ok(Some(header)),
+            Err(HeaderError::Missing(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
@@ -1,11 +1,15 @@
 //! JSON body extractor
 
+use serde::Serialize;
 use serde::de::DeserializeOwned;
 
-use super::FromRequest;
-use crate::http_helpers::{Body, HttpRequest};
+use super::{FromRequest, Rejection, content_type};
+use crate::http_helpers::{
+    Body, HeaderMap, HttpException, HttpRequest, HttpResponse, IntoResponse, ProblemDetails,
+    StatusCode, is_json,
+};
 
-/// Extractor for JSON request body
+/// Extractor for JSON request body, and a JSON response when returned from a handler
 ///
 /// # Example
 ///
@@ -20,10 +24,22 @@ use crate::http_helpers::{Body, HttpRequest};
 /// fn create_user(&self, Json(dto): Json<CreateUserDto>) -> String {
 ///     format!("Created user: {}", dto.name)
 /// }
+///
+/// #[get("/users/:id")]
+/// fn find_user(&self, Path(id): Path<u64>) -> Option<Json<UserDto>> {
+///     self.users.find(id).map(Json)
+/// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Json<T>(pub T);
 
+// Without `T: Debug`, so any `Serialize` type can be returned
+impl<T> std::fmt::Debug for Json<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Json<{}>", std::any::type_name::<T>())
+    }
+}
+
 impl<T> Json<T> {
     /// Extract the inner value
     pub fn into_inner(self) -> T {
@@ -50,6 +66,8 @@ impl<T> std::ops::DerefMut for Json<T> {
 pub enum JsonError {
     /// Request body is not JSON
     NotJson,
+    /// Body declared as JSON is malformed
+    SyntaxError(String),
     /// Failed to deserialize JSON body
     DeserializeError(String),
 }
@@ -58,6 +76,7 @@ impl std::fmt::Display for JsonError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             JsonError::NotJson => write!(f, "Request body is not JSON"),
+            JsonError::SyntaxError(msg) => write!(f, "Malformed JSON body: {}", msg),
             JsonError::DeserializeError(msg) => {
                 write!(f, "Failed to deserialize JSON body: {}", msg)
             }
@@ -67,12 +86,28 @@ impl std::fmt::Display for JsonError {
 
 impl std::error::Error for JsonError {}
 
+/// `415` for a body that is not JSON, `400` for malformed JSON, `422` for
+/// JSON that does not fit `T`
+impl Rejection for JsonError {
+    fn problem(&self) -> ProblemDetails {
+        let status = match self {
+            JsonError::NotJson => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            JsonError::SyntaxError(_) => StatusCode::BAD_REQUEST,
+            JsonError::DeserializeError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        ProblemDetails::new(status).with_detail(self.to_string())
+    }
+}
+
 impl<T: DeserializeOwned> FromRequest for Json<T> {
     type Error = JsonError;
 
     fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
         let json_value = match &req.body {
             Body::Json(value) => value.clone(),
+            // Declared as JSON but kept raw, e.g. by `Body::decode` on a syntax error
+            body if is_json(&content_type(req)) => serde_json::from_slice(&body.as_bytes())
+                .map_err(|e| JsonError::SyntaxError(e.to_string()))?,
             _ => return Err(JsonError::NotJson),
         };
 
@@ -81,4 +116,66 @@ impl<T: DeserializeOwned> FromRequest for Json<T> {
 
         Ok(Json(value))
     }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        match serde_json::to_value(&self.0) {
+            Ok(value) => HttpResponse {
+                body: Some(Body::Json(value)),
+                headers: HeaderMap::from_iter([("Content-Type", "application/json")]),
+                ..HttpResponse::new()
+            },
+            Err(e) => {
+                HttpException::internal_server_error(format!("Failed to serialize response: {}", e))
+                    .to_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+    }
+
+    fn json_body(response: &HttpResponse) -> &serde_json::Value {
+        match &response.body {
+            Some(Body::Json(value)) => value,
+            body => panic!("expected a JSON body, found {:?}", body),
+        }
+    }
+
+    #[test]
+    fn test_serializes_without_debug() {
+        let response = Json(User { name: "alice" }).to_response();
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            json_body(&response),
+            &serde_json::json!({ "name": "alice" })
+        );
+    }
+
+    #[test]
+    fn test_serialize_failure_is_a_server_error() {
+        let response = Json(HashMap::from([((1, 2), "tuple keys")])).to_response();
+        let expected = HttpException::internal_server_error(
+            "Failed to serialize response: key must be a string",
+        )
+        .to_response();
+        assert_eq!(response.status, 500);
+        assert_eq!(json_body(&response), json_body(&expected));
+    }
 }
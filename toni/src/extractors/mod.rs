@@ -17,21 +17,57 @@
 //! fn create_user(&self, Json(dto): Validated<Json<CreateUserDto>>) -> String {
 //!     format!("Created {}", dto.name)
 //! }
+//!
+//! #[post("/avatars")]
+//! fn upload(&self, Form(dto): Form<AvatarUpload>) -> String {
+//!     format!("Received {} bytes", dto.avatar.size)
+//! }
 //! ```
+//!
+//! A failed extraction answers with `application/problem+json` (RFC 7807),
+//! see [`Rejection`].
 
 mod body;
+mod cookies;
+mod form;
+mod form_deserializer;
+mod header;
 mod json;
+mod multipart;
 mod path;
+mod path_deserializer;
 mod query;
+mod rejection;
+mod strings_deserializer;
 mod validated;
 
-pub use body::Body;
-pub use json::Json;
-pub use path::Path;
-pub use query::Query;
-pub use validated::Validated;
+pub use body::{Body, BodyError};
+pub use cookies::Cookies;
+pub use form::{Form, FormError};
+pub use header::{Header, HeaderError};
+pub use json::{Json, JsonError};
+pub use multipart::{
+    FileData, Multipart, MultipartError, MultipartField, MultipartLimits, UploadedFile,
+};
+pub use path::{Path, PathError};
+pub use query::{Query, QueryError};
+pub use rejection::Rejection;
+pub(crate) use strings_deserializer::StringsDeserializer;
+pub use validated::{ValidatableExtractor, Validated, ValidationError, body_dto};
+
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
-use crate::http_helpers::HttpRequest;
+use crate::{
+    adapter::{BodyStream, BodyStreamError},
+    http_helpers::{
+        HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode, parse_query,
+        query_value,
+    },
+    injector::ModuleRef,
+};
 
 /// Trait for types that can be extracted from an HTTP request
 pub trait FromRequest: Sized {
@@ -40,4 +76,144 @@ pub trait FromRequest: Sized {
 
     /// Extract self from the request
     fn from_request(req: &HttpRequest) -> Result<Self, Self::Error>;
+
+    /// The response sent when extraction fails.
+    ///
+    /// Defaults to a `400` [`ProblemDetails`] with the error as `detail`;
+    /// extractors whose error is a [`Rejection`] return the error itself.
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(error.to_string()))
+    }
+}
+
+/// Extractor that can await and resolve providers, e.g. to load the current
+/// user from a repository.
+///
+/// Every [`FromRequest`] extractor implements it. Handler arguments of other
+/// types are extracted through it.
+///
+/// ```rust,ignore
+/// #[async_trait]
+/// impl AsyncFromRequest for CurrentUser {
+///     type Error = HttpException;
+///
+///     async fn extract(req: &HttpRequest, module: &ModuleRef) -> Result<Self, Self::Error> {
+///         let users = module
+///             .get::<UserRepository>(req)
+///             .await
+///             .ok_or_else(|| HttpException::internal_server_error("UserRepository is missing"))?;
+///         let token = req.headers.get_str("authorization").unwrap_or_default();
+///         users
+///             .find_by_token(token)
+///             .await
+///             .map(CurrentUser)
+///             .ok_or_else(|| HttpException::unauthorized("Invalid token"))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait AsyncFromRequest: Sized + Send {
+    /// The error type returned if extraction fails
+    type Error: std::fmt::Display + Send;
+
+    /// Extract self from the request, with the providers of the controller's module
+    async fn extract(req: &HttpRequest, module: &ModuleRef) -> Result<Self, Self::Error>;
+
+    /// The response sent when extraction fails, see [`FromRequest::rejection`]
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(error.to_string()))
+    }
+}
+
+#[async_trait]
+impl<T> AsyncFromRequest for T
+where
+    T: FromRequest + Send,
+    T::Error: Send,
+{
+    type Error = T::Error;
+
+    async fn extract(req: &HttpRequest, _module: &ModuleRef) -> Result<Self, Self::Error> {
+        T::from_request(req)
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        <T as FromRequest>::rejection(error)
+    }
+}
+
+/// Deserializes query strings and urlencoded forms, nested like
+/// [`query_value`] with numbers and booleans parsed from their text
+pub(crate) fn decode_strings<T: DeserializeOwned>(raw: &str) -> Result<T, String> {
+    let value = query_value(&parse_query(raw));
+    serde_path_to_error::deserialize(StringsDeserializer::new(&value, true))
+        .map_err(|error| error.to_string())
+}
+
+/// Urlencoded body of the request, decoded by [`decode_strings`]
+pub(crate) fn decode_urlencoded<T: DeserializeOwned>(req: &HttpRequest) -> Result<T, String> {
+    let body = req.body.as_bytes();
+    decode_strings(&String::from_utf8_lossy(&body))
+}
+
+/// The request body, read from its [`BodyStream`] on routes that stream it
+pub(crate) async fn read_body(req: &HttpRequest) -> Result<Cow<'_, [u8]>, BodyStreamError> {
+    match req.extensions.get::<BodyStream>().and_then(BodyStream::take) {
+        Some(reader) => reader.collect().await.map(Cow::Owned),
+        None => Ok(req.body.as_bytes()),
+    }
+}
+
+/// Content-Type header of the request, or an empty string if absent
+pub(crate) fn content_type(req: &HttpRequest) -> String {
+    req.headers
+        .get_str("content-type")
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Search {
+        q: String,
+        page: Option<u32>,
+        #[serde(default)]
+        tags: Vec<String>,
+        filter: Option<Filter>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Filter {
+        status: String,
+    }
+
+    #[test]
+    fn test_decode_strings() {
+        let search: Search = decode_strings("q=rust+web&page=2").unwrap();
+        assert_eq!((search.q.as_str(), search.page), ("rust web", Some(2)));
+
+        // Repeated and nested names
+        let search: Search = decode_strings("q=a&tags=x&tags=y&filter%5Bstatus%5D=open").unwrap();
+        assert_eq!(search.tags, ["x", "y"]);
+        assert_eq!(search.filter.unwrap().status, "open");
+
+        let search: Search = decode_strings("q=a&page=").unwrap();
+        assert_eq!(search.page, None);
+    }
+
+    #[test]
+    fn test_decodes_large_input() {
+        let tags = "tags=x&".repeat(20_000);
+        let search: Search = decode_strings(&format!("{}q=a&filter[status]=open", tags)).unwrap();
+        assert_eq!(search.tags.len(), 20_000);
+        assert_eq!(search.filter.unwrap().status, "open");
+
+        let error = decode_strings::<Search>("q=a&page=two").unwrap_err();
+        assert_eq!(error, "page: invalid type: string \"two\", expected u32");
+    }
 }
@@ -0,0 +1,934 @@
+//! Multipart/form-data extractor
+
+use std::borrow::Cow;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use std::{fs, io};
+
+use tempfile::NamedTempFile;
+
+use super::{FromRequest, Rejection, content_type};
+use crate::adapter::{BodyReader, BodyStream, BodyStreamError};
+use crate::http_helpers::{HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode};
+
+/// Limits applied while reading a multipart body
+///
+/// The defaults can be overridden per route with `#[upload_limits(...)]`. The
+/// limits also cap the body the adapter reads for handlers taking `Form`,
+/// `Multipart` or `Body`, see [`body_limit`](Self::body_limit):
+///
+/// ```rust,ignore
+/// #[post("/avatar")]
+/// #[upload_limits(max_files = 1, max_file_size = 2_097_152)]
+/// fn upload(&self, Form(dto): Form<AvatarUpload>) -> String {
+///     format!("Stored {} bytes", dto.avatar.size)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartLimits {
+    /// Maximum number of file parts
+    pub max_files: usize,
+    /// Maximum size of a single file in bytes
+    pub max_file_size: usize,
+    /// Maximum number of non-file parts
+    pub max_fields: usize,
+    /// Maximum size of a single non-file part in bytes
+    pub max_field_size: usize,
+    /// Files larger than this are written to a temporary file as their data
+    /// arrives instead of kept in memory
+    pub disk_threshold: Option<usize>,
+    /// Maximum size of the whole body in bytes, by default what the other limits allow
+    pub max_body_size: Option<usize>,
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 10,
+            max_file_size: 10 * 1024 * 1024,
+            max_fields: 100,
+            max_field_size: 64 * 1024,
+            disk_threshold: None,
+            max_body_size: None,
+        }
+    }
+}
+
+impl MultipartLimits {
+    /// Room for the boundary and headers of a part
+    const PART_OVERHEAD: usize = 1024;
+
+    /// The largest body the adapter reads for a route with these limits,
+    /// answering `413 Payload Too Large` past it
+    pub fn body_limit(&self) -> usize {
+        self.max_body_size.unwrap_or_else(|| {
+            let part = |count: usize, size: usize| {
+                count.saturating_mul(size.saturating_add(Self::PART_OVERHEAD))
+            };
+            part(self.max_files, self.max_file_size)
+                .saturating_add(part(self.max_fields, self.max_field_size))
+                .saturating_add(Self::PART_OVERHEAD)
+        })
+    }
+}
+
+/// Extractor that reads a `multipart/form-data` body part by part as it arrives
+///
+/// The adapter doesn't read the body of routes taking `Multipart` or `Form`
+/// up front but streams it to the handler: `next_field` waits for the next
+/// part's headers and a part's data comes in chunks. The route's
+/// [`body_limit`](MultipartLimits::body_limit) still caps the whole body, the
+/// part limits are enforced as data is read, and files above `disk_threshold`
+/// are written to disk chunk by chunk. Middleware, guards and interceptors of
+/// these routes see an empty `body`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/upload")]
+/// async fn upload(&self, mut multipart: Multipart) -> String {
+///     let mut names = Vec::new();
+///     while let Ok(Some(field)) = multipart.next_field().await {
+///         names.push(field.name().to_string());
+///     }
+///     names.join(",")
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Multipart {
+    reader: Option<BodyReader>,
+    buffer: Vec<u8>,
+    /// `--boundary`
+    delimiter: Vec<u8>,
+    /// `\r\n--boundary`, which ends the data of a part
+    closing: Vec<u8>,
+    state: State,
+    limits: MultipartLimits,
+    files: usize,
+    fields: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the first boundary
+    Preamble,
+    /// In the data of a part
+    Part,
+    /// At the boundary after a part
+    Boundary,
+    Finished,
+}
+
+/// What follows a boundary
+enum PartStart {
+    Closed,
+    Part {
+        headers: Vec<(String, String)>,
+        data_start: usize,
+    },
+}
+
+impl Multipart {
+    /// Largest header section of a part
+    const MAX_HEADERS_SIZE: usize = 8 * 1024;
+
+    /// Creates a reader over a buffered body using the given boundary
+    pub fn new(body: Vec<u8>, boundary: &str, limits: MultipartLimits) -> Self {
+        Self::with_source(None, body, boundary, limits)
+    }
+
+    /// Creates a reader over a body the adapter is still reading
+    pub fn from_stream(reader: BodyReader, boundary: &str, limits: MultipartLimits) -> Self {
+        Self::with_source(Some(reader), Vec::new(), boundary, limits)
+    }
+
+    fn with_source(
+        reader: Option<BodyReader>,
+        buffer: Vec<u8>,
+        boundary: &str,
+        limits: MultipartLimits,
+    ) -> Self {
+        let delimiter = format!("--{}", boundary).into_bytes();
+        let mut closing = b"\r\n".to_vec();
+        closing.extend_from_slice(&delimiter);
+        Self {
+            reader,
+            buffer,
+            delimiter,
+            closing,
+            state: State::Preamble,
+            limits,
+            files: 0,
+            fields: 0,
+        }
+    }
+
+    /// A reader over the buffered `body` of the request, even on routes that
+    /// stream it
+    pub(crate) fn from_buffered_request(req: &HttpRequest) -> Result<Self, MultipartError> {
+        let (boundary, limits) = Self::boundary_and_limits(req)?;
+        Ok(Self::new(req.body.as_bytes().into_owned(), &boundary, limits))
+    }
+
+    fn boundary_and_limits(req: &HttpRequest) -> Result<(String, MultipartLimits), MultipartError> {
+        let content_type = content_type(req);
+        if !content_type
+            .to_lowercase()
+            .starts_with("multipart/form-data")
+        {
+            return Err(MultipartError::NotMultipart(content_type));
+        }
+
+        let boundary = parse_boundary(&content_type).ok_or(MultipartError::MissingBoundary)?;
+        let limits = req
+            .extensions
+            .get::<MultipartLimits>()
+            .copied()
+            .unwrap_or_default();
+        Ok((boundary, limits))
+    }
+
+    pub fn limits(&self) -> &MultipartLimits {
+        &self.limits
+    }
+
+    /// Waits for the next part, or `None` once the closing boundary is
+    /// reached. What the previous part left unread is skipped.
+    pub async fn next_field(&mut self) -> Result<Option<MultipartField<'_>>, MultipartError> {
+        loop {
+            match self.state {
+                State::Finished => return Ok(None),
+                State::Boundary => break,
+                State::Part => while self.read_part_data().await?.is_some() {},
+                State::Preamble => match find(&self.buffer, &self.delimiter, 0) {
+                    Some(start) => {
+                        self.buffer.drain(..start);
+                        self.state = State::Boundary;
+                    }
+                    None => {
+                        let keep = self.delimiter.len() - 1;
+                        self.buffer.drain(..self.buffer.len().saturating_sub(keep));
+                        if !self.fill().await? {
+                            return Err(MultipartError::Malformed(
+                                "missing multipart boundary".to_string(),
+                            ));
+                        }
+                    }
+                },
+            }
+        }
+
+        let (headers, data_start) = loop {
+            match self.parse_part_start()? {
+                Some(PartStart::Closed) => {
+                    self.state = State::Finished;
+                    return Ok(None);
+                }
+                Some(PartStart::Part {
+                    headers,
+                    data_start,
+                }) => break (headers, data_start),
+                None => {
+                    self.fill().await?;
+                }
+            }
+        };
+
+        let (name, file_name) = parse_content_disposition(&headers)?;
+        let content_type = headers
+            .iter()
+            .find(|(key, _)| key == "content-type")
+            .map(|(_, value)| value.clone());
+
+        if file_name.is_some() {
+            self.files += 1;
+            if self.files > self.limits.max_files {
+                return Err(MultipartError::TooManyFiles(self.limits.max_files));
+            }
+        } else {
+            self.fields += 1;
+            if self.fields > self.limits.max_fields {
+                return Err(MultipartError::TooManyFields(self.limits.max_fields));
+            }
+        }
+
+        self.buffer.drain(..data_start);
+        self.state = State::Part;
+
+        Ok(Some(MultipartField {
+            multipart: self,
+            name,
+            file_name,
+            content_type,
+            headers,
+            size: 0,
+        }))
+    }
+
+    /// Reads another chunk into the buffer, `false` at the end of the body
+    async fn fill(&mut self) -> Result<bool, MultipartError> {
+        let Some(reader) = &mut self.reader else {
+            return Ok(false);
+        };
+        match reader.next_chunk().await {
+            Some(Ok(chunk)) => {
+                self.buffer.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            Some(Err(e)) => Err(MultipartError::Body(e)),
+            None => {
+                self.reader = None;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Parses the boundary line and part headers at the start of the buffer,
+    /// `None` while they have not fully arrived
+    fn parse_part_start(&self) -> Result<Option<PartStart>, MultipartError> {
+        let buffer = &self.buffer;
+        let incomplete = |msg: &str| match self.reader {
+            Some(_) => Ok(None),
+            None => Err(MultipartError::Malformed(msg.to_string())),
+        };
+
+        let mut pos = self.delimiter.len();
+        if buffer.len() < pos + 2 {
+            return incomplete("missing closing boundary");
+        }
+        if buffer[pos..].starts_with(b"--") {
+            return Ok(Some(PartStart::Closed));
+        }
+
+        while matches!(buffer.get(pos), Some(b' ') | Some(b'\t')) {
+            pos += 1;
+        }
+        if buffer.len() < pos + 4 {
+            return incomplete("unterminated part headers");
+        }
+        if !buffer[pos..].starts_with(b"\r\n") {
+            return Err(MultipartError::Malformed(
+                "expected line break after boundary".to_string(),
+            ));
+        }
+        pos += 2;
+
+        let (headers_raw, data_start) = if buffer[pos..].starts_with(b"\r\n") {
+            (&buffer[pos..pos], pos + 2)
+        } else {
+            match find(buffer, b"\r\n\r\n", pos) {
+                Some(end) => (&buffer[pos..end], end + 4),
+                None if buffer.len() - pos > Self::MAX_HEADERS_SIZE => {
+                    return Err(MultipartError::Malformed(
+                        "part headers are too large".to_string(),
+                    ));
+                }
+                None => return incomplete("unterminated part headers"),
+            }
+        };
+
+        Ok(Some(PartStart::Part {
+            headers: parse_headers(headers_raw)?,
+            data_start,
+        }))
+    }
+
+    /// The next piece of the current part's data, `None` at its end
+    async fn read_part_data(&mut self) -> Result<Option<Vec<u8>>, MultipartError> {
+        while self.state == State::Part {
+            if let Some(end) = find(&self.buffer, &self.closing, 0) {
+                // Keep the boundary, without the line break before it
+                let rest = self.buffer.split_off(end + 2);
+                let mut data = std::mem::replace(&mut self.buffer, rest);
+                data.truncate(end);
+                self.state = State::Boundary;
+                return Ok((!data.is_empty()).then_some(data));
+            }
+
+            // Anything but the start of a boundary split across chunks is data
+            let data_end = self
+                .buffer
+                .len()
+                .saturating_sub(self.closing.len() - 1);
+            if data_end > 0 {
+                let rest = self.buffer.split_off(data_end);
+                return Ok(Some(std::mem::replace(&mut self.buffer, rest)));
+            }
+
+            if !self.fill().await? {
+                return Err(MultipartError::Malformed(
+                    "missing closing boundary".to_string(),
+                ));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Runs a read over a buffered body, which never waits for data, to the end
+pub(crate) fn read_buffered<T, E: From<MultipartError>>(
+    read: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    match pin!(read).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(result) => result,
+        Poll::Pending => Err(MultipartError::Malformed("body is not buffered".to_string()).into()),
+    }
+}
+
+/// Error type for multipart extraction
+#[derive(Debug)]
+pub enum MultipartError {
+    /// Request is not `multipart/form-data`
+    NotMultipart(String),
+    /// Content-Type has no boundary parameter
+    MissingBoundary,
+    /// Body does not follow the multipart format
+    Malformed(String),
+    /// More file parts than allowed
+    TooManyFiles(usize),
+    /// A file part exceeds the size limit
+    FileTooLarge { field: String, limit: usize },
+    /// More non-file parts than allowed
+    TooManyFields(usize),
+    /// A non-file part exceeds the size limit
+    FieldTooLarge { field: String, limit: usize },
+    /// A text part is not valid UTF-8
+    InvalidUtf8(String),
+    /// Reading the streamed body failed or went past the route's limit
+    Body(BodyStreamError),
+    /// Failed to write an upload to disk
+    Io(String),
+}
+
+impl std::fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultipartError::NotMultipart(ct) => {
+                write!(f, "Expected multipart/form-data but got: {}", ct)
+            }
+            MultipartError::MissingBoundary => write!(f, "Multipart boundary is missing"),
+            MultipartError::Malformed(msg) => write!(f, "Malformed multipart body: {}", msg),
+            MultipartError::TooManyFiles(limit) => {
+                write!(f, "Too many files (limit is {})", limit)
+            }
+            MultipartError::FileTooLarge { field, limit } => {
+                write!(f, "File '{}' exceeds the {} byte limit", field, limit)
+            }
+            MultipartError::TooManyFields(limit) => {
+                write!(f, "Too many form fields (limit is {})", limit)
+            }
+            MultipartError::FieldTooLarge { field, limit } => {
+                write!(f, "Field '{}' exceeds the {} byte limit", field, limit)
+            }
+            MultipartError::InvalidUtf8(field) => {
+                write!(f, "Field '{}' is not valid UTF-8", field)
+            }
+            MultipartError::Body(e) => write!(f, "{}", e),
+            MultipartError::Io(msg) => write!(f, "Failed to store upload: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+/// `415` when the body is not multipart, `413` past the route's body limit,
+/// `500` when an upload cannot be stored, `400` otherwise
+impl Rejection for MultipartError {
+    fn problem(&self) -> ProblemDetails {
+        let status = match self {
+            MultipartError::NotMultipart(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            MultipartError::Body(BodyStreamError::TooLarge(_)) => StatusCode::PAYLOAD_TOO_LARGE,
+            MultipartError::Io(_) => {
+                return ProblemDetails::new(StatusCode::INTERNAL_SERVER_ERROR)
+                    .with_detail("Failed to store upload");
+            }
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ProblemDetails::new(status).with_detail(self.to_string())
+    }
+}
+
+impl FromRequest for Multipart {
+    type Error = MultipartError;
+
+    /// Reads the request's [`BodyStream`] when the adapter streams it, its
+    /// buffered `body` otherwise
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let (boundary, limits) = Self::boundary_and_limits(req)?;
+        match req.extensions.get::<BodyStream>().and_then(BodyStream::take) {
+            Some(reader) => Ok(Multipart::from_stream(reader, &boundary, limits)),
+            None => Ok(Multipart::new(
+                req.body.as_bytes().into_owned(),
+                &boundary,
+                limits,
+            )),
+        }
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// A single part of a multipart body, read before the next part
+#[derive(Debug)]
+pub struct MultipartField<'a> {
+    multipart: &'a mut Multipart,
+    name: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    headers: Vec<(String, String)>,
+    size: usize,
+}
+
+impl MultipartField<'_> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Part headers with lowercased names
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// True when the part was sent with a filename
+    pub fn is_file(&self) -> bool {
+        self.file_name.is_some()
+    }
+
+    /// The next piece of the part's data as it arrives, `None` at its end
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, MultipartError> {
+        let Some(chunk) = self.multipart.read_part_data().await? else {
+            return Ok(None);
+        };
+        self.size += chunk.len();
+
+        let limits = &self.multipart.limits;
+        if self.is_file() && self.size > limits.max_file_size {
+            return Err(MultipartError::FileTooLarge {
+                field: self.name.clone(),
+                limit: limits.max_file_size,
+            });
+        }
+        if !self.is_file() && self.size > limits.max_field_size {
+            return Err(MultipartError::FieldTooLarge {
+                field: self.name.clone(),
+                limit: limits.max_field_size,
+            });
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Reads the rest of the part into memory
+    pub async fn bytes(mut self) -> Result<Vec<u8>, MultipartError> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    pub async fn text(self) -> Result<String, MultipartError> {
+        let name = self.name.clone();
+        String::from_utf8(self.bytes().await?).map_err(|_| MultipartError::InvalidUtf8(name))
+    }
+
+    /// Reads the part into an upload. Past the route's `disk_threshold` the
+    /// data is moved to a temporary file, which then receives the rest as it
+    /// arrives.
+    pub async fn into_uploaded_file(mut self) -> Result<UploadedFile, MultipartError> {
+        let store_error = |e: io::Error| MultipartError::Io(e.to_string());
+        let threshold = self.multipart.limits.disk_threshold;
+        let mut bytes = Vec::new();
+        let mut temp_file: Option<NamedTempFile> = None;
+
+        while let Some(chunk) = self.chunk().await? {
+            match &mut temp_file {
+                Some(file) => file.write_all(&chunk).map_err(store_error)?,
+                None => {
+                    bytes.extend_from_slice(&chunk);
+                    if threshold.is_some_and(|threshold| bytes.len() > threshold) {
+                        let mut file = NamedTempFile::new().map_err(store_error)?;
+                        file.write_all(&bytes).map_err(store_error)?;
+                        bytes = Vec::new();
+                        temp_file = Some(file);
+                    }
+                }
+            }
+        }
+
+        let data = match temp_file {
+            Some(mut file) => {
+                file.flush().map_err(store_error)?;
+                FileData::TempFile(file)
+            }
+            None => FileData::Bytes(bytes),
+        };
+
+        Ok(UploadedFile {
+            filename: self.file_name,
+            content_type: self.content_type,
+            size: self.size,
+            data,
+        })
+    }
+}
+
+/// Where the contents of an uploaded file live
+#[derive(Debug)]
+pub enum FileData {
+    Bytes(Vec<u8>),
+    /// Temporary file, created with a random name and removed when dropped
+    /// unless persisted
+    TempFile(NamedTempFile),
+}
+
+/// A file received in a multipart body
+///
+/// Usable as a field of a `Form<T>` DTO:
+///
+/// ```rust,ignore
+/// #[derive(Deserialize)]
+/// struct AvatarUpload {
+///     user_id: u64,
+///     avatar: UploadedFile,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct UploadedFile {
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub size: usize,
+    pub data: FileData,
+}
+
+impl UploadedFile {
+    /// File contents, read from disk if the upload was spooled
+    pub fn bytes(&self) -> io::Result<Cow<'_, [u8]>> {
+        match &self.data {
+            FileData::Bytes(bytes) => Ok(Cow::Borrowed(bytes)),
+            FileData::TempFile(file) => {
+                let mut bytes = Vec::with_capacity(self.size);
+                file.reopen()?.read_to_end(&mut bytes)?;
+                Ok(Cow::Owned(bytes))
+            }
+        }
+    }
+
+    /// Path of the temporary file, if the upload was spooled to disk
+    pub fn temp_path(&self) -> Option<&Path> {
+        match &self.data {
+            FileData::TempFile(file) => Some(file.path()),
+            FileData::Bytes(_) => None,
+        }
+    }
+
+    /// Moves the upload to `path`, keeping it after this value is dropped
+    pub fn persist(self, path: impl AsRef<Path>) -> io::Result<()> {
+        let target = path.as_ref();
+        match self.data {
+            FileData::Bytes(bytes) => fs::write(target, bytes),
+            FileData::TempFile(file) => match file.persist(target) {
+                Ok(_) => Ok(()),
+                // Not on the same filesystem, the temporary file goes on drop
+                Err(e) => fs::copy(e.file.path(), target).map(|_| ()),
+            },
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if needle.is_empty() || from > haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
+
+pub(crate) fn parse_boundary(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("boundary") {
+            let value = value.trim().trim_matches('"');
+            (!value.is_empty()).then(|| value.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_headers(raw: &[u8]) -> Result<Vec<(String, String)>, MultipartError> {
+    let raw = String::from_utf8_lossy(raw);
+    raw.split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line.split_once(':').ok_or_else(|| {
+                MultipartError::Malformed(format!("invalid part header '{}'", line))
+            })?;
+            Ok((name.trim().to_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn parse_content_disposition(
+    headers: &[(String, String)],
+) -> Result<(String, Option<String>), MultipartError> {
+    let disposition = headers
+        .iter()
+        .find(|(key, _)| key == "content-disposition")
+        .map(|(_, value)| value.as_str())
+        .ok_or_else(|| {
+            MultipartError::Malformed("part is missing Content-Disposition".to_string())
+        })?;
+
+    let mut name = None;
+    let mut file_name = None;
+    let mut file_name_ext = None;
+
+    for param in split_params(disposition).into_iter().skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = unquote(value.trim());
+        match key.trim().to_lowercase().as_str() {
+            "name" => name = Some(value),
+            "filename" => file_name = Some(value),
+            "filename*" => file_name_ext = decode_ext_value(&value),
+            _ => {}
+        }
+    }
+
+    let name = name.ok_or_else(|| {
+        MultipartError::Malformed("Content-Disposition is missing a name".to_string())
+    })?;
+
+    Ok((name, file_name_ext.or(file_name)))
+}
+
+/// Splits `form-data; name="a;b"; filename="c"` on semicolons outside quotes
+fn split_params(value: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for c in value.chars() {
+        match c {
+            _ if escaped => {
+                current.push(c);
+                escaped = false;
+            }
+            '\\' if in_quotes => {
+                current.push(c);
+                escaped = true;
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ';' if !in_quotes => params.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    params.push(current);
+    params
+}
+
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
+    }
+}
+
+/// Decodes RFC 5987 values such as `UTF-8''na%C3%AFve.txt`
+fn decode_ext_value(value: &str) -> Option<String> {
+    let (charset, rest) = value.split_once('\'')?;
+    let (_, encoded) = rest.split_once('\'')?;
+    if !charset.eq_ignore_ascii_case("utf-8") {
+        return None;
+    }
+
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(parts: &[&str]) -> Vec<u8> {
+        let mut body = String::new();
+        for part in parts {
+            body.push_str("--XyZ\r\n");
+            body.push_str(part);
+            body.push_str("\r\n");
+        }
+        body.push_str("--XyZ--\r\n");
+        body.into_bytes()
+    }
+
+    #[test]
+    fn test_parse_boundary() {
+        assert_eq!(
+            parse_boundary("multipart/form-data; boundary=\"abc\""),
+            Some("abc".to_string())
+        );
+        assert_eq!(parse_boundary("multipart/form-data"), None);
+    }
+
+    #[tokio::test]
+    async fn test_reads_text_and_file_parts() {
+        let raw = body(&[
+            "Content-Disposition: form-data; name=\"title\"\r\n\r\nHello",
+            "Content-Disposition: form-data; name=\"doc\"; filename=\"a;b.txt\"\r\nContent-Type: text/plain\r\n\r\nline1\r\nline2",
+        ]);
+        let mut multipart = Multipart::new(raw, "XyZ", MultipartLimits::default());
+
+        let title = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(title.name(), "title");
+        assert!(!title.is_file());
+        assert_eq!(title.text().await.unwrap(), "Hello");
+
+        let doc = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(doc.file_name(), Some("a;b.txt"));
+        assert_eq!(doc.content_type(), Some("text/plain"));
+        assert_eq!(doc.bytes().await.unwrap(), b"line1\r\nline2");
+
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preserves_binary_content() {
+        let mut raw =
+            b"--XyZ\r\nContent-Disposition: form-data; name=\"f\"; filename=\"x.bin\"\r\n\r\n"
+                .to_vec();
+        raw.extend_from_slice(&[0, 159, 146, 150, 255]);
+        raw.extend_from_slice(b"\r\n--XyZ--\r\n");
+
+        let mut multipart = Multipart::new(raw, "XyZ", MultipartLimits::default());
+        let file = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(file.bytes().await.unwrap(), [0, 159, 146, 150, 255]);
+    }
+
+    #[tokio::test]
+    async fn test_enforces_file_limits() {
+        let raw = body(&[
+            "Content-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\n12345",
+            "Content-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n\r\n1",
+        ]);
+        let limits = MultipartLimits {
+            max_file_size: 4,
+            ..Default::default()
+        };
+        let mut multipart = Multipart::new(raw.clone(), "XyZ", limits);
+        let file = multipart.next_field().await.unwrap().unwrap();
+        assert!(matches!(
+            file.bytes().await,
+            Err(MultipartError::FileTooLarge { .. })
+        ));
+
+        let limits = MultipartLimits {
+            max_files: 1,
+            ..Default::default()
+        };
+        let mut multipart = Multipart::new(raw, "XyZ", limits);
+        assert!(multipart.next_field().await.is_ok());
+        assert!(matches!(
+            multipart.next_field().await,
+            Err(MultipartError::TooManyFiles(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hands_out_data_before_the_body_ends() {
+        let (mut sender, stream) = BodyStream::channel(None);
+        let mut multipart =
+            Multipart::from_stream(stream.take().unwrap(), "XyZ", MultipartLimits::default());
+
+        sender
+            .send(b"--XyZ\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\n0123456789".to_vec())
+            .await;
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        let first = field.chunk().await.unwrap().unwrap();
+        assert!(!first.is_empty() && b"0123456789".starts_with(&first));
+
+        sender.send(b"\r\n--XyZ--\r\n".to_vec()).await;
+        drop(sender);
+        let mut data = first;
+        data.extend(field.bytes().await.unwrap());
+        assert_eq!(data, b"0123456789");
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spools_large_files_to_disk_as_they_arrive() {
+        let (mut sender, stream) = BodyStream::channel(None);
+        tokio::spawn(async move {
+            sender
+                .send(b"--XyZ\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\n".to_vec())
+                .await;
+            for chunk in [b"01234".as_slice(), b"56789", b"\r\n--XyZ--\r\n"] {
+                sender.send(chunk.to_vec()).await;
+            }
+        });
+        let limits = MultipartLimits {
+            disk_threshold: Some(4),
+            ..Default::default()
+        };
+        let mut multipart = Multipart::from_stream(stream.take().unwrap(), "XyZ", limits);
+        let file = multipart
+            .next_field()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_uploaded_file()
+            .await
+            .unwrap();
+
+        let path = file.temp_path().unwrap().to_path_buf();
+        assert_eq!(file.size, 10);
+        assert_eq!(file.bytes().unwrap().as_ref(), b"0123456789");
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_reports_a_body_over_the_limit() {
+        let (mut sender, stream) = BodyStream::channel(Some(8));
+        tokio::spawn(async move {
+            sender
+                .send(b"--XyZ\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n".to_vec())
+                .await;
+        });
+        let mut multipart =
+            Multipart::from_stream(stream.take().unwrap(), "XyZ", MultipartLimits::default());
+
+        let error = multipart.next_field().await.unwrap_err();
+        assert_eq!(error.problem().status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
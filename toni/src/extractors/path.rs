@@ -1,16 +1,43 @@
 //! Path parameter extractor
 
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
+
+use serde::de::DeserializeOwned;
+
+use super::{FromRequest, Rejection, path_deserializer::from_path_params};
+use crate::http_helpers::{HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode};
+use crate::injector::HandlerInfo;
+use crate::router::{host_param_names, route_param_names};
 
 /// Extractor for path parameters
 ///
+/// A single parameter is parsed into `T`; several are read into a tuple, in
+/// route order, or into a struct by name. A value that does not parse
+/// answers `400 Bad Request` problem details naming the parameter in `param`.
+///
 /// # Example
 ///
 /// ```rust,ignore
-/// #[get("/users/:id")]
+/// #[get("/users/{id}")]
 /// fn get_user(&self, Path(id): Path<i32>) -> String {
 ///     format!("User {}", id)
 /// }
+///
+/// #[get("/orgs/{org}/repos/{repo}")]
+/// fn get_repo(&self, Path((org, repo)): Path<(String, u64)>) -> String {
+///     format!("Repo {} of {}", repo, org)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct RepoParams {
+///     org: String,
+///     repo: u64,
+/// }
+///
+/// #[get("/orgs/{org}/repos/{repo}/issues")]
+/// fn issues(&self, Path(params): Path<RepoParams>) -> String {
+///     format!("Issues of {}/{}", params.org, params.repo)
+/// }
 /// ```
 #[derive(Debug, Clone)]
 pub struct Path<T>(pub T);
@@ -43,6 +70,14 @@ pub enum PathError {
     NotFound(String),
     /// Failed to parse the parameter value
     ParseError(String),
+    /// A parameter's value does not fit its field
+    InvalidParam {
+        name: String,
+        value: String,
+        message: String,
+    },
+    /// The route has more or fewer parameters than the tuple
+    WrongNumberOfParams { expected: usize, found: usize },
 }
 
 impl std::fmt::Display for PathError {
@@ -50,12 +85,73 @@ impl std::fmt::Display for PathError {
         match self {
             PathError::NotFound(name) => write!(f, "Path parameter '{}' not found", name),
             PathError::ParseError(msg) => write!(f, "Failed to parse path parameter: {}", msg),
+            PathError::InvalidParam {
+                name,
+                value,
+                message,
+            } => write!(
+                f,
+                "Invalid value '{}' for path parameter '{}': {}",
+                value, name, message
+            ),
+            PathError::WrongNumberOfParams { expected, found } => {
+                write!(f, "Expected {} path parameters, found {}", expected, found)
+            }
         }
     }
 }
 
 impl std::error::Error for PathError {}
 
+impl Rejection for PathError {
+    fn problem(&self) -> ProblemDetails {
+        let problem = ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(self.to_string());
+        match self {
+            PathError::NotFound(name) | PathError::InvalidParam { name, .. } => {
+                problem.with_extension("param", name.as_str())
+            }
+            _ => problem,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Path<T> {
+    type Error = PathError;
+
+    fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        from_path_params(ordered_params(req)).map(Path)
+    }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// The path parameters in the order of the controller's host then the matched
+/// route template, the ones they do not name last, by name
+fn ordered_params(req: &HttpRequest) -> Vec<(String, String)> {
+    let mut params: Vec<(String, String)> = req
+        .path_params
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    let names = req
+        .extensions
+        .get::<Arc<HandlerInfo>>()
+        .map(|handler| {
+            let mut names = handler.host().map(host_param_names).unwrap_or_default();
+            names.extend(route_param_names(handler.route()));
+            names
+        })
+        .unwrap_or_default();
+
+    params.sort_by_key(|(name, _)| {
+        let position = names.iter().position(|param| param == name);
+        (position.unwrap_or(usize::MAX), name.clone())
+    });
+    params
+}
+
 /// Helper to extract a single path parameter by name
 pub fn extract_path_param<T: FromStr>(
     req: &crate::http_helpers::HttpRequest,
@@ -73,3 +169,80 @@ where
         .parse::<T>()
         .map_err(|e| PathError::ParseError(format!("{}: {}", name, e)))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::http_helpers::{Body, Extensions, HttpMethod};
+
+    fn request(route: &str, params: &[(&str, &str)]) -> HttpRequest {
+        request_on(HandlerInfo::new("RepoController", "get"), route, params)
+    }
+
+    fn request_on(handler: HandlerInfo, route: &str, params: &[(&str, &str)]) -> HttpRequest {
+        let mut extensions = Extensions::new();
+        extensions.insert(Arc::new(handler.with_route(
+            route.to_string(),
+            HttpMethod::GET,
+            "RepoModule",
+        )));
+        HttpRequest {
+            body: Body::Text(String::new()),
+            headers: Default::default(),
+            method: "GET".to_string(),
+            uri: "/".to_string(),
+            query_params: HashMap::new(),
+            path_params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            extensions,
+        }
+    }
+
+    #[test]
+    fn test_tuple_follows_route_order() {
+        let params = [("repo", "42"), ("org", "toni"), ("issue", "7")];
+        for route in [
+            "/orgs/{org}/repos/{repo}/issues/{issue}",
+            "/orgs/:org/repos/:repo/issues/:issue",
+        ] {
+            let Path((org, repo, issue)) =
+                Path::<(String, u64, u32)>::from_request(&request(route, &params)).unwrap();
+            assert_eq!((org.as_str(), repo, issue), ("toni", 42, 7));
+        }
+    }
+
+    #[test]
+    fn test_tuple_starts_with_host_params() {
+        let handler = HandlerInfo::new("RepoController", "get").with_host(":tenant.example.com");
+        let req = request_on(
+            handler,
+            "/repos/:repo",
+            &[("repo", "42"), ("tenant", "acme")],
+        );
+        let Path((tenant, repo)) = Path::<(String, u64)>::from_request(&req).unwrap();
+        assert_eq!((tenant.as_str(), repo), ("acme", 42));
+    }
+
+    #[test]
+    fn test_rejection_names_the_parameter() {
+        let req = request(
+            "/orgs/{org}/repos/{repo}",
+            &[("org", "toni"), ("repo", "x")],
+        );
+        let error = Path::<(String, u64)>::from_request(&req).unwrap_err();
+
+        let problem = error.problem();
+        assert_eq!(problem.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(problem.extension("param"), Some(&"repo".into()));
+        assert!(
+            problem
+                .detail()
+                .unwrap()
+                .starts_with("Invalid value 'x' for path parameter 'repo'")
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Serde deserializer over path parameters
+//!
+//! Maps and structs read the parameters by name, tuples and sequences read
+//! them in route order, and any other type reads the only parameter. Values
+//! are parsed from their text like query strings, and errors name the
+//! parameter that failed.
+
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::PathError;
+
+/// Deserializes `T` from the `(name, value)` pairs of a route, in route order
+pub(crate) fn from_path_params<T: DeserializeOwned>(
+    params: Vec<(String, String)>,
+) -> Result<T, PathError> {
+    T::deserialize(PathDeserializer { params }).map_err(|error| match error {
+        Error::Missing(name) => PathError::NotFound(name),
+        Error::Count { expected, found } => PathError::WrongNumberOfParams { expected, found },
+        Error::Param {
+            name,
+            value,
+            message,
+        } => PathError::InvalidParam {
+            name,
+            value,
+            message,
+        },
+        Error::Custom(message) => PathError::ParseError(message),
+    })
+}
+
+#[derive(Debug)]
+enum Error {
+    Missing(String),
+    Count {
+        expected: usize,
+        found: usize,
+    },
+    Param {
+        name: String,
+        value: String,
+        message: String,
+    },
+    Custom(String),
+}
+
+impl Error {
+    /// Blames `name` for an error raised while deserializing its value
+    fn in_param(self, name: &str, value: &str) -> Self {
+        match self {
+            Error::Custom(message) => Error::Param {
+                name: name.to_string(),
+                value: value.to_string(),
+                message,
+            },
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Missing(name) => write!(f, "missing path parameter '{}'", name),
+            Error::Count { expected, found } => {
+                write!(f, "expected {} path parameters, found {}", expected, found)
+            }
+            Error::Param { name, message, .. } => write!(f, "'{}': {}", name, message),
+            Error::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Error::Missing(field.to_string())
+    }
+}
+
+struct PathDeserializer {
+    params: Vec<(String, String)>,
+}
+
+impl PathDeserializer {
+    fn single(mut self) -> Result<ValueDeserializer, Error> {
+        match self.params.len() {
+            1 => {
+                let (name, value) = self.params.remove(0);
+                Ok(ValueDeserializer { name, value })
+            }
+            found => Err(Error::Count { expected: 1, found }),
+        }
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let value = self.single()?;
+            let (name, text) = (value.name.clone(), value.value.clone());
+            value.$method(visitor).map_err(|error| error.in_param(&name, &text))
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for PathDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(ParamsAccess {
+            params: self.params.into_iter(),
+            current: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(ValuesAccess(self.params.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        if self.params.len() != len {
+            return Err(Error::Count {
+                expected: len,
+                found: self.params.len(),
+            });
+        }
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let value = self.single()?;
+        let (param, text) = (value.name.clone(), value.value.clone());
+        value
+            .deserialize_enum(name, variants, visitor)
+            .map_err(|error| error.in_param(&param, &text))
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+        deserialize_identifier
+    }
+}
+
+struct ParamsAccess {
+    params: std::vec::IntoIter<(String, String)>,
+    current: Option<(String, String)>,
+}
+
+impl<'de> MapAccess<'de> for ParamsAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.params.next() {
+            Some((name, value)) => {
+                let key = seed.deserialize(name.as_str().into_deserializer())?;
+                self.current = Some((name, value));
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (name, value) = self
+            .current
+            .take()
+            .ok_or_else(|| Error::Custom("value requested before key".to_string()))?;
+        seed.deserialize(ValueDeserializer {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .map_err(|error| error.in_param(&name, &value))
+    }
+}
+
+struct ValuesAccess(std::vec::IntoIter<(String, String)>);
+
+impl<'de> SeqAccess<'de> for ValuesAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.0.next() {
+            Some((name, value)) => seed
+                .deserialize(ValueDeserializer {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .map(Some)
+                .map_err(|error| error.in_param(&name, &value)),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// The value of one path parameter
+struct ValueDeserializer {
+    name: String,
+    value: String,
+}
+
+impl ValueDeserializer {
+    fn invalid(self, message: impl fmt::Display) -> Error {
+        Error::Param {
+            name: self.name,
+            value: self.value,
+            message: message.to_string(),
+        }
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident),*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.value.parse() {
+                Ok(value) => visitor.$visit(value),
+                Err(error) => Err(self.invalid(error)),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.value)
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32, deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128, deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16, deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64, deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32, deserialize_f64 => visit_f64,
+        deserialize_char => visit_char
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct RepoParams {
+        org: String,
+        repo: u64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Visibility {
+        Public,
+        Private,
+    }
+
+    #[test]
+    fn test_deserializes_tuples_structs_and_single_values() {
+        let repo = params(&[("org", "toni"), ("repo", "42")]);
+
+        let (org, id): (String, u64) = from_path_params(repo.clone()).unwrap();
+        assert_eq!((org.as_str(), id), ("toni", 42));
+
+        let parsed: RepoParams = from_path_params(repo.clone()).unwrap();
+        assert_eq!(
+            parsed,
+            RepoParams {
+                org: "toni".to_string(),
+                repo: 42
+            }
+        );
+
+        let map: HashMap<String, String> = from_path_params(repo).unwrap();
+        assert_eq!(map["repo"], "42");
+
+        let id: u32 = from_path_params(params(&[("id", "7")])).unwrap();
+        assert_eq!(id, 7);
+
+        let visibility: Visibility = from_path_params(params(&[("v", "public")])).unwrap();
+        assert_eq!(visibility, Visibility::Public);
+        let visibility: Visibility = from_path_params(params(&[("v", "private")])).unwrap();
+        assert_eq!(visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_errors_name_the_parameter() {
+        let repo = params(&[("org", "toni"), ("repo", "latest")]);
+
+        match from_path_params::<(String, u64)>(repo.clone()) {
+            Err(PathError::InvalidParam { name, value, .. }) => {
+                assert_eq!((name.as_str(), value.as_str()), ("repo", "latest"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match from_path_params::<RepoParams>(repo) {
+            Err(PathError::InvalidParam { name, .. }) => assert_eq!(name, "repo"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match from_path_params::<Visibility>(params(&[("v", "secret")])) {
+            Err(PathError::InvalidParam { name, .. }) => assert_eq!(name, "v"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_errors_on_missing_or_extra_parameters() {
+        assert!(matches!(
+            from_path_params::<RepoParams>(params(&[("org", "toni")])),
+            Err(PathError::NotFound(name)) if name == "repo"
+        ));
+        assert!(matches!(
+            from_path_params::<(String, u64)>(params(&[("org", "toni")])),
+            Err(PathError::WrongNumberOfParams {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            from_path_params::<u64>(params(&[("org", "toni"), ("repo", "42")])),
+            Err(PathError::WrongNumberOfParams {
+                expected: 1,
+                found: 2
+            })
+        ));
+    }
+}
@@ -2,11 +2,16 @@
 
 use serde::de::DeserializeOwned;
 
-use super::FromRequest;
-use crate::http_helpers::HttpRequest;
+use super::{FromRequest, Rejection, decode_strings};
+use crate::http_helpers::{HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode};
 
 /// Extractor for query parameters
 ///
+/// The query string is percent-decoded and nested like
+/// [`query_value`](crate::http_helpers::query_value): repeated names fill a
+/// `Vec`, `filter[status]` fills a nested struct, and numbers and booleans are
+/// parsed from their text.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -64,21 +69,22 @@ impl std::fmt::Display for QueryError {
 
 impl std::error::Error for QueryError {}
 
+impl Rejection for QueryError {
+    fn problem(&self) -> ProblemDetails {
+        ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(self.to_string())
+    }
+}
+
 impl<T: DeserializeOwned> FromRequest for Query<T> {
     type Error = QueryError;
 
     fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
-        // Convert query_params HashMap to a format serde can deserialize
-        let query_string: String = req
-            .query_params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("&");
-
-        let value: T = serde_urlencoded::from_str(&query_string)
-            .map_err(|e| QueryError::DeserializeError(e.to_string()))?;
+        let value: T = decode_strings(req.query_string()).map_err(QueryError::DeserializeError)?;
 
         Ok(Query(value))
     }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
 }
@@ -0,0 +1,66 @@
+//! Problem details for extractor errors
+
+use super::{
+    BodyError, FormError, HeaderError, JsonError, MultipartError, PathError, QueryError,
+    ValidationError,
+};
+use crate::http_helpers::{Exception, HttpResponse, IntoResponse, ProblemDetails};
+
+/// An extractor error answered with RFC 7807 problem details.
+///
+/// Rejections go through the exception filters, where they can be matched by
+/// type or, for all of them at once, through [`Exception::problem`]:
+///
+/// ```
+/// use toni::http_helpers::{Body, Exception, HttpRequest, HttpResponse};
+/// use toni::traits_helpers::ExceptionFilter;
+///
+/// /// Answers every rejection with the legacy error body
+/// struct LegacyRejections;
+///
+/// impl ExceptionFilter for LegacyRejections {
+///     fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+///         let problem = exception.problem()?;
+///         Some(HttpResponse {
+///             body: Some(Body::Json(serde_json::json!({
+///                 "error": problem.title(),
+///                 "details": problem.detail(),
+///             }))),
+///             status: problem.status().as_u16(),
+///             ..HttpResponse::new()
+///         })
+///     }
+/// }
+/// ```
+pub trait Rejection: std::error::Error + Send + Sync + 'static {
+    fn problem(&self) -> ProblemDetails;
+}
+
+macro_rules! rejection_response {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl IntoResponse for $error {
+                type Response = HttpResponse;
+
+                fn to_response(&self) -> Self::Response {
+                    self.problem().to_response()
+                }
+
+                fn exception(&self) -> Option<Exception<'_>> {
+                    Some(Exception::from_rejection(self))
+                }
+            }
+        )*
+    };
+}
+
+rejection_response!(
+    BodyError,
+    FormError,
+    HeaderError,
+    JsonError,
+    MultipartError,
+    PathError,
+    QueryError,
+    ValidationError,
+);
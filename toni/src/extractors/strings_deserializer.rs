@@ -0,0 +1,314 @@
+//! Serde deserializer over a [`query_value`](crate::http_helpers::query_value)
+//! tree, whose scalars are all strings
+//!
+//! Numbers and booleans are parsed from their text when the target type asks
+//! for them, in the same pass that reads the tree. A name sent once where a
+//! list is expected becomes a list of one.
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    DeserializeSeed, Deserializer, Error as _, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::{Error, Map, Value};
+
+/// Reads `value` leniently: strings fill number and boolean fields, numbers
+/// and booleans fill string fields.
+///
+/// `lists` also lets a single value fill a list, as sent by query strings and
+/// forms.
+pub(crate) struct StringsDeserializer<'de> {
+    value: &'de Value,
+    lists: bool,
+}
+
+impl<'de> StringsDeserializer<'de> {
+    pub(crate) fn new(value: &'de Value, lists: bool) -> Self {
+        Self { value, lists }
+    }
+}
+
+macro_rules! parse_text {
+    ($($method:ident => $visit:ident),*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.value {
+                Value::String(text) => match text.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(Error::invalid_type(Unexpected::Str(text), &visitor)),
+                },
+                value => value.$method(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for StringsDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Object(object) => visitor.visit_map(ObjectAccess::new(object, self.lists)),
+            Value::Array(items) => visitor.visit_seq(ItemsAccess::new(items, self.lists)),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(text) => match text.trim() {
+                "true" | "on" | "1" => visitor.visit_bool(true),
+                "false" | "off" | "0" => visitor.visit_bool(false),
+                _ => Err(Error::invalid_type(Unexpected::Str(text), &visitor)),
+            },
+            value => value.deserialize_bool(visitor),
+        }
+    }
+
+    parse_text! {
+        deserialize_i8 => visit_i8, deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32, deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128, deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16, deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64, deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32, deserialize_f64 => visit_f64
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Number(number) => visitor.visit_string(number.to_string()),
+            Value::Bool(flag) => visitor.visit_string(flag.to_string()),
+            value => value.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            Value::String(text) if text.is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Array(items) => visitor.visit_seq(ItemsAccess::new(items, self.lists)),
+            Value::Object(_) | Value::Null => self.value.deserialize_seq(visitor),
+            scalar if self.lists => {
+                visitor.visit_seq(ItemsAccess::new(std::slice::from_ref(scalar), true))
+            }
+            value => value.deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Object(object) => visitor.visit_map(ObjectAccess::new(object, self.lists)),
+            value => value.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Value::String(text) => visitor.visit_enum(text.as_str().into_deserializer()),
+            value => value.deserialize_enum(name, variants, visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct identifier ignored_any
+    }
+}
+
+struct ObjectAccess<'de> {
+    fields: serde_json::map::Iter<'de>,
+    value: Option<&'de Value>,
+    lists: bool,
+}
+
+impl<'de> ObjectAccess<'de> {
+    fn new(object: &'de Map<String, Value>, lists: bool) -> Self {
+        Self {
+            fields: object.iter(),
+            value: None,
+            lists,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for ObjectAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(BorrowedStrDeserializer::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::custom("value requested before key"))?;
+        seed.deserialize(StringsDeserializer::new(value, self.lists))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+struct ItemsAccess<'de> {
+    items: std::slice::Iter<'de, Value>,
+    lists: bool,
+}
+
+impl<'de> ItemsAccess<'de> {
+    fn new(items: &'de [Value], lists: bool) -> Self {
+        Self {
+            items: items.iter(),
+            lists,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ItemsAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.items.next() {
+            Some(item) => seed
+                .deserialize(StringsDeserializer::new(item, self.lists))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Line {
+        qty: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Batch {
+        ids: Vec<u32>,
+        lines: Vec<Line>,
+        tags: Vec<String>,
+        archived: bool,
+        page: Option<u32>,
+        label: String,
+    }
+
+    fn batch(value: Value) -> Result<Batch, Error> {
+        Batch::deserialize(StringsDeserializer::new(&value, true))
+    }
+
+    #[test]
+    fn test_parses_scalars_from_text() {
+        let ids: Vec<Value> = (0..10_000).map(|id| json!(id.to_string())).collect();
+        let batch = batch(json!({
+            "ids": ids,
+            "lines": [{ "qty": "1" }, { "qty": " 2" }],
+            "tags": "rust",
+            "archived": "true",
+            "page": "",
+            "label": 7
+        }))
+        .unwrap();
+        assert_eq!(batch.ids[9_999], 9_999);
+        assert_eq!(batch.lines[1].qty, 2);
+        assert_eq!(batch.tags, ["rust"]);
+        assert!(batch.archived);
+        assert_eq!(batch.page, None);
+        assert_eq!(batch.label, "7");
+    }
+
+    #[test]
+    fn test_reports_text_that_isnt_a_number() {
+        let value =
+            json!({ "ids": ["1", "x"], "lines": [], "tags": [], "archived": "on", "label": "" });
+        let error = batch(value).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: string \"x\", expected u32"
+        );
+    }
+
+    #[test]
+    fn test_only_fills_lists_when_asked() {
+        let value = json!({ "tags": "rust" });
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Tags {
+            tags: Vec<String>,
+        }
+        assert!(Tags::deserialize(StringsDeserializer::new(&value, false)).is_err());
+    }
+}
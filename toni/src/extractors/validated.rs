@@ -1,9 +1,16 @@
 //! Validated extractor wrapper
 
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use validator::Validate;
 
-use super::FromRequest;
-use crate::http_helpers::HttpRequest;
+use super::{FromRequest, Rejection};
+use crate::{
+    http_helpers::{
+        Body as HttpBody, HttpRequest, HttpResponse, IntoResponse, ProblemDetails, StatusCode,
+    },
+    pipes::{FieldErrors, ValidationPipe},
+};
 
 /// Wrapper that validates the inner extractor
 ///
@@ -55,8 +62,10 @@ impl<T> std::ops::DerefMut for Validated<T> {
 pub enum ValidationError {
     /// Extraction failed
     ExtractionError(String),
-    /// Validation failed
-    ValidationFailed(validator::ValidationErrors),
+    /// Decoding or validation failed for some fields
+    ValidationFailed(FieldErrors),
+    /// The inner extractor rejected the request
+    Rejected(Box<ProblemDetails>),
 }
 
 impl std::fmt::Display for ValidationError {
@@ -66,26 +75,58 @@ impl std::fmt::Display for ValidationError {
             ValidationError::ValidationFailed(errors) => {
                 write!(f, "Validation failed: {}", errors)
             }
+            ValidationError::Rejected(problem) => write!(f, "{}", problem),
         }
     }
 }
 
 impl std::error::Error for ValidationError {}
 
+/// `400` with the failures keyed by field path as `errors`, or the problem
+/// of the inner extractor
+impl Rejection for ValidationError {
+    fn problem(&self) -> ProblemDetails {
+        match self {
+            ValidationError::ExtractionError(_) => {
+                ProblemDetails::new(StatusCode::BAD_REQUEST).with_detail(self.to_string())
+            }
+            ValidationError::ValidationFailed(errors) => {
+                ProblemDetails::new(StatusCode::BAD_REQUEST)
+                    .with_detail("Validation failed")
+                    .with_extension("errors", serde_json::to_value(errors).unwrap_or_default())
+            }
+            ValidationError::Rejected(problem) => (**problem).clone(),
+        }
+    }
+}
+
 /// Trait for extractors that contain validatable data
-pub trait ValidatableExtractor {
+pub trait ValidatableExtractor: Sized {
     type Inner: Validate;
 
     fn get_inner(&self) -> &Self::Inner;
+
+    /// Decodes the request with the [`ValidationPipe`] settings, or `None` to
+    /// fall back to `FromRequest`
+    fn decode(_req: &HttpRequest, _pipe: &ValidationPipe) -> Option<Result<Self, ValidationError>> {
+        None
+    }
 }
 
 // Implement for Json<T> where T: Validate
-impl<T: Validate> ValidatableExtractor for super::Json<T> {
+impl<T: Validate + DeserializeOwned> ValidatableExtractor for super::Json<T> {
     type Inner = T;
 
     fn get_inner(&self) -> &Self::Inner {
         &self.0
     }
+
+    fn decode(req: &HttpRequest, pipe: &ValidationPipe) -> Option<Result<Self, ValidationError>> {
+        match &req.body {
+            HttpBody::Json(value) => Some(pipe.decode(value.clone(), false).map(super::Json)),
+            _ => None,
+        }
+    }
 }
 
 // Implement for Path<T> where T: Validate
@@ -98,41 +139,66 @@ impl<T: Validate> ValidatableExtractor for super::Path<T> {
 }
 
 // Implement for Query<T> where T: Validate
-impl<T: Validate> ValidatableExtractor for super::Query<T> {
+impl<T: Validate + DeserializeOwned> ValidatableExtractor for super::Query<T> {
     type Inner = T;
 
     fn get_inner(&self) -> &Self::Inner {
         &self.0
     }
+
+    fn decode(req: &HttpRequest, pipe: &ValidationPipe) -> Option<Result<Self, ValidationError>> {
+        Some(pipe.decode(req.query_value(), true).map(super::Query))
+    }
 }
 
 // Implement for Body<T> where T: Validate
-impl<T: Validate> ValidatableExtractor for super::body::Body<T> {
+impl<T: Validate + DeserializeOwned> ValidatableExtractor for super::body::Body<T> {
     type Inner = T;
 
     fn get_inner(&self) -> &Self::Inner {
         &self.0
     }
+
+    fn decode(req: &HttpRequest, pipe: &ValidationPipe) -> Option<Result<Self, ValidationError>> {
+        let (value, strings) = super::body::body_value(req)?;
+        Some(pipe.decode(value, strings).map(super::body::Body))
+    }
 }
 
 impl<E> FromRequest for Validated<E>
 where
     E: FromRequest + ValidatableExtractor,
-    E::Error: std::fmt::Display,
+    E::Error: Rejection,
 {
     type Error = ValidationError;
 
     fn from_request(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let pipe = ValidationPipe::for_request(req);
+
         // First extract the inner value
-        let extracted =
-            E::from_request(req).map_err(|e| ValidationError::ExtractionError(e.to_string()))?;
+        let extracted = match E::decode(req, &pipe) {
+            Some(result) => result?,
+            None => E::from_request(req)
+                .map_err(|e| ValidationError::Rejected(Box::new(e.problem())))?,
+        };
 
         // Then validate it
-        extracted
-            .get_inner()
-            .validate()
-            .map_err(ValidationError::ValidationFailed)?;
+        pipe.validate(extracted.get_inner())?;
 
         Ok(Validated(extracted))
     }
+
+    fn rejection(error: Self::Error) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        Box::new(error)
+    }
+}
+
+/// Decodes the JSON body of a body DTO with the request's [`ValidationPipe`] settings
+#[doc(hidden)]
+pub fn body_dto<T: DeserializeOwned>(req: &HttpRequest) -> Result<T, ValidationError> {
+    let body = match &req.body {
+        HttpBody::Json(value) => value.clone(),
+        _ => Value::Object(Default::default()),
+    };
+    ValidationPipe::for_request(req).decode(body, false)
 }
@@ -1,8 +1,111 @@
+use std::borrow::Cow;
+
 use serde_json::Value;
 
 #[derive(Debug, Clone)]
 pub enum Body {
     Text(String),
     Json(Value),
-    // Binary(Vec<u8>),
+    Binary(Vec<u8>),
+}
+
+impl Body {
+    /// Builds a body from raw request bytes.
+    ///
+    /// Valid JSON becomes `Json`, other UTF-8 becomes `Text`, and anything else
+    /// is kept untouched as `Binary`.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => match serde_json::from_str::<Value>(&text) {
+                Ok(json) => Body::Json(json),
+                Err(_) => Body::Text(text),
+            },
+            Err(e) => Body::Binary(e.into_bytes()),
+        }
+    }
+
+    /// Builds a request body according to its `Content-Type`.
+    ///
+    /// Only JSON media types (`application/json`, `application/*+json`) are
+    /// parsed, and only when the body is valid JSON; without a content type
+    /// the body is sniffed like [`Body::from_bytes`]. Forms, multipart and
+    /// other bodies stay raw until an extractor decodes them.
+    pub fn decode(bytes: Vec<u8>, content_type: Option<&str>) -> Self {
+        match content_type {
+            None => Self::from_bytes(bytes),
+            Some(content_type) if is_json(content_type) => {
+                match serde_json::from_slice::<Value>(&bytes) {
+                    Ok(json) => Body::Json(json),
+                    Err(_) => Self::raw(bytes),
+                }
+            }
+            Some(_) => Self::raw(bytes),
+        }
+    }
+
+    /// `Text` for UTF-8, `Binary` otherwise
+    fn raw(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => Body::Text(text),
+            Err(e) => Body::Binary(e.into_bytes()),
+        }
+    }
+
+    /// Raw bytes of the body. `Json` bodies are re-serialized.
+    pub fn as_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            Body::Text(text) => Cow::Borrowed(text.as_bytes()),
+            Body::Binary(bytes) => Cow::Borrowed(bytes),
+            Body::Json(json) => Cow::Owned(serde_json::to_vec(json).unwrap_or_default()),
+        }
+    }
+}
+
+/// Whether a `Content-Type` names JSON, parameters such as `charset` ignored
+pub(crate) fn is_json(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    media_type == "application/json"
+        || media_type.starts_with("application/") && media_type.ends_with("+json")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_follows_content_type() {
+        let json = br#"{"name":"ana"}"#.to_vec();
+
+        for content_type in [
+            Some("application/json"),
+            Some("Application/JSON; charset=utf-8"),
+            Some("application/problem+json"),
+            None,
+        ] {
+            assert!(
+                matches!(Body::decode(json.clone(), content_type), Body::Json(value) if value == json!({"name": "ana"})),
+                "{:?}",
+                content_type
+            );
+        }
+        assert!(matches!(
+            Body::decode(json.clone(), Some("text/plain")),
+            Body::Text(_)
+        ));
+        assert!(matches!(
+            Body::decode(b"name=ana".to_vec(), Some("application/json")),
+            Body::Text(_)
+        ));
+        assert!(matches!(
+            Body::decode(vec![0xff, 0xfe], Some("application/octet-stream")),
+            Body::Binary(_)
+        ));
+    }
 }
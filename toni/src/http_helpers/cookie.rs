@@ -0,0 +1,425 @@
+//! Cookies and `Set-Cookie` response headers.
+//!
+//! Values and paths are written percent-encoded where they hold characters a
+//! `Set-Cookie` header can't carry, like `;` or a line break, and values are
+//! decoded again when read.
+//!
+//! # Examples
+//!
+//! ```
+//! use toni::http_helpers::{Cookie, CookieJar, SameSite};
+//!
+//! let mut jar = CookieJar::new();
+//! jar.add(
+//!     Cookie::new("theme", "dark")
+//!         .path("/")
+//!         .http_only(true)
+//!         .same_site(SameSite::Lax)
+//!         .max_age(3600),
+//! );
+//!
+//! assert_eq!(
+//!     jar.set_cookie_headers(),
+//!     vec!["theme=dark; Path=/; Max-Age=3600; HttpOnly; SameSite=Lax"]
+//! );
+//! ```
+
+use std::fmt;
+
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+
+use super::{CookieKey, HttpResponse, IntoResponse};
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    /// Browsers require `Secure` with `SameSite=None`, so it is always emitted.
+    None,
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SameSite::Strict => write!(f, "Strict"),
+            SameSite::Lax => write!(f, "Lax"),
+            SameSite::None => write!(f, "None"),
+        }
+    }
+}
+
+/// Characters outside RFC 6265 `cookie-octet`, and `%` so values decode back
+const VALUE: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b',')
+    .add(b';')
+    .add(b'\\')
+    .add(b'%');
+
+/// Characters that would end a `Path` attribute early
+const PATH: &AsciiSet = &CONTROLS.add(b';');
+
+/// RFC 7230 token characters, what a cookie name is made of
+fn is_token(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+/// A cookie sent in a `Set-Cookie` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<i64>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or has characters other than RFC 7230 token
+    /// characters.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        assert!(is_token(&name), "Invalid cookie name {:?}", name);
+        Self {
+            name,
+            value: value.into(),
+            path: None,
+            domain: None,
+            max_age: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `domain` has characters other than letters, digits, `-` and
+    /// `.`.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        let domain = domain.into();
+        assert!(
+            domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.'),
+            "Invalid cookie domain {:?}",
+            domain
+        );
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Lifetime in seconds. Without it the cookie lasts for the browser session.
+    pub fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    pub(crate) fn with_value(mut self, value: String) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Parses a `Cookie` request header into name/value pairs, values
+    /// percent-decoded.
+    pub fn parse_header(header: &str) -> Vec<(String, String)> {
+        header
+            .split(';')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                let value = percent_decode_str(value).decode_utf8_lossy();
+                Some((name.to_string(), value.into_owned()))
+            })
+            .collect()
+    }
+}
+
+/// Formats the cookie as a `Set-Cookie` header value.
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}={}",
+            self.name,
+            utf8_percent_encode(&self.value, VALUE)
+        )?;
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", utf8_percent_encode(path, PATH))?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+            if max_age <= 0 {
+                write!(f, "; Expires=Thu, 01 Jan 1970 00:00:00 GMT")?;
+            }
+        }
+        if self.secure || self.same_site == Some(SameSite::None) {
+            write!(f, "; Secure")?;
+        }
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cookies to set on a response.
+///
+/// Return it from a handler, alone or paired with a body, to emit one
+/// `Set-Cookie` header per cookie. Signed and encrypted cookies need a jar
+/// holding a [`CookieKey`], which [`Cookies::jar`](crate::extractors::Cookies::jar)
+/// provides when the application was created with
+/// [`ToniFactory::use_cookie_key`](crate::ToniFactory::use_cookie_key).
+///
+/// ```rust,ignore
+/// #[post("/login")]
+/// fn login(&self, cookies: Cookies) -> (CookieJar, Body) {
+///     let mut jar = cookies.jar();
+///     jar.add_signed(Cookie::new("session", "u-42").http_only(true));
+///     (jar, Body::Text("Welcome".to_string()))
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+    key: Option<CookieKey>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a jar able to add signed and encrypted cookies.
+    pub fn with_key(key: CookieKey) -> Self {
+        Self {
+            cookies: Vec::new(),
+            key: Some(key),
+        }
+    }
+
+    pub fn add(&mut self, cookie: Cookie) -> &mut Self {
+        self.cookies.retain(|c| c.name != cookie.name);
+        self.cookies.push(cookie);
+        self
+    }
+
+    /// Adds a cookie whose value is signed, so tampering is detected on read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jar was created without a key.
+    pub fn add_signed(&mut self, cookie: Cookie) -> &mut Self {
+        let value = self.expect_key().sign(&cookie.name, &cookie.value);
+        self.add(cookie.with_value(value))
+    }
+
+    /// Adds a cookie whose value is encrypted and authenticated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jar was created without a key.
+    pub fn add_private(&mut self, cookie: Cookie) -> &mut Self {
+        let value = self.expect_key().encrypt(&cookie.name, &cookie.value);
+        self.add(cookie.with_value(value))
+    }
+
+    /// Expires the cookie `name` on the client. The path must match the one it was set with.
+    pub fn remove(&mut self, name: impl Into<String>, path: impl Into<String>) -> &mut Self {
+        self.add(Cookie::new(name, "").path(path).max_age(0))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Cookie> {
+        self.cookies.iter().find(|c| c.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cookie> {
+        self.cookies.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// One `Set-Cookie` header value per cookie.
+    pub fn set_cookie_headers(&self) -> Vec<String> {
+        self.cookies.iter().map(|c| c.to_string()).collect()
+    }
+
+    /// Appends the `Set-Cookie` headers to a response.
+    pub fn apply(&self, response: &mut HttpResponse) {
+        for cookie in &self.cookies {
+            response.set_cookie(cookie);
+        }
+    }
+
+    fn expect_key(&self) -> &CookieKey {
+        self.key.as_ref().expect(
+            "CookieJar has no key; use Cookies::jar() with ToniFactory::use_cookie_key or CookieJar::with_key",
+        )
+    }
+}
+
+impl IntoResponse for CookieJar {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let mut response = HttpResponse::new();
+        self.apply(&mut response);
+        response
+    }
+}
+
+impl<T> IntoResponse for (CookieJar, T)
+where
+    T: IntoResponse<Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let mut response = self.1.to_response();
+        self.0.apply(&mut response);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_helpers::Body;
+
+    #[test]
+    fn test_set_cookie_attributes() {
+        let cookie = Cookie::new("sid", "abc")
+            .path("/app")
+            .domain("example.com")
+            .max_age(60)
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::Strict);
+
+        assert_eq!(
+            cookie.to_string(),
+            "sid=abc; Path=/app; Domain=example.com; Max-Age=60; Secure; HttpOnly; SameSite=Strict"
+        );
+    }
+
+    #[test]
+    fn test_same_site_none_implies_secure() {
+        let cookie = Cookie::new("a", "b").same_site(SameSite::None);
+        assert_eq!(cookie.to_string(), "a=b; Secure; SameSite=None");
+    }
+
+    #[test]
+    fn test_encodes_what_would_break_the_header() {
+        let cookie = Cookie::new("q", "a; Domain=evil.com\r\nX: 50%").path("/a;b c");
+        assert_eq!(
+            cookie.to_string(),
+            "q=a%3B%20Domain=evil.com%0D%0AX:%2050%25; Path=/a%3Bb c"
+        );
+        assert_eq!(
+            Cookie::parse_header(&cookie.to_string())[0],
+            ("q".to_string(), "a; Domain=evil.com\r\nX: 50%".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid cookie name")]
+    fn test_rejects_invalid_names() {
+        Cookie::new("sid; Path=/", "abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid cookie domain")]
+    fn test_rejects_invalid_domains() {
+        let _ = Cookie::new("sid", "abc").domain("example.com; Secure");
+    }
+
+    #[test]
+    fn test_remove_expires_cookie() {
+        let mut jar = CookieJar::new();
+        jar.remove("sid", "/");
+        assert_eq!(
+            jar.set_cookie_headers(),
+            vec!["sid=; Path=/; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]
+        );
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            Cookie::parse_header("a=1; b=\"two\";c=x=y; =skip"),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "two".to_string()),
+                ("c".to_string(), "x=y".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jar_with_body_emits_one_header_per_cookie() {
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("a", "1")).add(Cookie::new("b", "2"));
+
+        let response = (jar, Body::Text("ok".to_string())).to_response();
+        let set_cookies: Vec<_> = response
+            .headers
+            .get_all("set-cookie")
+            .filter_map(|v| v.to_str())
+            .collect();
+        assert_eq!(set_cookies, vec!["a=1", "b=2"]);
+    }
+}
@@ -0,0 +1,174 @@
+//! Keys for signed and encrypted cookies.
+//!
+//! Signed cookies carry an HMAC-SHA256 tag and stay readable by the client.
+//! Private cookies are encrypted with AES-256-GCM. Both bind the cookie name,
+//! so a value cannot be moved to another cookie.
+
+use std::fmt;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_LEN: usize = 12;
+
+/// Secret used to sign and encrypt cookies.
+///
+/// # Examples
+///
+/// ```
+/// use toni::http_helpers::CookieKey;
+///
+/// let key = CookieKey::from_secret("a secret of at least thirty-two bytes!");
+/// let signed = key.sign("session", "u-42");
+///
+/// assert_eq!(key.verify("session", &signed).as_deref(), Some("u-42"));
+/// assert_eq!(key.verify("other", &signed), None);
+/// ```
+#[derive(Clone)]
+pub struct CookieKey {
+    signing: [u8; 32],
+    encryption: [u8; 32],
+}
+
+impl CookieKey {
+    /// Derives signing and encryption keys from a secret.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret is shorter than 32 bytes.
+    pub fn from_secret(secret: impl AsRef<[u8]>) -> Self {
+        let secret = secret.as_ref();
+        assert!(
+            secret.len() >= 32,
+            "cookie secret must be at least 32 bytes, got {}",
+            secret.len()
+        );
+
+        Self {
+            signing: derive(secret, b"toni-cookie-signing"),
+            encryption: derive(secret, b"toni-cookie-encryption"),
+        }
+    }
+
+    /// Returns `value` with an authentication tag appended.
+    pub fn sign(&self, name: &str, value: &str) -> String {
+        let tag = self.tag(name, value).finalize().into_bytes();
+        format!("{}.{}", value, URL_SAFE_NO_PAD.encode(tag))
+    }
+
+    /// Returns the original value if the signature is valid for this cookie name.
+    pub fn verify(&self, name: &str, signed: &str) -> Option<String> {
+        let (value, tag) = signed.rsplit_once('.')?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+        self.tag(name, value).verify_slice(&tag).ok()?;
+        Some(value.to_string())
+    }
+
+    /// Encrypts `value`, returning the nonce and ciphertext as base64.
+    pub fn encrypt(&self, name: &str, value: &str) -> String {
+        let cipher = Aes256Gcm::new((&self.encryption).into());
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption does not fail for cookie-sized input");
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        URL_SAFE_NO_PAD.encode(data)
+    }
+
+    /// Decrypts a value produced by [`encrypt`](Self::encrypt) for the same cookie name.
+    pub fn decrypt(&self, name: &str, encrypted: &str) -> Option<String> {
+        let data = URL_SAFE_NO_PAD.decode(encrypted).ok()?;
+        if data.len() <= NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+        let cipher = Aes256Gcm::new((&self.encryption).into());
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+
+    fn tag(&self, name: &str, value: &str) -> HmacSha256 {
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(&self.signing).expect("HMAC accepts any key size");
+        mac.update(name.as_bytes());
+        mac.update(b"=");
+        mac.update(value.as_bytes());
+        mac
+    }
+}
+
+impl fmt::Debug for CookieKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CookieKey(..)")
+    }
+}
+
+fn derive(secret: &[u8], label: &[u8]) -> [u8; 32] {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(secret).expect("HMAC accepts any key size");
+    mac.update(label);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> CookieKey {
+        CookieKey::from_secret("0123456789abcdef0123456789abcdef")
+    }
+
+    #[test]
+    fn test_signed_value_detects_tampering() {
+        let key = key();
+        let signed = key.sign("sid", "user-1");
+        assert!(signed.starts_with("user-1."));
+
+        let tampered = signed.replacen("user-1", "user-2", 1);
+        assert_eq!(key.verify("sid", &tampered), None);
+        assert_eq!(key.verify("sid", "user-1"), None);
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip() {
+        let key = key();
+        let encrypted = key.encrypt("sid", "secret value");
+        assert!(!encrypted.contains("secret"));
+        assert_eq!(
+            key.decrypt("sid", &encrypted).as_deref(),
+            Some("secret value")
+        );
+        assert_eq!(key.decrypt("other", &encrypted), None);
+
+        let other = CookieKey::from_secret("another secret that is long enough!!");
+        assert_eq!(other.decrypt("sid", &encrypted), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 32 bytes")]
+    fn test_rejects_short_secret() {
+        CookieKey::from_secret("short");
+    }
+}
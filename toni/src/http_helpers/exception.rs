@@ -0,0 +1,155 @@
+use std::{any::Any, error::Error};
+
+use super::{HttpException, HttpResponse, IntoResponse, ProblemDetails};
+use crate::extractors::Rejection;
+
+/// An error raised while handling a request, as seen by exception filters.
+///
+/// Filters match on the error type with [`Exception::downcast_ref`], which
+/// also looks inside boxed middleware errors and `anyhow::Error`s. Extractor
+/// rejections also expose their [`ProblemDetails`], so one filter can restyle
+/// all of them.
+pub struct Exception<'a> {
+    error: ErrorRef<'a>,
+    response: HttpResponse,
+}
+
+enum ErrorRef<'a> {
+    Value(&'a dyn Any),
+    Rejection(&'a dyn Rejection),
+    Boxed(&'a (dyn Error + Send + Sync + 'static)),
+    Anyhow(&'a anyhow::Error),
+}
+
+impl<'a> Exception<'a> {
+    /// `response` is what gets sent when no filter handles the error.
+    pub fn new(error: &'a dyn Any, response: HttpResponse) -> Self {
+        Self {
+            error: ErrorRef::Value(error),
+            response,
+        }
+    }
+
+    /// Wraps an extractor rejection, answered with its problem details
+    pub fn from_rejection(rejection: &'a dyn Rejection) -> Self {
+        Self {
+            error: ErrorRef::Rejection(rejection),
+            response: rejection.problem().to_response(),
+        }
+    }
+
+    /// Wraps a middleware error; unless it is an `HttpException`, the
+    /// fallback response is a 500 that does not leak the error message.
+    pub fn from_error(error: &'a (dyn Error + Send + Sync + 'static)) -> Self {
+        Self::with_fallback(ErrorRef::Boxed(error))
+    }
+
+    /// Same as [`Exception::from_error`], for `anyhow::Error`
+    pub fn from_anyhow(error: &'a anyhow::Error) -> Self {
+        Self::with_fallback(ErrorRef::Anyhow(error))
+    }
+
+    fn with_fallback(error: ErrorRef<'a>) -> Self {
+        let mut exception = Self {
+            error,
+            response: HttpResponse::new(),
+        };
+        exception.response = match exception.downcast_ref::<HttpException>() {
+            Some(http_exception) => http_exception.to_response(),
+            None => HttpException::internal_server_error(
+                "An error occurred while processing the request",
+            )
+            .to_response(),
+        };
+        exception
+    }
+
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: Error + Send + Sync + 'static,
+    {
+        match self.error {
+            ErrorRef::Value(error) => error.downcast_ref::<T>(),
+            ErrorRef::Rejection(error) => (error as &dyn Error).downcast_ref::<T>(),
+            ErrorRef::Boxed(error) => error.downcast_ref::<T>(),
+            ErrorRef::Anyhow(error) => error.downcast_ref::<T>(),
+        }
+    }
+
+    pub fn is<T>(&self) -> bool
+    where
+        T: Error + Send + Sync + 'static,
+    {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// The problem details of an extractor rejection
+    pub fn problem(&self) -> Option<ProblemDetails> {
+        match self.error {
+            ErrorRef::Rejection(rejection) => Some(rejection.problem()),
+            _ => None,
+        }
+    }
+
+    /// The response sent when no filter handles the error
+    pub fn response(&self) -> &HttpResponse {
+        &self.response
+    }
+
+    pub fn into_response(self) -> HttpResponse {
+        self.response
+    }
+}
+
+impl IntoResponse for anyhow::Error {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        Exception::from_anyhow(self).into_response()
+    }
+
+    fn exception(&self) -> Option<Exception<'_>> {
+        Some(Exception::from_anyhow(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct DomainError;
+
+    impl std::fmt::Display for DomainError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "domain error")
+        }
+    }
+
+    impl std::error::Error for DomainError {}
+
+    #[test]
+    fn test_downcast_value() {
+        let error = HttpException::not_found("missing");
+        let exception = error.exception().unwrap();
+
+        assert_eq!(
+            exception.downcast_ref::<HttpException>().unwrap().message(),
+            "missing"
+        );
+        assert!(!exception.is::<DomainError>());
+        assert_eq!(exception.response().status, 404);
+    }
+
+    #[test]
+    fn test_downcast_anyhow() {
+        let error = anyhow::Error::new(DomainError);
+        let exception = Exception::from_anyhow(&error);
+
+        assert!(exception.is::<DomainError>());
+        assert_eq!(exception.response().status, 500);
+
+        let error: Box<dyn Error + Send + Sync> = HttpException::forbidden("nope").into();
+        assert_eq!(Exception::from_error(error.as_ref()).response().status, 403);
+    }
+}
@@ -0,0 +1,269 @@
+//! Case-insensitive, multi-valued header storage.
+//!
+//! Header names are compared ignoring ASCII case and stored lowercased.
+//! A name may carry several values (e.g. `Set-Cookie`), kept in insertion order.
+//! Values are raw bytes so non-UTF-8 headers survive a round trip.
+//!
+//! # Examples
+//!
+//! ```
+//! use toni::http_helpers::HeaderMap;
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert("Content-Type", "text/plain");
+//! headers.append("Set-Cookie", "a=1");
+//! headers.append("set-cookie", "b=2");
+//!
+//! assert_eq!(headers.get_str("content-type"), Some("text/plain"));
+//! assert_eq!(headers.get_all("SET-COOKIE").count(), 2);
+//! ```
+
+use std::fmt;
+
+/// A single header value.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HeaderValue(Vec<u8>);
+
+impl HeaderValue {
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self(bytes.into())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The value as UTF-8, or `None` if it contains invalid bytes.
+    pub fn to_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+}
+
+impl fmt::Debug for HeaderValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_str() {
+            Some(s) => fmt::Debug::fmt(s, f),
+            None => write!(f, "b{:?}", String::from_utf8_lossy(&self.0)),
+        }
+    }
+}
+
+impl From<&str> for HeaderValue {
+    fn from(value: &str) -> Self {
+        Self(value.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for HeaderValue {
+    fn from(value: String) -> Self {
+        Self(value.into_bytes())
+    }
+}
+
+impl From<&String> for HeaderValue {
+    fn from(value: &String) -> Self {
+        Self(value.as_bytes().to_vec())
+    }
+}
+
+impl From<Vec<u8>> for HeaderValue {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq<str> for HeaderValue {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for HeaderValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+/// Headers of a request or response.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct HeaderMap {
+    entries: Vec<(String, HeaderValue)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// First value for `name`.
+    pub fn get(&self, name: &str) -> Option<&HeaderValue> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// First value for `name` as UTF-8.
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(HeaderValue::to_str)
+    }
+
+    /// All values for `name`, in the order they were added.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a HeaderValue> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Sets `name` to a single value, replacing any existing values.
+    ///
+    /// Returns the first previous value, if any.
+    pub fn insert(
+        &mut self,
+        name: impl AsRef<str>,
+        value: impl Into<HeaderValue>,
+    ) -> Option<HeaderValue> {
+        let previous = self.remove(name.as_ref());
+        self.append(name, value);
+        previous
+    }
+
+    /// Adds a value for `name`, keeping existing values.
+    pub fn append(&mut self, name: impl AsRef<str>, value: impl Into<HeaderValue>) {
+        self.entries
+            .push((name.as_ref().to_ascii_lowercase(), value.into()));
+    }
+
+    /// Removes all values for `name`, returning the first one.
+    pub fn remove(&mut self, name: &str) -> Option<HeaderValue> {
+        let mut first = None;
+        self.entries.retain(|(k, v)| {
+            if k.eq_ignore_ascii_case(name) {
+                if first.is_none() {
+                    first = Some(v.clone());
+                }
+                false
+            } else {
+                true
+            }
+        });
+        first
+    }
+
+    /// All name/value pairs, with lowercased names.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &HeaderValue)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Distinct header names, in order of first appearance.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        for (k, _) in &self.entries {
+            if !keys.contains(&k.as_str()) {
+                keys.push(k);
+            }
+        }
+        keys
+    }
+
+    /// Number of values, counting each value of a repeated header.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl fmt::Debug for HeaderMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: AsRef<str>, V: Into<HeaderValue>> Extend<(K, V)> for HeaderMap {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.append(k, v);
+        }
+    }
+}
+
+impl<K: AsRef<str>, V: Into<HeaderValue>> FromIterator<(K, V)> for HeaderMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.extend(iter);
+        headers
+    }
+}
+
+impl From<Vec<(String, String)>> for HeaderMap {
+    fn from(headers: Vec<(String, String)>) -> Self {
+        headers.into_iter().collect()
+    }
+}
+
+impl IntoIterator for HeaderMap {
+    type Item = (String, HeaderValue);
+    type IntoIter = std::vec::IntoIter<(String, HeaderValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderMap {
+    type Item = (&'a str, &'a HeaderValue);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (String, HeaderValue)>,
+        fn(&'a (String, HeaderValue)) -> (&'a str, &'a HeaderValue),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive_lookup() {
+        let headers: HeaderMap = vec![("X-Request-ID".to_string(), "abc".to_string())].into();
+        assert_eq!(headers.get_str("x-request-id"), Some("abc"));
+        assert_eq!(headers.get_str("X-REQUEST-ID"), Some("abc"));
+        assert!(headers.contains_key("x-Request-Id"));
+    }
+
+    #[test]
+    fn test_insert_replaces_and_append_keeps() {
+        let mut headers = HeaderMap::new();
+        headers.append("Vary", "Accept");
+        headers.append("vary", "Origin");
+        assert_eq!(headers.get_all("VARY").count(), 2);
+
+        let previous = headers.insert("Vary", "Cookie");
+        assert_eq!(previous.unwrap(), "Accept");
+        assert_eq!(headers.get_all("vary").collect::<Vec<_>>(), vec!["Cookie"]);
+        assert_eq!(headers.len(), 1);
+    }
+
+    #[test]
+    fn test_non_utf8_values_are_preserved() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-raw", vec![0xff, 0xfe]);
+        assert_eq!(headers.get("x-raw").unwrap().as_bytes(), &[0xff, 0xfe]);
+        assert_eq!(headers.get_str("x-raw"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use std::{any::Any, fmt};
+
+use serde_json::Value;
+
+use super::{Body, Exception, HeaderMap, HttpResponse, IntoResponse, StatusCode};
+
+/// An error that carries its own HTTP status.
+///
+/// Return it from a handler as `Result<T, HttpException>`, or from a
+/// middleware as `Err(HttpException::unauthorized("...").into())`. Unless an
+/// exception filter handles it, it becomes a JSON response:
+///
+/// ```
+/// use toni::http_helpers::{HttpException, IntoResponse};
+///
+/// let response = HttpException::not_found("User 7 not found").to_response();
+/// assert_eq!(response.status, 404);
+/// ```
+///
+/// ```json
+/// { "statusCode": 404, "error": "Not Found", "message": "User 7 not found" }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpException {
+    status: StatusCode,
+    message: String,
+    details: Option<Value>,
+}
+
+impl HttpException {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn method_not_allowed(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::METHOD_NOT_ALLOWED, message)
+    }
+
+    pub fn not_acceptable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_ACCEPTABLE, message)
+    }
+
+    pub fn request_timeout(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::REQUEST_TIMEOUT, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+
+    pub fn gone(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::GONE, message)
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::PAYLOAD_TOO_LARGE, message)
+    }
+
+    pub fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, message)
+    }
+
+    pub fn unprocessable_entity(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, message)
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, message)
+    }
+
+    pub fn internal_server_error(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub fn not_implemented(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_IMPLEMENTED, message)
+    }
+
+    pub fn bad_gateway(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_GATEWAY, message)
+    }
+
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, message)
+    }
+
+    pub fn gateway_timeout(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::GATEWAY_TIMEOUT, message)
+    }
+
+    /// Adds a `details` field to the response body
+    pub fn with_details(mut self, details: impl Into<Value>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn details(&self) -> Option<&Value> {
+        self.details.as_ref()
+    }
+}
+
+impl fmt::Display for HttpException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for HttpException {}
+
+impl IntoResponse for HttpException {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let mut body = serde_json::json!({
+            "statusCode": self.status.as_u16(),
+            "error": self.status.canonical_reason().unwrap_or("Error"),
+            "message": self.message,
+        });
+        if let Some(details) = &self.details {
+            body["details"] = details.clone();
+        }
+
+        HttpResponse {
+            body: Some(Body::Json(body)),
+            status: self.status.as_u16(),
+            headers: HeaderMap::from_iter([("Content-Type", "application/json")]),
+        }
+    }
+
+    fn exception(&self) -> Option<Exception<'_>> {
+        Some(Exception::new(self as &dyn Any, self.to_response()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_body() {
+        let response = HttpException::conflict("Email already taken")
+            .with_details(serde_json::json!({ "field": "email" }))
+            .to_response();
+
+        assert_eq!(response.status, 409);
+        assert_eq!(
+            response.headers.get_str("content-type"),
+            Some("application/json")
+        );
+        let Some(Body::Json(body)) = response.body else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "statusCode": 409,
+                "error": "Conflict",
+                "message": "Email already taken",
+                "details": { "field": "email" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let exception = HttpException::unauthorized("Missing token");
+        assert_eq!(exception.to_string(), "401 Unauthorized: Missing token");
+    }
+}
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
-use super::{Body, Extensions};
+use serde_json::Value;
+
+use super::{Body, Cookie, Extensions, HeaderMap, parse_query, query_value};
 
 #[derive(Clone, Debug)]
 pub struct HttpRequest {
     pub body: Body,
-    pub headers: Vec<(String, String)>,
+    pub headers: HeaderMap,
     pub method: String,
     pub uri: String,
     pub query_params: HashMap<String, String>,
@@ -14,26 +16,196 @@ pub struct HttpRequest {
 }
 
 impl HttpRequest {
+    /// Builds a request from what the server received, so every adapter
+    /// decodes the query string and body the same way.
+    ///
+    /// `query_params` keeps the last value of each name; the raw query string
+    /// stays in `uri` for [`query_pairs`](Self::query_pairs) and the `Query`
+    /// extractor. The body is decoded with [`Body::decode`].
+    pub fn from_parts(
+        method: impl Into<String>,
+        uri: impl Into<String>,
+        headers: HeaderMap,
+        path_params: HashMap<String, String>,
+        body: Vec<u8>,
+    ) -> Self {
+        let uri = uri.into();
+        let body = Body::decode(body, headers.get_str("content-type"));
+        let mut request = Self {
+            body,
+            headers,
+            method: method.into(),
+            uri,
+            query_params: HashMap::new(),
+            path_params,
+            extensions: Extensions::new(),
+        };
+        request.query_params = request.query_pairs().into_iter().collect();
+        request
+    }
+
+    /// The path of `uri`, without the query string
+    pub fn path(&self) -> &str {
+        let uri = self.uri.split(['?', '#']).next().unwrap_or_default();
+        match split_authority(uri) {
+            Some((_, "")) => "/",
+            Some((_, path)) => path,
+            None => uri,
+        }
+    }
+
+    /// The host the request was sent to, from the `Host` header or the
+    /// authority of an absolute `uri` (HTTP/2). May carry a port.
+    pub fn host(&self) -> Option<&str> {
+        self.header("host").or_else(|| {
+            split_authority(&self.uri)
+                .map(|(authority, _)| authority)
+                .filter(|authority| !authority.is_empty())
+        })
+    }
+
+    /// The raw query string of `uri`, without `?`
+    pub fn query_string(&self) -> &str {
+        let uri = self.uri.split('#').next().unwrap_or_default();
+        uri.split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default()
+    }
+
+    /// Percent-decoded query pairs, in order and repeated names included.
+    ///
+    /// Falls back to `query_params` for requests built without a query string
+    /// in `uri`.
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        let query = self.query_string();
+        if !query.is_empty() || self.query_params.is_empty() {
+            return parse_query(query);
+        }
+        let mut pairs: Vec<_> = self
+            .query_params
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    /// Every value sent for a query parameter, e.g. `?tag=a&tag=b`
+    pub fn query_all(&self, name: &str) -> Vec<String> {
+        self.query_pairs()
+            .into_iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// The query string nested into a JSON object of strings, see [`query_value`]
+    pub fn query_value(&self) -> Value {
+        query_value(&self.query_pairs())
+    }
+
     /// Get a reference to the headers
-    pub fn headers(&self) -> &Vec<(String, String)> {
+    pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
     /// Get a mutable reference to the headers
-    pub fn headers_mut(&mut self) -> &mut Vec<(String, String)> {
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
         &mut self.headers
     }
 
-    /// Get a specific header value by name (case-sensitive)
+    /// Get the first value of a header by name (case-insensitive)
     pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get_str(name)
+    }
+
+    /// Check if a header exists (case-insensitive)
+    pub fn has_header(&self, name: &str) -> bool {
+        self.headers.contains_key(name)
+    }
+
+    /// Cookies sent in `Cookie` headers, in order
+    pub fn cookies(&self) -> Vec<(String, String)> {
         self.headers
-            .iter()
+            .get_all("cookie")
+            .filter_map(|v| v.to_str())
+            .flat_map(Cookie::parse_header)
+            .collect()
+    }
+
+    /// Get a cookie value by name
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.cookies()
+            .into_iter()
             .find(|(k, _)| k == name)
-            .map(|(_, v)| v.as_str())
+            .map(|(_, v)| v)
     }
+}
 
-    /// Check if a header exists
-    pub fn has_header(&self, name: &str) -> bool {
-        self.headers.iter().any(|(k, _)| k == name)
+/// `https://example.com/users` to `("example.com", "/users")`, `None` for
+/// a bare path
+fn split_authority(uri: &str) -> Option<(&str, &str)> {
+    let (_, rest) = uri.split_once("://")?;
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    Some(rest.split_at(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_from_parts_decodes_query_and_body() {
+        let headers = HeaderMap::from_iter([("Content-Type", "application/x-www-form-urlencoded")]);
+        let req = HttpRequest::from_parts(
+            "POST",
+            "/search?q=rust%20web&tag=a&tag=b#top",
+            headers,
+            HashMap::new(),
+            br#"{"looks":"like json"}"#.to_vec(),
+        );
+
+        assert_eq!(req.path(), "/search");
+        assert_eq!(req.query_string(), "q=rust%20web&tag=a&tag=b");
+        assert_eq!(req.query_params["q"], "rust web");
+        assert_eq!(req.query_params["tag"], "b");
+        assert_eq!(req.query_all("tag"), vec!["a", "b"]);
+        assert_eq!(
+            req.query_value(),
+            json!({ "q": "rust web", "tag": ["a", "b"] })
+        );
+        assert!(matches!(req.body, Body::Text(_)));
+    }
+
+    #[test]
+    fn test_query_pairs_fall_back_to_query_params() {
+        let mut req =
+            HttpRequest::from_parts("GET", "/", HeaderMap::new(), HashMap::new(), Vec::new());
+        req.query_params.insert("page".to_string(), "2".to_string());
+
+        assert_eq!(
+            req.query_pairs(),
+            vec![("page".to_string(), "2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_host_and_path_of_absolute_uris() {
+        let req = HttpRequest::from_parts(
+            "GET",
+            "https://acme.example.com:8443/users?page=2",
+            HeaderMap::new(),
+            HashMap::new(),
+            Vec::new(),
+        );
+        assert_eq!(req.host(), Some("acme.example.com:8443"));
+        assert_eq!(req.path(), "/users");
+
+        let headers = HeaderMap::from_iter([("Host", "api.example.com")]);
+        let req = HttpRequest::from_parts("GET", "/", headers, HashMap::new(), Vec::new());
+        assert_eq!(req.host(), Some("api.example.com"));
+        assert_eq!(req.path(), "/");
     }
 }
@@ -1,4 +1,4 @@
-use super::Body;
+use super::{Body, Cookie, HeaderMap};
 
 #[derive(Debug)]
 pub struct HttpResponseDefault {
@@ -10,16 +10,21 @@ pub struct HttpResponseDefault {
 pub struct HttpResponse {
     pub body: Option<Body>,
     pub status: u16,
-    pub headers: Vec<(String, String)>,
+    pub headers: HeaderMap,
 }
 impl HttpResponse {
     pub fn new() -> Self {
         Self {
             body: None,
             status: 200,
-            headers: vec![],
+            headers: HeaderMap::new(),
         }
     }
+
+    /// Appends a `Set-Cookie` header for the cookie
+    pub fn set_cookie(&mut self, cookie: &Cookie) {
+        self.headers.append("Set-Cookie", cookie.to_string());
+    }
 }
 
 impl Default for HttpResponse {
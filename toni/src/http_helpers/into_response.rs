@@ -2,12 +2,18 @@ use std::fmt::Debug;
 
 use serde_json::Value;
 
-use super::{Body, HttpResponse};
+use super::{Body, Exception, HeaderMap, HttpException, HttpResponse, StatusCode};
 
 pub trait IntoResponse: Debug {
     type Response;
 
     fn to_response(&self) -> Self::Response;
+
+    /// The error this response stands for, if any, so exception filters can
+    /// replace it. Plain responses are never routed to filters.
+    fn exception(&self) -> Option<Exception<'_>> {
+        None
+    }
 }
 
 impl IntoResponse for HttpResponse {
@@ -43,6 +49,17 @@ impl IntoResponse for u16 {
 impl IntoResponse for Vec<(String, String)> {
     type Response = HttpResponse;
 
+    fn to_response(&self) -> Self::Response {
+        HttpResponse {
+            headers: self.clone().into(),
+            ..HttpResponse::new()
+        }
+    }
+}
+
+impl IntoResponse for HeaderMap {
+    type Response = HttpResponse;
+
     fn to_response(&self) -> Self::Response {
         HttpResponse {
             headers: self.clone(),
@@ -63,24 +80,89 @@ impl IntoResponse for (u16, Body) {
     }
 }
 
-// impl<T1, T2> IntoResponse for (T1, T2)
-// where
-//     T1: IntoResponse<Response = HttpResponse>,
-//     T2: IntoResponse<Response = HttpResponse>,
-// {
-//     type Response = HttpResponse;
+impl IntoResponse for StatusCode {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        HttpResponse {
+            status: self.as_u16(),
+            ..HttpResponse::new()
+        }
+    }
+}
 
-//     fn to_response(&self) -> HttpResponse {
-//         let mut response = self.0.to_response();
-//         let part = self.1.to_response();
+/// Overrides the status of the inner response
+impl<T> IntoResponse for (StatusCode, T)
+where
+    T: IntoResponse<Response = HttpResponse>,
+{
+    type Response = HttpResponse;
 
-//         response.status = part.status;
-//         response.headers.extend(part.headers);
-//         response.body = part.body;
+    fn to_response(&self) -> Self::Response {
+        let mut response = self.1.to_response();
+        response.status = self.0.as_u16();
+        response
+    }
+}
 
-//         response
-//     }
-// }
+/// Overrides the status and the given headers of the inner response
+impl<T> IntoResponse for (StatusCode, HeaderMap, T)
+where
+    T: IntoResponse<Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let mut response = self.2.to_response();
+        response.status = self.0.as_u16();
+        for name in self.1.keys() {
+            response.headers.remove(name);
+        }
+        response.headers.extend(self.1.clone());
+        response
+    }
+}
+
+/// Lets handlers use `?` when both the value and the error are responses.
+/// The error goes through the exception filters.
+impl<T, E> IntoResponse for Result<T, E>
+where
+    T: IntoResponse<Response = HttpResponse>,
+    E: IntoResponse<Response = HttpResponse> + 'static,
+{
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        match self {
+            Ok(value) => value.to_response(),
+            Err(error) => error.to_response(),
+        }
+    }
+
+    fn exception(&self) -> Option<Exception<'_>> {
+        match self {
+            Ok(_) => None,
+            Err(error) => error
+                .exception()
+                .or_else(|| Some(Exception::new(error, error.to_response()))),
+        }
+    }
+}
+
+/// `None` becomes the body of `HttpException::not_found`
+impl<T> IntoResponse for Option<T>
+where
+    T: IntoResponse<Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        match self {
+            Some(value) => value.to_response(),
+            None => HttpException::not_found("Not Found").to_response(),
+        }
+    }
+}
 
 impl IntoResponse for Value {
     type Response = HttpResponse;
@@ -88,7 +170,7 @@ impl IntoResponse for Value {
     fn to_response(&self) -> Self::Response {
         HttpResponse {
             body: Some(Body::Json(self.clone())),
-            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            headers: HeaderMap::from_iter([("Content-Type", "application/json")]),
             ..HttpResponse::new()
         }
     }
@@ -115,3 +197,47 @@ impl IntoResponse for &'static str {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_headers_tuple_overrides_inner_response() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Content-Type", "application/vnd.api+json");
+        headers.append("Vary", "Accept");
+
+        let response = (StatusCode::ACCEPTED, headers, Value::Null).to_response();
+        assert_eq!(response.status, 202);
+        assert_eq!(
+            response.headers.get_all("content-type").collect::<Vec<_>>(),
+            vec!["application/vnd.api+json"]
+        );
+        assert_eq!(response.headers.get_str("vary"), Some("Accept"));
+    }
+
+    #[test]
+    fn test_result_and_option() {
+        let ok: Result<&'static str, StatusCode> = Ok("done");
+        assert_eq!(ok.to_response().status, 200);
+
+        let err: Result<&'static str, StatusCode> = Err(StatusCode::CONFLICT);
+        assert_eq!(err.to_response().status, 409);
+
+        let none: Option<String> = None;
+        let response = none.to_response();
+        assert_eq!(response.status, 404);
+        let Some(Body::Json(body)) = response.body else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "statusCode": 404,
+                "error": "Not Found",
+                "message": "Not Found"
+            })
+        );
+    }
+}
@@ -1,6 +1,7 @@
 #[path = "body.enum.rs"]
 mod body;
 pub use self::body::Body;
+pub(crate) use self::body::is_json;
 
 #[path = "http_response.enum.rs"]
 mod http_response;
@@ -18,5 +19,37 @@ pub use self::http_method::HttpMethod;
 mod into_response;
 pub use self::into_response::IntoResponse;
 
+mod status_code;
+pub use self::status_code::StatusCode;
+
+mod exception;
+pub use self::exception::Exception;
+
+mod http_exception;
+pub use self::http_exception::HttpException;
+
+mod problem_details;
+pub use self::problem_details::ProblemDetails;
+
+mod responses;
+pub use self::responses::{Decorated, NoContent, Redirect, ResponseDecorators};
+
 mod extensions;
 pub use self::extensions::Extensions;
+
+mod cookie;
+pub use self::cookie::{Cookie, CookieJar, SameSite};
+
+mod cookie_key;
+pub use self::cookie_key::CookieKey;
+
+mod query_string;
+pub use self::query_string::{parse_query, query_value};
+
+mod header_map;
+pub use self::header_map::{HeaderMap, HeaderValue};
+
+mod typed_headers;
+pub use self::typed_headers::{
+    AcceptLanguage, Authorization, ETag, IfNoneMatch, LanguageRange, TypedHeader,
+};
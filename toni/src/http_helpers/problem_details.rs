@@ -0,0 +1,190 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use super::{Body, Exception, HeaderMap, HttpResponse, IntoResponse, StatusCode};
+use crate::extractors::Rejection;
+
+/// An RFC 7807 error body, sent as `application/problem+json`.
+///
+/// Extractors answer with it when they reject a request:
+///
+/// ```
+/// use toni::http_helpers::{IntoResponse, ProblemDetails, StatusCode};
+///
+/// let response = ProblemDetails::new(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+///     .with_detail("Request body is not JSON")
+///     .to_response();
+/// assert_eq!(response.status, 415);
+/// ```
+///
+/// ```json
+/// {
+///   "type": "about:blank",
+///   "title": "Unsupported Media Type",
+///   "status": 415,
+///   "detail": "Request body is not JSON"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProblemDetails {
+    problem_type: String,
+    title: String,
+    status: StatusCode,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    /// A problem titled with the canonical reason of `status`
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// URI identifying the kind of problem, `about:blank` by default
+    pub fn with_type(mut self, problem_type: impl Into<String>) -> Self {
+        self.problem_type = problem_type.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Explanation specific to this occurrence
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// URI of this occurrence, usually the request path
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds a member next to the standard ones, e.g. `errors`
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn problem_type(&self) -> &str {
+        &self.problem_type
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    pub fn extension(&self, key: &str) -> Option<&Value> {
+        self.extensions.get(key)
+    }
+
+    /// The problem as a JSON object
+    pub fn to_json(&self) -> Value {
+        let mut body = Map::new();
+        body.insert("type".to_string(), self.problem_type.clone().into());
+        body.insert("title".to_string(), self.title.clone().into());
+        body.insert("status".to_string(), self.status.as_u16().into());
+        if let Some(detail) = &self.detail {
+            body.insert("detail".to_string(), detail.clone().into());
+        }
+        if let Some(instance) = &self.instance {
+            body.insert("instance".to_string(), instance.clone().into());
+        }
+        for (key, value) in &self.extensions {
+            body.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        Value::Object(body)
+    }
+}
+
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.status, detail),
+            None => write!(f, "{}: {}", self.status, self.title),
+        }
+    }
+}
+
+impl std::error::Error for ProblemDetails {}
+
+impl Rejection for ProblemDetails {
+    fn problem(&self) -> ProblemDetails {
+        self.clone()
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        HttpResponse {
+            body: Some(Body::Json(self.to_json())),
+            status: self.status.as_u16(),
+            headers: HeaderMap::from_iter([("Content-Type", "application/problem+json")]),
+        }
+    }
+
+    fn exception(&self) -> Option<Exception<'_>> {
+        Some(Exception::from_rejection(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_body() {
+        let response = ProblemDetails::new(StatusCode::UNPROCESSABLE_ENTITY)
+            .with_detail("missing field `email`")
+            .with_instance("/users")
+            .with_extension("field", "email")
+            .with_extension("status", 200)
+            .to_response();
+
+        assert_eq!(response.status, 422);
+        assert_eq!(
+            response.headers.get_str("content-type"),
+            Some("application/problem+json")
+        );
+        let Some(Body::Json(body)) = response.body else {
+            panic!("expected a JSON body");
+        };
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Unprocessable Entity",
+                "status": 422,
+                "detail": "missing field `email`",
+                "instance": "/users",
+                "field": "email",
+            })
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Query string decoding shared by every adapter.
+//!
+//! Pairs are percent-decoded the way browsers encode forms (`+` is a space).
+//! [`query_value`] nests them: repeated names and `name[]` collect into
+//! arrays, `filter[status]` builds objects and `items[0][name]` indexes
+//! arrays.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_json::json;
+//! use toni::http_helpers::{parse_query, query_value};
+//!
+//! let pairs = parse_query("q=caf%C3%A9+au+lait&tag=a&tag=b&filter[status]=open");
+//! assert_eq!(pairs[0], ("q".to_string(), "café au lait".to_string()));
+//!
+//! assert_eq!(
+//!     query_value(&pairs),
+//!     json!({
+//!         "q": "café au lait",
+//!         "tag": ["a", "b"],
+//!         "filter": { "status": "open" },
+//!     })
+//! );
+//! ```
+
+use serde_json::{Map, Value};
+
+/// Percent-decoded `(name, value)` pairs, in order
+pub fn parse_query(raw: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(raw.trim_start_matches('?').as_bytes())
+        .into_owned()
+        .collect()
+}
+
+/// Nests decoded pairs into a JSON object whose scalars are all strings.
+///
+/// A pair that conflicts with an earlier one, e.g. `a=1&a[b]=2`, is dropped.
+pub fn query_value(pairs: &[(String, String)]) -> Value {
+    let mut root = Value::Object(Map::new());
+    for (name, value) in pairs {
+        let segments = key_segments(name);
+        insert(&mut root, &segments, value);
+    }
+    root
+}
+
+/// `items[0][name]` → `["items", "0", "name"]`; a malformed key is one segment
+fn key_segments(name: &str) -> Vec<&str> {
+    let Some(open) = name
+        .find('[')
+        .filter(|&open| open > 0 && name.ends_with(']'))
+    else {
+        return vec![name];
+    };
+    let mut segments = vec![&name[..open]];
+    for part in name[open + 1..name.len() - 1].split("][") {
+        if part.contains(['[', ']']) {
+            return vec![name];
+        }
+        segments.push(part);
+    }
+    segments
+}
+
+fn is_index(segment: &str) -> bool {
+    !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The container a segment indexes into
+fn container_for(segment: &str) -> Value {
+    if segment.is_empty() || is_index(segment) {
+        Value::Array(Vec::new())
+    } else {
+        Value::Object(Map::new())
+    }
+}
+
+fn insert(target: &mut Value, segments: &[&str], value: &str) {
+    let Some((&segment, rest)) = segments.split_first() else {
+        return;
+    };
+
+    match target {
+        Value::Object(object) => {
+            let Some(&next) = rest.first() else {
+                match object.get_mut(segment) {
+                    Some(Value::Array(items)) => items.push(value.into()),
+                    Some(existing @ Value::String(_)) => {
+                        let first = existing.take();
+                        *existing = Value::Array(vec![first, value.into()]);
+                    }
+                    Some(_) => {}
+                    None => {
+                        object.insert(segment.to_string(), value.into());
+                    }
+                }
+                return;
+            };
+            let child = object
+                .entry(segment.to_string())
+                .or_insert_with(|| container_for(next));
+            insert(child, rest, value);
+        }
+        Value::Array(items) => {
+            let Some(&next) = rest.first() else {
+                items.push(value.into());
+                return;
+            };
+            // `items[0][name]` reuses element 0, `items[][name]` or a new
+            // index starts another element
+            let position = segment
+                .parse::<usize>()
+                .ok()
+                .filter(|&index| index < items.len());
+            let child = match position {
+                Some(index) => &mut items[index],
+                None => {
+                    items.push(container_for(next));
+                    items.last_mut().unwrap()
+                }
+            };
+            insert(child, rest, value);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn nested(raw: &str) -> Value {
+        query_value(&parse_query(raw))
+    }
+
+    #[test]
+    fn test_decodes_pairs() {
+        assert_eq!(
+            parse_query("?a=1&b=x%20y&c=a+b&d&=e"),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "x y".to_string()),
+                ("c".to_string(), "a b".to_string()),
+                ("d".to_string(), String::new()),
+                (String::new(), "e".to_string()),
+            ]
+        );
+        assert!(parse_query("").is_empty());
+    }
+
+    #[test]
+    fn test_nests_values() {
+        assert_eq!(
+            nested("tag=a&tag=b&ids[]=1&ids[]=2&one[]=x"),
+            json!({ "tag": ["a", "b"], "ids": ["1", "2"], "one": ["x"] })
+        );
+        assert_eq!(
+            nested("filter[status]=open&filter[owner][name]=ana&filter%5Blabel%5D=bug"),
+            json!({ "filter": { "status": "open", "owner": { "name": "ana" }, "label": "bug" } })
+        );
+        assert_eq!(
+            nested("items[0][name]=a&items[0][qty]=1&items[1][name]=b"),
+            json!({ "items": [{ "name": "a", "qty": "1" }, { "name": "b" }] })
+        );
+    }
+
+    #[test]
+    fn test_keeps_odd_keys_flat_and_drops_conflicts() {
+        assert_eq!(
+            nested("[a]=1&b[=2&c]=3&d=4&d[e]=5"),
+            json!({ "[a]": "1", "b[": "2", "c]": "3", "d": "4" })
+        );
+    }
+}
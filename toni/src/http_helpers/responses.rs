@@ -0,0 +1,252 @@
+//! Ready-made responses for handlers.
+
+use super::{Exception, HttpResponse, IntoResponse, StatusCode};
+
+/// A redirect to another location.
+///
+/// ```
+/// use toni::http_helpers::{IntoResponse, Redirect};
+///
+/// let response = Redirect::to("/login").to_response();
+/// assert_eq!(response.status, 303);
+/// assert_eq!(response.headers.get_str("location"), Some("/login"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    status: StatusCode,
+    location: String,
+}
+
+impl Redirect {
+    /// `303 See Other`: the client follows up with a GET, e.g. after a form POST.
+    pub fn to(uri: impl Into<String>) -> Self {
+        Self::with_status(StatusCode::SEE_OTHER, uri)
+    }
+
+    /// `307 Temporary Redirect`: the client repeats the same method and body.
+    pub fn temporary(uri: impl Into<String>) -> Self {
+        Self::with_status(StatusCode::TEMPORARY_REDIRECT, uri)
+    }
+
+    /// `308 Permanent Redirect`: like `temporary`, but may be cached.
+    pub fn permanent(uri: impl Into<String>) -> Self {
+        Self::with_status(StatusCode::PERMANENT_REDIRECT, uri)
+    }
+
+    /// A redirect with any status, e.g. `302 Found` or `301 Moved Permanently`.
+    pub fn with_status(status: StatusCode, uri: impl Into<String>) -> Self {
+        Self {
+            status,
+            location: uri.into(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+}
+
+impl IntoResponse for Redirect {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let mut response = HttpResponse::new();
+        response.status = self.status.as_u16();
+        response.headers.insert("Location", self.location.as_str());
+        response
+    }
+}
+
+/// An empty `204 No Content` response.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        HttpResponse {
+            status: StatusCode::NO_CONTENT.as_u16(),
+            ..HttpResponse::new()
+        }
+    }
+}
+
+/// What `#[http_code]`, `#[header]` and `#[redirect]` do to a handler's
+/// response. Errors pass through untouched, so exception filters still see
+/// them. The attributes reject header names that aren't RFC 7230 tokens, and
+/// values or locations with a CR, LF or NUL, at compile time.
+///
+/// ```
+/// use toni::http_helpers::{IntoResponse, ResponseDecorators, StatusCode};
+///
+/// let decorators = ResponseDecorators::new()
+///     .status(StatusCode::CREATED)
+///     .header("Cache-Control", "no-store");
+///
+/// let response = decorators.wrap("created").to_response();
+/// assert_eq!(response.status, 201);
+/// assert_eq!(response.headers.get_str("cache-control"), Some("no-store"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseDecorators {
+    status: Option<StatusCode>,
+    headers: Vec<(String, String)>,
+    redirect: Option<Redirect>,
+}
+
+impl ResponseDecorators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the status of responses that would be `200 OK`
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Adds a header, unless the handler's response sets it
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Turns a `2xx` response into a redirect. Redirects the handler returned
+    /// and error statuses are kept
+    pub fn redirect(mut self, status: StatusCode, location: impl Into<String>) -> Self {
+        self.redirect = Some(Redirect::with_status(status, location));
+        self
+    }
+
+    pub fn wrap<T>(self, response: T) -> Decorated<T> {
+        Decorated {
+            response,
+            decorators: self,
+        }
+    }
+
+    fn apply(&self, mut response: HttpResponse) -> HttpResponse {
+        if let Some(status) = self.status
+            && response.status == StatusCode::OK.as_u16()
+        {
+            response.status = status.as_u16();
+        }
+
+        if let Some(redirect) = &self.redirect
+            && (200..300).contains(&response.status)
+        {
+            response.status = redirect.status().as_u16();
+            response.headers.remove("location");
+            response.headers.insert("Location", redirect.location());
+            response.body = None;
+        }
+
+        for (name, value) in &self.headers {
+            if !response.headers.contains_key(name) {
+                response.headers.insert(name.as_str(), value.as_str());
+            }
+        }
+        response
+    }
+}
+
+/// A handler's response with its method's [`ResponseDecorators`] applied
+#[derive(Debug)]
+pub struct Decorated<T> {
+    response: T,
+    decorators: ResponseDecorators,
+}
+
+impl<T> IntoResponse for Decorated<T>
+where
+    T: IntoResponse<Response = HttpResponse>,
+{
+    type Response = HttpResponse;
+
+    fn to_response(&self) -> Self::Response {
+        let response = self.response.to_response();
+        if self.response.exception().is_some() {
+            return response;
+        }
+        self.decorators.apply(response)
+    }
+
+    fn exception(&self) -> Option<Exception<'_>> {
+        self.response.exception()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_helpers::{HeaderMap, HttpException};
+
+    #[test]
+    fn test_status_only_replaces_ok() {
+        let decorators = ResponseDecorators::new().status(StatusCode::CREATED);
+        assert_eq!(decorators.clone().wrap("done").to_response().status, 201);
+        assert_eq!(decorators.wrap(NoContent).to_response().status, 204);
+    }
+
+    #[test]
+    fn test_headers_keep_the_handlers_own() {
+        let decorators = ResponseDecorators::new()
+            .header("Cache-Control", "no-store")
+            .header("X-Version", "1");
+        let handler_headers = HeaderMap::from_iter([("cache-control", "max-age=60")]);
+
+        let response = decorators.wrap(handler_headers).to_response();
+        assert_eq!(
+            response.headers.get_str("cache-control"),
+            Some("max-age=60")
+        );
+        assert_eq!(response.headers.get_str("x-version"), Some("1"));
+    }
+
+    #[test]
+    fn test_redirect() {
+        let decorators = ResponseDecorators::new().redirect(StatusCode::FOUND, "/login");
+
+        let response = decorators.clone().wrap("ignored").to_response();
+        assert_eq!(response.status, 302);
+        assert_eq!(response.headers.get_str("location"), Some("/login"));
+        assert!(response.body.is_none());
+
+        // The handler can pick another location
+        let response = decorators.wrap(Redirect::to("/home")).to_response();
+        assert_eq!(response.status, 303);
+        assert_eq!(response.headers.get_str("location"), Some("/home"));
+    }
+
+    #[test]
+    fn test_redirect_keeps_error_statuses() {
+        let decorators = ResponseDecorators::new().redirect(StatusCode::FOUND, "/login");
+
+        let response = decorators
+            .wrap((StatusCode::UNAUTHORIZED, "sign in first"))
+            .to_response();
+        assert_eq!(response.status, 401);
+        assert!(response.headers.get_str("location").is_none());
+        assert!(response.body.is_some());
+    }
+
+    #[test]
+    fn test_errors_pass_through() {
+        let decorators = ResponseDecorators::new()
+            .status(StatusCode::CREATED)
+            .header("Cache-Control", "no-store")
+            .redirect(StatusCode::FOUND, "/login");
+        let result: Result<&str, HttpException> = Err(HttpException::not_found("gone"));
+
+        let decorated = decorators.wrap(result);
+        assert!(decorated.exception().is_some());
+        let response = decorated.to_response();
+        assert_eq!(response.status, 404);
+        assert!(response.headers.get_str("cache-control").is_none());
+    }
+}
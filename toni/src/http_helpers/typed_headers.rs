@@ -0,0 +1,272 @@
+//! Typed parsing of common request headers.
+//!
+//! Implement [`TypedHeader`] to use your own type with the
+//! [`Header<T>`](crate::extractors::Header) extractor.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::HeaderValue;
+
+/// A header that can be parsed from its raw values.
+pub trait TypedHeader: Sized {
+    /// Header name, matched case-insensitively.
+    fn name() -> &'static str;
+
+    /// Parses the header from all values sent under its name (at least one).
+    fn decode(values: &[&HeaderValue]) -> Result<Self, String>;
+}
+
+fn single_str<'a>(values: &[&'a HeaderValue]) -> Result<&'a str, String> {
+    values
+        .first()
+        .and_then(|v| v.to_str())
+        .map(str::trim)
+        .ok_or_else(|| "header value is not valid UTF-8".to_string())
+}
+
+/// Comma-separated items across all values of a header.
+fn list_items<'a>(values: &[&'a HeaderValue]) -> Result<Vec<&'a str>, String> {
+    let mut items = Vec::new();
+    for value in values {
+        let value = value
+            .to_str()
+            .ok_or_else(|| "header value is not valid UTF-8".to_string())?;
+        items.extend(value.split(',').map(str::trim).filter(|s| !s.is_empty()));
+    }
+    Ok(items)
+}
+
+/// The `Authorization` header, split into scheme and credentials.
+///
+/// ```
+/// use toni::http_helpers::{Authorization, HeaderValue, TypedHeader};
+///
+/// let value = HeaderValue::from("Bearer abc.def");
+/// let auth = Authorization::decode(&[&value]).unwrap();
+/// assert_eq!(auth.bearer(), Some("abc.def"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authorization {
+    pub scheme: String,
+    pub credentials: String,
+}
+
+impl Authorization {
+    /// The token of a `Bearer` authorization.
+    pub fn bearer(&self) -> Option<&str> {
+        self.scheme
+            .eq_ignore_ascii_case("bearer")
+            .then_some(self.credentials.as_str())
+    }
+
+    /// The decoded user and password of a `Basic` authorization.
+    pub fn basic(&self) -> Option<(String, String)> {
+        if !self.scheme.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+        let decoded = STANDARD.decode(&self.credentials).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (user, password) = decoded.split_once(':')?;
+        Some((user.to_string(), password.to_string()))
+    }
+}
+
+impl TypedHeader for Authorization {
+    fn name() -> &'static str {
+        "authorization"
+    }
+
+    fn decode(values: &[&HeaderValue]) -> Result<Self, String> {
+        let value = single_str(values)?;
+        let (scheme, credentials) = value
+            .split_once(' ')
+            .ok_or_else(|| "expected '<scheme> <credentials>'".to_string())?;
+        Ok(Authorization {
+            scheme: scheme.to_string(),
+            credentials: credentials.trim().to_string(),
+        })
+    }
+}
+
+/// An entity tag from `If-None-Match` or `ETag`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag {
+    pub tag: String,
+    pub weak: bool,
+}
+
+impl ETag {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let (weak, quoted) = match raw.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let tag = quoted
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .ok_or_else(|| format!("invalid entity tag '{}'", raw))?;
+        Ok(ETag {
+            tag: tag.to_string(),
+            weak,
+        })
+    }
+}
+
+/// The `If-None-Match` header.
+///
+/// ```
+/// use toni::http_helpers::{HeaderValue, IfNoneMatch, TypedHeader};
+///
+/// let value = HeaderValue::from("W/\"v1\", \"v2\"");
+/// let header = IfNoneMatch::decode(&[&value]).unwrap();
+/// assert!(header.matches("\"v1\""));
+/// assert!(!header.matches("\"v3\""));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfNoneMatch {
+    /// `*`, matching any current representation
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl IfNoneMatch {
+    /// Weak comparison against an `ETag` value such as `"v1"` or `W/"v1"`.
+    pub fn matches(&self, etag: &str) -> bool {
+        match self {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Tags(tags) => match ETag::parse(etag.trim()) {
+                Ok(current) => tags.iter().any(|t| t.tag == current.tag),
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+impl TypedHeader for IfNoneMatch {
+    fn name() -> &'static str {
+        "if-none-match"
+    }
+
+    fn decode(values: &[&HeaderValue]) -> Result<Self, String> {
+        let items = list_items(values)?;
+        if items == ["*"] {
+            return Ok(IfNoneMatch::Any);
+        }
+        items
+            .into_iter()
+            .map(ETag::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(IfNoneMatch::Tags)
+    }
+}
+
+/// A language range with its quality from `Accept-Language`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageRange {
+    pub tag: String,
+    pub quality: f32,
+}
+
+/// The `Accept-Language` header, ordered by preference.
+///
+/// ```
+/// use toni::http_helpers::{AcceptLanguage, HeaderValue, TypedHeader};
+///
+/// let value = HeaderValue::from("fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5");
+/// let header = AcceptLanguage::decode(&[&value]).unwrap();
+/// assert_eq!(header.negotiate(&["en", "fr"]), Some("fr"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptLanguage(pub Vec<LanguageRange>);
+
+impl AcceptLanguage {
+    /// Language ranges from most to least preferred.
+    pub fn ranges(&self) -> &[LanguageRange] {
+        &self.0
+    }
+
+    /// The best match among `available` languages.
+    ///
+    /// A range matches a language equal to it or one of its prefixes
+    /// (`fr-CH` accepts `fr`), and `*` matches anything.
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        for range in self.0.iter().filter(|r| r.quality > 0.0) {
+            if range.tag == "*" {
+                return available.first().copied();
+            }
+            let found = available.iter().find(|lang| {
+                let lang = lang.to_ascii_lowercase();
+                let tag = range.tag.to_ascii_lowercase();
+                tag == lang || tag.starts_with(&format!("{}-", lang))
+            });
+            if let Some(lang) = found {
+                return Some(lang);
+            }
+        }
+        None
+    }
+}
+
+impl TypedHeader for AcceptLanguage {
+    fn name() -> &'static str {
+        "accept-language"
+    }
+
+    fn decode(values: &[&HeaderValue]) -> Result<Self, String> {
+        let mut ranges = list_items(values)?
+            .into_iter()
+            .map(|item| {
+                let mut parts = item.split(';');
+                let tag = parts.next().unwrap_or_default().trim().to_string();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map(|q| {
+                        q.parse::<f32>()
+                            .map_err(|_| format!("invalid quality '{}'", q))
+                    })
+                    .transpose()?
+                    .unwrap_or(1.0);
+                Ok(LanguageRange { tag, quality })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Stable sort keeps the client's order between equal qualities
+        ranges.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+        Ok(AcceptLanguage(ranges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_authorization() {
+        let value = HeaderValue::from("Basic dXNlcjpwYXNz");
+        let auth = Authorization::decode(&[&value]).unwrap();
+        assert_eq!(auth.basic(), Some(("user".to_string(), "pass".to_string())));
+        assert_eq!(auth.bearer(), None);
+    }
+
+    #[test]
+    fn test_if_none_match_across_values() {
+        let first = HeaderValue::from("\"a\"");
+        let second = HeaderValue::from("W/\"b\"");
+        let header = IfNoneMatch::decode(&[&first, &second]).unwrap();
+        assert!(header.matches("W/\"a\""));
+        assert!(header.matches("\"b\""));
+
+        let any = HeaderValue::from("*");
+        assert_eq!(IfNoneMatch::decode(&[&any]).unwrap(), IfNoneMatch::Any);
+    }
+
+    #[test]
+    fn test_accept_language_orders_by_quality() {
+        let value = HeaderValue::from("en;q=0.5, de, pt-BR;q=0.8");
+        let header = AcceptLanguage::decode(&[&value]).unwrap();
+        let tags: Vec<_> = header.ranges().iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, vec!["de", "pt-BR", "en"]);
+        assert_eq!(header.negotiate(&["en", "pt"]), Some("pt"));
+    }
+}
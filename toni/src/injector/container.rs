@@ -7,12 +7,12 @@ use crate::{
     middleware::MiddlewareManager,
     structs_helpers::EnhancerMetadata,
     traits_helpers::{
-        Controller, ControllerTrait, Guard, Interceptor, ModuleMetadata, Pipe, Provider,
-        ProviderTrait,
+        Controller, ControllerTrait, ExceptionFilter, FallbackHandler, Gateway, Guard, Interceptor,
+        ModuleMetadata, Pipe, Provider, ProviderTrait,
     },
 };
 
-use super::{InstanceWrapper, module::Module};
+use super::{GatewayWrapper, InstanceWrapper, ModuleRef, module::Module};
 
 pub struct ToniContainer {
     modules: FxHashMap<String, Module>,
@@ -25,6 +25,9 @@ pub struct ToniContainer {
     global_guards: Vec<Arc<dyn Guard>>,
     global_interceptors: Vec<Arc<dyn Interceptor>>,
     global_pipes: Vec<Arc<dyn Pipe>>,
+    global_filters: Vec<Arc<dyn ExceptionFilter>>,
+    /// Answers requests no route matches
+    fallback_handler: Option<Arc<dyn FallbackHandler>>,
 }
 
 impl Default for ToniContainer {
@@ -43,6 +46,8 @@ impl ToniContainer {
             global_guards: Vec::new(),
             global_interceptors: Vec::new(),
             global_pipes: Vec::new(),
+            global_filters: Vec::new(),
+            fallback_handler: None,
         }
     }
 
@@ -58,11 +63,27 @@ impl ToniContainer {
         self.global_pipes.push(pipe);
     }
 
+    pub fn add_global_filter(&mut self, filter: Arc<dyn ExceptionFilter>) {
+        self.global_filters.push(filter);
+    }
+
+    pub fn set_fallback_handler(&mut self, handler: Arc<dyn FallbackHandler>) {
+        self.fallback_handler = Some(handler);
+    }
+
+    pub fn get_fallback_handler(&self) -> Option<Arc<dyn FallbackHandler>> {
+        self.fallback_handler.clone()
+    }
+
     pub fn get_global_enhancers(&self) -> EnhancerMetadata {
         EnhancerMetadata {
             guards: self.global_guards.clone(),
             interceptors: self.global_interceptors.clone(),
             pipes: self.global_pipes.clone(),
+            param_pipes: vec![],
+            filters: self.global_filters.clone(),
+            // Global middleware goes through the middleware manager
+            middleware: vec![],
         }
     }
 
@@ -99,6 +120,19 @@ impl ToniContainer {
         Ok(())
     }
 
+    pub fn add_gateway(
+        &mut self,
+        module_ref_token: &String,
+        gateway: Box<dyn Gateway>,
+    ) -> Result<()> {
+        let module_ref = self
+            .modules
+            .get_mut(module_ref_token)
+            .ok_or_else(|| anyhow!("Module not found"))?;
+        module_ref.add_gateway(gateway);
+        Ok(())
+    }
+
     pub fn add_provider(
         &mut self,
         module_ref_token: &String,
@@ -130,6 +164,7 @@ impl ToniContainer {
         module_ref_token: &String,
         controller_instance: Arc<Box<dyn ControllerTrait>>,
         enhancer_metadata: EnhancerMetadata,
+        providers: ModuleRef,
     ) -> Result<()> {
         let global_enhancers = self.get_global_enhancers();
         let module_ref = self
@@ -140,10 +175,24 @@ impl ToniContainer {
             controller_instance,
             enhancer_metadata,
             global_enhancers,
+            providers,
         );
         Ok(())
     }
 
+    pub fn add_gateway_instance(
+        &mut self,
+        module_ref_token: &String,
+        gateway_instance: Arc<GatewayWrapper>,
+    ) -> Result<()> {
+        let module_ref = self
+            .modules
+            .get_mut(module_ref_token)
+            .ok_or_else(|| anyhow!("Module not found"))?;
+        module_ref.add_gateway_instance(gateway_instance);
+        Ok(())
+    }
+
     pub fn add_export(&mut self, module_ref_token: &String, provider_token: String) -> Result<()> {
         let module_ref = self
             .modules
@@ -188,6 +237,17 @@ impl ToniContainer {
         Ok(module_ref.get_controllers_manager())
     }
 
+    pub fn get_gateways_manager(
+        &self,
+        module_ref_token: &String,
+    ) -> Result<&FxHashMap<String, Arc<dyn Gateway>>> {
+        let module_ref = self
+            .modules
+            .get(module_ref_token)
+            .ok_or_else(|| anyhow!("Module not found"))?;
+        Ok(module_ref.get_gateways_manager())
+    }
+
     pub fn get_providers_instance(
         &self,
         module_ref_token: &String,
@@ -234,6 +294,17 @@ impl ToniContainer {
         Ok(module_ref.drain_controllers_instances())
     }
 
+    pub fn get_gateways_instance(
+        &mut self,
+        module_ref_token: &String,
+    ) -> Result<Drain<'_, String, Arc<GatewayWrapper>>> {
+        let module_ref = self
+            .modules
+            .get_mut(module_ref_token)
+            .ok_or_else(|| anyhow!("Module not found"))?;
+        Ok(module_ref.drain_gateways_instances())
+    }
+
     pub fn get_imported_modules(&self, module_ref_token: &String) -> Result<&FxHashSet<String>> {
         let module_ref = self
             .modules
@@ -337,6 +408,10 @@ impl ToniContainer {
         self.global_providers.get(token).cloned()
     }
 
+    pub fn get_global_providers(&self) -> &FxHashMap<String, Arc<Box<dyn ProviderTrait>>> {
+        &self.global_providers
+    }
+
     /// Register a provider token as globally available (during scan phase)
     pub fn register_global_provider_token(&mut self, token: String) {
         self.global_provider_tokens.insert(token);
@@ -1,14 +1,23 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
 use crate::{
     http_helpers::{HttpRequest, HttpResponse, IntoResponse},
     traits_helpers::validate::Validatable,
 };
 
+use super::HandlerInfo;
+
 #[derive(Debug)]
 pub struct Context {
     original_request: HttpRequest,
     response: Option<Box<dyn IntoResponse<Response = HttpResponse> + Send>>,
     should_abort: bool,
     dto: Option<Box<dyn Validatable>>,
+    handler: Option<Arc<HandlerInfo>>,
+    started_at: Instant,
 }
 
 impl Context {
@@ -18,13 +27,38 @@ impl Context {
             response: None,
             should_abort: false,
             dto: None,
+            handler: None,
+            started_at: Instant::now(),
         }
     }
 
+    /// The handler serving the request, `None` for WebSocket handshakes
+    pub fn handler(&self) -> Option<&HandlerInfo> {
+        self.handler.as_deref()
+    }
+
+    pub(crate) fn set_handler(&mut self, handler: Arc<HandlerInfo>) {
+        self.handler = Some(handler);
+    }
+
+    /// Time since the request reached the route, middleware included
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub(crate) fn set_started_at(&mut self, started_at: Instant) {
+        self.started_at = started_at;
+    }
+
     pub fn take_request(&self) -> &HttpRequest {
         &self.original_request
     }
 
+    /// Changes here (e.g. extensions set by a guard) are seen by the handler
+    pub fn take_request_mut(&mut self) -> &mut HttpRequest {
+        &mut self.original_request
+    }
+
     pub fn set_response(
         &mut self,
         response: Box<dyn IntoResponse<Response = HttpResponse> + Send>,
@@ -44,6 +78,12 @@ impl Context {
         // }
     }
 
+    pub fn take_response(
+        &mut self,
+    ) -> Option<Box<dyn IntoResponse<Response = HttpResponse> + Send>> {
+        self.response.take()
+    }
+
     pub fn abort(&mut self) {
         self.should_abort = true;
     }
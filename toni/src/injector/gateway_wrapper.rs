@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tokio::sync::mpsc::Receiver;
+
+use crate::{
+    http_helpers::{HttpRequest, HttpResponse},
+    traits_helpers::{GatewayTrait, Guard, run_guards},
+    websocket::{WsClient, WsEvent, WsMessage, WsServer},
+};
+
+use super::{
+    Context,
+    catch_unwind::{CatchUnwind, panic_message},
+};
+
+/// Runtime wrapper around a gateway instance.
+///
+/// Adapters drive a connection through this type: `authorize` on the upgrade
+/// request, `connect` once the socket is open, `handle_message` for every
+/// incoming frame and `disconnect` when the socket closes.
+pub struct GatewayWrapper {
+    instance: Arc<Box<dyn GatewayTrait>>,
+    guards: Vec<Arc<dyn Guard>>,
+    server: WsServer,
+}
+
+impl GatewayWrapper {
+    pub fn new(
+        instance: Arc<Box<dyn GatewayTrait>>,
+        guards: Vec<Arc<dyn Guard>>,
+        server: WsServer,
+    ) -> Self {
+        Self {
+            instance,
+            guards,
+            server,
+        }
+    }
+
+    pub fn get_path(&self) -> String {
+        self.instance.get_path()
+    }
+
+    pub fn get_token(&self) -> String {
+        self.instance.get_token()
+    }
+
+    pub fn server(&self) -> &WsServer {
+        &self.server
+    }
+
+    /// Runs the gateway guards against the handshake request.
+    ///
+    /// Returns the response to send instead of upgrading when a guard denies.
+    pub async fn authorize(&self, req: &HttpRequest) -> Result<(), HttpResponse> {
+        let mut context = Context::from_request(req.clone());
+
+        if run_guards(
+            &self.guards,
+            &mut context,
+            "WebSocket handshake rejected by guard",
+        )
+        .await
+        {
+            return Ok(());
+        }
+        Err(context.get_response().to_response())
+    }
+
+    /// Registers a new client and runs the `#[on_connection]` hook
+    pub async fn connect(&self, req: &HttpRequest) -> (WsClient, Receiver<WsMessage>) {
+        let (client, receiver) = self.server.register();
+        if let Err(payload) = CatchUnwind::new(self.instance.on_connection(&client, req)).await {
+            self.log_panic("on_connection", payload);
+        }
+        (client, receiver)
+    }
+
+    /// Dispatches an incoming frame to the matching `#[subscribe_message]` handler
+    pub async fn handle_message(&self, client: &WsClient, message: WsMessage) {
+        let text = match message {
+            WsMessage::Text(text) => text,
+            WsMessage::Binary(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => {
+                    client.emit(
+                        "error",
+                        json!({ "message": "Binary frames must be UTF-8 JSON" }),
+                    );
+                    return;
+                }
+            },
+            WsMessage::Close => return,
+        };
+
+        let WsEvent { event, data } = match WsEvent::parse(&text) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                client.emit(
+                    "error",
+                    json!({ "message": format!("Invalid message: {}", e) }),
+                );
+                return;
+            }
+        };
+
+        // A panicking handler must not take the socket task down with it,
+        // otherwise `disconnect` never runs and the client stays registered
+        let result = CatchUnwind::new(self.instance.handle_message(client, &event, data)).await;
+
+        match result {
+            Ok(Ok(Some(reply))) => {
+                client.emit(&event, reply);
+            }
+            Ok(Ok(None)) => {}
+            Ok(Err(message)) => {
+                client.emit("error", json!({ "event": event, "message": message }));
+            }
+            Err(payload) => {
+                self.log_panic(&format!("event '{}'", event), payload);
+                client.emit(
+                    "error",
+                    json!({
+                        "event": event,
+                        "message": "An error occurred while handling the message"
+                    }),
+                );
+            }
+        }
+    }
+
+    /// Runs the `#[on_disconnect]` hook and drops the client from every room
+    pub async fn disconnect(&self, client: &WsClient) {
+        if let Err(payload) = CatchUnwind::new(self.instance.on_disconnect(client)).await {
+            self.log_panic("on_disconnect", payload);
+        }
+        self.server.unregister(client.id());
+    }
+
+    fn log_panic(&self, stage: &str, payload: Box<dyn std::any::Any + Send>) {
+        eprintln!(
+            "❌ Panic in gateway {} {}: {}",
+            self.instance.get_path(),
+            stage,
+            panic_message(payload.as_ref())
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::any::TypeId;
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use crate::http_helpers::HttpMethod;
+
+/// Static values attached with `#[set_metadata]` or `#[roles]`
+#[derive(Debug, Clone, Default)]
+pub struct Metadata(FxHashMap<String, Value>);
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) {
+        self.0.insert(key.into(), value);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Identifies the handler serving a request, with its controller- and
+/// method-level metadata.
+#[derive(Debug, Clone)]
+pub struct HandlerInfo {
+    controller: String,
+    controller_type: Option<TypeId>,
+    handler: String,
+    route: String,
+    host: Option<String>,
+    body_limit: Option<usize>,
+    streams_body: bool,
+    method: HttpMethod,
+    module: String,
+    controller_metadata: Metadata,
+    handler_metadata: Metadata,
+}
+
+impl HandlerInfo {
+    pub fn new(controller: impl Into<String>, handler: impl Into<String>) -> Self {
+        Self {
+            controller: controller.into(),
+            controller_type: None,
+            handler: handler.into(),
+            route: String::new(),
+            host: None,
+            body_limit: None,
+            streams_body: false,
+            method: HttpMethod::GET,
+            module: String::new(),
+            controller_metadata: Metadata::new(),
+            handler_metadata: Metadata::new(),
+        }
+    }
+
+    pub fn with_metadata(mut self, controller: Metadata, handler: Metadata) -> Self {
+        self.controller_metadata = controller;
+        self.handler_metadata = handler;
+        self
+    }
+
+    /// Records the controller struct, to target it with `for_controllers`
+    pub fn with_controller_type<C: 'static>(mut self) -> Self {
+        self.controller_type = Some(TypeId::of::<C>());
+        self
+    }
+
+    /// Restricts the handler to a host, from `#[controller(host = "...")]`
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Caps the body the adapter reads, from `#[upload_limits]`
+    pub fn with_body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    /// Hands the body to the handler as it arrives, for `Multipart` and `Form`
+    pub fn with_streamed_body(mut self) -> Self {
+        self.streams_body = true;
+        self
+    }
+
+    /// Filled in when the route is registered
+    pub(crate) fn with_route(mut self, route: String, method: HttpMethod, module: &str) -> Self {
+        self.route = route;
+        self.method = method;
+        self.module = module.to_string();
+        self
+    }
+
+    /// Name of the controller struct
+    pub fn controller(&self) -> &str {
+        &self.controller
+    }
+
+    /// Type of the controller struct, when known
+    pub fn controller_type(&self) -> Option<TypeId> {
+        self.controller_type
+    }
+
+    /// Name of the handler method
+    pub fn handler(&self) -> &str {
+        &self.handler
+    }
+
+    /// The route template the request matched, e.g. `/users/{id}`
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    /// The host pattern of the controller, e.g. `:tenant.example.com`
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// The largest body the adapter reads for the handler, in bytes
+    pub fn body_limit(&self) -> Option<usize> {
+        self.body_limit
+    }
+
+    /// Whether the adapter streams the body instead of reading it up front
+    pub fn streams_body(&self) -> bool {
+        self.streams_body
+    }
+
+    pub fn method(&self) -> HttpMethod {
+        self.method
+    }
+
+    /// Token of the module declaring the controller
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    pub fn controller_metadata(&self) -> &Metadata {
+        &self.controller_metadata
+    }
+
+    pub fn handler_metadata(&self) -> &Metadata {
+        &self.handler_metadata
+    }
+}
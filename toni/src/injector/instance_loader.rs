@@ -1,15 +1,18 @@
 use anyhow::{Result, anyhow};
 use rustc_hash::FxHashMap;
 use std::{
+    any::Any,
     cell::{RefCell, RefMut},
     rc::Rc,
     sync::Arc,
 };
 
-use super::{DependencyGraph, ToniContainer};
+use super::{DependencyGraph, GatewayWrapper, ModuleRef, ToniContainer};
 use crate::{
+    ProviderScope,
     structs_helpers::EnhancerMetadata,
-    traits_helpers::{ControllerTrait, ProviderTrait},
+    traits_helpers::{ControllerTrait, EnhancerSource, Gateway, ProviderTrait},
+    websocket::WsServer,
 };
 
 pub struct ToniInstanceLoader {
@@ -83,6 +86,8 @@ impl ToniInstanceLoader {
             .await?;
         self.create_instances_of_controllers(module_token.clone())
             .await?;
+        self.create_instances_of_gateways(module_token.clone())
+            .await?;
         Ok(())
     }
 
@@ -173,31 +178,229 @@ impl ToniInstanceLoader {
             }
             instances
         };
-        self.add_controllers_instances(module_token, controllers_instances)?;
+
+        let mut controllers = Vec::with_capacity(controllers_instances.len());
+        for (_controller_instance_token, controller_instance) in controllers_instances {
+            let handler = controller_instance.get_handler();
+            let owner = format!("handler '{}::{}'", handler.controller(), handler.handler());
+            let enhancer_metadata = EnhancerMetadata {
+                guards: self
+                    .resolve_enhancers(
+                        &module_token,
+                        &owner,
+                        "Guard",
+                        controller_instance.get_guards(),
+                    )
+                    .await?,
+                param_pipes: self
+                    .resolve_enhancers(
+                        &module_token,
+                        &owner,
+                        "Pipe",
+                        controller_instance.get_param_pipes(),
+                    )
+                    .await?,
+                pipes: controller_instance.get_pipes(),
+                interceptors: controller_instance.get_interceptors(),
+                filters: controller_instance.get_filters(),
+                middleware: self
+                    .resolve_enhancers(
+                        &module_token,
+                        &owner,
+                        "Middleware",
+                        controller_instance.get_middleware(),
+                    )
+                    .await?,
+            };
+            controllers.push((controller_instance, enhancer_metadata));
+        }
+        let module_ref = self.module_ref(&module_token)?;
+        self.add_controllers_instances(module_token, controllers, module_ref)?;
         Ok(())
     }
 
+    /// The providers the module can inject: its own, the exports of its
+    /// imports and the global ones registered so far
+    fn module_ref(&self, module_token: &String) -> Result<ModuleRef> {
+        let container = self.container.borrow();
+        let mut providers = container.get_global_providers().clone();
+
+        for imported_module in container.get_imported_modules(module_token)? {
+            for token in container.get_exports_instances_tokens(imported_module)? {
+                if let Some(instance) =
+                    container.get_provider_instance_by_token(imported_module, token)?
+                {
+                    providers.insert(token.clone(), instance.clone());
+                }
+            }
+        }
+        providers.extend(
+            container
+                .get_providers_instance(module_token)?
+                .iter()
+                .map(|(token, instance)| (token.clone(), instance.clone())),
+        );
+
+        Ok(ModuleRef::new(module_token.clone(), providers))
+    }
+
+    /// Builds the `kind` enhancers of `owner`, a handler or the module's
+    /// middleware configuration, taking provider ones from the module's scope.
+    /// One that cannot be resolved fails the bootstrap.
+    pub(crate) async fn resolve_enhancers<T: ?Sized>(
+        &self,
+        module_token: &String,
+        owner: &str,
+        kind: &str,
+        sources: Vec<EnhancerSource<T>>,
+    ) -> Result<Vec<Arc<T>>> {
+        let mut enhancers = Vec::with_capacity(sources.len());
+        for source in sources {
+            let enhancer = match source {
+                EnhancerSource::Instance(enhancer) => enhancer,
+                EnhancerSource::Provider { token, resolve } => {
+                    self.resolve_enhancer_provider(module_token, owner, kind, &token, resolve)
+                        .await?
+                }
+            };
+            enhancers.push(enhancer);
+        }
+        Ok(enhancers)
+    }
+
+    /// Builds the `kind` enhancer `token` of `owner` from the provider of that
+    /// token in the module's scope. The enhancer serves every request, so the
+    /// provider can't be request-scoped.
+    async fn resolve_enhancer_provider<T: ?Sized>(
+        &self,
+        module_token: &String,
+        owner: &str,
+        kind: &str,
+        token: &String,
+        resolve: fn(Box<dyn Any + Send>) -> Option<Arc<T>>,
+    ) -> Result<Arc<T>> {
+        let provider = match self.resolve_dependencies(module_token, vec![token.clone()], None) {
+            Ok(mut resolved) => resolved.remove(token),
+            Err(e) if e.to_string().contains("DEFERRED:") => return Err(e),
+            Err(e) => {
+                return Err(e.context(format!(
+                    "{} '{}' of {} must derive Default or be a provider of module '{}'",
+                    kind, token, owner, module_token
+                )));
+            }
+        };
+        let provider = provider.ok_or_else(|| anyhow!("Provider '{}' not found", token))?;
+        if matches!(provider.get_scope(), ProviderScope::Request) {
+            return Err(anyhow!(
+                "{} '{}' of {} can't be request-scoped",
+                kind,
+                token,
+                owner
+            ));
+        }
+        resolve(provider.execute(vec![], None).await).ok_or_else(|| {
+            anyhow!(
+                "Provider '{}' is not the {} type",
+                token,
+                kind.to_lowercase()
+            )
+        })
+    }
+
     fn add_controllers_instances(
         &self,
         module_token: String,
-        controllers_instances: FxHashMap<String, Arc<Box<dyn ControllerTrait>>>,
+        controllers: Vec<(Arc<Box<dyn ControllerTrait>>, EnhancerMetadata)>,
+        module_ref: ModuleRef,
     ) -> Result<()> {
         let mut container_mut = self.container.borrow_mut();
-        for (_controller_instance_token, controller_instance) in controllers_instances {
-            let enhancer_metadata = EnhancerMetadata {
-                guards: controller_instance.get_guards(),
-                pipes: controller_instance.get_pipes(),
-                interceptors: controller_instance.get_interceptors(),
-            };
+        for (controller_instance, enhancer_metadata) in controllers {
             container_mut.add_controller_instance(
                 &module_token,
                 controller_instance,
                 enhancer_metadata,
+                module_ref.clone(),
             )?;
         }
         Ok(())
     }
 
+    async fn create_instances_of_gateways(&self, module_token: String) -> Result<()> {
+        let has_gateways = !self
+            .container
+            .borrow()
+            .get_gateways_manager(&module_token)?
+            .is_empty();
+        if !has_gateways {
+            return Ok(());
+        }
+
+        // Every gateway shares the built-in WsServer for client and room tracking
+        let server = {
+            let resolved =
+                self.resolve_dependencies(&module_token, vec!["WsServer".to_string()], None)?;
+            let provider = resolved
+                .get("WsServer")
+                .ok_or_else(|| anyhow!("WsServer provider not found"))?
+                .clone();
+            *provider
+                .execute(vec![], None)
+                .await
+                .downcast::<WsServer>()
+                .map_err(|_| anyhow!("WsServer provider returned an unexpected type"))?
+        };
+
+        // Clone the managers out so no container borrow is held across `.await`
+        let gateways_manager: Vec<Arc<dyn Gateway>> = self
+            .container
+            .borrow()
+            .get_gateways_manager(&module_token)?
+            .values()
+            .cloned()
+            .collect();
+
+        let mut gateways = Vec::new();
+        for gateway_manager in gateways_manager {
+            let dependencies = gateway_manager.get_dependencies();
+            let resolved_dependencies =
+                self.resolve_dependencies(&module_token, dependencies, None)?;
+            let gateways_instances = gateway_manager
+                .get_all_gateways(&resolved_dependencies)
+                .await
+                .map_err(|e| anyhow!(e))?;
+            for (_gateway_instance_token, gateway_instance) in gateways_instances {
+                let owner = format!("gateway '{}'", gateway_instance.get_token());
+                let guards = self
+                    .resolve_enhancers(
+                        &module_token,
+                        &owner,
+                        "Guard",
+                        gateway_instance.get_guards(),
+                    )
+                    .await?;
+                gateways.push(GatewayWrapper::new(
+                    gateway_instance,
+                    guards,
+                    server.clone(),
+                ));
+            }
+        }
+        self.add_gateways_instances(module_token, gateways)?;
+        Ok(())
+    }
+
+    fn add_gateways_instances(
+        &self,
+        module_token: String,
+        gateways: Vec<GatewayWrapper>,
+    ) -> Result<()> {
+        let mut container_mut = self.container.borrow_mut();
+        for wrapper in gateways {
+            container_mut.add_gateway_instance(&module_token, Arc::new(wrapper))?;
+        }
+        Ok(())
+    }
+
     fn resolve_dependencies(
         &self,
         module_token: &String,
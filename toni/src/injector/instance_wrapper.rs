@@ -1,14 +1,22 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc, time::Instant};
 
 use crate::{
     async_trait,
-    http_helpers::{HttpMethod, HttpRequest, HttpResponse, IntoResponse},
+    http_helpers::{Exception, HttpException, HttpMethod, HttpRequest, HttpResponse, IntoResponse},
     middleware::{Middleware, MiddlewareChain},
+    pipes::ValidationPipe,
+    router::{HostPattern, RoutePath},
     structs_helpers::EnhancerMetadata,
-    traits_helpers::{ControllerTrait, Guard, Interceptor, InterceptorNext, Pipe},
+    traits_helpers::{
+        ControllerTrait, ExceptionFilter, Guard, Interceptor, InterceptorNext, ParamPipes, Pipe,
+        run_guards,
+    },
 };
 
-use super::Context;
+use super::{
+    Context, HandlerInfo, ModuleRef,
+    catch_unwind::{CatchUnwind, panic_message},
+};
 
 /// Represents the next step in the interceptor chain
 struct ChainNext {
@@ -30,12 +38,31 @@ impl InterceptorNext for ChainNext {
     }
 }
 
+/// Cloned to give each adapter route its own `fallback`
+#[derive(Clone)]
 pub struct InstanceWrapper {
     instance: Arc<Box<dyn ControllerTrait>>,
+    handler: Arc<HandlerInfo>,
     guards: Vec<Arc<dyn Guard>>,
     interceptors: Vec<Arc<dyn Interceptor>>,
     pipes: Vec<Arc<dyn Pipe>>,
+    param_pipes: Option<ParamPipes>,
+    module_ref: ModuleRef,
+    /// Most specific first: method, then controller, then global
+    filters: Vec<Arc<dyn ExceptionFilter>>,
     middleware_chain: MiddlewareChain,
+    /// `#[use_middleware]` of the handler, run after the global and module middleware
+    handler_middleware: Vec<Arc<dyn Middleware>>,
+    /// Parameter constraints the router can't check
+    route: Option<RoutePath>,
+    /// The host requests must be sent to, also unknown to the router
+    host: Option<HostPattern>,
+    /// The adapter's parameter names and the route's own, for a route sharing
+    /// its path with one naming them differently
+    param_aliases: Vec<(String, String)>,
+    /// Answers requests failing the host or the constraints: the next route
+    /// on the same path, or the 404 and 405 handler
+    fallback: Option<Arc<InstanceWrapper>>,
 }
 
 impl InstanceWrapper {
@@ -43,6 +70,7 @@ impl InstanceWrapper {
         instance: Arc<Box<dyn ControllerTrait>>,
         enhancer_metadata: EnhancerMetadata,
         global_enhancers: EnhancerMetadata,
+        module_token: &str,
     ) -> Self {
         // Merge enhancers: global first, then controller/method
         // Execution order: global < controller < method
@@ -55,12 +83,32 @@ impl InstanceWrapper {
         let mut pipes = global_enhancers.pipes;
         pipes.extend(enhancer_metadata.pipes);
 
+        let mut filters = global_enhancers.filters;
+        filters.extend(enhancer_metadata.filters);
+        filters.reverse();
+
+        let param_pipes = (!enhancer_metadata.param_pipes.is_empty())
+            .then(|| ParamPipes(enhancer_metadata.param_pipes.into()));
+
         Self {
+            handler: Arc::new(instance.get_handler().with_route(
+                instance.get_path(),
+                instance.get_method(),
+                module_token,
+            )),
             instance,
             guards,
             interceptors,
             pipes,
+            param_pipes,
+            module_ref: ModuleRef::default(),
+            filters,
             middleware_chain: MiddlewareChain::new(),
+            handler_middleware: enhancer_metadata.middleware,
+            route: None,
+            host: None,
+            param_aliases: Vec::new(),
+            fallback: None,
         }
     }
 
@@ -72,78 +120,215 @@ impl InstanceWrapper {
         self.instance.get_method()
     }
 
+    pub fn get_handler(&self) -> &HandlerInfo {
+        &self.handler
+    }
+
+    /// Providers for the `AsyncFromRequest` extractors of the handler
+    pub fn set_module_ref(&mut self, module_ref: ModuleRef) {
+        self.module_ref = module_ref;
+    }
+
+    /// Requests whose parameters fail the route's constraints get a 404
+    pub fn set_route(&mut self, route: RoutePath) {
+        self.route = Some(route);
+    }
+
+    /// Requests sent to other hosts are handed to the fallback
+    pub fn set_host(&mut self, host: HostPattern) {
+        self.host = Some(host);
+    }
+
+    /// Renames the parameters the adapter captured before the constraints are
+    /// checked
+    pub fn set_param_aliases(&mut self, aliases: Vec<(String, String)>) {
+        self.param_aliases = aliases;
+    }
+
+    /// Handles requests failing the route's host or constraints
+    pub fn set_fallback(&mut self, fallback: Arc<InstanceWrapper>) {
+        self.fallback = Some(fallback);
+    }
+
     pub fn add_middleware(&mut self, middleware: Arc<dyn Middleware>) {
         self.middleware_chain.use_middleware(middleware);
     }
 
+    /// Sets the global and module middleware of the route, followed by the
+    /// handler's own
     pub fn set_middleware(&mut self, middleware: Vec<Arc<dyn Middleware>>) {
-        for m in middleware {
+        let handler_middleware = self.handler_middleware.iter().cloned();
+        for m in middleware.into_iter().chain(handler_middleware) {
             self.middleware_chain.use_middleware(m);
         }
     }
 
+    /// Checks the host and the constraints, then exposes the host
+    /// parameters like path parameters
+    fn accepts(&self, req: &mut HttpRequest) -> bool {
+        let host_params = match &self.host {
+            Some(host) => match req.host().and_then(|value| host.captures(value)) {
+                Some(params) => params,
+                None => return false,
+            },
+            None => Default::default(),
+        };
+        // The fallback still expects the adapter's names
+        let renamed = (!self.param_aliases.is_empty()).then(|| {
+            req.path_params
+                .iter()
+                .map(|(name, value)| {
+                    let name = self
+                        .param_aliases
+                        .iter()
+                        .find(|(alias, _)| alias == name)
+                        .map_or(name, |(_, own)| own);
+                    (name.clone(), value.clone())
+                })
+                .collect()
+        });
+        if let Some(route) = &self.route
+            && !route.matches(renamed.as_ref().unwrap_or(&req.path_params))
+        {
+            return false;
+        }
+        if let Some(renamed) = renamed {
+            req.path_params = renamed;
+        }
+        req.path_params.extend(host_params);
+        true
+    }
+
     pub async fn handle_request(
         &self,
-        req: HttpRequest,
+        mut req: HttpRequest,
     ) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        if !self.accepts(&mut req) {
+            if let Some(fallback) = &self.fallback {
+                return Box::pin(fallback.handle_request(req)).await;
+            }
+            let message = format!("Cannot {} {}", req.method, req.path());
+            return Box::new(HttpException::not_found(message));
+        }
+
+        let started_at = Instant::now();
         let instance = self.instance.clone();
+        let handler = self.handler.clone();
         let guards = self.guards.clone();
         let interceptors = self.interceptors.clone();
         let pipes = self.pipes.clone();
+        let param_pipes = self.param_pipes.clone();
+        let module_ref = self.module_ref.clone();
+        let filters = self.filters.clone();
+
+        // Middleware consumes the request, keep a copy for the filters
+        let filter_req = (!self.filters.is_empty()).then(|| req.clone());
 
         // Execute middleware chain with controller as the final handler
-        let middleware_result = self
-            .middleware_chain
-            .execute(req, move |req| {
-                let instance = instance.clone();
-                let guards = guards.clone();
-                let interceptors = interceptors.clone();
-                let pipes = pipes.clone();
-
-                Box::pin(async move {
-                    Self::execute_controller_logic(req, instance, guards, interceptors, pipes).await
-                })
+        let chain = self.middleware_chain.execute(req, move |mut req| {
+            // Read by the extraction code generated for `#[pipe]` arguments
+            if let Some(param_pipes) = &param_pipes {
+                req.extensions.insert(param_pipes.clone());
+            }
+            // Read by `AsyncFromRequest` extractors
+            req.extensions.insert(module_ref.clone());
+            // Read by `Path` to order tuples like the route template
+            req.extensions.insert(handler.clone());
+            let instance = instance.clone();
+            let handler = handler.clone();
+            let guards = guards.clone();
+            let interceptors = interceptors.clone();
+            let pipes = pipes.clone();
+            let filters = filters.clone();
+
+            Box::pin(async move {
+                let mut context = Context::from_request(req);
+                context.set_handler(handler);
+                context.set_started_at(started_at);
+
+                Self::execute_controller_logic(
+                    context,
+                    instance,
+                    guards,
+                    interceptors,
+                    pipes,
+                    filters,
+                )
+                .await
             })
-            .await;
+        });
+
+        // A panic anywhere in the pipeline becomes a 500 instead of dropping the connection
+        let middleware_result = match CatchUnwind::new(chain).await {
+            Ok(result) => result,
+            Err(payload) => {
+                eprintln!(
+                    "❌ Panic in {} {}: {}",
+                    self.get_method().as_str(),
+                    self.get_path(),
+                    panic_message(payload.as_ref())
+                );
+                let exception = HttpException::internal_server_error(
+                    "An error occurred while processing the request",
+                );
+                let exception = Exception::new(&exception, exception.to_response());
+                return Box::new(self.catch(exception, filter_req.as_ref()));
+            }
+        };
 
         // Handle the result from middleware chain
         match middleware_result {
             Ok(response) => Box::new(response),
             Err(e) => {
-                // Convert error to HTTP response
                 eprintln!("❌ Middleware error: {}", e);
-                let mut error_response = HttpResponse::new();
-                error_response.status = 500;
-                error_response.body = Some(crate::http_helpers::Body::Json(serde_json::json!({
-                    "error": "Internal Server Error",
-                    "message": "An error occurred while processing the request"
-                })));
-                Box::new(error_response)
+                let exception = Exception::from_error(e.as_ref());
+                Box::new(self.catch(exception, filter_req.as_ref()))
             }
         }
     }
 
+    /// `req` is only kept when there are filters to run
+    fn catch(&self, exception: Exception, req: Option<&HttpRequest>) -> HttpResponse {
+        match req {
+            Some(req) => Self::apply_filters(&self.filters, exception, req),
+            None => exception.into_response(),
+        }
+    }
+
     /// Execute the controller logic with guards, interceptors, and pipes
     async fn execute_controller_logic(
-        req: HttpRequest,
+        mut context: Context,
         instance: Arc<Box<dyn ControllerTrait>>,
         guards: Vec<Arc<dyn Guard>>,
         interceptors: Vec<Arc<dyn Interceptor>>,
         pipes: Vec<Arc<dyn Pipe>>,
+        filters: Vec<Arc<dyn ExceptionFilter>>,
     ) -> HttpResponse {
-        let mut context = Context::from_request(req);
-
-        // Execute guards
-        for guard in &guards {
-            if !guard.can_activate(&context) {
-                return context.get_response().to_response();
-            }
+        // Execute guards, then interceptors wrapping the handler
+        if run_guards(&guards, &mut context, "Forbidden resource").await {
+            Self::execute_with_interceptors(&mut context, &interceptors, &instance, &pipes).await;
         }
 
-        // Execute interceptors wrapping the handler
-        Self::execute_with_interceptors(&mut context, &interceptors, &instance, &pipes).await;
+        // An interceptor skipped `next` without setting a response
+        let Some(response) = context.take_response() else {
+            return HttpException::internal_server_error("No response was produced").to_response();
+        };
+        match response.exception() {
+            Some(exception) => Self::apply_filters(&filters, exception, context.take_request()),
+            None => response.to_response(),
+        }
+    }
 
-        context.get_response().to_response()
+    /// First filter to handle the exception wins, otherwise its own response is used
+    fn apply_filters(
+        filters: &[Arc<dyn ExceptionFilter>],
+        exception: Exception,
+        req: &HttpRequest,
+    ) -> HttpResponse {
+        filters
+            .iter()
+            .find_map(|filter| filter.catch(&exception, req))
+            .unwrap_or_else(|| exception.into_response())
     }
 
     /// Execute handler wrapped by interceptors (onion/Russian doll pattern)
@@ -173,39 +358,49 @@ impl InstanceWrapper {
         first.intercept(context, Box::new(next)).await;
     }
 
-    /// Execute the actual handler (pipes + controller)
+    /// Execute the actual handler (body DTO validation, pipes and controller)
     async fn execute_handler(
         context: &mut Context,
         instance: &Arc<Box<dyn ControllerTrait>>,
         pipes: &[Arc<dyn Pipe>],
     ) {
-        // Get and validate DTO
-        let dto = instance.get_body_dto(context.take_request());
-        if let Some(dto) = dto {
-            match dto.validate_dto() {
-                Ok(()) => {
-                    context.set_dto(dto);
-                }
-                Err(validation_errors) => {
-                    let error_body = serde_json::json!({
-                        "error": "Validation failed",
-                        "details": validation_errors.to_string()
-                    });
-                    let response = crate::http_helpers::HttpResponse {
-                        body: Some(crate::http_helpers::Body::Json(error_body)),
-                        status: 400,
-                        headers: vec![],
-                    };
-                    context.set_response(Box::new(response));
-                    context.abort();
-                    return;
-                }
+        // Decode and validate the body DTO first, so pipes can read it. The
+        // settings are the route's last `ValidationPipe`, the global one or
+        // the defaults, made available to the extractors right away.
+        let validation = pipes
+            .iter()
+            .rev()
+            .find_map(|pipe| (pipe.as_ref() as &dyn Any).downcast_ref::<ValidationPipe>())
+            .cloned();
+        if let Some(validation) = &validation {
+            context
+                .take_request_mut()
+                .extensions
+                .insert(validation.clone());
+        }
+        let validation = validation.unwrap_or_default();
+        let dto = instance
+            .get_body_dto(context.take_request())
+            .and_then(|dto| match dto {
+                Some(dto) => match dto.validate_dto() {
+                    Ok(()) => Ok(Some(dto)),
+                    Err(errors) => Err(validation.reject(&errors)),
+                },
+                None => Ok(None),
+            });
+        match dto {
+            Ok(Some(dto)) => context.set_dto(dto),
+            Ok(None) => {}
+            Err(error) => {
+                context.set_response(Box::new(error));
+                context.abort();
+                return;
             }
         }
 
         // Execute pipes
         for pipe in pipes {
-            pipe.process(context);
+            pipe.process(context).await;
             if context.should_abort() {
                 return;
             }
@@ -217,3 +412,132 @@ impl InstanceWrapper {
         context.set_response(controller_response);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+    use serde_json::json;
+    use validator::Validate;
+
+    use super::*;
+    use crate::{
+        extractors::{ValidationError, body_dto},
+        http_helpers::{Body, Extensions},
+        traits_helpers::{GuardSource, validate::Validatable},
+    };
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct SignupDto {
+        #[validate(length(min = 8))]
+        password: String,
+    }
+
+    struct SignupController;
+
+    #[async_trait]
+    impl ControllerTrait for SignupController {
+        fn get_token(&self) -> String {
+            "SignupController".to_string()
+        }
+
+        async fn execute(
+            &self,
+            _req: HttpRequest,
+        ) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+            Box::new(HttpResponse::new())
+        }
+
+        fn get_path(&self) -> String {
+            "/signup".to_string()
+        }
+
+        fn get_method(&self) -> HttpMethod {
+            HttpMethod::POST
+        }
+
+        fn get_guards(&self) -> Vec<GuardSource> {
+            vec![]
+        }
+
+        fn get_pipes(&self) -> Vec<Arc<dyn Pipe>> {
+            vec![]
+        }
+
+        fn get_interceptors(&self) -> Vec<Arc<dyn Interceptor>> {
+            vec![]
+        }
+
+        fn get_body_dto(
+            &self,
+            req: &HttpRequest,
+        ) -> Result<Option<Box<dyn Validatable>>, ValidationError> {
+            body_dto::<SignupDto>(req).map(|dto| Some(Box::new(dto) as Box<dyn Validatable>))
+        }
+    }
+
+    /// Ends the request unless the body DTO was validated before the pipes
+    struct RequireDtoPipe;
+
+    #[async_trait]
+    impl Pipe for RequireDtoPipe {
+        async fn process(&self, context: &mut Context) {
+            if context.get_dto().is_none() {
+                context.set_response(Box::new(HttpException::internal_server_error(
+                    "The pipe saw no DTO",
+                )));
+                context.abort();
+            }
+        }
+    }
+
+    fn enhancers(pipes: Vec<Arc<dyn Pipe>>) -> EnhancerMetadata {
+        EnhancerMetadata {
+            guards: vec![],
+            pipes,
+            param_pipes: vec![],
+            interceptors: vec![],
+            filters: vec![],
+            middleware: vec![],
+        }
+    }
+
+    async fn signup(global: Vec<Arc<dyn Pipe>>, body: serde_json::Value) -> HttpResponse {
+        let wrapper = InstanceWrapper::new(
+            Arc::new(Box::new(SignupController)),
+            enhancers(vec![Arc::new(RequireDtoPipe)]),
+            enhancers(global),
+            "AppModule",
+        );
+        let req = HttpRequest {
+            body: Body::Json(body),
+            headers: Default::default(),
+            method: "POST".to_string(),
+            uri: "/signup".to_string(),
+            query_params: HashMap::new(),
+            path_params: HashMap::new(),
+            extensions: Extensions::new(),
+        };
+        wrapper.handle_request(req).await.to_response()
+    }
+
+    #[tokio::test]
+    async fn test_pipes_see_the_validated_dto() {
+        let response = signup(vec![], json!({ "password": "long enough" })).await;
+        assert_eq!(response.status, 200);
+
+        let response = signup(vec![], json!({ "password": "short" })).await;
+        assert_eq!(response.status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_dto_is_decoded_with_the_route_validation_pipe() {
+        let body = json!({ "password": "long enough", "admin": true });
+        assert_eq!(signup(vec![], body.clone()).await.status, 200);
+
+        let global: Vec<Arc<dyn Pipe>> =
+            vec![Arc::new(ValidationPipe::new().forbid_unknown_fields(true))];
+        assert_eq!(signup(global, body).await.status, 400);
+    }
+}
@@ -5,11 +5,26 @@ mod instance_loader;
 pub use self::instance_loader::ToniInstanceLoader;
 mod module;
 
+mod module_ref;
+pub use self::module_ref::ModuleRef;
+pub(crate) use self::module_ref::type_token;
+
 mod dependency_graph;
 pub use self::dependency_graph::DependencyGraph;
 
 mod instance_wrapper;
 pub use self::instance_wrapper::InstanceWrapper;
 
+mod gateway_wrapper;
+pub use self::gateway_wrapper::GatewayWrapper;
+
 mod context;
 pub use self::context::Context;
+
+mod handler_info;
+pub use self::handler_info::{HandlerInfo, Metadata};
+
+mod reflector;
+pub use self::reflector::Reflector;
+
+mod catch_unwind;
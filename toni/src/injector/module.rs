@@ -2,21 +2,25 @@ use std::{collections::hash_map::Drain, sync::Arc};
 
 use rustc_hash::{FxHashMap, FxHashSet};
 
-use super::InstanceWrapper;
+use super::{GatewayWrapper, InstanceWrapper, ModuleRef};
 
 use crate::{
     structs_helpers::EnhancerMetadata,
-    traits_helpers::{Controller, ControllerTrait, ModuleMetadata, Provider, ProviderTrait},
+    traits_helpers::{
+        Controller, ControllerTrait, Gateway, ModuleMetadata, Provider, ProviderTrait,
+    },
 };
 pub struct Module {
     _token: String,
     _name: String,
     controllers: FxHashMap<String, Box<dyn Controller>>,
     providers: FxHashMap<String, Box<dyn Provider>>,
+    gateways: FxHashMap<String, Arc<dyn Gateway>>,
     imports: FxHashSet<String>,
     exports: FxHashSet<String>,
     controllers_instances: FxHashMap<String, Arc<InstanceWrapper>>,
     providers_instances: FxHashMap<String, Arc<Box<dyn ProviderTrait>>>,
+    gateways_instances: FxHashMap<String, Arc<GatewayWrapper>>,
     exports_instances: FxHashSet<String>,
     metadata: Box<dyn ModuleMetadata>,
 }
@@ -28,10 +32,12 @@ impl Module {
             _name: name.to_string(),
             controllers: FxHashMap::default(),
             providers: FxHashMap::default(),
+            gateways: FxHashMap::default(),
             imports: FxHashSet::default(),
             exports: FxHashSet::default(),
             controllers_instances: FxHashMap::default(),
             providers_instances: FxHashMap::default(),
+            gateways_instances: FxHashMap::default(),
             exports_instances: FxHashSet::default(),
             metadata,
         }
@@ -46,6 +52,10 @@ impl Module {
         self.providers.insert(provider.get_name(), provider);
     }
 
+    pub fn add_gateway(&mut self, gateway: Box<dyn Gateway>) {
+        self.gateways.insert(gateway.get_name(), Arc::from(gateway));
+    }
+
     pub fn add_import(&mut self, module_token: String) {
         self.imports.insert(module_token);
     }
@@ -59,14 +69,24 @@ impl Module {
         controller: Arc<Box<dyn ControllerTrait>>,
         enhancer_metadata: EnhancerMetadata,
         global_enhancers: EnhancerMetadata,
+        module_ref: ModuleRef,
     ) {
         let token = controller.get_token();
-        let instance_wrapper =
-            InstanceWrapper::new(controller, enhancer_metadata, global_enhancers);
+        let mut instance_wrapper = InstanceWrapper::new(
+            controller,
+            enhancer_metadata,
+            global_enhancers,
+            &self._token,
+        );
+        instance_wrapper.set_module_ref(module_ref);
         self.controllers_instances
             .insert(token, Arc::new(instance_wrapper));
     }
 
+    pub fn add_gateway_instance(&mut self, gateway: Arc<GatewayWrapper>) {
+        self.gateways_instances.insert(gateway.get_token(), gateway);
+    }
+
     pub fn add_provider_instance(&mut self, provider: Arc<Box<dyn ProviderTrait>>) {
         self.providers_instances
             .insert(provider.get_token(), provider);
@@ -104,6 +124,14 @@ impl Module {
         self.controllers_instances.drain()
     }
 
+    pub fn get_gateways_manager(&self) -> &FxHashMap<String, Arc<dyn Gateway>> {
+        &self.gateways
+    }
+
+    pub fn drain_gateways_instances(&mut self) -> Drain<'_, String, Arc<GatewayWrapper>> {
+        self.gateways_instances.drain()
+    }
+
     pub fn get_imported_modules(&self) -> &FxHashSet<String> {
         &self.imports
     }
@@ -0,0 +1,102 @@
+use std::{
+    any::{Any, type_name},
+    sync::Arc,
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{http_helpers::HttpRequest, traits_helpers::ProviderTrait};
+
+/// Resolves the providers a controller's module can inject, while handling
+/// a request.
+///
+/// Handed to [`AsyncFromRequest`](crate::extractors::AsyncFromRequest)
+/// extractors. It sees the module's own providers, the exports of the
+/// modules it imports and the global providers registered before it.
+#[derive(Clone, Default)]
+pub struct ModuleRef {
+    module: String,
+    providers: Arc<FxHashMap<String, Arc<Box<dyn ProviderTrait>>>>,
+}
+
+impl ModuleRef {
+    pub(crate) fn new(
+        module: impl Into<String>,
+        providers: FxHashMap<String, Arc<Box<dyn ProviderTrait>>>,
+    ) -> Self {
+        Self {
+            module: module.into(),
+            providers: Arc::new(providers),
+        }
+    }
+
+    /// Token of the module
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Resolves the provider of type `T`, as `#[inject]` on a `T` field would.
+    ///
+    /// Request-scoped providers are built for `req`.
+    pub async fn get<T: Send + 'static>(&self, req: &HttpRequest) -> Option<T> {
+        let instance = self.execute(&type_token::<T>(), req).await?;
+        instance.downcast::<T>().ok().map(|instance| *instance)
+    }
+
+    /// Resolves a provider by token, e.g. one registered with
+    /// `provider_value!("API_KEY", ..)`
+    pub async fn get_by_token<T: Clone + Send + 'static>(
+        &self,
+        token: &str,
+        req: &HttpRequest,
+    ) -> Option<T> {
+        let instance = self.execute(token, req).await?;
+        match instance.downcast::<T>() {
+            Ok(instance) => Some(*instance),
+            // Value providers share their value behind an `Arc`
+            Err(instance) => instance
+                .downcast::<Arc<dyn Any + Send + Sync>>()
+                .ok()?
+                .downcast_ref::<T>()
+                .cloned(),
+        }
+    }
+
+    pub fn has(&self, token: &str) -> bool {
+        self.providers.contains_key(token)
+    }
+
+    async fn execute(&self, token: &str, req: &HttpRequest) -> Option<Box<dyn Any + Send>> {
+        let provider = self.providers.get(token)?;
+        Some(provider.execute(vec![], Some(req)).await)
+    }
+}
+
+/// The token `#[inject]` uses for a type: its name without the module path,
+/// generic arguments kept as `type_name` spells them
+pub(crate) fn type_token<T>() -> String {
+    let name = type_name::<T>();
+    let (path, generics) = name.split_at(name.find('<').unwrap_or(name.len()));
+    let base = path.rsplit("::").next().unwrap_or(path);
+    format!("{}{}", base, generics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    mod repo {
+        pub struct UserRepository;
+        pub struct Config<T>(T);
+    }
+
+    #[test]
+    fn test_type_token() {
+        assert_eq!(type_token::<repo::UserRepository>(), "UserRepository");
+        assert_eq!(
+            type_token::<repo::Config<repo::UserRepository>>(),
+            format!("Config<{}>", type_name::<repo::UserRepository>())
+        );
+    }
+}
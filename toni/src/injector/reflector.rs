@@ -0,0 +1,116 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::Context;
+
+/// Reads the metadata of the handler serving a request, for guards and
+/// interceptors.
+///
+/// ```ignore
+/// #[roles("admin")]
+/// #[get("/users")]
+/// fn list(&self, req: HttpRequest) -> ToniBody { /* ... */ }
+///
+/// // In a guard:
+/// let roles: Vec<String> = Reflector.get_all_and_merge("roles", context);
+/// ```
+///
+/// Values that do not deserialize into the requested type are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reflector;
+
+impl Reflector {
+    /// The method-level value, or the controller-level one when the method sets none
+    pub fn get<T: DeserializeOwned>(&self, key: &str, context: &Context) -> Option<T> {
+        let handler = context.handler()?;
+        let value = handler
+            .handler_metadata()
+            .get(key)
+            .or_else(|| handler.controller_metadata().get(key))?;
+        T::deserialize(value).ok()
+    }
+
+    /// Controller-level values followed by method-level ones, with arrays flattened
+    pub fn get_all_and_merge<T: DeserializeOwned>(&self, key: &str, context: &Context) -> Vec<T> {
+        let Some(handler) = context.handler() else {
+            return Vec::new();
+        };
+
+        [handler.controller_metadata(), handler.handler_metadata()]
+            .into_iter()
+            .filter_map(|metadata| metadata.get(key))
+            .flat_map(|value| match value {
+                Value::Array(items) => items.iter().collect(),
+                value => vec![value],
+            })
+            .filter_map(|value| T::deserialize(value).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        http_helpers::{Body, Extensions, HeaderMap, HttpRequest},
+        injector::{HandlerInfo, Metadata},
+    };
+
+    fn context(controller: &[(&str, Value)], handler: &[(&str, Value)]) -> Context {
+        let metadata = |entries: &[(&str, Value)]| {
+            let mut metadata = Metadata::new();
+            for (key, value) in entries {
+                metadata.insert(*key, value.clone());
+            }
+            metadata
+        };
+        let mut context = Context::from_request(HttpRequest {
+            body: Body::Text(String::new()),
+            headers: HeaderMap::new(),
+            method: "GET".to_string(),
+            uri: "/".to_string(),
+            query_params: HashMap::new(),
+            path_params: HashMap::new(),
+            extensions: Extensions::new(),
+        });
+        context.set_handler(Arc::new(
+            HandlerInfo::new("UserController", "list")
+                .with_metadata(metadata(controller), metadata(handler)),
+        ));
+        context
+    }
+
+    #[test]
+    fn test_get_overrides() {
+        let context = context(
+            &[("roles", json!(["user"])), ("public", json!(false))],
+            &[("roles", json!(["admin"]))],
+        );
+
+        let roles: Option<Vec<String>> = Reflector.get("roles", &context);
+        assert_eq!(roles, Some(vec!["admin".to_string()]));
+        assert_eq!(Reflector.get::<bool>("public", &context), Some(false));
+        assert_eq!(Reflector.get::<bool>("missing", &context), None);
+        assert_eq!(Reflector.get::<u32>("public", &context), None);
+    }
+
+    #[test]
+    fn test_get_all_and_merge() {
+        let context = context(
+            &[("roles", json!(["user", "auditor"]))],
+            &[("roles", json!("admin"))],
+        );
+
+        let roles: Vec<String> = Reflector.get_all_and_merge("roles", &context);
+        assert_eq!(roles, ["user", "auditor", "admin"]);
+        assert!(
+            Reflector
+                .get_all_and_merge::<String>("scopes", &context)
+                .is_empty()
+        );
+    }
+}
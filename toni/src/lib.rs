@@ -9,6 +9,7 @@ pub mod http_helpers;
 pub mod injector;
 pub mod middleware;
 pub mod module_helpers;
+pub mod pipes;
 pub mod provider_scope;
 mod request;
 mod router;
@@ -17,15 +18,18 @@ mod structs_helpers;
 mod toni_application;
 pub mod toni_factory;
 pub mod traits_helpers;
+pub mod websocket;
 
 // Re-exports for adapter crates
-pub use adapter::RouteAdapter;
+pub use adapter::{BodySender, BodyStream, PayloadTooLarge, RouteAdapter};
 pub use http_adapter::HttpAdapter;
 pub use http_helpers::{Body, HttpMethod, HttpRequest, HttpResponse, IntoResponse};
-pub use injector::InstanceWrapper;
+pub use injector::{GatewayWrapper, InstanceWrapper};
 
 // Re-export built-in providers
 pub use request::{Request, RequestManager};
+pub use router::{HostPattern, RoutePath, RoutePathError, RouteSegment};
+pub use websocket::{WsClient, WsMessage, WsServer};
 
 // Re-export dependencies used in macro-generated code
 // This allows users to only depend on `toni` without needing to add these explicitly
@@ -36,7 +40,7 @@ pub use rustc_hash::FxHashMap;
 pub use provider_scope::ProviderScope;
 
 // Re-export trait so users wont have to import manually
-pub use extractors::FromRequest;
+pub use extractors::{AsyncFromRequest, FromRequest};
 
 // Re-export macros
 pub use toni_macros::*;
@@ -60,6 +60,7 @@ impl Next for ChainLink {
     }
 }
 
+#[derive(Clone)]
 pub struct MiddlewareChain {
     middleware_stack: Vec<Arc<dyn Middleware>>,
 }
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use crate::http_helpers::{CookieKey, HttpRequest};
+use crate::traits_helpers::middleware::{Middleware, MiddlewareResult, Next};
+
+/// Makes the cookie key available to extractors through request extensions.
+///
+/// Registered by [`ToniFactory::use_cookie_key`](crate::ToniFactory::use_cookie_key).
+pub struct CookieKeyMiddleware {
+    key: CookieKey,
+}
+
+impl CookieKeyMiddleware {
+    pub fn new(key: CookieKey) -> Self {
+        Self { key }
+    }
+}
+
+#[async_trait]
+impl Middleware for CookieKeyMiddleware {
+    async fn handle(&self, mut req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        req.extensions.insert(self.key.clone());
+        next.run(req).await
+    }
+}
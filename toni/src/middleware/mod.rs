@@ -6,7 +6,11 @@ pub use route_pattern::{IntoRoutePattern, RoutePattern};
 mod module_middleware;
 pub use module_middleware::MiddlewareManager;
 
+mod cookie_key;
+pub use cookie_key::CookieKeyMiddleware;
+
 // Re-export core traits
 pub use crate::traits_helpers::middleware::{
-    FunctionalMiddleware, Middleware, MiddlewareConfiguration, MiddlewareFn, MiddlewareResult, Next,
+    FunctionalMiddleware, Middleware, MiddlewareConfiguration, MiddlewareFn, MiddlewareResult,
+    MiddlewareSource, Next,
 };
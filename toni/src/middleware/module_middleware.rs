@@ -1,7 +1,13 @@
+use async_trait::async_trait;
 use rustc_hash::FxHashMap;
+use std::any::TypeId;
 use std::sync::Arc;
 
-use crate::traits_helpers::middleware::{Middleware, MiddlewareConfiguration};
+use crate::http_helpers::HttpRequest;
+use crate::router::RoutePath;
+use crate::traits_helpers::middleware::{
+    Middleware, MiddlewareConfiguration, MiddlewareResult, Next,
+};
 
 /// Middleware manager for organizing middleware by module
 ///
@@ -63,6 +69,21 @@ impl MiddlewareManager {
         module_token: &str,
         route_path: &str,
         method: &str,
+    ) -> Vec<Arc<dyn Middleware>> {
+        self.get_middleware_for_handler(module_token, route_path, method, None)
+    }
+
+    /// Like [`get_middleware_for_route`](Self::get_middleware_for_route), for
+    /// a route served by the `controller` type
+    ///
+    /// Middleware that only some requests of the route select, e.g. by host
+    /// or through a parameter, is checked again for each request.
+    pub fn get_middleware_for_handler(
+        &self,
+        module_token: &str,
+        route_path: &str,
+        method: &str,
+        controller: Option<TypeId>,
     ) -> Vec<Arc<dyn Middleware>> {
         let mut middleware = Vec::new();
 
@@ -71,9 +92,31 @@ impl MiddlewareManager {
 
         // Add module-specific middleware if applicable
         if let Some(configs) = self.module_middleware.get(module_token) {
+            let route = RoutePath::parse(route_path).ok();
             for config in configs {
-                if config.should_apply(route_path, method) {
-                    middleware.extend(config.middleware.iter().cloned());
+                let applies = route
+                    .as_ref()
+                    .and_then(|route| config.applies_to_route(route, method, controller));
+                match applies {
+                    Some(true) => middleware.extend(config.middleware.iter().cloned()),
+                    Some(false) => {}
+                    None => {
+                        // Decided again for each request
+                        let scope = Arc::new(MiddlewareConfiguration {
+                            middleware: Vec::new(),
+                            include_patterns: config.include_patterns.clone(),
+                            exclude_patterns: config.exclude_patterns.clone(),
+                            include_controllers: config.include_controllers.clone(),
+                        });
+                        for inner in &config.middleware {
+                            middleware.push(Arc::new(ScopedMiddleware {
+                                inner: inner.clone(),
+                                config: scope.clone(),
+                                method: method.to_string(),
+                                controller,
+                            }) as Arc<dyn Middleware>);
+                        }
+                    }
                 }
             }
         }
@@ -92,6 +135,29 @@ impl MiddlewareManager {
     }
 }
 
+/// Runs `inner` only for the requests its configuration selects
+struct ScopedMiddleware {
+    inner: Arc<dyn Middleware>,
+    config: Arc<MiddlewareConfiguration>,
+    /// The route's method, which implicit `HEAD` requests don't carry
+    method: String,
+    controller: Option<TypeId>,
+}
+
+#[async_trait]
+impl Middleware for ScopedMiddleware {
+    async fn handle(&self, req: HttpRequest, next: Box<dyn Next>) -> MiddlewareResult {
+        let applies =
+            self.config
+                .should_apply_to(req.path(), &self.method, req.host(), self.controller);
+        if applies {
+            self.inner.handle(req, next).await
+        } else {
+            next.run(req).await
+        }
+    }
+}
+
 impl Default for MiddlewareManager {
     fn default() -> Self {
         Self::new()
@@ -150,4 +216,96 @@ mod tests {
         let middleware = manager.get_middleware_for_route("TestModule", "/api/test", "GET");
         assert_eq!(middleware.len(), 1);
     }
+
+    #[test]
+    fn test_host_scoped_middleware() {
+        use crate::middleware::RoutePattern;
+
+        let mut manager = MiddlewareManager::new();
+        manager.add_for_module(
+            "TestModule".to_string(),
+            MiddlewareConfiguration {
+                middleware: vec![Arc::new(DummyMiddleware::new("admin"))],
+                include_patterns: vec![
+                    RoutePattern::all_methods("/*").on_host("admin.example.com"),
+                ],
+                ..Default::default()
+            },
+        );
+
+        let config = &manager.get_module_middleware()["TestModule"][0];
+        assert!(!config.should_apply("/users", "GET"));
+        assert!(config.should_apply_to_host("/users", "GET", Some("admin.example.com")));
+        assert!(!config.should_apply_to_host("/users", "GET", Some("api.example.com")));
+        assert!(!config.should_apply_to_host("/users", "GET", None));
+
+        let middleware = manager.get_middleware_for_route("TestModule", "/users", "GET");
+        assert_eq!(middleware.len(), 1);
+    }
+
+    #[test]
+    fn test_resolves_routes_when_possible() {
+        use crate::middleware::RoutePattern;
+
+        let mut manager = MiddlewareManager::new();
+        manager.add_for_module(
+            "TestModule".to_string(),
+            MiddlewareConfiguration {
+                middleware: vec![Arc::new(DummyMiddleware::new("users"))],
+                include_patterns: vec![RoutePattern::all_methods("/users/:id/*")],
+                exclude_patterns: vec![RoutePattern::single_method("/users/*/public", "GET")],
+                ..Default::default()
+            },
+        );
+        let count = |route: &str, method: &str| {
+            manager
+                .get_middleware_for_route("TestModule", route, method)
+                .len()
+        };
+
+        assert_eq!(count("/users/1/posts", "GET"), 1);
+        assert_eq!(count("/users/1/public", "GET"), 0);
+        assert_eq!(count("/users/1/public", "POST"), 1);
+        assert_eq!(count("/teams/:id/posts", "GET"), 0);
+        assert_eq!(count("/teams/1", "GET"), 0);
+        // `/users/1/` matches, so whether it applies depends on the request
+        assert_eq!(count("/users/1", "GET"), 1);
+        // Parameters are only known per request
+        assert_eq!(count("/users/:id/:section", "GET"), 1);
+    }
+
+    #[test]
+    fn test_controller_scoped_middleware() {
+        use crate::middleware::RoutePattern;
+
+        struct UserController;
+        struct TeamController;
+
+        let mut manager = MiddlewareManager::new();
+        manager.add_for_module(
+            "TestModule".to_string(),
+            MiddlewareConfiguration {
+                middleware: vec![Arc::new(DummyMiddleware::new("users"))],
+                include_controllers: vec![TypeId::of::<UserController>()],
+                exclude_patterns: vec![RoutePattern::all_methods("/users/login")],
+                ..Default::default()
+            },
+        );
+        let count = |route: &str, controller: TypeId| {
+            manager
+                .get_middleware_for_handler("TestModule", route, "GET", Some(controller))
+                .len()
+        };
+
+        assert_eq!(count("/users", TypeId::of::<UserController>()), 1);
+        assert_eq!(count("/anywhere/:id", TypeId::of::<UserController>()), 1);
+        assert_eq!(count("/users/login", TypeId::of::<UserController>()), 0);
+        assert_eq!(count("/users", TypeId::of::<TeamController>()), 0);
+        assert_eq!(
+            manager
+                .get_middleware_for_route("TestModule", "/users", "GET")
+                .len(),
+            0
+        );
+    }
 }
@@ -1,8 +1,38 @@
-/// Represents a route pattern with optional HTTP method filtering
+//! Paths middleware is applied to.
+//!
+//! Patterns are matched against the request path, one `/` segment at a time:
+//!
+//! - `*` matches one segment, e.g. `/users/*/posts`
+//! - `*` ending the pattern matches whatever follows, like a prefix:
+//!   `/api*` matches `/api`, `/apiv2` and `/api/users`, `/users/*` matches
+//!   `/users/` and anything below it, but not `/users`
+//! - `**` matches any number of segments, none included
+//! - `:id`, `{id}` and `:id(int)` match one segment like a route parameter
+//! - `{a,b}` matches either alternative and `*` inside a segment matches any
+//!   text, e.g. `/{users,teams}/:id/*` or `/files/*.pdf`
+//!
+//! [`RoutePattern::regex`] takes a regular expression for anything else.
+//!
+//! An invalid pattern matches nothing, and fails the module's middleware
+//! configuration when the application is created. The `try_` constructors
+//! report it right away instead.
+
+use regex::Regex;
+
+use crate::router::{
+    HostPattern, RoutePath, RoutePathError, RouteSegment, constraint_pattern, parse_segment,
+    split_outside_constraints,
+};
+
+/// Represents a route pattern with optional HTTP method and host filtering
 #[derive(Debug, Clone)]
 pub struct RoutePattern {
+    /// The pattern as written
     pub path: String,
     pub methods: Option<Vec<String>>,
+    /// Checked against each request's `Host`, `None` for any host
+    pub host: Option<HostPattern>,
+    matcher: Result<PathMatcher, RoutePathError>,
 }
 
 impl RoutePattern {
@@ -10,40 +40,120 @@ impl RoutePattern {
         Self {
             path: path.to_string(),
             methods: None,
+            host: None,
+            matcher: PathMatcher::glob(path),
         }
     }
 
+    /// Like [`all_methods`](Self::all_methods), failing on an invalid pattern
+    pub fn try_all_methods(path: &str) -> Result<Self, RoutePathError> {
+        Self::all_methods(path).checked()
+    }
+
     pub fn single_method(path: &str, method: &str) -> Self {
-        Self {
-            path: path.to_string(),
-            methods: Some(vec![method.to_string()]),
-        }
+        Self::all_methods(path).with_methods(vec![method])
     }
 
     pub fn methods(path: &str, methods: Vec<&str>) -> Self {
+        Self::all_methods(path).with_methods(methods)
+    }
+
+    /// A pattern matching request paths with a regular expression, e.g.
+    /// `^/v[0-9]+/`. Paths start with `/` and have no trailing `/`.
+    pub fn regex(pattern: &str) -> Self {
+        let matcher = Regex::new(pattern)
+            .map(|regex| {
+                let root = regex.is_match("/");
+                PathMatcher::new(regex, root, false, Vec::new())
+            })
+            .map_err(|error| RoutePathError::InvalidRegex {
+                pattern: pattern.to_string(),
+                message: error.to_string(),
+            });
         Self {
-            path: path.to_string(),
-            // Empty vec means all methods (allows mixing with specific methods in same Vec)
-            methods: if methods.is_empty() {
-                None
-            } else {
-                Some(methods.iter().map(|s| s.to_string()).collect())
-            },
+            path: pattern.to_string(),
+            methods: None,
+            host: None,
+            matcher,
         }
     }
 
-    pub fn matches(&self, path: &str, method: &str) -> bool {
-        let path_matches = if self.path.ends_with('*') {
-            let prefix = &self.path[..self.path.len() - 1];
-            path.starts_with(prefix)
+    /// Like [`regex`](Self::regex), failing on an invalid regular expression
+    pub fn try_regex(pattern: &str) -> Result<Self, RoutePathError> {
+        Self::regex(pattern).checked()
+    }
+
+    /// Restricts the pattern to `methods`, all of them when empty
+    pub fn with_methods(mut self, methods: Vec<&str>) -> Self {
+        // Empty vec means all methods (allows mixing with specific methods in same Vec)
+        self.methods = if methods.is_empty() {
+            None
         } else {
-            path == self.path
+            Some(methods.iter().map(|s| s.to_string()).collect())
         };
+        self
+    }
+
+    /// Restricts the pattern to requests sent to `host`, e.g.
+    /// `admin.example.com` or `:tenant.example.com`
+    pub fn on_host(mut self, host: &str) -> Self {
+        match HostPattern::parse(host) {
+            Ok(pattern) => self.host = Some(pattern),
+            Err(error) => self.matcher = Err(error),
+        }
+        self
+    }
+
+    /// Like [`on_host`](Self::on_host), failing on an invalid host pattern
+    pub fn try_on_host(self, host: &str) -> Result<Self, RoutePathError> {
+        self.on_host(host).checked()
+    }
+
+    /// Why the pattern matches nothing, when it is invalid
+    pub fn error(&self) -> Option<&RoutePathError> {
+        self.matcher.as_ref().err()
+    }
+
+    fn checked(self) -> Result<Self, RoutePathError> {
+        match self.matcher {
+            Err(error) => Err(error),
+            Ok(_) => Ok(self),
+        }
+    }
+
+    /// Whether a request for `path` with `method` matches, whatever its host
+    pub fn matches(&self, path: &str, method: &str) -> bool {
+        self.matches_method(method)
+            && self
+                .matcher
+                .as_ref()
+                .is_ok_and(|matcher| matcher.is_match(path))
+    }
 
-        if !path_matches {
-            return false;
+    /// Whether a request sent to `host` matches, always true without a host
+    pub fn matches_host(&self, host: Option<&str>) -> bool {
+        match &self.host {
+            None => true,
+            Some(pattern) => host.is_some_and(|host| pattern.captures(host).is_some()),
+        }
+    }
+
+    /// Whether every request to `route` matches, none of them, or `None` when
+    /// it depends on the request
+    pub(crate) fn matches_route(&self, route: &RoutePath, method: &str) -> Option<bool> {
+        let Ok(matcher) = &self.matcher else {
+            return Some(false);
+        };
+        if !self.matches_method(method) {
+            return Some(false);
+        }
+        match matcher.matches_route(route) {
+            Some(true) if self.host.is_some() => None,
+            decided => decided,
         }
+    }
 
+    fn matches_method(&self, method: &str) -> bool {
         match &self.methods {
             None => true,
             Some(methods) => methods.iter().any(|m| m.eq_ignore_ascii_case(method)),
@@ -51,11 +161,189 @@ impl RoutePattern {
     }
 }
 
+/// A compiled path pattern
+#[derive(Debug, Clone)]
+struct PathMatcher {
+    /// Matched against normalized paths
+    regex: Regex,
+    /// Whether `/` matches, which globs can't tell from segments
+    root: bool,
+    /// Whether the pattern ends with `*` and only has to match the start of
+    /// the path, trailing `/` included
+    open: bool,
+    /// Leading segments without wildcards, to rule routes out before requests
+    prefix: Vec<String>,
+}
+
+impl PathMatcher {
+    fn new(regex: Regex, root: bool, open: bool, prefix: Vec<String>) -> Self {
+        Self {
+            regex,
+            root,
+            open,
+            prefix,
+        }
+    }
+
+    fn glob(pattern: &str) -> Result<Self, RoutePathError> {
+        let open = pattern.ends_with('*') && !pattern.ends_with("**");
+        let regex = glob_regex(pattern, open)?;
+        let regex = Regex::new(&regex).map_err(|error| RoutePathError::InvalidRegex {
+            pattern: pattern.to_string(),
+            message: error.to_string(),
+        })?;
+        let prefix = pattern
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .take_while(|segment| is_literal(segment))
+            .map(str::to_string)
+            .collect();
+        let root = regex.is_match(if open { "/" } else { "" });
+        Ok(Self::new(regex, root, open, prefix))
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        let mut normalized = normalize(path);
+        if normalized == "/" {
+            return self.root;
+        }
+        if self.open && path.ends_with('/') {
+            normalized.push('/');
+        }
+        self.regex.is_match(&normalized)
+    }
+
+    fn matches_route(&self, route: &RoutePath) -> Option<bool> {
+        let mut literals = Vec::new();
+        for segment in route.segments() {
+            match segment {
+                RouteSegment::Static(text) => literals.push(text.as_str()),
+                _ => {
+                    // Requests only share the literal segments before the first parameter
+                    let differs = literals
+                        .iter()
+                        .zip(&self.prefix)
+                        .any(|(literal, prefix)| literal != prefix);
+                    return if differs { Some(false) } else { None };
+                }
+            }
+        }
+        let path = format!("/{}", literals.join("/"));
+        if self.is_match(&path) {
+            Some(true)
+        } else if self.open && self.is_match(&format!("{}/", path)) {
+            // `/users/*` matches the route `/users` only when requested as `/users/`
+            None
+        } else {
+            Some(false)
+        }
+    }
+}
+
+/// `//users/42/` to `/users/42`
+fn normalize(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    format!("/{}", segments.join("/"))
+}
+
+fn is_literal(segment: &str) -> bool {
+    !segment.contains(['*', '{', '}', ':', '('])
+}
+
+/// Translates a glob pattern to a regex over normalized paths. An `open`
+/// pattern drops its trailing `*` and matches the start of paths.
+fn glob_regex(pattern: &str, open: bool) -> Result<String, RoutePathError> {
+    let mut segments = split_outside_constraints(pattern, '/')?;
+    let mut rest = "";
+    if open {
+        rest = segments.pop().unwrap_or_default();
+        rest = &rest[..rest.len() - 1];
+    }
+    let mut body = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let last = index + 1 == segments.len();
+        match *segment {
+            "**" => body.push_str("(?:/[^/]+)*"),
+            "*" if last => body.push_str("(?:/[^/]+)+"),
+            "*" => body.push_str("/[^/]+"),
+            s if s.starts_with(':') || (s.starts_with('{') && !s.contains(',')) => {
+                body.push_str(&param_regex(&parse_segment(s)?))
+            }
+            s => {
+                body.push('/');
+                body.push_str(&literal_regex(s)?);
+            }
+        }
+    }
+    if open {
+        body.push('/');
+        body.push_str(&literal_regex(rest)?);
+        Ok(format!("^{}", body))
+    } else {
+        Ok(format!("^{}$", body))
+    }
+}
+
+/// `:id(int)`, `{id}` or `:*rest` as one or more segments
+fn param_regex(segment: &RouteSegment) -> String {
+    match segment {
+        RouteSegment::Static(text) => format!("/{}", regex::escape(text)),
+        RouteSegment::Param {
+            constraint,
+            optional,
+            ..
+        } => {
+            let value = constraint.as_deref().map_or("[^/]+", constraint_pattern);
+            if *optional {
+                format!("(?:/(?:{}))?", value)
+            } else {
+                format!("/(?:{})", value)
+            }
+        }
+        RouteSegment::Wildcard(_) => "(?:/[^/]+)+".to_string(),
+    }
+}
+
+/// A segment of text, `*` and `{a,b}` alternatives
+fn literal_regex(segment: &str) -> Result<String, RoutePathError> {
+    let mut regex = String::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find(['*', '{']) {
+        regex.push_str(&regex::escape(&rest[..start]));
+        if rest[start..].starts_with('*') {
+            regex.push_str("[^/]*");
+            rest = &rest[start + 1..];
+            continue;
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| RoutePathError::Unbalanced(segment.to_string()))?;
+        let alternatives: Vec<String> = rest[start + 1..start + end]
+            .split(',')
+            .map(|alternative| regex::escape(alternative).replace(r"\*", "[^/]*"))
+            .collect();
+        regex.push_str(&format!("(?:{})", alternatives.join("|")));
+        rest = &rest[start + end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(RoutePathError::Unbalanced(segment.to_string()));
+    }
+    regex.push_str(&regex::escape(rest));
+    Ok(regex)
+}
+
 /// Trait for types that can be converted into a RoutePattern
 pub trait IntoRoutePattern {
     fn into_route_pattern(self) -> RoutePattern;
 }
 
+// A pattern built with the constructors, e.g. with a host
+impl IntoRoutePattern for RoutePattern {
+    fn into_route_pattern(self) -> RoutePattern {
+        self
+    }
+}
+
 // Just a string path (all methods)
 impl IntoRoutePattern for &str {
     fn into_route_pattern(self) -> RoutePattern {
@@ -139,4 +427,129 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_route_pattern_on_host() {
+        let pattern = RoutePattern::all_methods("/*").on_host(":tenant.example.com");
+        assert!(pattern.matches("/users", "GET"));
+        assert!(pattern.matches_host(Some("acme.example.com:3000")));
+        assert!(!pattern.matches_host(Some("example.com")));
+        assert!(!pattern.matches_host(None));
+
+        assert!(RoutePattern::all_methods("/*").matches_host(None));
+    }
+
+    #[test]
+    fn test_route_pattern_globs() {
+        let matches =
+            |pattern: &str, path: &str| RoutePattern::all_methods(pattern).matches(path, "GET");
+
+        assert!(matches("/users", "/users"));
+        assert!(matches("/users", "/users/"));
+        assert!(!matches("/users", "/users/42"));
+
+        assert!(matches("/users/*", "/users/42"));
+        assert!(matches("/users/*", "/users/42/posts"));
+        assert!(!matches("/users/*", "/users"));
+        assert!(matches("/*", "/"));
+        assert!(matches("/users/*/posts", "/users/42/posts"));
+        assert!(!matches("/users/*/posts", "/users/42/7/posts"));
+
+        assert!(matches("/api/**/export", "/api/export"));
+        assert!(matches("/api/**/export", "/api/a/b/export"));
+        assert!(matches("/**", "/"));
+        assert!(matches("/**", "/a/b"));
+
+        assert!(matches("/users/:id/*", "/users/42/posts"));
+        assert!(!matches("/users/:id/*", "/users/42"));
+        assert!(matches("/users/:id(int)", "/users/42"));
+        assert!(!matches("/users/:id(int)", "/users/me"));
+        assert!(matches("/users/{id}/:tab?", "/users/42"));
+
+        assert!(matches("/{users,teams}/*", "/teams/7"));
+        assert!(!matches("/{users,teams}/*", "/groups/7"));
+        assert!(matches("/files/*.{pdf,csv}", "/files/report.csv"));
+        assert!(!matches("/files/*.{pdf,csv}", "/files/report.txt"));
+        assert!(matches("/files/a.b", "/files/a.b"));
+        assert!(!matches("/files/a.b", "/files/axb"));
+    }
+
+    #[test]
+    fn test_route_pattern_trailing_star_is_a_prefix() {
+        let matches =
+            |pattern: &str, path: &str| RoutePattern::all_methods(pattern).matches(path, "GET");
+
+        assert!(matches("/api*", "/api"));
+        assert!(matches("/api*", "/apiv2"));
+        assert!(matches("/api*", "/api/users"));
+        assert!(!matches("/api*", "/ap"));
+
+        assert!(matches("/users/*", "/users/"));
+        assert!(matches("/users/*", "/users//42"));
+        assert!(!matches("/users/*", "/users"));
+        assert!(!matches("/users/*", "/usersx"));
+
+        let route = |template: &str| RoutePath::parse(template).unwrap();
+        let pattern = RoutePattern::all_methods("/users/*");
+        assert_eq!(pattern.matches_route(&route("/users"), "GET"), None);
+        assert_eq!(pattern.matches_route(&route("/usersx"), "GET"), Some(false));
+        assert_eq!(
+            RoutePattern::all_methods("/api*").matches_route(&route("/apiv2/users"), "GET"),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns_match_nothing() {
+        let pattern = RoutePattern::all_methods("/users/:id(");
+        assert!(pattern.error().is_some());
+        assert!(!pattern.matches("/users/1", "GET"));
+        assert!(RoutePattern::try_all_methods("/users/:id(").is_err());
+
+        assert!(RoutePattern::regex("^/v[0-9+/").error().is_some());
+        assert!(matches!(
+            RoutePattern::try_regex("^/v[0-9+/"),
+            Err(RoutePathError::InvalidRegex { .. })
+        ));
+
+        let pattern = RoutePattern::all_methods("/*").on_host(":tenant?.example.com");
+        assert!(!pattern.matches("/users", "GET"));
+        assert!(
+            RoutePattern::all_methods("/*")
+                .try_on_host(":tenant?.example.com")
+                .is_err()
+        );
+        assert!(RoutePattern::try_all_methods("/users/*").is_ok());
+    }
+
+    #[test]
+    fn test_route_pattern_regex() {
+        let pattern = RoutePattern::regex("^/v[0-9]+/").with_methods(vec!["POST"]);
+        assert!(pattern.matches("/v2/users", "POST"));
+        assert!(!pattern.matches("/v2/users", "GET"));
+        assert!(!pattern.matches("/latest/users", "POST"));
+    }
+
+    #[test]
+    fn test_route_pattern_decides_routes() {
+        let route = |template: &str| RoutePath::parse(template).unwrap();
+        let pattern = RoutePattern::single_method("/users/:id/*", "GET");
+
+        assert_eq!(
+            pattern.matches_route(&route("/users/1/posts"), "GET"),
+            Some(true)
+        );
+        assert_eq!(
+            pattern.matches_route(&route("/users/1/posts"), "POST"),
+            Some(false)
+        );
+        assert_eq!(
+            pattern.matches_route(&route("/teams/:id"), "GET"),
+            Some(false)
+        );
+        assert_eq!(pattern.matches_route(&route("/users/:id"), "GET"), None);
+
+        let pattern = pattern.on_host("admin.example.com");
+        assert_eq!(pattern.matches_route(&route("/users/1/posts"), "GET"), None);
+    }
 }
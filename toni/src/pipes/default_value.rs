@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::{
+    http_helpers::HttpException,
+    traits_helpers::{ArgumentMetadata, PipeTransform},
+};
+
+/// Replaces a missing argument with a default; list it before parse pipes
+#[derive(Debug, Clone)]
+pub struct DefaultValuePipe {
+    value: Value,
+}
+
+impl DefaultValuePipe {
+    pub fn new(value: impl Into<Value>) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PipeTransform for DefaultValuePipe {
+    async fn transform(
+        &self,
+        value: Value,
+        _metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException> {
+        Ok(match value {
+            Value::Null => self.value.clone(),
+            value => value,
+        })
+    }
+}
@@ -0,0 +1,137 @@
+//! Built-in pipes
+//!
+//! [`ValidationPipe`] configures how `Validated<T>` extractors and body DTOs
+//! are decoded and validated. The parameter pipes are attached to a `Path`
+//! or `Query` argument with `#[pipe(..)]`; they run in order and reject
+//! invalid input with `400 Bad Request` naming the parameter.
+//!
+//! ```rust,ignore
+//! use toni::pipes::{DefaultValuePipe, ParseIntPipe, ParseUuidPipe};
+//!
+//! #[get("/users/{id}")]
+//! fn find_one(&self, #[pipe(ParseUuidPipe)] Path(id): Path<String>) -> ToniBody { /* ... */ }
+//!
+//! #[get("/users")]
+//! fn list(
+//!     &self,
+//!     #[pipe(DefaultValuePipe::new(1), ParseIntPipe)] Query(page): Query<u32>,
+//! ) -> ToniBody { /* ... */ }
+//! ```
+
+mod default_value;
+mod parse_enum;
+mod parse_int;
+mod parse_uuid;
+mod validation;
+
+pub use default_value::DefaultValuePipe;
+pub use parse_enum::ParseEnumPipe;
+pub use parse_int::ParseIntPipe;
+pub use parse_uuid::ParseUuidPipe;
+pub use validation::{FieldError, FieldErrors, ValidationPipe};
+
+use serde_json::Value;
+
+use crate::{http_helpers::HttpException, traits_helpers::ArgumentMetadata};
+
+/// The raw string of an argument, or a `400` when it is missing or not a string
+fn raw_str<'a>(
+    value: &'a Value,
+    metadata: &ArgumentMetadata,
+    expected: &str,
+) -> Result<&'a str, HttpException> {
+    match value {
+        Value::String(raw) => Ok(raw),
+        Value::Null => Err(HttpException::bad_request(format!(
+            "Parameter '{}' is required",
+            metadata.name()
+        ))),
+        _ => Err(invalid(metadata, expected)),
+    }
+}
+
+fn invalid(metadata: &ArgumentMetadata, expected: &str) -> HttpException {
+    HttpException::bad_request(format!(
+        "Parameter '{}' must be {}",
+        metadata.name(),
+        expected
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+    use crate::traits_helpers::{ParamSource, PipeTransform};
+
+    fn metadata() -> ArgumentMetadata {
+        ArgumentMetadata::new("id", ParamSource::Path)
+    }
+
+    async fn message(pipe: &dyn PipeTransform, value: Value) -> String {
+        let exception = pipe.transform(value, &metadata()).await.unwrap_err();
+        assert_eq!(exception.status().as_u16(), 400);
+        exception.message().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_parse_int_pipe() {
+        let value = ParseIntPipe.transform(json!("-42"), &metadata()).await;
+        assert_eq!(value.unwrap(), json!(-42));
+        assert_eq!(
+            message(&ParseIntPipe, json!("4.2")).await,
+            "Parameter 'id' must be an integer"
+        );
+        assert_eq!(
+            message(&ParseIntPipe, Value::Null).await,
+            "Parameter 'id' is required"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_uuid_pipe() {
+        let uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let value = ParseUuidPipe.transform(json!(uuid), &metadata()).await;
+        assert_eq!(value.unwrap(), json!(uuid));
+        for invalid in [
+            "67e55044",
+            "67e55044-10b1-426f-9247-bb680e5fe0cz",
+            "67e5504410b1426f9247bb680e5fe0c8",
+        ] {
+            assert_eq!(
+                message(&ParseUuidPipe, json!(invalid)).await,
+                "Parameter 'id' must be a UUID"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_value_pipe() {
+        let pipe = DefaultValuePipe::new(10);
+        let value = pipe.transform(Value::Null, &metadata()).await;
+        assert_eq!(value.unwrap(), json!(10));
+        let value = pipe.transform(json!("3"), &metadata()).await;
+        assert_eq!(value.unwrap(), json!("3"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_enum_pipe() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        #[allow(dead_code)]
+        enum Status {
+            Active,
+            Archived,
+        }
+
+        let pipe = ParseEnumPipe::<Status>::default();
+        let value = pipe.transform(json!("active"), &metadata()).await;
+        assert_eq!(value.unwrap(), json!("active"));
+        assert_eq!(
+            message(&pipe, json!("deleted")).await,
+            "Parameter 'id' must be one of: active, archived"
+        );
+    }
+}
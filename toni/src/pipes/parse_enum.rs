@@ -0,0 +1,63 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::raw_str;
+use crate::{
+    http_helpers::HttpException,
+    traits_helpers::{ArgumentMetadata, PipeTransform},
+};
+
+/// Checks that the argument names a variant of `E`, as `E` deserializes it
+///
+/// ```rust,ignore
+/// #[get("/orders")]
+/// fn list(&self, #[pipe(ParseEnumPipe::<Status>)] Query(status): Query<Status>) -> ToniBody { /* ... */ }
+/// ```
+pub struct ParseEnumPipe<E>(PhantomData<fn() -> E>);
+
+impl<E> Default for ParseEnumPipe<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[async_trait]
+impl<E: DeserializeOwned + 'static> PipeTransform for ParseEnumPipe<E> {
+    async fn transform(
+        &self,
+        value: Value,
+        metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException> {
+        raw_str(&value, metadata, "a string")?;
+        match E::deserialize(&value) {
+            Ok(_) => Ok(value),
+            Err(e) => {
+                let message = match variants(&e.to_string()) {
+                    variants if variants.is_empty() => format!("is invalid: {}", e),
+                    variants => format!("must be one of: {}", variants),
+                };
+                Err(HttpException::bad_request(format!(
+                    "Parameter '{}' {}",
+                    metadata.name(),
+                    message
+                )))
+            }
+        }
+    }
+}
+
+/// The variant names from serde's "unknown variant `x`, expected one of `a`, `b`"
+fn variants(message: &str) -> String {
+    let expected = message
+        .split_once("expected ")
+        .map_or(message, |(_, expected)| expected);
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
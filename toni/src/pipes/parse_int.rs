@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{invalid, raw_str};
+use crate::{
+    http_helpers::HttpException,
+    traits_helpers::{ArgumentMetadata, PipeTransform},
+};
+
+/// Parses the argument as an integer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseIntPipe;
+
+#[async_trait]
+impl PipeTransform for ParseIntPipe {
+    async fn transform(
+        &self,
+        value: Value,
+        metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException> {
+        if value.is_i64() || value.is_u64() {
+            return Ok(value);
+        }
+
+        let raw = raw_str(&value, metadata, "an integer")?;
+        if let Ok(number) = raw.parse::<i64>() {
+            return Ok(Value::from(number));
+        }
+        raw.parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| invalid(metadata, "an integer"))
+    }
+}
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{invalid, raw_str};
+use crate::{
+    http_helpers::HttpException,
+    traits_helpers::{ArgumentMetadata, PipeTransform},
+};
+
+/// Checks that the argument is a hyphenated UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseUuidPipe;
+
+fn is_uuid(raw: &str) -> bool {
+    raw.len() == 36
+        && raw.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[async_trait]
+impl PipeTransform for ParseUuidPipe {
+    async fn transform(
+        &self,
+        value: Value,
+        metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException> {
+        if is_uuid(raw_str(&value, metadata, "a UUID")?) {
+            Ok(value)
+        } else {
+            Err(invalid(metadata, "a UUID"))
+        }
+    }
+}
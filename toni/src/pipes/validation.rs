@@ -0,0 +1,448 @@
+use std::{collections::BTreeMap, fmt, fmt::Write};
+
+use async_trait::async_trait;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use serde_path_to_error::Segment;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+use crate::{
+    extractors::{StringsDeserializer, ValidationError},
+    http_helpers::{HttpException, HttpRequest},
+    injector::Context,
+    traits_helpers::Pipe,
+};
+
+/// Settings for `Validated<T>` extractors and body DTOs.
+///
+/// Registered as a global pipe, it applies to every handler; without one the
+/// defaults below are used.
+///
+/// ```rust,ignore
+/// factory.use_global_pipes(Arc::new(
+///     ValidationPipe::new()
+///         .forbid_unknown_fields(true)
+///         .transform(true),
+/// ));
+/// ```
+///
+/// Fields the DTO does not declare are always left out of it;
+/// `forbid_unknown_fields` rejects them instead. `transform` converts JSON
+/// strings such as `"42"` or `"true"` into the number or boolean a field
+/// expects, which query strings and forms always get. Failures answer
+/// `400 Bad Request` problem details with [`FieldErrors`] as `errors`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationPipe {
+    forbid_unknown_fields: bool,
+    transform: bool,
+    stop_at_first_error: bool,
+}
+
+impl ValidationPipe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject fields the DTO does not declare
+    pub fn forbid_unknown_fields(mut self, forbid: bool) -> Self {
+        self.forbid_unknown_fields = forbid;
+        self
+    }
+
+    /// Convert JSON strings to the numbers and booleans fields expect
+    pub fn transform(mut self, transform: bool) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Report only the first failing field
+    pub fn stop_at_first_error(mut self, stop: bool) -> Self {
+        self.stop_at_first_error = stop;
+        self
+    }
+
+    /// The pipe registered for the request, or the defaults
+    pub fn for_request(req: &HttpRequest) -> Self {
+        req.extensions.get::<Self>().cloned().unwrap_or_default()
+    }
+
+    /// Deserializes `value` into `T`, reporting type errors and unknown fields
+    /// by path. `strings` marks input whose scalars are all strings, such as
+    /// query strings and forms, where a name sent once also fills a list.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        value: Value,
+        strings: bool,
+    ) -> Result<T, ValidationError> {
+        let mut unknown = Vec::new();
+        let mut track = |path: serde_ignored::Path| unknown.push(ignored_path(&path));
+        let result: Result<T, _> = if strings || self.transform {
+            let lenient = StringsDeserializer::new(&value, strings);
+            serde_path_to_error::deserialize(serde_ignored::Deserializer::new(lenient, &mut track))
+        } else {
+            serde_path_to_error::deserialize(serde_ignored::Deserializer::new(&value, &mut track))
+        };
+
+        let error = match result {
+            Ok(dto) if unknown.is_empty() || !self.forbid_unknown_fields => return Ok(dto),
+            Ok(_) => {
+                let mut errors = FieldErrors::default();
+                for path in unknown {
+                    errors.push(path, FieldError::new("unknown_field", "Unknown field"));
+                }
+                return Err(self.finish(errors));
+            }
+            Err(error) => error,
+        };
+
+        let segments: Vec<Segment> = error.path().iter().cloned().collect();
+        let path = segment_path(&segments);
+        let message = error.inner().to_string();
+        let (path, error) = match missing_field(&message) {
+            Some(field) => (
+                join(&path, field),
+                FieldError::new("required", "Field is required"),
+            ),
+            None if path.is_empty() => return Err(ValidationError::ExtractionError(message)),
+            None => (path, FieldError::new("type", message)),
+        };
+        let mut errors = FieldErrors::default();
+        errors.push(path, error);
+        Err(ValidationError::ValidationFailed(errors))
+    }
+
+    /// Runs the `validator` rules of `dto`
+    pub fn validate<T: Validate>(&self, dto: &T) -> Result<(), ValidationError> {
+        dto.validate().map_err(|errors| self.reject(&errors))
+    }
+
+    pub(crate) fn reject(&self, errors: &ValidationErrors) -> ValidationError {
+        let mut field_errors = FieldErrors::default();
+        collect(errors, "", &mut field_errors);
+        self.finish(field_errors)
+    }
+
+    fn finish(&self, mut errors: FieldErrors) -> ValidationError {
+        if self.stop_at_first_error {
+            errors.keep_first();
+        }
+        ValidationError::ValidationFailed(errors)
+    }
+}
+
+/// Makes the settings available to the extractors through request extensions
+#[async_trait]
+impl Pipe for ValidationPipe {
+    async fn process(&self, context: &mut Context) {
+        context.take_request_mut().extensions.insert(self.clone());
+    }
+}
+
+/// Validation failures keyed by field path, e.g. `address.zip` or `items[0].qty`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct FieldErrors(BTreeMap<String, Vec<FieldError>>);
+
+impl FieldErrors {
+    pub fn get(&self, path: &str) -> Option<&[FieldError]> {
+        self.0.get(path).map(Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[FieldError])> {
+        self.0
+            .iter()
+            .map(|(path, errors)| (path.as_str(), errors.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn push(&mut self, path: String, error: FieldError) {
+        self.0.entry(path).or_default().push(error);
+    }
+
+    fn keep_first(&mut self) {
+        if let Some((path, mut errors)) = self.0.pop_first() {
+            errors.truncate(1);
+            self.0 = BTreeMap::from([(path, errors)]);
+        }
+    }
+}
+
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (path, errors) in self.iter() {
+            for error in errors {
+                if !first {
+                    f.write_str("; ")?;
+                }
+                write!(f, "{}: {}", path, error.message)?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<FieldErrors> for HttpException {
+    fn from(errors: FieldErrors) -> Self {
+        HttpException::bad_request("Validation failed")
+            .with_details(serde_json::to_value(errors).unwrap_or_default())
+    }
+}
+
+/// One failed constraint, with the `validator` code and params (`min`, `value`, ..)
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub code: String,
+    pub message: String,
+    pub params: Map<String, Value>,
+}
+
+impl FieldError {
+    fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            params: Map::new(),
+        }
+    }
+}
+
+fn collect(errors: &ValidationErrors, prefix: &str, out: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        let path = join(prefix, field);
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    let message = match &error.message {
+                        Some(message) => message.to_string(),
+                        None => format!("Failed the '{}' constraint", error.code),
+                    };
+                    out.push(
+                        path.clone(),
+                        FieldError {
+                            code: error.code.to_string(),
+                            message,
+                            params: error
+                                .params
+                                .iter()
+                                .map(|(key, value)| (key.to_string(), value.clone()))
+                                .collect(),
+                        },
+                    );
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => collect(errors, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect(errors, &format!("{}[{}]", path, index), out);
+                }
+            }
+        }
+    }
+}
+
+fn join(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", prefix, field)
+    }
+}
+
+fn segment_path(segments: &[Segment]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Seq { index } => {
+                let _ = write!(path, "[{}]", index);
+            }
+            Segment::Map { key } | Segment::Enum { variant: key } => path = join(&path, key),
+            Segment::Unknown => {}
+        }
+    }
+    path
+}
+
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}[{}]", ignored_path(parent), index)
+        }
+        serde_ignored::Path::Map { parent, key } => join(&ignored_path(parent), key),
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+/// The field of serde's "missing field `name`"
+fn missing_field(message: &str) -> Option<&str> {
+    message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Address {
+        #[validate(length(min = 5))]
+        zip: String,
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Order {
+        #[validate(range(min = 1, message = "Quantity must be positive"))]
+        quantity: u32,
+        #[validate(nested)]
+        address: Address,
+        #[validate(nested)]
+        items: Vec<Address>,
+    }
+
+    fn field_errors(result: Result<impl fmt::Debug, ValidationError>) -> FieldErrors {
+        match result.unwrap_err() {
+            ValidationError::ValidationFailed(errors) => errors,
+            error => panic!("unexpected {}", error),
+        }
+    }
+
+    #[test]
+    fn test_nested_and_list_errors() {
+        let pipe = ValidationPipe::new();
+        let order: Order = pipe
+            .decode(
+                json!({ "quantity": 0, "address": { "zip": "123" }, "items": [{ "zip": "12345" }, { "zip": "1" }] }),
+                false,
+            )
+            .unwrap();
+
+        let errors = field_errors(pipe.validate(&order));
+        let paths: Vec<_> = errors.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, ["address.zip", "items[1].zip", "quantity"]);
+
+        let quantity = &errors.get("quantity").unwrap()[0];
+        assert_eq!(quantity.code, "range");
+        assert_eq!(quantity.message, "Quantity must be positive");
+        assert_eq!(quantity.params["min"], json!(1));
+        assert_eq!(
+            errors.get("address.zip").unwrap()[0].params["value"],
+            json!("123")
+        );
+
+        let errors = field_errors(pipe.stop_at_first_error(true).validate(&order));
+        assert_eq!(errors.len(), 1);
+        assert!(errors.get("address.zip").is_some());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let pipe = ValidationPipe::new();
+        let errors = field_errors(
+            pipe.decode::<Order>(json!({ "quantity": 1, "address": {}, "items": [] }), false),
+        );
+        assert_eq!(errors.get("address.zip").unwrap()[0].code, "required");
+
+        let errors = field_errors(pipe.decode::<Order>(
+            json!({ "quantity": "2", "address": { "zip": "12345" }, "items": [] }),
+            false,
+        ));
+        assert_eq!(errors.get("quantity").unwrap()[0].code, "type");
+
+        assert!(matches!(
+            pipe.decode::<Order>(json!([]), false),
+            Err(ValidationError::ExtractionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_single_strings_fill_lists() {
+        #[derive(Debug, Deserialize)]
+        struct Filter {
+            tags: Vec<String>,
+            ids: Vec<u32>,
+        }
+
+        let filter: Filter = ValidationPipe::new()
+            .decode(json!({ "tags": "1", "ids": "7" }), true)
+            .unwrap();
+        assert_eq!(filter.tags, vec!["1"]);
+        assert_eq!(filter.ids, vec![7]);
+    }
+
+    #[test]
+    fn test_converts_list_items() {
+        #[derive(Debug, Deserialize)]
+        struct Line {
+            qty: u32,
+        }
+        #[derive(Debug, Deserialize)]
+        struct Batch {
+            ids: Vec<u32>,
+            lines: Vec<Line>,
+        }
+
+        let ids: Vec<Value> = (0..10_000).map(|id| json!(id.to_string())).collect();
+        let batch: Batch = ValidationPipe::new()
+            .decode(
+                json!({ "ids": ids, "lines": [{ "qty": "1" }, { "qty": "2" }] }),
+                true,
+            )
+            .unwrap();
+        assert_eq!(batch.ids[9_999], 9_999);
+        assert_eq!(batch.lines[1].qty, 2);
+
+        // One item that isn't a number is still reported at its own path
+        let errors = field_errors(
+            ValidationPipe::new()
+                .decode::<Batch>(json!({ "ids": ["1", "x", "3"], "lines": [] }), true),
+        );
+        assert_eq!(errors.get("ids[1]").unwrap()[0].code, "type");
+    }
+
+    #[test]
+    fn test_transform() {
+        let value =
+            json!({ "quantity": "2", "address": { "zip": 12345 }, "items": [{ "zip": "54321" }] });
+        let order: Order = ValidationPipe::new()
+            .transform(true)
+            .decode(value, false)
+            .unwrap();
+        assert_eq!(order.quantity, 2);
+        assert_eq!(order.address.zip, "12345");
+        assert_eq!(order.items[0].zip, "54321");
+    }
+
+    #[test]
+    fn test_forbid_unknown_fields() {
+        let value = json!({ "quantity": 1, "admin": true, "address": { "zip": "12345", "extra": 1 }, "items": [] });
+        assert!(
+            ValidationPipe::new()
+                .decode::<Order>(value.clone(), false)
+                .is_ok()
+        );
+
+        let errors = field_errors(
+            ValidationPipe::new()
+                .forbid_unknown_fields(true)
+                .decode::<Order>(value, false),
+        );
+        let paths: Vec<_> = errors.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, ["address.extra", "admin"]);
+        assert_eq!(errors.get("admin").unwrap()[0].code, "unknown_field");
+    }
+}
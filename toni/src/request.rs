@@ -120,7 +120,7 @@ use std::sync::Arc;
 
 use crate::FxHashMap;
 use crate::async_trait;
-use crate::http_helpers::{Body, Extensions, HttpRequest};
+use crate::http_helpers::{Body, Extensions, HeaderMap, HttpRequest};
 use crate::provider_scope::ProviderScope;
 use crate::traits_helpers::{Provider, ProviderTrait};
 
@@ -177,12 +177,12 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// use std::collections::HashMap;
     ///
     /// let http_req = HttpRequest {
     ///     body: Body::Text("".to_string()),
-    ///     headers: vec![("content-type".to_string(), "application/json".to_string())],
+    ///     headers: vec![("content-type".to_string(), "application/json".to_string())].into(),
     ///     method: "GET".to_string(),
     ///     uri: "/users/123".to_string(),
     ///     query_params: HashMap::new(),
@@ -204,11 +204,11 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// # let http_req = HttpRequest {
     /// #     body: Body::Text("".to_string()),
-    /// #     headers: vec![],
+    /// #     headers: HeaderMap::new(),
     /// #     method: "POST".to_string(),
     /// #     uri: "/".to_string(),
     /// #     query_params: HashMap::new(),
@@ -227,11 +227,11 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// # let http_req = HttpRequest {
     /// #     body: Body::Text("".to_string()),
-    /// #     headers: vec![],
+    /// #     headers: HeaderMap::new(),
     /// #     method: "GET".to_string(),
     /// #     uri: "/users/123".to_string(),
     /// #     query_params: HashMap::new(),
@@ -250,14 +250,15 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// let http_req = HttpRequest {
     ///     body: Body::Text("".to_string()),
     ///     headers: vec![
     ///         ("Content-Type".to_string(), "application/json".to_string()),
     ///         ("Authorization".to_string(), "Bearer token123".to_string()),
-    ///     ],
+    ///     ]
+    ///     .into(),
     ///     method: "GET".to_string(),
     ///     uri: "/".to_string(),
     ///     query_params: HashMap::new(),
@@ -272,24 +273,19 @@ impl Request {
     /// assert_eq!(request.header("X-Custom"), None);
     /// ```
     pub fn header(&self, name: &str) -> Option<&str> {
-        let name_lower = name.to_lowercase();
-        self.inner
-            .headers
-            .iter()
-            .find(|(k, _)| k.to_lowercase() == name_lower)
-            .map(|(_, v)| v.as_str())
+        self.inner.headers.get_str(name)
     }
 
-    /// Get all headers as a slice.
+    /// Get all headers.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// # let http_req = HttpRequest {
     /// #     body: Body::Text("".to_string()),
-    /// #     headers: vec![("content-type".to_string(), "text/plain".to_string())],
+    /// #     headers: vec![("content-type".to_string(), "text/plain".to_string())].into(),
     /// #     method: "GET".to_string(),
     /// #     uri: "/".to_string(),
     /// #     query_params: HashMap::new(),
@@ -300,7 +296,7 @@ impl Request {
     /// let headers = request.headers();
     /// assert_eq!(headers.len(), 1);
     /// ```
-    pub fn headers(&self) -> &[(String, String)] {
+    pub fn headers(&self) -> &HeaderMap {
         &self.inner.headers
     }
 
@@ -309,7 +305,7 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// let mut query_params = HashMap::new();
     /// query_params.insert("page".to_string(), "1".to_string());
@@ -317,7 +313,7 @@ impl Request {
     ///
     /// let http_req = HttpRequest {
     ///     body: Body::Text("".to_string()),
-    ///     headers: vec![],
+    ///     headers: HeaderMap::new(),
     ///     method: "GET".to_string(),
     ///     uri: "/users?page=1&limit=10".to_string(),
     ///     query_params,
@@ -337,14 +333,14 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// let mut path_params = HashMap::new();
     /// path_params.insert("id".to_string(), "123".to_string());
     ///
     /// let http_req = HttpRequest {
     ///     body: Body::Text("".to_string()),
-    ///     headers: vec![],
+    ///     headers: HeaderMap::new(),
     ///     method: "GET".to_string(),
     ///     uri: "/users/123".to_string(),
     ///     query_params: HashMap::new(),
@@ -364,11 +360,11 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// let http_req = HttpRequest {
     ///     body: Body::Text("Hello, World!".to_string()),
-    ///     headers: vec![],
+    ///     headers: HeaderMap::new(),
     ///     method: "POST".to_string(),
     ///     uri: "/".to_string(),
     ///     query_params: HashMap::new(),
@@ -394,14 +390,14 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// #[derive(Clone)]
     /// struct UserId(String);
     ///
     /// let mut http_req = HttpRequest {
     ///     body: Body::Text("".to_string()),
-    ///     headers: vec![],
+    ///     headers: HeaderMap::new(),
     ///     method: "GET".to_string(),
     ///     uri: "/".to_string(),
     ///     query_params: HashMap::new(),
@@ -423,11 +419,11 @@ impl Request {
     /// # Examples
     ///
     /// ```rust
-    /// # use toni::{Request, HttpRequest, Body, http_helpers::Extensions};
+    /// # use toni::{Request, HttpRequest, Body, http_helpers::{Extensions, HeaderMap}};
     /// # use std::collections::HashMap;
     /// # let http_req = HttpRequest {
     /// #     body: Body::Text("".to_string()),
-    /// #     headers: vec![],
+    /// #     headers: HeaderMap::new(),
     /// #     method: "GET".to_string(),
     /// #     uri: "/".to_string(),
     /// #     query_params: HashMap::new(),
@@ -465,7 +461,7 @@ impl Provider for RequestManager {
         let provider_wrapper = Request {
             inner: Arc::new(HttpRequest {
                 body: Body::Text(String::new()),
-                headers: vec![],
+                headers: HeaderMap::new(),
                 method: String::new(),
                 uri: String::new(),
                 query_params: HashMap::new(),
@@ -511,7 +507,8 @@ mod tests {
             headers: vec![
                 ("content-type".to_string(), "application/json".to_string()),
                 ("Authorization".to_string(), "Bearer token123".to_string()),
-            ],
+            ]
+            .into(),
             method: "POST".to_string(),
             uri: "/users/123?page=1".to_string(),
             query_params,
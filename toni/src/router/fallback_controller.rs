@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{host_pattern::HostPattern, route_path::RoutePath};
+use crate::extractors::ValidationError;
+use crate::http_helpers::{HttpMethod, HttpRequest, HttpResponse, IntoResponse};
+use crate::traits_helpers::{
+    ControllerTrait, FallbackHandler, GuardSource, Interceptor, Pipe, validate::Validatable,
+};
+
+/// Answers requests no route matches: `405` with an `Allow` header when
+/// routes have the path under other methods, `404` otherwise.
+pub(crate) struct FallbackController {
+    routes: Vec<(RoutePath, Option<HostPattern>, HttpMethod)>,
+    handler: Arc<dyn FallbackHandler>,
+}
+
+impl FallbackController {
+    pub(crate) fn new(
+        routes: Vec<(RoutePath, Option<HostPattern>, HttpMethod)>,
+        handler: Arc<dyn FallbackHandler>,
+    ) -> Self {
+        Self { routes, handler }
+    }
+
+    /// Methods the path answers to on the request's host, implicit HEAD and
+    /// OPTIONS included
+    fn allowed_methods(&self, req: &HttpRequest) -> Vec<HttpMethod> {
+        let path = req.path();
+        let on_host = |host: &Option<HostPattern>| {
+            host.as_ref().is_none_or(|host| {
+                req.host()
+                    .is_some_and(|value| host.captures(value).is_some())
+            })
+        };
+
+        let mut allow: Vec<HttpMethod> = Vec::new();
+        for (route, host, method) in &self.routes {
+            if !allow.contains(method) && route.matches_path(path) && on_host(host) {
+                allow.push(*method);
+            }
+        }
+        if allow.is_empty() {
+            return allow;
+        }
+
+        if allow.contains(&HttpMethod::GET) && !allow.contains(&HttpMethod::HEAD) {
+            allow.push(HttpMethod::HEAD);
+        }
+        if !allow.contains(&HttpMethod::OPTIONS) {
+            allow.push(HttpMethod::OPTIONS);
+        }
+        allow.sort_by_key(|method| *method as u8);
+        allow
+    }
+}
+
+#[async_trait]
+impl ControllerTrait for FallbackController {
+    fn get_token(&self) -> String {
+        "FALLBACK".to_string()
+    }
+
+    async fn execute(
+        &self,
+        req: HttpRequest,
+    ) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        let allow = self.allowed_methods(&req);
+        if allow.is_empty() {
+            return Box::new(self.handler.not_found(&req));
+        }
+
+        let mut response = self.handler.method_not_allowed(&req, &allow);
+        if !response.headers.contains_key("allow") {
+            let allow: Vec<&str> = allow.iter().map(|method| method.as_str()).collect();
+            response.headers.insert("Allow", allow.join(", "));
+        }
+        Box::new(response)
+    }
+
+    fn get_path(&self) -> String {
+        "/*".to_string()
+    }
+
+    fn get_method(&self) -> HttpMethod {
+        HttpMethod::GET
+    }
+
+    fn get_guards(&self) -> Vec<GuardSource> {
+        vec![]
+    }
+
+    fn get_pipes(&self) -> Vec<Arc<dyn Pipe>> {
+        vec![]
+    }
+
+    fn get_interceptors(&self) -> Vec<Arc<dyn Interceptor>> {
+        vec![]
+    }
+
+    fn get_body_dto(
+        &self,
+        _req: &HttpRequest,
+    ) -> Result<Option<Box<dyn Validatable>>, ValidationError> {
+        Ok(None)
+    }
+}
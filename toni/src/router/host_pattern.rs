@@ -0,0 +1,220 @@
+//! The host a controller is restricted to, with
+//! `#[controller("/", host = ":tenant.example.com")]`.
+//!
+//! Labels use the route syntax: `:tenant` or `{tenant}` captures a label and
+//! `:tenant(alpha)` constrains it. Captured labels are exposed like path
+//! parameters. Matching ignores case and the port.
+//!
+//! Routes without a host match any host, and answer only when no route
+//! restricted to the request's host does.
+//!
+//! # Examples
+//!
+//! ```
+//! use toni::HostPattern;
+//!
+//! let host = HostPattern::parse("{tenant}.Example.com").unwrap();
+//! assert_eq!(host.to_string(), ":tenant.example.com");
+//!
+//! let params = host.captures("acme.example.com:8080").unwrap();
+//! assert_eq!(params["tenant"], "acme");
+//! assert!(host.captures("example.com").is_none());
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use regex::Regex;
+
+use super::route_path::{
+    RoutePathError, RouteSegment, compile_constraints, parse_segment, split_outside_constraints,
+};
+
+/// A parsed host pattern
+#[derive(Debug, Clone)]
+pub struct HostPattern {
+    labels: Vec<RouteSegment>,
+    /// Compiled constraints, anchored to the whole label
+    constraints: Vec<(String, Regex)>,
+}
+
+impl HostPattern {
+    pub fn parse(template: &str) -> Result<Self, RoutePathError> {
+        let mut labels: Vec<RouteSegment> = Vec::new();
+        for raw in split_outside_constraints(template, '.')? {
+            let label = match parse_segment(raw)? {
+                RouteSegment::Static(text) => RouteSegment::Static(text.to_ascii_lowercase()),
+                param @ RouteSegment::Param {
+                    optional: false, ..
+                } => param,
+                _ => return Err(RoutePathError::InvalidHostLabel(raw.to_string())),
+            };
+
+            if let Some(name) = label.name()
+                && labels.iter().any(|other| other.name() == Some(name))
+            {
+                return Err(RoutePathError::DuplicateParam(name.to_string()));
+            }
+            labels.push(label);
+        }
+
+        let constraints = compile_constraints(&labels)?;
+        Ok(Self {
+            labels,
+            constraints,
+        })
+    }
+
+    pub fn labels(&self) -> &[RouteSegment] {
+        &self.labels
+    }
+
+    /// Parameter names in the order they appear
+    pub fn param_names(&self) -> Vec<&str> {
+        self.labels.iter().filter_map(RouteSegment::name).collect()
+    }
+
+    /// Whether the pattern captures nothing, e.g. `api.example.com`
+    pub fn is_static(&self) -> bool {
+        self.labels
+            .iter()
+            .all(|label| matches!(label, RouteSegment::Static(_)))
+    }
+
+    /// The parameters captured from `host`, or `None` if it doesn't match.
+    /// The host is a `Host` header value and may carry a port.
+    pub fn captures(&self, host: &str) -> Option<HashMap<String, String>> {
+        let host = strip_port(host).trim_end_matches('.').to_ascii_lowercase();
+        let parts: Vec<&str> = host.split('.').collect();
+        if parts.len() != self.labels.len() {
+            return None;
+        }
+
+        let mut params = HashMap::new();
+        for (label, part) in self.labels.iter().zip(parts) {
+            match label {
+                RouteSegment::Static(text) if text != part => return None,
+                RouteSegment::Param { name, .. } if !part.is_empty() => {
+                    params.insert(name.clone(), part.to_string());
+                }
+                RouteSegment::Param { .. } => return None,
+                _ => {}
+            }
+        }
+
+        self.constraints
+            .iter()
+            .all(|(name, regex)| regex.is_match(&params[name]))
+            .then_some(params)
+    }
+}
+
+impl fmt::Display for HostPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, label) in self.labels.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", label)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parameter names of a host template, in order, without compiling it
+pub(crate) fn host_param_names(template: &str) -> Vec<String> {
+    split_outside_constraints(template, '.')
+        .and_then(|raw| raw.into_iter().map(parse_segment).collect())
+        .map(|labels: Vec<RouteSegment>| {
+            labels
+                .iter()
+                .filter_map(|label| label.name().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `example.com:8080` to `example.com`, leaving IPv6 addresses whole
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port))
+            if !port.is_empty()
+                && port.chars().all(|c| c.is_ascii_digit())
+                && (!name.contains(':') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captures(pattern: &str, host: &str) -> Option<Vec<(String, String)>> {
+        let mut params: Vec<_> = HostPattern::parse(pattern)
+            .unwrap()
+            .captures(host)?
+            .into_iter()
+            .collect();
+        params.sort();
+        Some(params)
+    }
+
+    #[test]
+    fn test_matches_static_hosts() {
+        assert_eq!(captures("api.example.com", "api.example.com"), Some(vec![]));
+        assert_eq!(
+            captures("api.example.com", "API.Example.com:3000"),
+            Some(vec![])
+        );
+        assert_eq!(
+            captures("api.example.com", "api.example.com."),
+            Some(vec![])
+        );
+        assert_eq!(captures("api.example.com", "admin.example.com"), None);
+        assert_eq!(captures("api.example.com", "v2.api.example.com"), None);
+        assert_eq!(captures("localhost", "localhost:8080"), Some(vec![]));
+    }
+
+    #[test]
+    fn test_captures_labels() {
+        assert_eq!(
+            captures(":tenant.{region}.example.com", "Acme.eu.example.com"),
+            Some(vec![
+                ("region".to_string(), "eu".to_string()),
+                ("tenant".to_string(), "acme".to_string())
+            ])
+        );
+        assert_eq!(captures(":tenant.example.com", "example.com"), None);
+        assert_eq!(captures(":tenant.example.com", ".example.com"), None);
+
+        assert!(captures(":id(int).example.com", "42.example.com").is_some());
+        assert_eq!(captures(":id(int).example.com", "acme.example.com"), None);
+    }
+
+    #[test]
+    fn test_strips_ports() {
+        assert_eq!(strip_port("example.com:8080"), "example.com");
+        assert_eq!(strip_port("example.com"), "example.com");
+        assert_eq!(strip_port("[::1]:8080"), "[::1]");
+        assert_eq!(strip_port("::1"), "::1");
+    }
+
+    #[test]
+    fn test_rejects_invalid_hosts() {
+        assert_eq!(
+            HostPattern::parse(":tenant?.example.com").unwrap_err(),
+            RoutePathError::InvalidHostLabel(":tenant?".to_string())
+        );
+        assert_eq!(
+            HostPattern::parse("*rest.example.com").unwrap_err(),
+            RoutePathError::InvalidHostLabel("*rest".to_string())
+        );
+        assert_eq!(
+            HostPattern::parse(":a.:a.com").unwrap_err(),
+            RoutePathError::DuplicateParam("a".to_string())
+        );
+        assert!(HostPattern::parse(":id(\\d.+).example.com").is_ok());
+    }
+}
@@ -1,2 +1,13 @@
+mod fallback_controller;
+mod host_pattern;
+mod options_controller;
+mod route_path;
+mod route_table;
 mod routes_resolve;
+pub use self::host_pattern::HostPattern;
+pub(crate) use self::host_pattern::host_param_names;
+pub use self::route_path::{RoutePath, RoutePathError, RouteSegment};
+pub(crate) use self::route_path::{
+    constraint_pattern, parse_segment, route_param_names, split_outside_constraints,
+};
 pub use self::routes_resolve::RoutesResolver;
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::extractors::ValidationError;
+use crate::http_helpers::{HttpMethod, HttpRequest, HttpResponse, IntoResponse};
+use crate::traits_helpers::{
+    ControllerTrait, GuardSource, Interceptor, Pipe, validate::Validatable,
+};
+
+/// Answers `OPTIONS` for a path that has no explicit `#[options]` handler,
+/// listing the methods registered on it in an `Allow` header.
+pub(crate) struct OptionsController {
+    path: String,
+    allow: Vec<HttpMethod>,
+}
+
+impl OptionsController {
+    pub(crate) fn new(path: &str, mut allow: Vec<HttpMethod>) -> Self {
+        // Controllers are registered in no particular order, keep the header stable
+        allow.sort_by_key(|method| *method as u8);
+        Self {
+            path: path.to_string(),
+            allow,
+        }
+    }
+
+    fn allow_header(&self) -> String {
+        self.allow
+            .iter()
+            .map(|method| method.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[async_trait]
+impl ControllerTrait for OptionsController {
+    fn get_token(&self) -> String {
+        format!("OPTIONS {}", self.path)
+    }
+
+    async fn execute(
+        &self,
+        _req: HttpRequest,
+    ) -> Box<dyn IntoResponse<Response = HttpResponse> + Send> {
+        let mut response = HttpResponse::new();
+        response.status = 204;
+        response.headers.insert("Allow", self.allow_header());
+        Box::new(response)
+    }
+
+    fn get_path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn get_method(&self) -> HttpMethod {
+        HttpMethod::OPTIONS
+    }
+
+    fn get_guards(&self) -> Vec<GuardSource> {
+        vec![]
+    }
+
+    fn get_pipes(&self) -> Vec<Arc<dyn Pipe>> {
+        vec![]
+    }
+
+    fn get_interceptors(&self) -> Vec<Arc<dyn Interceptor>> {
+        vec![]
+    }
+
+    fn get_body_dto(
+        &self,
+        _req: &HttpRequest,
+    ) -> Result<Option<Box<dyn Validatable>>, ValidationError> {
+        Ok(None)
+    }
+}
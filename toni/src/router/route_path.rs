@@ -0,0 +1,654 @@
+//! The route syntax shared by every adapter.
+//!
+//! Controllers write one template and each `HttpAdapter` translates it to its
+//! router:
+//!
+//! - `:id` or `{id}` captures a segment
+//! - `:id?` makes a trailing segment optional
+//! - `*path` or `{*path}` captures the rest of the path, at least one character
+//! - `:id(\d+)` only matches values the regex accepts in full; `int`, `uuid`,
+//!   `alpha` and `alnum` are shorthands
+//!
+//! Repeated and trailing slashes are dropped: `/users/` and `/users` are the
+//! same route, and both URLs reach it.
+//!
+//! # Examples
+//!
+//! ```
+//! use toni::RoutePath;
+//!
+//! let route = RoutePath::parse("/users/{id(int)}//files/*path/").unwrap();
+//! assert_eq!(route.to_string(), "/users/:id(int)/files/*path");
+//! assert_eq!(route.param_names(), ["id", "path"]);
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use regex::Regex;
+use regex_automata::{
+    Anchored,
+    dfa::{Automaton, dense},
+    util::start,
+};
+
+/// One `/`-separated part of a route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteSegment {
+    Static(String),
+    Param {
+        name: String,
+        /// The constraint as written, e.g. `\d+` or `int`
+        constraint: Option<String>,
+        optional: bool,
+    },
+    Wildcard(String),
+}
+
+impl RouteSegment {
+    /// Name of the parameter, `None` for static text
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            RouteSegment::Static(_) => None,
+            RouteSegment::Param { name, .. } | RouteSegment::Wildcard(name) => Some(name),
+        }
+    }
+
+    fn is_optional(&self) -> bool {
+        matches!(self, RouteSegment::Param { optional: true, .. })
+    }
+}
+
+impl fmt::Display for RouteSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteSegment::Static(text) => write!(f, "{}", text),
+            RouteSegment::Param {
+                name,
+                constraint,
+                optional,
+            } => {
+                write!(f, ":{}", name)?;
+                if let Some(constraint) = constraint {
+                    write!(f, "({})", constraint)?;
+                }
+                if *optional {
+                    write!(f, "?")?;
+                }
+                Ok(())
+            }
+            RouteSegment::Wildcard(name) => write!(f, "*{}", name),
+        }
+    }
+}
+
+/// A parsed route template
+#[derive(Debug, Clone)]
+pub struct RoutePath {
+    segments: Vec<RouteSegment>,
+    /// Compiled constraints, anchored to the whole value
+    constraints: Vec<(String, Regex)>,
+}
+
+impl RoutePath {
+    pub fn parse(template: &str) -> Result<Self, RoutePathError> {
+        let mut segments: Vec<RouteSegment> = Vec::new();
+        for raw in split_segments(template)? {
+            let segment = parse_segment(raw)?;
+
+            if matches!(segments.last(), Some(RouteSegment::Wildcard(_))) {
+                return Err(RoutePathError::WildcardNotLast(template.to_string()));
+            }
+            if segments.last().is_some_and(RouteSegment::is_optional) && !segment.is_optional() {
+                return Err(RoutePathError::OptionalNotTrailing(template.to_string()));
+            }
+            if let Some(name) = segment.name()
+                && segments.iter().any(|other| other.name() == Some(name))
+            {
+                return Err(RoutePathError::DuplicateParam(name.to_string()));
+            }
+            segments.push(segment);
+        }
+
+        let constraints = compile_constraints(&segments)?;
+
+        Ok(Self {
+            segments,
+            constraints,
+        })
+    }
+
+    pub fn segments(&self) -> &[RouteSegment] {
+        &self.segments
+    }
+
+    /// Parameter names in the order they appear
+    pub fn param_names(&self) -> Vec<&str> {
+        self.segments
+            .iter()
+            .filter_map(RouteSegment::name)
+            .collect()
+    }
+
+    /// One route per number of optional segments kept, shortest first; none
+    /// of them has optional segments left
+    pub fn expand(&self) -> Vec<RoutePath> {
+        let required = self
+            .segments
+            .iter()
+            .take_while(|segment| !segment.is_optional())
+            .count();
+
+        (required..=self.segments.len())
+            .map(|len| {
+                let segments: Vec<RouteSegment> = self.segments[..len]
+                    .iter()
+                    .cloned()
+                    .map(|segment| match segment {
+                        RouteSegment::Param {
+                            name, constraint, ..
+                        } => RouteSegment::Param {
+                            name,
+                            constraint,
+                            optional: false,
+                        },
+                        segment => segment,
+                    })
+                    .collect();
+                let constraints = self
+                    .constraints
+                    .iter()
+                    .filter(|(name, _)| segments.iter().any(|s| s.name() == Some(name)))
+                    .cloned()
+                    .collect();
+                RoutePath {
+                    segments,
+                    constraints,
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the captured values satisfy the constraints
+    pub fn matches(&self, params: &HashMap<String, String>) -> bool {
+        self.constraints
+            .iter()
+            .all(|(name, regex)| params.get(name).is_none_or(|value| regex.is_match(value)))
+    }
+
+    /// Whether a request path reaches the route, constraints included
+    pub fn matches_path(&self, path: &str) -> bool {
+        let parts: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+        let mut params = HashMap::new();
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let Some(&part) = parts.get(index) else {
+                // Only optional segments may be left
+                if segment.is_optional() {
+                    break;
+                }
+                return false;
+            };
+            match segment {
+                RouteSegment::Static(text) => {
+                    if text != part {
+                        return false;
+                    }
+                }
+                RouteSegment::Param { name, .. } => {
+                    params.insert(name.clone(), part.to_string());
+                }
+                RouteSegment::Wildcard(name) => {
+                    params.insert(name.clone(), parts[index..].join("/"));
+                    return self.matches(&params);
+                }
+            }
+        }
+        parts.len() <= self.segments.len() && self.matches(&params)
+    }
+
+    /// Builds a router's path; `param` renders a named segment and `wildcard`
+    /// the rest of the path
+    pub fn render(
+        &self,
+        param: impl Fn(&str) -> String,
+        wildcard: impl Fn(&str) -> String,
+    ) -> String {
+        if self.segments.is_empty() {
+            return "/".to_string();
+        }
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                RouteSegment::Static(text) => format!("/{}", text),
+                RouteSegment::Param { name, .. } => format!("/{}", param(name)),
+                RouteSegment::Wildcard(name) => format!("/{}", wildcard(name)),
+            })
+            .collect()
+    }
+
+    /// Whether `/users/` should reach the route as well as `/users`; not for
+    /// the root, and a wildcard captures the slash itself
+    pub fn has_trailing_slash_variant(&self) -> bool {
+        !self.segments.is_empty()
+            && !matches!(self.segments.last(), Some(RouteSegment::Wildcard(_)))
+    }
+}
+
+impl fmt::Display for RoutePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, "/");
+        }
+        for segment in &self.segments {
+            write!(f, "/{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parameter names of a template without compiling its constraints; empty
+/// when the template is invalid
+pub(crate) fn route_param_names(template: &str) -> Vec<String> {
+    split_segments(template)
+        .and_then(|raw| raw.into_iter().map(parse_segment).collect())
+        .map(|segments: Vec<RouteSegment>| {
+            segments
+                .iter()
+                .filter_map(|segment| segment.name().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Error for a template that isn't valid route syntax
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutePathError {
+    /// A `{`, `}`, `(` or `)` without its pair
+    Unbalanced(String),
+    /// A parameter name that is empty or not an identifier
+    InvalidName(String),
+    /// A parameter sharing its segment with text, e.g. `file.{ext}`
+    PartialSegment(String),
+    DuplicateParam(String),
+    WildcardNotLast(String),
+    OptionalNotTrailing(String),
+    InvalidConstraint {
+        name: String,
+        message: String,
+    },
+    /// An optional or wildcard label in a host pattern
+    InvalidHostLabel(String),
+    /// A middleware pattern that doesn't compile to a regular expression
+    InvalidRegex {
+        pattern: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for RoutePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoutePathError::Unbalanced(segment) => {
+                write!(f, "Unbalanced brackets in segment '{}'", segment)
+            }
+            RoutePathError::InvalidName(segment) => {
+                write!(f, "Invalid parameter name in segment '{}'", segment)
+            }
+            RoutePathError::PartialSegment(segment) => write!(
+                f,
+                "Parameters must fill a whole segment, found '{}'",
+                segment
+            ),
+            RoutePathError::DuplicateParam(name) => {
+                write!(f, "Parameter '{}' is declared twice", name)
+            }
+            RoutePathError::WildcardNotLast(template) => {
+                write!(f, "A wildcard must be the last segment of '{}'", template)
+            }
+            RoutePathError::OptionalNotTrailing(template) => write!(
+                f,
+                "Only trailing segments can be optional in '{}'",
+                template
+            ),
+            RoutePathError::InvalidConstraint { name, message } => {
+                write!(f, "Invalid constraint on parameter '{}': {}", name, message)
+            }
+            RoutePathError::InvalidHostLabel(label) => write!(
+                f,
+                "Host labels can't be optional or wildcards, found '{}'",
+                label
+            ),
+            RoutePathError::InvalidRegex { pattern, message } => {
+                write!(f, "Invalid route regex '{}': {}", pattern, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RoutePathError {}
+
+/// The regex of a named constraint, or the constraint itself
+pub(crate) fn constraint_pattern(constraint: &str) -> &str {
+    match constraint {
+        "int" => r"-?\d+",
+        "uuid" => r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+        "alpha" => "[a-zA-Z]+",
+        "alnum" => "[a-zA-Z0-9]+",
+        pattern => pattern,
+    }
+}
+
+/// Compiles the constraints of parameters, anchored to the whole value
+pub(super) fn compile_constraints(
+    segments: &[RouteSegment],
+) -> Result<Vec<(String, Regex)>, RoutePathError> {
+    let mut constraints = Vec::new();
+    for segment in segments {
+        if let RouteSegment::Param {
+            name,
+            constraint: Some(constraint),
+            ..
+        } = segment
+        {
+            let pattern = format!("^(?:{})$", constraint_pattern(constraint));
+            let regex =
+                Regex::new(&pattern).map_err(|error| RoutePathError::InvalidConstraint {
+                    name: name.clone(),
+                    message: error.to_string(),
+                })?;
+            constraints.push((name.clone(), regex));
+        }
+    }
+    Ok(constraints)
+}
+
+/// Whether no segment value satisfies both constraints, found by walking
+/// their DFAs side by side. Patterns without a DFA are assumed to overlap.
+pub(crate) fn constraints_disjoint(a: &str, b: &str) -> bool {
+    let build = |constraint: &str| {
+        dense::DFA::new(&format!("^(?:{})$", constraint_pattern(constraint))).ok()
+    };
+    let (Some(a), Some(b)) = (build(a), build(b)) else {
+        return false;
+    };
+    let config = start::Config::new().anchored(Anchored::Yes);
+    let (Ok(a_start), Ok(b_start)) = (a.start_state(&config), b.start_state(&config)) else {
+        return false;
+    };
+
+    // Pairs of states, and whether a byte was read to reach them
+    let mut seen = HashSet::new();
+    let mut pending = vec![(a_start, b_start, false)];
+    while let Some(state) = pending.pop() {
+        let (x, y, read) = state;
+        if !seen.insert(state) || a.is_dead_state(x) || b.is_dead_state(y) {
+            continue;
+        }
+        if a.is_quit_state(x) || b.is_quit_state(y) {
+            return false;
+        }
+        // Segment values are never empty
+        if read && a.is_match_state(a.next_eoi_state(x)) && b.is_match_state(b.next_eoi_state(y)) {
+            return false;
+        }
+        for byte in (0..=u8::MAX).filter(|&byte| byte != b'/') {
+            pending.push((a.next_state(x, byte), b.next_state(y, byte), true));
+        }
+    }
+    true
+}
+
+fn split_segments(template: &str) -> Result<Vec<&str>, RoutePathError> {
+    split_outside_constraints(template, '/')
+}
+
+/// Splits on `separator` outside of constraints, skipping empty parts
+pub(crate) fn split_outside_constraints(
+    template: &str,
+    separator: char,
+) -> Result<Vec<&str>, RoutePathError> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (index, c) in template.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| RoutePathError::Unbalanced(template.to_string()))?;
+            }
+            c if c == separator && depth == 0 => {
+                segments.push(&template[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Err(RoutePathError::Unbalanced(template.to_string()));
+    }
+    segments.push(&template[start..]);
+    segments.retain(|segment| !segment.is_empty());
+    Ok(segments)
+}
+
+pub(crate) fn parse_segment(raw: &str) -> Result<RouteSegment, RoutePathError> {
+    let param = if let Some(inner) = raw.strip_prefix('{') {
+        inner
+            .strip_suffix('}')
+            .ok_or_else(|| RoutePathError::Unbalanced(raw.to_string()))?
+    } else if let Some(inner) = raw.strip_prefix(':') {
+        inner
+    } else if raw.starts_with('*') {
+        raw
+    } else if raw.contains(['{', '}']) {
+        return Err(RoutePathError::PartialSegment(raw.to_string()));
+    } else {
+        return Ok(RouteSegment::Static(raw.to_string()));
+    };
+
+    if let Some(name) = param.strip_prefix('*') {
+        check_name(name, raw)?;
+        return Ok(RouteSegment::Wildcard(name.to_string()));
+    }
+
+    let (param, optional) = match param.strip_suffix('?') {
+        Some(param) => (param, true),
+        None => (param, false),
+    };
+    let (name, constraint) = match param.find('(') {
+        Some(open) => {
+            let constraint = param[open + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| RoutePathError::Unbalanced(raw.to_string()))?;
+            (&param[..open], Some(constraint.to_string()))
+        }
+        None => (param, None),
+    };
+    check_name(name, raw)?;
+
+    Ok(RouteSegment::Param {
+        name: name.to_string(),
+        constraint,
+        optional,
+    })
+}
+
+fn check_name(name: &str, segment: &str) -> Result<(), RoutePathError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(RoutePathError::InvalidName(segment.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn axum_style(route: &RoutePath) -> String {
+        route.render(
+            |name| format!("{{{}}}", name),
+            |name| format!("{{*{}}}", name),
+        )
+    }
+
+    #[test]
+    fn test_normalizes_both_syntaxes() {
+        for template in [
+            "/users/:id/files/*path",
+            "/users/{id}/files/{*path}",
+            "users//{id}/files/*path/",
+        ] {
+            let route = RoutePath::parse(template).unwrap();
+            assert_eq!(route.to_string(), "/users/:id/files/*path");
+            assert_eq!(axum_style(&route), "/users/{id}/files/{*path}");
+        }
+        assert_eq!(RoutePath::parse("").unwrap().to_string(), "/");
+        assert_eq!(RoutePath::parse("//").unwrap().to_string(), "/");
+        assert_eq!(
+            RoutePath::parse("/v1:beta/items").unwrap().to_string(),
+            "/v1:beta/items"
+        );
+    }
+
+    #[test]
+    fn test_constraints() {
+        let route = RoutePath::parse(r"/posts/:year(\d{4})/{slug(alpha)}/:id(int)").unwrap();
+        assert_eq!(route.param_names(), ["year", "slug", "id"]);
+        assert_eq!(
+            route.to_string(),
+            r"/posts/:year(\d{4})/:slug(alpha)/:id(int)"
+        );
+        assert_eq!(axum_style(&route), "/posts/{year}/{slug}/{id}");
+
+        assert!(route.matches(&params(&[("year", "2024"), ("slug", "news"), ("id", "-3")])));
+        assert!(!route.matches(&params(&[("year", "24"), ("slug", "news"), ("id", "3")])));
+        assert!(!route.matches(&params(&[("year", "2024"), ("slug", "n3ws"), ("id", "3")])));
+        assert!(!route.matches(&params(&[("year", "2024"), ("slug", "news"), ("id", "3a")])));
+
+        // Slashes inside a constraint don't split the segment
+        let route = RoutePath::parse(r"/dates/:date(\d+/\d+)").unwrap();
+        assert_eq!(route.param_names(), ["date"]);
+    }
+
+    #[test]
+    fn test_disjoint_constraints() {
+        assert!(constraints_disjoint(r"\d+", "[a-z]+"));
+        assert!(constraints_disjoint("int", "alpha"));
+        assert!(constraints_disjoint("uuid", "int"));
+        assert!(constraints_disjoint("draft|published", "archived"));
+        assert!(!constraints_disjoint("int", r"\d{4}"));
+        assert!(!constraints_disjoint("alnum", "alpha"));
+        // Both accept an empty value, which no segment has
+        assert!(constraints_disjoint("a|", "b|"));
+        // Only a value with a slash would match both
+        assert!(constraints_disjoint("a/b", "a.b"));
+    }
+
+    #[test]
+    fn test_expands_optional_segments() {
+        let route = RoutePath::parse(r"/archive/:year(int)?/:month?").unwrap();
+        let expanded: Vec<String> = route.expand().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            expanded,
+            [
+                "/archive",
+                "/archive/:year(int)",
+                "/archive/:year(int)/:month"
+            ]
+        );
+        assert!(!route.expand()[1].matches(&params(&[("year", "x")])));
+        assert_eq!(RoutePath::parse("/users").unwrap().expand().len(), 1);
+    }
+
+    #[test]
+    fn test_matches_request_paths() {
+        let route = RoutePath::parse("/users/:id(int)/posts/:slug?").unwrap();
+        assert!(route.matches_path("/users/7/posts"));
+        assert!(route.matches_path("/users/7/posts/hello/"));
+        assert!(!route.matches_path("/users/me/posts"));
+        assert!(!route.matches_path("/users/7"));
+        assert!(!route.matches_path("/users/7/posts/hello/extra"));
+
+        let route = RoutePath::parse("/files/*path").unwrap();
+        assert!(route.matches_path("/files/a/b"));
+        assert!(!route.matches_path("/files/"));
+
+        assert!(RoutePath::parse("/").unwrap().matches_path("/"));
+        assert!(!RoutePath::parse("/").unwrap().matches_path("/users"));
+    }
+
+    #[test]
+    fn test_trailing_slash_variant() {
+        let variant = |template: &str| {
+            RoutePath::parse(template)
+                .unwrap()
+                .has_trailing_slash_variant()
+        };
+        assert!(variant("/users/:id"));
+        assert!(!variant("/"));
+        assert!(!variant("/files/*path"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_templates() {
+        let error = |template: &str| RoutePath::parse(template).unwrap_err();
+        assert_eq!(
+            error("/files/*path/x"),
+            RoutePathError::WildcardNotLast("/files/*path/x".into())
+        );
+        assert_eq!(
+            error("/a/:b?/c"),
+            RoutePathError::OptionalNotTrailing("/a/:b?/c".into())
+        );
+        assert_eq!(
+            error("/a/:id/b/{id}"),
+            RoutePathError::DuplicateParam("id".into())
+        );
+        assert_eq!(error("/a/:"), RoutePathError::InvalidName(":".into()));
+        assert_eq!(error("/a/*"), RoutePathError::InvalidName("*".into()));
+        assert_eq!(error("/a/{id"), RoutePathError::Unbalanced("{id".into()));
+        assert_eq!(
+            error("/a/:id(\\d+"),
+            RoutePathError::Unbalanced("/a/:id(\\d+".into())
+        );
+        assert_eq!(
+            error("/file.{ext}"),
+            RoutePathError::PartialSegment("file.{ext}".into())
+        );
+        assert!(matches!(
+            error("/a/:id([)"),
+            RoutePathError::InvalidConstraint { .. }
+        ));
+    }
+
+    #[test]
+    fn test_param_names_without_compiling() {
+        assert_eq!(
+            route_param_names(r"/orgs/{org}/repos/:repo(\d+)/*rest"),
+            ["org", "repo", "rest"]
+        );
+        assert!(route_param_names("/a/{b").is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+//! Every route of the application, checked for conflicts before any of them
+//! reaches the adapter.
+//!
+//! Axum panics on a duplicate and Actix silently serves the first match, so
+//! routes the adapters can't tell apart fail bootstrap instead. Routes that
+//! only overlap, like `/users/me` and `/users/:id`, are allowed with a warning
+//! and registered most specific first, which is what Axum does anyway.
+//!
+//! The same route may be declared once per host. The adapters only see the
+//! path, so those routes share it and are tried in turn, most specific host
+//! first and routes without a host last. Routes whose constraints no value
+//! satisfies both, like `/items/:id(int)` and `/items/:slug(alpha)`, share
+//! their path the same way.
+//!
+//! Gateways are upgraded from a `GET` on their path, on any host, so they
+//! conflict with every `GET` route of the same path.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Result, bail};
+
+use super::{
+    host_pattern::HostPattern,
+    route_path::{RoutePath, RouteSegment, constraints_disjoint},
+};
+use crate::{http_helpers::HttpMethod, injector::InstanceWrapper};
+
+/// One expansion of a controller route
+pub(crate) struct RouteEntry {
+    pub(crate) module_token: String,
+    /// The template as written, what middleware is matched against
+    pub(crate) template: String,
+    /// Without optional segments, those are expanded into several entries
+    pub(crate) route: RoutePath,
+    pub(crate) host: Option<HostPattern>,
+    pub(crate) method: HttpMethod,
+    pub(crate) handler: Arc<InstanceWrapper>,
+    /// `UserController::find_one (UsersModule)`, for messages
+    owner: String,
+}
+
+impl RouteEntry {
+    /// Whether the adapter sees both routes as the same path, whatever the
+    /// parameters are named
+    pub(crate) fn shares_path(&self, other: &RouteEntry) -> bool {
+        shape(&self.route) == shape(&other.route)
+    }
+}
+
+/// One expansion of a gateway path
+struct GatewayEntry {
+    route: RoutePath,
+    /// `ChatGateway (ChatModule)`, for messages
+    owner: String,
+}
+
+#[derive(Default)]
+pub(crate) struct RouteTable {
+    entries: Vec<RouteEntry>,
+    gateways: Vec<GatewayEntry>,
+}
+
+impl RouteTable {
+    pub(crate) fn add(
+        &mut self,
+        module_token: &str,
+        template: &str,
+        route: &RoutePath,
+        host: Option<&HostPattern>,
+        method: HttpMethod,
+        handler: Arc<InstanceWrapper>,
+    ) {
+        let info = handler.get_handler();
+        let owner = format!(
+            "{}::{} ({})",
+            info.controller(),
+            info.handler(),
+            module_token
+        );
+        for variant in route.expand() {
+            self.entries.push(RouteEntry {
+                module_token: module_token.to_string(),
+                template: template.to_string(),
+                route: variant,
+                host: host.cloned(),
+                method,
+                handler: handler.clone(),
+                owner: owner.clone(),
+            });
+        }
+    }
+
+    /// Gateways are only checked, the adapters register them on their own
+    pub(crate) fn add_gateway(&mut self, module_token: &str, route: &RoutePath, gateway: &str) {
+        let owner = format!("{} ({})", gateway, module_token);
+        for variant in route.expand() {
+            self.gateways.push(GatewayEntry {
+                route: variant,
+                owner: owner.clone(),
+            });
+        }
+    }
+
+    /// Fails on routes the adapters can't tell apart and warns about overlaps
+    pub(crate) fn check(&self) -> Result<()> {
+        let mut conflicts = Vec::new();
+
+        for (index, route) in self.entries.iter().enumerate() {
+            for other in &self.entries[..index] {
+                if shape(&route.route) != shape(&other.route) {
+                    if route.method == other.method && overlaps(&route.route, &other.route) {
+                        eprintln!(
+                            "⚠️ {} {} by {} overlaps {} by {}, the more specific route wins",
+                            route.method.as_str(),
+                            route.route,
+                            route.owner,
+                            other.route,
+                            other.owner
+                        );
+                    }
+                    continue;
+                }
+
+                if route.method == other.method && host_name(route) == host_name(other) {
+                    // Disjoint constraints are told apart like hosts
+                    if !overlaps(&route.route, &other.route) {
+                        continue;
+                    }
+                    conflicts.push(format!(
+                        "{} {}{} is declared by {} and by {}",
+                        route.method.as_str(),
+                        host_name(route),
+                        route.route,
+                        other.owner,
+                        route.owner
+                    ));
+                } else if route.route.param_names() != other.route.param_names() {
+                    // Routers share parameter names between the methods of a path
+                    conflicts.push(format!(
+                        "{} {} by {} and {} {} by {} name the same parameters differently",
+                        other.method.as_str(),
+                        other.route,
+                        other.owner,
+                        route.method.as_str(),
+                        route.route,
+                        route.owner
+                    ));
+                }
+            }
+        }
+
+        for (index, gateway) in self.gateways.iter().enumerate() {
+            let earlier = self.gateways[..index]
+                .iter()
+                .map(|other| (HttpMethod::GET, &other.route, &other.owner));
+            let routes = self
+                .entries
+                .iter()
+                .map(|other| (other.method, &other.route, &other.owner));
+
+            for (method, route, owner) in earlier.chain(routes) {
+                if shape(&gateway.route) != shape(route) {
+                    if method == HttpMethod::GET && overlaps(&gateway.route, route) {
+                        eprintln!(
+                            "⚠️ GET {} by {} overlaps {} by {}, the more specific route wins",
+                            gateway.route, gateway.owner, route, owner
+                        );
+                    }
+                    continue;
+                }
+
+                if method == HttpMethod::GET {
+                    conflicts.push(format!(
+                        "GET {} is declared by {} and by {}",
+                        gateway.route, owner, gateway.owner
+                    ));
+                } else if gateway.route.param_names() != route.param_names() {
+                    conflicts.push(format!(
+                        "{} {} by {} and GET {} by {} name the same parameters differently",
+                        method.as_str(),
+                        route,
+                        owner,
+                        gateway.route,
+                        gateway.owner
+                    ));
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            bail!("Conflicting routes:\n  {}", conflicts.join("\n  "));
+        }
+        Ok(())
+    }
+
+    /// Static segments before parameters before wildcards, so every adapter
+    /// picks the most specific route. Then the same for hosts.
+    pub(crate) fn into_sorted(mut self) -> Vec<RouteEntry> {
+        self.entries.sort_by_key(|entry| {
+            let host = match &entry.host {
+                Some(host) if host.is_static() => 0,
+                Some(_) => 1,
+                None => 2,
+            };
+            (specificity(&entry.route), host)
+        });
+        self.entries
+    }
+}
+
+/// `api.example.com`, empty for routes on any host
+fn host_name(entry: &RouteEntry) -> String {
+    entry
+        .host
+        .as_ref()
+        .map(HostPattern::to_string)
+        .unwrap_or_default()
+}
+
+/// What the router sees of a route: static text, and where the captures are
+fn shape(route: &RoutePath) -> Vec<&str> {
+    route
+        .segments()
+        .iter()
+        .map(|segment| match segment {
+            RouteSegment::Static(text) => text.as_str(),
+            RouteSegment::Param { .. } => ":",
+            RouteSegment::Wildcard(_) => "*",
+        })
+        .collect()
+}
+
+fn specificity(route: &RoutePath) -> Vec<u8> {
+    route
+        .segments()
+        .iter()
+        .map(|segment| match segment {
+            RouteSegment::Static(_) => 0,
+            RouteSegment::Param { .. } => 1,
+            RouteSegment::Wildcard(_) => 2,
+        })
+        .collect()
+}
+
+/// Whether some path matches both routes, constraints included
+fn overlaps(a: &RoutePath, b: &RoutePath) -> bool {
+    // Static text each route's parameters would have to accept
+    let mut a_values = HashMap::new();
+    let mut b_values = HashMap::new();
+
+    let (a_segments, b_segments) = (a.segments(), b.segments());
+    for index in 0..a_segments.len().max(b_segments.len()) {
+        match (a_segments.get(index), b_segments.get(index)) {
+            (Some(RouteSegment::Wildcard(_)), Some(_))
+            | (Some(_), Some(RouteSegment::Wildcard(_))) => break,
+            (Some(RouteSegment::Static(x)), Some(RouteSegment::Static(y))) if x != y => {
+                return false;
+            }
+            (Some(RouteSegment::Param { name, .. }), Some(RouteSegment::Static(text))) => {
+                a_values.insert(name.clone(), text.clone());
+            }
+            (Some(RouteSegment::Static(text)), Some(RouteSegment::Param { name, .. })) => {
+                b_values.insert(name.clone(), text.clone());
+            }
+            (
+                Some(RouteSegment::Param {
+                    constraint: Some(x),
+                    ..
+                }),
+                Some(RouteSegment::Param {
+                    constraint: Some(y),
+                    ..
+                }),
+            ) if constraints_disjoint(x, y) => return false,
+            (Some(_), Some(_)) => {}
+            // One route is longer, and the shorter one doesn't end in a wildcard
+            _ => return false,
+        }
+    }
+
+    a.matches(&a_values) && b.matches(&b_values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{router::options_controller::OptionsController, structs_helpers::EnhancerMetadata};
+
+    fn handler() -> Arc<InstanceWrapper> {
+        let no_enhancers = || EnhancerMetadata {
+            guards: vec![],
+            pipes: vec![],
+            param_pipes: vec![],
+            interceptors: vec![],
+            filters: vec![],
+            middleware: vec![],
+        };
+        // Stands in for a controller, the table doesn't call it
+        let controller = OptionsController::new("/", vec![]);
+        Arc::new(InstanceWrapper::new(
+            Arc::new(Box::new(controller)),
+            no_enhancers(),
+            no_enhancers(),
+            "AppModule",
+        ))
+    }
+
+    fn table(routes: &[(HttpMethod, &str)]) -> RouteTable {
+        let mut table = RouteTable::default();
+        for (method, template) in routes {
+            // `api.example.com/users` restricts the route to a host
+            let (host, template) = match template.find('/') {
+                Some(0) | None => (None, *template),
+                Some(slash) => (
+                    Some(HostPattern::parse(&template[..slash]).unwrap()),
+                    &template[slash..],
+                ),
+            };
+            let route = RoutePath::parse(template).unwrap();
+            table.add(
+                "AppModule",
+                template,
+                &route,
+                host.as_ref(),
+                *method,
+                handler(),
+            );
+        }
+        table
+    }
+
+    fn check(routes: &[(HttpMethod, &str)]) -> Result<(), String> {
+        table(routes).check().map_err(|error| error.to_string())
+    }
+
+    fn overlap(a: &str, b: &str) -> bool {
+        overlaps(&RoutePath::parse(a).unwrap(), &RoutePath::parse(b).unwrap())
+    }
+
+    #[test]
+    fn test_rejects_duplicates() {
+        let error = check(&[
+            (HttpMethod::GET, "/users/:id"),
+            (HttpMethod::GET, "/users/{id}/"),
+        ])
+        .unwrap_err();
+        assert!(error.starts_with("Conflicting routes:"));
+        assert!(error.contains("GET /users/:id is declared by"));
+
+        // An unconstrained parameter accepts what the constrained one does
+        assert!(
+            check(&[
+                (HttpMethod::GET, "/a/:id(int)"),
+                (HttpMethod::GET, "/a/:slug")
+            ])
+            .is_err()
+        );
+        assert!(
+            check(&[
+                (HttpMethod::GET, "/a/:id(int)"),
+                (HttpMethod::GET, r"/a/:year(\d{4})")
+            ])
+            .is_err()
+        );
+        // An optional segment expands onto the other route
+        assert!(
+            check(&[
+                (HttpMethod::GET, "/archive/:year?"),
+                (HttpMethod::GET, "/archive")
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_allows_disjoint_constraints() {
+        check(&[
+            (HttpMethod::GET, r"/items/:id(\d+)"),
+            (HttpMethod::GET, "/items/:slug([a-z]+)"),
+            (HttpMethod::GET, "/items/:code(uuid)"),
+        ])
+        .unwrap();
+
+        // Other methods still share the parameter names of the path
+        let error = check(&[
+            (HttpMethod::GET, r"/items/:id(\d+)"),
+            (HttpMethod::GET, "/items/:slug([a-z]+)"),
+            (HttpMethod::DELETE, r"/items/:id(\d+)"),
+        ])
+        .unwrap_err();
+        assert!(error.contains("name the same parameters differently"));
+    }
+
+    #[test]
+    fn test_rejects_parameters_named_differently() {
+        let error = check(&[
+            (HttpMethod::GET, "/users/:id"),
+            (HttpMethod::DELETE, "/users/:user_id"),
+        ])
+        .unwrap_err();
+        assert!(error.contains("name the same parameters differently"));
+    }
+
+    #[test]
+    fn test_allows_distinct_and_overlapping_routes() {
+        check(&[
+            (HttpMethod::GET, "/users/:id"),
+            (HttpMethod::DELETE, "/users/:id"),
+            (HttpMethod::GET, "/users/me"),
+            (HttpMethod::GET, "/users/:id/posts"),
+            (HttpMethod::GET, "/files/*path"),
+        ])
+        .unwrap();
+    }
+
+    #[test]
+    fn test_detects_overlaps() {
+        assert!(overlap("/users/:id", "/users/me"));
+        assert!(overlap("/files/*path", "/files/readme"));
+        assert!(overlap("/files/*path", "/files/a/b"));
+        assert!(!overlap("/files/*path", "/files"));
+        assert!(!overlap("/users/:id", "/users/:id/posts"));
+        assert!(!overlap("/users/me", "/teams/me"));
+        assert!(!overlap("/users/:id(int)", "/users/me"));
+        assert!(!overlap("/users/:id(int)", "/users/:name(alpha)"));
+        assert!(overlap("/users/:id(int)", "/users/:name(alnum)"));
+    }
+
+    #[test]
+    fn test_sorts_specific_routes_first() {
+        let sorted: Vec<String> = table(&[
+            (HttpMethod::GET, "/files/*path"),
+            (HttpMethod::GET, "/users/:id"),
+            (HttpMethod::GET, "/files/:name"),
+            (HttpMethod::GET, "/users/me"),
+        ])
+        .into_sorted()
+        .iter()
+        .map(|entry| entry.route.to_string())
+        .collect();
+        assert_eq!(
+            sorted,
+            ["/users/me", "/users/:id", "/files/:name", "/files/*path"]
+        );
+    }
+
+    #[test]
+    fn test_allows_one_route_per_host() {
+        check(&[
+            (HttpMethod::GET, "api.example.com/users"),
+            (HttpMethod::GET, ":tenant.example.com/users"),
+            (HttpMethod::GET, "/users"),
+        ])
+        .unwrap();
+
+        let error = check(&[
+            (HttpMethod::GET, "api.example.com/users/:id"),
+            (HttpMethod::GET, "API.example.com/users/{id}"),
+        ])
+        .unwrap_err();
+        assert!(error.contains("GET api.example.com/users/:id is declared by"));
+
+        // The adapter still sees one path
+        let error = check(&[
+            (HttpMethod::GET, "api.example.com/users/:id"),
+            (HttpMethod::GET, "/users/:user_id"),
+        ])
+        .unwrap_err();
+        assert!(error.contains("name the same parameters differently"));
+    }
+
+    #[test]
+    fn test_checks_gateways() {
+        let gateway = |table: &mut RouteTable, template: &str| {
+            let route = RoutePath::parse(template).unwrap();
+            table.add_gateway("AppModule", &route, "ChatGateway");
+        };
+
+        let mut routes = table(&[
+            (HttpMethod::POST, "/chat/:room"),
+            (HttpMethod::GET, "api.example.com/feed"),
+        ]);
+        gateway(&mut routes, "/chat/{room}");
+        gateway(&mut routes, "/events/*topic");
+        routes.check().unwrap();
+
+        // The upgrade is a GET, on any host
+        let mut routes = table(&[(HttpMethod::GET, "api.example.com/feed")]);
+        gateway(&mut routes, "/feed/");
+        let error = routes.check().unwrap_err().to_string();
+        assert!(error.contains("GET /feed is declared by"));
+        assert!(error.contains("and by ChatGateway (AppModule)"));
+
+        let mut routes = table(&[(HttpMethod::POST, "/chat/:id")]);
+        gateway(&mut routes, "/chat/:room");
+        let error = routes.check().unwrap_err().to_string();
+        assert!(error.contains("name the same parameters differently"));
+
+        let mut routes = RouteTable::default();
+        gateway(&mut routes, "/ws/:room?");
+        gateway(&mut routes, "/ws");
+        assert!(routes.check().is_err());
+    }
+
+    #[test]
+    fn test_sorts_specific_hosts_first() {
+        let sorted: Vec<String> = table(&[
+            (HttpMethod::GET, "/users"),
+            (HttpMethod::GET, ":tenant.example.com/users"),
+            (HttpMethod::GET, "api.example.com/users"),
+            (HttpMethod::GET, "api.example.com/users/:id"),
+        ])
+        .into_sorted()
+        .iter()
+        .map(|entry| format!("{}{}", host_name(entry), entry.route))
+        .collect();
+        assert_eq!(
+            sorted,
+            [
+                "api.example.com/users",
+                ":tenant.example.com/users",
+                "/users",
+                "api.example.com/users/:id"
+            ]
+        );
+    }
+}
@@ -1,64 +1,403 @@
-use anyhow::Result;
-use std::{cell::RefCell, rc::Rc};
+use anyhow::{Result, anyhow};
+use std::{any::TypeId, cell::RefCell, rc::Rc, sync::Arc};
 
-use crate::{http_adapter::HttpAdapter, injector::ToniContainer};
+use super::{
+    fallback_controller::FallbackController,
+    host_pattern::HostPattern,
+    options_controller::OptionsController,
+    route_path::{RoutePath, RouteSegment},
+    route_table::{RouteEntry, RouteTable},
+};
+use crate::{
+    http_adapter::HttpAdapter,
+    http_helpers::HttpMethod,
+    injector::{GatewayWrapper, InstanceWrapper, ToniContainer},
+    middleware::Middleware,
+    structs_helpers::EnhancerMetadata,
+    traits_helpers::{ControllerTrait, DefaultFallbackHandler},
+};
 
 pub struct RoutesResolver {
     container: Rc<RefCell<ToniContainer>>,
 }
 
+/// A controller route, parsed but not yet wired
+struct ParsedRoute {
+    module_token: String,
+    template: String,
+    route: RoutePath,
+    host: Option<HostPattern>,
+    handler: Arc<InstanceWrapper>,
+}
+
+/// A path registered on the adapter, kept to derive implicit HEAD and OPTIONS routes
+struct RegisteredRoute<'a> {
+    /// The adapter's path, one per trailing-slash variant
+    path: String,
+    entry: &'a RouteEntry,
+    /// What the adapter calls, `entry.handler` chained to the same route on
+    /// other hosts or with disjoint constraints
+    handler: Arc<InstanceWrapper>,
+}
+
 impl RoutesResolver {
     pub fn new(container: Rc<RefCell<ToniContainer>>) -> Self {
         Self { container }
     }
 
-    pub fn resolve(&mut self, http_adapter: &mut impl HttpAdapter) -> Result<()> {
+    pub fn resolve<A: HttpAdapter>(&mut self, http_adapter: &mut A) -> Result<()> {
         let modules_token = self.container.borrow().get_modules_token();
 
-        for module_token in modules_token {
-            self.register_routes(module_token, http_adapter)?;
+        // The fallback needs every route to tell a 404 from a 405
+        let mut parsed = Vec::new();
+        for module_token in &modules_token {
+            parsed.extend(self.parse_routes(module_token)?);
+        }
+        let fallback = self.fallback(&parsed);
+        let mut gateways = Vec::new();
+        for module_token in &modules_token {
+            gateways.extend(self.parse_gateways(module_token)?);
+        }
+
+        // Every route is checked before the adapter sees any of them
+        let mut table = RouteTable::default();
+        for ParsedRoute {
+            module_token,
+            template,
+            route,
+            host,
+            mut handler,
+        } in parsed
+        {
+            let method = handler.get_method();
+            let controller = handler.get_handler().controller_type();
+            let route_middleware =
+                self.route_middleware(&module_token, &template, method, controller);
+
+            // Apply middleware, host and constraints to controller
+            if let Some(wrapper) = Arc::get_mut(&mut handler) {
+                wrapper.set_middleware(route_middleware);
+                wrapper.set_route(route.clone());
+                if let Some(host) = &host {
+                    wrapper.set_host(host.clone());
+                }
+                wrapper.set_fallback(fallback.clone());
+            }
+
+            table.add(
+                &module_token,
+                &template,
+                &route,
+                host.as_ref(),
+                method,
+                handler,
+            );
+        }
+        for (module_token, route, gateway) in &gateways {
+            table.add_gateway(module_token, route, &gateway.get_token());
+        }
+        table.check()?;
+
+        let entries = table.into_sorted();
+        // Routes sharing a path are registered under the first one's names
+        let mut groups: Vec<(String, HttpMethod, Vec<&RouteEntry>)> = Vec::new();
+        for entry in &entries {
+            let mut shared = groups
+                .iter_mut()
+                .filter(|(_, method, group)| *method == entry.method && group[0].shares_path(entry))
+                .peekable();
+            if shared.peek().is_none() {
+                for path in Self::native_paths::<A>(&entry.route) {
+                    groups.push((path, entry.method, vec![entry]));
+                }
+            } else {
+                shared.for_each(|(_, _, group)| group.push(entry));
+            }
+        }
+
+        let mut registered = Vec::new();
+        for (path, method, group) in groups {
+            let handler = Self::chain_routes(&group);
+            http_adapter.add_route(&path, method, handler.clone());
+            registered.push(RegisteredRoute {
+                path,
+                entry: group[0],
+                handler,
+            });
+        }
+        self.register_implicit_routes(&registered, &fallback, http_adapter);
+        http_adapter.set_fallback(fallback);
+
+        for (_, route, gateway) in gateways {
+            for variant in route.expand() {
+                for path in Self::native_paths::<A>(&variant) {
+                    http_adapter.add_websocket_route(&path, gateway.clone());
+                }
+            }
         }
         Ok(())
     }
 
-    fn register_routes(
-        &mut self,
-        module_token: String,
-        http_adapter: &mut impl HttpAdapter,
-    ) -> Result<()> {
+    fn parse_routes(&mut self, module_token: &String) -> Result<Vec<ParsedRoute>> {
         let controllers_vec: Vec<_> = {
             let mut container = self.container.borrow_mut();
-            let controllers = container.get_controllers_instance(&module_token)?;
+            let controllers = container.get_controllers_instance(module_token)?;
             controllers.collect()
         };
 
-        // Process each controller
-        for (_, mut controller) in controllers_vec {
-            let route_path = controller.get_path();
-            let route_method = controller.get_method();
-
-            let route_middleware = {
-                let container = self.container.borrow(); // Immutable borrow
-                if let Some(middleware_manager) = container.get_middleware_manager() {
-                    middleware_manager.get_middleware_for_route(
-                        &module_token,
-                        &route_path,
-                        &route_method.as_str(),
-                    )
-                } else {
-                    Vec::new()
+        controllers_vec
+            .into_iter()
+            .map(|(_, handler)| {
+                let template = handler.get_path();
+                let route = RoutePath::parse(&template)
+                    .map_err(|error| anyhow!("Invalid route '{}': {}", template, error))?;
+                let host = handler
+                    .get_handler()
+                    .host()
+                    .map(|host| {
+                        HostPattern::parse(host)
+                            .map_err(|error| anyhow!("Invalid host '{}': {}", host, error))
+                    })
+                    .transpose()?;
+
+                // Host parameters are read like path parameters
+                if let Some(host) = &host
+                    && let Some(name) = host
+                        .param_names()
+                        .into_iter()
+                        .find(|name| route.param_names().contains(name))
+                {
+                    return Err(anyhow!(
+                        "Invalid route '{}': parameter '{}' is also captured from the host",
+                        template,
+                        name
+                    ));
                 }
-            };
 
-            // Apply middleware to controller
-            if let Some(wrapper) = std::sync::Arc::get_mut(&mut controller) {
-                wrapper.set_middleware(route_middleware);
+                Ok(ParsedRoute {
+                    module_token: module_token.clone(),
+                    template,
+                    route,
+                    host,
+                    handler,
+                })
+            })
+            .collect()
+    }
+
+    /// Answers unmatched requests, behind the global middleware only
+    fn fallback(&self, parsed: &[ParsedRoute]) -> Arc<InstanceWrapper> {
+        let container = self.container.borrow();
+        let handler = container
+            .get_fallback_handler()
+            .unwrap_or_else(|| Arc::new(DefaultFallbackHandler));
+        let routes = parsed
+            .iter()
+            .map(|parsed| {
+                (
+                    parsed.route.clone(),
+                    parsed.host.clone(),
+                    parsed.handler.get_method(),
+                )
+            })
+            .collect();
+
+        let controller: Arc<Box<dyn ControllerTrait>> =
+            Arc::new(Box::new(FallbackController::new(routes, handler)));
+        let mut wrapper = InstanceWrapper::new(controller, no_enhancers(), no_enhancers(), "");
+        if let Some(middleware_manager) = container.get_middleware_manager() {
+            wrapper.set_middleware(middleware_manager.get_global_middleware().to_vec());
+        }
+        Arc::new(wrapper)
+    }
+
+    /// The adapter's paths for a route, `/users` and `/users/`
+    fn native_paths<A: HttpAdapter>(route: &RoutePath) -> Vec<String> {
+        let path = A::native_path(route);
+        if route.has_trailing_slash_variant() {
+            vec![path.clone(), format!("{}/", path)]
+        } else {
+            vec![path]
+        }
+    }
+
+    /// One handler for routes sharing a path and method on different hosts
+    /// or with disjoint constraints, each passing the requests it doesn't
+    /// accept to the next
+    fn chain_routes(group: &[&RouteEntry]) -> Arc<InstanceWrapper> {
+        let names = group[0].route.param_names();
+        let link = |entry: &RouteEntry, next: Option<Arc<InstanceWrapper>>| {
+            let aliases: Vec<(String, String)> = names
+                .iter()
+                .zip(entry.route.param_names())
+                .filter(|(name, own)| *name != own)
+                .map(|(name, own)| (name.to_string(), own.to_string()))
+                .collect();
+            if next.is_none() && aliases.is_empty() {
+                return entry.handler.clone();
+            }
+            let mut wrapper = (*entry.handler).clone();
+            wrapper.set_param_aliases(aliases);
+            if let Some(next) = next {
+                wrapper.set_fallback(next);
             }
+            Arc::new(wrapper)
+        };
 
-            // Register route
-            http_adapter.add_route(&route_path, controller.get_method(), controller);
+        let (last, rest) = group.split_last().expect("groups have a route");
+        rest.iter()
+            .rev()
+            .fold(link(last, None), |next, entry| link(entry, Some(next)))
+    }
+
+    /// Derives HEAD from GET and answers OPTIONS with an `Allow` header, for
+    /// paths that don't define those methods themselves.
+    fn register_implicit_routes(
+        &self,
+        registered: &[RegisteredRoute],
+        fallback: &Arc<InstanceWrapper>,
+        http_adapter: &mut impl HttpAdapter,
+    ) {
+        let mut paths: Vec<&str> = Vec::new();
+        for route in registered {
+            if !paths.contains(&route.path.as_str()) {
+                paths.push(&route.path);
+            }
         }
 
-        Ok(())
+        for path in paths {
+            let routes: Vec<&RegisteredRoute> =
+                registered.iter().filter(|r| r.path == path).collect();
+
+            let mut allow: Vec<HttpMethod> = Vec::new();
+            for route in &routes {
+                if !allow.contains(&route.entry.method) {
+                    allow.push(route.entry.method);
+                }
+            }
+
+            if !allow.contains(&HttpMethod::HEAD)
+                && let Some(get) = routes.iter().find(|r| r.entry.method == HttpMethod::GET)
+            {
+                // The server drops the body of HEAD responses
+                http_adapter.add_route(path, HttpMethod::HEAD, get.handler.clone());
+                allow.push(HttpMethod::HEAD);
+            }
+
+            if !allow.contains(&HttpMethod::OPTIONS) {
+                allow.push(HttpMethod::OPTIONS);
+
+                let first = routes[0].entry;
+                let template = &first.template;
+                let controller: Arc<Box<dyn ControllerTrait>> =
+                    Arc::new(Box::new(OptionsController::new(template, allow)));
+                let mut wrapper = InstanceWrapper::new(
+                    controller,
+                    no_enhancers(),
+                    no_enhancers(),
+                    &first.module_token,
+                );
+                // Middleware of a controller applies when it serves every method
+                let controller_of =
+                    |entry: &RouteEntry| entry.handler.get_handler().controller_type();
+                let controller = controller_of(first).filter(|_| {
+                    routes
+                        .iter()
+                        .all(|r| controller_of(r.entry) == controller_of(first))
+                });
+                wrapper.set_middleware(self.route_middleware(
+                    &first.module_token,
+                    template,
+                    HttpMethod::OPTIONS,
+                    controller,
+                ));
+                // Constraints and hosts only apply when every method shares them
+                let key = |entry: &RouteEntry| {
+                    let host = entry.host.as_ref().map(HostPattern::to_string);
+                    (entry.route.to_string(), host)
+                };
+                let chained = routes
+                    .iter()
+                    .any(|r| !Arc::ptr_eq(&r.handler, &r.entry.handler));
+                if !chained && routes.iter().all(|r| key(r.entry) == key(first)) {
+                    wrapper.set_route(first.route.clone());
+                    if let Some(host) = &first.host {
+                        wrapper.set_host(host.clone());
+                    }
+                    wrapper.set_fallback(fallback.clone());
+                }
+
+                http_adapter.add_route(path, HttpMethod::OPTIONS, Arc::new(wrapper));
+            }
+        }
+    }
+
+    fn route_middleware(
+        &self,
+        module_token: &str,
+        route_path: &str,
+        method: HttpMethod,
+        controller: Option<TypeId>,
+    ) -> Vec<Arc<dyn Middleware>> {
+        let container = self.container.borrow(); // Immutable borrow
+        if let Some(middleware_manager) = container.get_middleware_manager() {
+            middleware_manager.get_middleware_for_handler(
+                module_token,
+                route_path,
+                method.as_str(),
+                controller,
+            )
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Gateway paths are parsed like controller routes. Nothing checks
+    /// constraints on the upgrade request, so they are rejected.
+    fn parse_gateways(
+        &mut self,
+        module_token: &String,
+    ) -> Result<Vec<(String, RoutePath, Arc<GatewayWrapper>)>> {
+        let gateways_vec: Vec<_> = {
+            let mut container = self.container.borrow_mut();
+            let gateways = container.get_gateways_instance(module_token)?;
+            gateways.collect()
+        };
+
+        gateways_vec
+            .into_iter()
+            .map(|(_, gateway)| {
+                let template = gateway.get_path();
+                let route = RoutePath::parse(&template)
+                    .map_err(|error| anyhow!("Invalid gateway '{}': {}", template, error))?;
+                let constrained = route.segments().iter().find_map(|segment| match segment {
+                    RouteSegment::Param {
+                        name,
+                        constraint: Some(_),
+                        ..
+                    } => Some(name),
+                    _ => None,
+                });
+                if let Some(name) = constrained {
+                    return Err(anyhow!(
+                        "Invalid gateway '{}': parameter '{}' can't be constrained",
+                        template,
+                        name
+                    ));
+                }
+                Ok((module_token.clone(), route, gateway))
+            })
+            .collect()
+    }
+}
+
+fn no_enhancers() -> EnhancerMetadata {
+    EnhancerMetadata {
+        guards: vec![],
+        pipes: vec![],
+        param_pipes: vec![],
+        interceptors: vec![],
+        filters: vec![],
+        middleware: vec![],
     }
 }
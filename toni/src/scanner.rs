@@ -3,7 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use anyhow::{Result, anyhow};
 
 use crate::{
-    injector::ToniContainer,
+    injector::{ToniContainer, ToniInstanceLoader},
     module_helpers::module_enum::ModuleDefinition,
     traits_helpers::{MiddlewareConsumer, ModuleMetadata},
 };
@@ -59,6 +59,7 @@ impl ToniDependenciesScanner {
         for module_token in modules_token {
             self.insert_providers(module_token.clone())?;
             self.insert_controllers(module_token.clone())?;
+            self.insert_gateways(module_token.clone())?;
             self.insert_exports(module_token.clone())?;
         }
 
@@ -99,6 +100,25 @@ impl ToniDependenciesScanner {
         Ok(())
     }
 
+    pub fn insert_gateways(&mut self, module_token: String) -> Result<()> {
+        let mut container = self.container.borrow_mut();
+        let module_ref = container.get_module_by_token(&module_token);
+        let resolved_module_ref = match module_ref {
+            Some(module_ref) => module_ref,
+            None => return Err(anyhow!("Module not found")),
+        };
+
+        let gateways = resolved_module_ref.get_metadata().gateways();
+
+        if let Some(gateways) = gateways {
+            for gateway in gateways {
+                container.add_gateway(&module_token, gateway)?;
+            }
+        };
+
+        Ok(())
+    }
+
     pub fn insert_providers(&mut self, module_token: String) -> Result<()> {
         let mut container = self.container.borrow_mut();
         let module_ref = container.get_module_by_token(&module_token);
@@ -143,15 +163,17 @@ impl ToniDependenciesScanner {
         Ok(())
     }
 
-    pub fn scan_middleware(&mut self) -> Result<()> {
+    /// Registers the middleware of every module, once providers exist to
+    /// build the injected ones
+    pub async fn scan_middleware(&mut self) -> Result<()> {
         let modules_token = self.container.borrow().get_modules_token();
         for module_token in modules_token {
-            self.register_module_middleware(&module_token)?;
+            self.register_module_middleware(&module_token).await?;
         }
         Ok(())
     }
 
-    fn register_module_middleware(&mut self, module_token: &str) -> Result<()> {
+    async fn register_module_middleware(&mut self, module_token: &str) -> Result<()> {
         let middleware_configs = {
             let container = self.container.borrow();
 
@@ -163,16 +185,33 @@ impl ToniDependenciesScanner {
 
             let mut consumer = MiddlewareConsumer::new();
             metadata.configure_middleware(&mut consumer);
-            consumer.build()
+            consumer.build().map_err(|error| {
+                anyhow!("Invalid middleware route in module {}: {}", module_token, error)
+            })?
         };
 
+        let loader = ToniInstanceLoader::new(self.container.clone());
+        let mut resolved_configs = Vec::with_capacity(middleware_configs.len());
+        for (sources, mut config) in middleware_configs {
+            let middleware = loader
+                .resolve_enhancers(
+                    &module_token.to_string(),
+                    "configure_middleware",
+                    "Middleware",
+                    sources,
+                )
+                .await?;
+            config.middleware.extend(middleware);
+            resolved_configs.push(config);
+        }
+
         let mut container_mut = self.container.borrow_mut();
 
         let middleware_manager = container_mut
             .get_middleware_manager_mut()
             .ok_or_else(|| anyhow!("Middleware manager not initialized"))?;
 
-        for config in middleware_configs {
+        for config in resolved_configs {
             middleware_manager.add_for_module(module_token.to_string(), config);
         }
 
@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
-use crate::traits_helpers::{Guard, Interceptor, Pipe};
+use crate::traits_helpers::{
+    ExceptionFilter, Guard, Interceptor, Pipe, PipeTransform, middleware::Middleware,
+};
 
 pub struct EnhancerMetadata {
     pub guards: Vec<Arc<dyn Guard>>,
     pub pipes: Vec<Arc<dyn Pipe>>,
+    /// `#[pipe]` pipes of the handler's arguments, in declaration order
+    pub param_pipes: Vec<Arc<dyn PipeTransform>>,
     pub interceptors: Vec<Arc<dyn Interceptor>>,
+    pub filters: Vec<Arc<dyn ExceptionFilter>>,
+    /// `#[use_middleware]` of the controller, then of the method
+    pub middleware: Vec<Arc<dyn Middleware>>,
 }
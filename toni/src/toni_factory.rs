@@ -4,10 +4,11 @@ use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::middleware::Middleware;
+use crate::http_helpers::CookieKey;
+use crate::middleware::{CookieKeyMiddleware, Middleware};
 use crate::module_helpers::module_enum::ModuleDefinition;
 use crate::toni_application::ToniApplication;
-use crate::traits_helpers::{Guard, Interceptor, Pipe};
+use crate::traits_helpers::{ExceptionFilter, FallbackHandler, Guard, Interceptor, Pipe};
 use crate::{
     http_adapter::HttpAdapter,
     injector::{ToniContainer, ToniInstanceLoader},
@@ -20,6 +21,8 @@ pub struct ToniFactory {
     global_guards: Vec<Arc<dyn Guard>>,
     global_interceptors: Vec<Arc<dyn Interceptor>>,
     global_pipes: Vec<Arc<dyn Pipe>>,
+    global_filters: Vec<Arc<dyn ExceptionFilter>>,
+    fallback_handler: Option<Arc<dyn FallbackHandler>>,
 }
 
 impl ToniFactory {
@@ -30,6 +33,8 @@ impl ToniFactory {
             global_guards: Vec::new(),
             global_interceptors: Vec::new(),
             global_pipes: Vec::new(),
+            global_filters: Vec::new(),
+            fallback_handler: None,
         }
     }
 
@@ -38,6 +43,11 @@ impl ToniFactory {
         self
     }
 
+    /// Sets the key used for signed and encrypted cookies
+    pub fn use_cookie_key(&mut self, key: CookieKey) -> &mut Self {
+        self.use_global_middleware(Arc::new(CookieKeyMiddleware::new(key)))
+    }
+
     pub fn use_global_guards(&mut self, guard: Arc<dyn Guard>) -> &mut Self {
         self.global_guards.push(guard);
         self
@@ -53,6 +63,17 @@ impl ToniFactory {
         self
     }
 
+    pub fn use_global_filters(&mut self, filter: Arc<dyn ExceptionFilter>) -> &mut Self {
+        self.global_filters.push(filter);
+        self
+    }
+
+    /// Customizes the `404` and `405` responses for requests no route matches
+    pub fn use_fallback_handler(&mut self, handler: Arc<dyn FallbackHandler>) -> &mut Self {
+        self.fallback_handler = Some(handler);
+        self
+    }
+
     pub async fn create(
         &self,
         module: ModuleDefinition,
@@ -115,14 +136,21 @@ impl ToniFactory {
             for pipe in &self.global_pipes {
                 container_mut.add_global_pipe(pipe.clone());
             }
+            for filter in &self.global_filters {
+                container_mut.add_global_filter(filter.clone());
+            }
+            if let Some(handler) = &self.fallback_handler {
+                container_mut.set_fallback_handler(handler.clone());
+            }
         }
 
-        scanner.scan_middleware()?;
-
         ToniInstanceLoader::new(container.clone())
             .create_instances_of_dependencies()
             .await?;
 
+        // Middleware may be a provider, resolved from the module's instances
+        scanner.scan_middleware().await?;
+
         Ok(())
     }
 }
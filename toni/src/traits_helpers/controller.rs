@@ -3,9 +3,16 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use rustc_hash::FxHashMap;
 
-use crate::http_helpers::{HttpMethod, HttpRequest, HttpResponse, IntoResponse};
+use crate::{
+    extractors::ValidationError,
+    http_helpers::{HttpMethod, HttpRequest, HttpResponse, IntoResponse},
+    injector::HandlerInfo,
+};
 
-use super::{Guard, Interceptor, Pipe, provider::ProviderTrait, validate::Validatable};
+use super::{
+    ExceptionFilter, GuardSource, Interceptor, Pipe, PipeSource, middleware::MiddlewareSource,
+    provider::ProviderTrait, validate::Validatable,
+};
 
 #[async_trait]
 pub trait ControllerTrait: Send + Sync {
@@ -16,10 +23,28 @@ pub trait ControllerTrait: Send + Sync {
     ) -> Box<dyn IntoResponse<Response = HttpResponse> + Send>;
     fn get_path(&self) -> String;
     fn get_method(&self) -> HttpMethod;
-    fn get_guards(&self) -> Vec<Arc<dyn Guard>>;
+    fn get_guards(&self) -> Vec<GuardSource>;
     fn get_pipes(&self) -> Vec<Arc<dyn Pipe>>;
+    /// Pipes attached to the handler's arguments with `#[pipe]`
+    fn get_param_pipes(&self) -> Vec<PipeSource> {
+        vec![]
+    }
     fn get_interceptors(&self) -> Vec<Arc<dyn Interceptor>>;
-    fn get_body_dto(&self, req: &HttpRequest) -> Option<Box<dyn Validatable>>;
+    fn get_filters(&self) -> Vec<Arc<dyn ExceptionFilter>> {
+        vec![]
+    }
+    /// Middleware attached with `#[use_middleware]`, controller-level first
+    fn get_middleware(&self) -> Vec<MiddlewareSource> {
+        vec![]
+    }
+    /// The body DTO to validate before the handler runs, if the handler takes one
+    fn get_body_dto(
+        &self,
+        req: &HttpRequest,
+    ) -> Result<Option<Box<dyn Validatable>>, ValidationError>;
+    fn get_handler(&self) -> HandlerInfo {
+        HandlerInfo::new(self.get_token(), "execute")
+    }
 }
 #[async_trait]
 pub trait Controller {
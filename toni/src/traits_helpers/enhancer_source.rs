@@ -0,0 +1,65 @@
+use std::{any::Any, marker::PhantomData, sync::Arc};
+
+use crate::injector::type_token;
+
+/// Where an enhancer listed on a controller comes from: built in place, or
+/// taken from the provider `token` of the module once providers exist, with
+/// `resolve` downcasting the provider's instance
+pub enum EnhancerSource<T: ?Sized> {
+    Instance(Arc<T>),
+    Provider {
+        token: String,
+        resolve: fn(Box<dyn Any + Send>) -> Option<Arc<T>>,
+    },
+}
+
+impl<E: ?Sized> EnhancerSource<E> {
+    /// The provider of type `T`, found by the token `#[inject]` would use
+    pub fn provider<T: IntoEnhancer<E>>() -> Self {
+        Self::Provider {
+            token: type_token::<T>(),
+            resolve: |instance| Some(instance.downcast::<T>().ok()?.into_enhancer()),
+        }
+    }
+}
+
+/// Turns a concrete guard, pipe or middleware into the trait object `E`
+#[doc(hidden)]
+pub trait IntoEnhancer<E: ?Sized>: 'static {
+    fn into_enhancer(self: Box<Self>) -> Arc<E>;
+}
+
+/// Picks the `EnhancerSource` for an enhancer type in macro-generated code:
+/// `Default` types are built in place, others are resolved from the module's
+/// providers
+#[doc(hidden)]
+pub struct EnhancerRef<T>(PhantomData<T>);
+
+impl<T> EnhancerRef<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait DefaultEnhancerSource<E: ?Sized> {
+    fn enhancer_source(&self) -> EnhancerSource<E>;
+}
+
+impl<E: ?Sized, T: IntoEnhancer<E> + Default> DefaultEnhancerSource<E> for &EnhancerRef<T> {
+    fn enhancer_source(&self) -> EnhancerSource<E> {
+        EnhancerSource::Instance(Box::new(T::default()).into_enhancer())
+    }
+}
+
+#[doc(hidden)]
+pub trait ProviderEnhancerSource<E: ?Sized> {
+    fn enhancer_source(&self) -> EnhancerSource<E>;
+}
+
+impl<E: ?Sized, T: IntoEnhancer<E>> ProviderEnhancerSource<E> for EnhancerRef<T> {
+    fn enhancer_source(&self) -> EnhancerSource<E> {
+        EnhancerSource::provider::<T>()
+    }
+}
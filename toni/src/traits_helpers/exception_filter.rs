@@ -0,0 +1,32 @@
+use crate::http_helpers::{Exception, HttpRequest, HttpResponse};
+
+/// Turns errors into responses.
+///
+/// Filters run for errors returned by handlers (`Err(..)` or an
+/// `HttpException`), failed body validation and middleware errors. They are
+/// tried from the most specific to the most general (method, controller,
+/// then global) and the first one returning `Some` wins; within one list the
+/// last filter is tried first. Return `None` to leave an error to the next
+/// filter.
+///
+/// ```
+/// use toni::http_helpers::{Exception, HttpException, HttpRequest, HttpResponse};
+/// use toni::traits_helpers::ExceptionFilter;
+///
+/// struct NotFoundPage;
+///
+/// impl ExceptionFilter for NotFoundPage {
+///     fn catch(&self, exception: &Exception, _req: &HttpRequest) -> Option<HttpResponse> {
+///         let error = exception.downcast_ref::<HttpException>()?;
+///         if error.status().as_u16() != 404 {
+///             return None;
+///         }
+///         let mut response = exception.response().clone();
+///         response.headers.insert("Cache-Control", "no-store");
+///         Some(response)
+///     }
+/// }
+/// ```
+pub trait ExceptionFilter: Send + Sync {
+    fn catch(&self, exception: &Exception, req: &HttpRequest) -> Option<HttpResponse>;
+}
@@ -0,0 +1,41 @@
+use crate::http_helpers::{HttpException, HttpMethod, HttpRequest, HttpResponse, IntoResponse};
+
+/// Answers requests that no route matches.
+///
+/// Both methods default to the JSON body of an `HttpException`. Global
+/// middleware runs before them, and Toni adds the `Allow` header to a `405`
+/// when the handler leaves it out. Register one with
+/// `ToniFactory::use_fallback_handler`.
+///
+/// ```
+/// use toni::http_helpers::{Body, HttpRequest, HttpResponse};
+/// use toni::traits_helpers::FallbackHandler;
+///
+/// struct NotFoundPage;
+///
+/// impl FallbackHandler for NotFoundPage {
+///     fn not_found(&self, req: &HttpRequest) -> HttpResponse {
+///         let mut response = HttpResponse::new();
+///         response.status = 404;
+///         response.body = Some(Body::Text(format!("Nothing at {}", req.path())));
+///         response
+///     }
+/// }
+/// ```
+pub trait FallbackHandler: Send + Sync {
+    /// No route has the request's path
+    fn not_found(&self, req: &HttpRequest) -> HttpResponse {
+        HttpException::not_found(format!("Cannot {} {}", req.method, req.path())).to_response()
+    }
+
+    /// Routes have the request's path but not its method; `allow` lists theirs
+    fn method_not_allowed(&self, req: &HttpRequest, _allow: &[HttpMethod]) -> HttpResponse {
+        HttpException::method_not_allowed(format!("Cannot {} {}", req.method, req.path()))
+            .to_response()
+    }
+}
+
+/// The JSON responses, when no handler is registered
+pub(crate) struct DefaultFallbackHandler;
+
+impl FallbackHandler for DefaultFallbackHandler {}
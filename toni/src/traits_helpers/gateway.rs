@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use crate::http_helpers::HttpRequest;
+use crate::websocket::WsClient;
+
+use super::{GuardSource, provider::ProviderTrait};
+
+#[async_trait]
+pub trait GatewayTrait: Send + Sync {
+    fn get_token(&self) -> String;
+    fn get_path(&self) -> String;
+    fn get_guards(&self) -> Vec<GuardSource>;
+    fn get_events(&self) -> Vec<String>;
+    async fn on_connection(&self, client: &WsClient, req: &HttpRequest);
+    async fn on_disconnect(&self, client: &WsClient);
+    /// Dispatches an event to its `#[subscribe_message]` handler.
+    ///
+    /// `Ok(Some(value))` is sent back to the client under the same event name.
+    async fn handle_message(
+        &self,
+        client: &WsClient,
+        event: &str,
+        data: Value,
+    ) -> Result<Option<Value>, String>;
+}
+
+#[async_trait]
+pub trait Gateway {
+    /// Builds the gateway from its resolved `dependencies`. `Err` names the
+    /// dependency that could not be injected and fails the bootstrap.
+    async fn get_all_gateways(
+        &self,
+        dependencies: &FxHashMap<String, Arc<Box<dyn ProviderTrait>>>,
+    ) -> Result<FxHashMap<String, Arc<Box<dyn GatewayTrait>>>, String>;
+    fn get_name(&self) -> String;
+    fn get_token(&self) -> String;
+    fn get_dependencies(&self) -> Vec<String>;
+}
@@ -1,5 +1,55 @@
-use crate::injector::Context;
+use std::sync::Arc;
 
+use async_trait::async_trait;
+
+use super::{EnhancerSource, IntoEnhancer};
+use crate::{http_helpers::HttpException, injector::Context};
+
+/// Decides whether a request reaches the handler.
+///
+/// `Ok(false)` denies with `403 Forbidden`, `Err` denies with the given
+/// exception; both go through the exception filters. A guard can hand data to
+/// the handler through `context.take_request_mut().extensions`.
+#[async_trait]
 pub trait Guard: Send + Sync {
-    fn can_activate(&self, context: &Context) -> bool;
+    async fn can_activate(&self, context: &mut Context) -> Result<bool, HttpException>;
+}
+
+/// Where a guard listed in `#[use_guards]` comes from.
+///
+/// Guards implementing `Default` are built directly; any other guard must be
+/// a singleton `#[injectable]` provider visible to the controller's module, so
+/// it can use injected services. Anything else fails the bootstrap.
+pub type GuardSource = EnhancerSource<dyn Guard>;
+
+impl<T: Guard + 'static> IntoEnhancer<dyn Guard> for T {
+    fn into_enhancer(self: Box<Self>) -> Arc<dyn Guard> {
+        Arc::from(self as Box<dyn Guard>)
+    }
+}
+
+/// Runs `guards` in order and reports whether the request may proceed.
+///
+/// On denial the context response is set: the guard's `Err`, a response the
+/// guard set itself, or `403 Forbidden` with `denied` as message.
+pub(crate) async fn run_guards(
+    guards: &[Arc<dyn Guard>],
+    context: &mut Context,
+    denied: &str,
+) -> bool {
+    for guard in guards {
+        match guard.can_activate(context).await {
+            Ok(true) => continue,
+            Ok(false) => {
+                let response = context
+                    .take_response()
+                    .unwrap_or_else(|| Box::new(HttpException::forbidden(denied)));
+                context.set_response(response);
+            }
+            Err(exception) => context.set_response(Box::new(exception)),
+        }
+        context.abort();
+        return false;
+    }
+    true
 }
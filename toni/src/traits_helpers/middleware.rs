@@ -1,8 +1,11 @@
 use async_trait::async_trait;
+use std::any::TypeId;
 use std::sync::Arc;
 
+use super::{EnhancerSource, IntoEnhancer};
 use crate::http_helpers::{HttpRequest, HttpResponse};
 use crate::middleware::RoutePattern;
+use crate::router::RoutePath;
 
 /// Result type for middleware chain execution
 pub type MiddlewareResult = Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>;
@@ -48,12 +51,31 @@ impl Middleware for FunctionalMiddleware {
     }
 }
 
+/// Where a module's or a handler's middleware comes from
+///
+/// Provider middleware, e.g. a `#[middleware]` struct with injected fields,
+/// is built from the module's providers once they are instantiated. It must
+/// be a singleton provider visible to the module, or the bootstrap fails.
+pub type MiddlewareSource = EnhancerSource<dyn Middleware>;
+
+impl<T: Middleware + 'static> IntoEnhancer<dyn Middleware> for T {
+    fn into_enhancer(self: Box<Self>) -> Arc<dyn Middleware> {
+        Arc::from(self as Box<dyn Middleware>)
+    }
+}
+
 /// Middleware configuration for a module
+///
+/// The middleware applies to requests matching an include pattern or sent to
+/// one of the included controllers, to all of them without either, unless an
+/// exclude pattern matches.
 #[derive(Default)]
 pub struct MiddlewareConfiguration {
     pub middleware: Vec<Arc<dyn Middleware>>,
     pub include_patterns: Vec<RoutePattern>,
     pub exclude_patterns: Vec<RoutePattern>,
+    /// Controllers whose routes are included, see `for_controllers`
+    pub include_controllers: Vec<TypeId>,
 }
 
 impl MiddlewareConfiguration {
@@ -61,28 +83,79 @@ impl MiddlewareConfiguration {
         Self::default()
     }
 
-    /// Check if this middleware should apply to the given path and HTTP method
+    /// Check if this middleware should apply to a request for the given path
+    /// and HTTP method, without a host or controller
     pub fn should_apply(&self, path: &str, method: &str) -> bool {
-        // If no patterns specified, apply to all
-        if self.include_patterns.is_empty() && self.exclude_patterns.is_empty() {
-            return true;
-        }
+        self.should_apply_to_host(path, method, None)
+    }
 
-        // Check exclusions first - if excluded, don't apply
-        for pattern in &self.exclude_patterns {
-            if pattern.matches(path, method) {
-                return false;
-            }
-        }
+    /// Check if this middleware should apply to a request sent to `host`
+    pub fn should_apply_to_host(&self, path: &str, method: &str, host: Option<&str>) -> bool {
+        self.should_apply_to(path, method, host, None)
+    }
+
+    /// Check if this middleware should apply to a request handled by the
+    /// `controller` type
+    pub fn should_apply_to(
+        &self,
+        path: &str,
+        method: &str,
+        host: Option<&str>,
+        controller: Option<TypeId>,
+    ) -> bool {
+        let matches =
+            |pattern: &RoutePattern| pattern.matches(path, method) && pattern.matches_host(host);
 
-        // If include patterns exist, path must match one
-        if !self.include_patterns.is_empty() {
-            return self
+        let included = self.includes_everything()
+            || controller.is_some_and(|controller| self.include_controllers.contains(&controller))
+            || self.include_patterns.iter().any(matches);
+        included && !self.exclude_patterns.iter().any(matches)
+    }
+
+    /// Whether this middleware applies to every request of a route, to none
+    /// of them, or `None` when it depends on the request
+    pub(crate) fn applies_to_route(
+        &self,
+        route: &RoutePath,
+        method: &str,
+        controller: Option<TypeId>,
+    ) -> Option<bool> {
+        let included = if self.includes_everything()
+            || controller.is_some_and(|controller| self.include_controllers.contains(&controller))
+        {
+            Some(true)
+        } else {
+            any(self
                 .include_patterns
                 .iter()
-                .any(|pattern| pattern.matches(path, method));
+                .map(|pattern| pattern.matches_route(route, method)))
+        };
+        let excluded = any(self
+            .exclude_patterns
+            .iter()
+            .map(|pattern| pattern.matches_route(route, method)));
+
+        match (included, excluded) {
+            (Some(false), _) | (_, Some(true)) => Some(false),
+            (Some(true), Some(false)) => Some(true),
+            _ => None,
         }
+    }
 
-        true
+    fn includes_everything(&self) -> bool {
+        self.include_patterns.is_empty() && self.include_controllers.is_empty()
+    }
+}
+
+/// `Some(true)` if one is true, `None` if one can't be told yet
+fn any(decisions: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(false);
+    for decision in decisions {
+        match decision {
+            Some(true) => return Some(true),
+            None => result = None,
+            Some(false) => {}
+        }
     }
+    result
 }
@@ -8,14 +8,34 @@ pub use self::provider::{Provider, ProviderTrait};
 mod controller;
 pub use self::controller::{Controller, ControllerTrait};
 
+mod gateway;
+pub use self::gateway::{Gateway, GatewayTrait};
+
 mod interceptor;
 pub use self::interceptor::{Interceptor, InterceptorNext};
 
+mod enhancer_source;
+pub use self::enhancer_source::EnhancerSource;
+#[doc(hidden)]
+pub use self::enhancer_source::{
+    DefaultEnhancerSource, EnhancerRef, IntoEnhancer, ProviderEnhancerSource,
+};
+
 mod guard;
-pub use self::guard::Guard;
+pub use self::guard::{Guard, GuardSource};
+pub(crate) use self::guard::run_guards;
+
+mod exception_filter;
+pub use self::exception_filter::ExceptionFilter;
+
+mod fallback_handler;
+pub(crate) use self::fallback_handler::DefaultFallbackHandler;
+pub use self::fallback_handler::FallbackHandler;
 
 mod pipe;
-pub use self::pipe::Pipe;
+pub use self::pipe::{
+    ArgumentMetadata, ParamPipes, ParamSource, Pipe, PipeSource, PipeTransform, apply_param_pipes,
+};
 
 mod validator;
 pub use self::validator::validate;
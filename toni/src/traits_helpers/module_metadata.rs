@@ -1,6 +1,8 @@
-use super::{Controller, Provider};
+use super::{Controller, Gateway, Provider};
 use crate::middleware::{IntoRoutePattern, RoutePattern};
-use crate::traits_helpers::middleware::{Middleware, MiddlewareConfiguration};
+use crate::router::RoutePathError;
+use crate::traits_helpers::middleware::{Middleware, MiddlewareConfiguration, MiddlewareSource};
+use std::any::TypeId;
 use std::sync::Arc;
 
 pub trait ModuleMetadata {
@@ -11,6 +13,11 @@ pub trait ModuleMetadata {
     fn providers(&self) -> Option<Vec<Box<dyn Provider>>>;
     fn exports(&self) -> Option<Vec<String>>;
 
+    /// WebSocket gateways declared by this module
+    fn gateways(&self) -> Option<Vec<Box<dyn Gateway>>> {
+        None
+    }
+
     /// Returns true if this module is global (exports available everywhere)
     fn is_global(&self) -> bool {
         false // Default: non-global
@@ -64,6 +71,10 @@ impl<T: ModuleMetadata> ModuleMetadata for GlobalModuleWrapper<T> {
         self.inner.exports()
     }
 
+    fn gateways(&self) -> Option<Vec<Box<dyn Gateway>>> {
+        self.inner.gateways()
+    }
+
     fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
         self.inner.configure_middleware(consumer)
     }
@@ -81,18 +92,18 @@ impl<T: ModuleMetadata> ModuleMetadata for GlobalModuleWrapper<T> {
 ///     fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
 ///         // Apply logger to all routes
 ///         consumer
-///             .apply(MyLoggerMiddleware::new())
+///             .apply_instance(MyLoggerMiddleware::new())
 ///             .for_routes(vec!["/users/*"]);
 ///
 ///         // Apply auth to specific routes, excluding public endpoints
 ///         consumer
-///             .apply(MyAuthMiddleware::new())
+///             .apply_instance(MyAuthMiddleware::new())
 ///             .for_routes(vec!["/users/*"])
 ///             .exclude(vec!["/users/public/*"]);
 ///
 ///         // Multiple middleware can be applied to the same routes
 ///         consumer
-///             .apply(MyRateLimitMiddleware::new(100, 60000))
+///             .apply_instance(MyRateLimitMiddleware::new(100, 60000))
 ///             .for_routes(vec![("/users/create", "POST")]);
 ///     }
 /// }
@@ -100,16 +111,23 @@ impl<T: ModuleMetadata> ModuleMetadata for GlobalModuleWrapper<T> {
 ///
 /// # Route Patterns
 ///
-/// Supports glob-like patterns and HTTP method filtering:
+/// Patterns are matched against the request path, with HTTP method filtering:
 /// - `/users` - Exact match, all HTTP methods
 /// - `/api/*` - All routes starting with /api/, all HTTP methods
+/// - `/users/:id/*` - One segment, then anything below it
+/// - `/api/**/export` - Any number of segments in between
+/// - `/{users,teams}/*.csv` - Alternatives and wildcards within a segment
+/// - `RoutePattern::regex("^/v[0-9]+/")` - A regular expression
 /// - `("/users", "POST")` - Only POST requests to /users
 /// - `("/api/*", ["GET", "POST"])` - Only GET and POST to /api/*
+///
+/// Middleware can also target controllers with `.for_controllers::<UserController>()`.
 pub struct MiddlewareConsumer {
-    configurations: Vec<MiddlewareConfiguration>,
-    current_middleware: Vec<Arc<dyn Middleware>>,
+    configurations: Vec<(Vec<MiddlewareSource>, MiddlewareConfiguration)>,
+    current_middleware: Vec<MiddlewareSource>,
     current_includes: Vec<RoutePattern>,
     current_excludes: Vec<RoutePattern>,
+    current_controllers: Vec<TypeId>,
 }
 
 impl MiddlewareConsumer {
@@ -119,31 +137,60 @@ impl MiddlewareConsumer {
             current_middleware: Vec::new(),
             current_includes: Vec::new(),
             current_excludes: Vec::new(),
+            current_controllers: Vec::new(),
         }
     }
 
-    /// Apply middleware to routes
+    /// Apply a middleware instance to routes
     ///
     /// Returns a proxy that requires you to specify routes via `.for_routes()` or `.for_route()`.
+    /// Middleware with injected dependencies is applied with [`apply`](Self::apply) instead.
     ///
     /// # Example
     /// ```ignore
     /// // Single middleware
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .for_routes(vec!["/api/*"]);
     ///
     /// // Multiple middleware on same routes
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
-    ///     .apply_also(MyAuthMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
+    ///     .apply_also_instance(MyAuthMiddleware::new())
     ///     .for_routes(vec!["/api/*"]);
     /// ```
-    pub fn apply<M>(&mut self, middleware: M) -> MiddlewareConfigProxy<'_>
+    pub fn apply_instance<M>(&mut self, middleware: M) -> MiddlewareConfigProxy<'_>
+    where
+        M: Middleware + 'static,
+    {
+        self.current_middleware
+            .push(MiddlewareSource::Instance(Arc::new(middleware)));
+        MiddlewareConfigProxy { consumer: self }
+    }
+
+    /// Apply middleware built by dependency injection
+    ///
+    /// `M` is resolved from the module's providers once they are instantiated,
+    /// so it must be listed in `providers` or exported by an imported module,
+    /// e.g. a `#[middleware]` struct with `#[inject]` fields.
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[module(providers: [TokenVerifier, AuthMiddleware])]
+    /// impl UserModule {
+    ///     fn configure_middleware(&self, consumer: &mut MiddlewareConsumer) {
+    ///         consumer
+    ///             .apply::<AuthMiddleware>()
+    ///             .for_routes(vec!["/users/*"]);
+    ///     }
+    /// }
+    /// ```
+    pub fn apply<M>(&mut self) -> MiddlewareConfigProxy<'_>
     where
         M: Middleware + 'static,
     {
-        self.current_middleware.push(Arc::new(middleware));
+        self.current_middleware
+            .push(MiddlewareSource::provider::<M>());
         MiddlewareConfigProxy { consumer: self }
     }
 
@@ -151,18 +198,31 @@ impl MiddlewareConsumer {
     fn finalize_current(&mut self) {
         if !self.current_middleware.is_empty() {
             let config = MiddlewareConfiguration {
-                middleware: std::mem::take(&mut self.current_middleware),
+                middleware: Vec::new(),
                 include_patterns: std::mem::take(&mut self.current_includes),
                 exclude_patterns: std::mem::take(&mut self.current_excludes),
+                include_controllers: std::mem::take(&mut self.current_controllers),
             };
-            self.configurations.push(config);
+            let sources = std::mem::take(&mut self.current_middleware);
+            self.configurations.push((sources, config));
         }
     }
 
-    /// Get all configurations
-    pub fn build(mut self) -> Vec<MiddlewareConfiguration> {
+    /// Get all configurations, with the middleware each one applies still to
+    /// resolve from the module, or the first invalid route pattern
+    pub fn build(
+        mut self,
+    ) -> Result<Vec<(Vec<MiddlewareSource>, MiddlewareConfiguration)>, RoutePathError> {
         self.finalize_current();
-        self.configurations
+        let invalid = self
+            .configurations
+            .iter()
+            .flat_map(|(_, config)| config.include_patterns.iter().chain(&config.exclude_patterns))
+            .find_map(|pattern| pattern.error());
+        match invalid {
+            Some(error) => Err(error.clone()),
+            None => Ok(self.configurations),
+        }
     }
 }
 
@@ -178,11 +238,14 @@ impl Default for MiddlewareConsumer {
 /// or `.done()` after applying middleware.
 ///
 /// # Methods
-/// - `.apply_also()` - Add another middleware to the same configuration
+/// - `.apply_also_instance()` - Add another middleware instance to the same configuration
+/// - `.apply_also::<M>()` - Add middleware resolved from the module's providers
 /// - `.for_route()` - Add a single route (chainable, returns proxy)
 /// - `.for_routes()` - Add multiple routes and finalize (returns consumer)
 /// - `.exclude_route()` - Exclude a single route (chainable, returns proxy)
 /// - `.exclude()` - Exclude multiple routes (chainable, returns proxy)
+/// - `.for_controller::<C>()` - Add every route of a controller (chainable, returns proxy)
+/// - `.for_controllers::<C>()` - Add every route of a controller and finalize (returns consumer)
 /// - `.done()` - Finalize configuration (returns consumer)
 #[must_use = "Middleware proxy must call .for_routes(), .for_route(), or .done() to complete configuration"]
 pub struct MiddlewareConfigProxy<'a> {
@@ -190,23 +253,37 @@ pub struct MiddlewareConfigProxy<'a> {
 }
 
 impl<'a> MiddlewareConfigProxy<'a> {
-    /// Add another middleware to the same configuration
+    /// Add another middleware instance to the same configuration
     ///
     /// This allows you to group multiple middleware that should apply to the same routes.
     ///
     /// # Example
     /// ```ignore
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
-    ///     .apply_also(MyAuthMiddleware::new())
-    ///     .apply_also(MyCorsMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
+    ///     .apply_also_instance(MyAuthMiddleware::new())
+    ///     .apply_also_instance(MyCorsMiddleware::new())
     ///     .for_routes(vec!["/api/*"]);
     /// ```
-    pub fn apply_also<M>(self, middleware: M) -> Self
+    pub fn apply_also_instance<M>(self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.consumer
+            .current_middleware
+            .push(MiddlewareSource::Instance(Arc::new(middleware)));
+        self
+    }
+
+    /// Add middleware built by dependency injection to the same configuration,
+    /// see [`MiddlewareConsumer::apply`]
+    pub fn apply_also<M>(self) -> Self
     where
         M: Middleware + 'static,
     {
-        self.consumer.current_middleware.push(Arc::new(middleware));
+        self.consumer
+            .current_middleware
+            .push(MiddlewareSource::provider::<M>());
         self
     }
 
@@ -226,14 +303,14 @@ impl<'a> MiddlewareConfigProxy<'a> {
     /// ```ignore
     /// // Chain multiple routes
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .for_route("/api/*")
     ///     .for_route("/admin/*")
     ///     .done();
     ///
     /// // Mix with exclusions
     /// consumer
-    ///     .apply(MyAuthMiddleware::new())
+    ///     .apply_instance(MyAuthMiddleware::new())
     ///     .for_route("/api/*")
     ///     .exclude_route("/api/public/*")
     ///     .done();
@@ -265,12 +342,12 @@ impl<'a> MiddlewareConfigProxy<'a> {
     /// ```ignore
     /// // Multiple simple paths (all methods)
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .for_routes(vec!["/api/*", "/admin/*", "/users/*"]);
     ///
     /// // Multiple routes with HTTP method arrays (same size)
     /// consumer
-    ///     .apply(MyAuthMiddleware::new())
+    ///     .apply_instance(MyAuthMiddleware::new())
     ///     .for_routes(vec![
     ///         ("/api/users/*", ["GET", "POST"]),
     ///         ("/api/posts/*", ["GET", "POST"]),
@@ -278,7 +355,7 @@ impl<'a> MiddlewareConfigProxy<'a> {
     ///
     /// // Different-sized arrays? Use Vec instead
     /// consumer
-    ///     .apply(MyCorsMiddleware::new())
+    ///     .apply_instance(MyCorsMiddleware::new())
     ///     .for_routes(vec![
     ///         ("/api/users/*", vec!["GET", "POST"]),
     ///         ("/api/admin/*", vec!["GET", "POST", "DELETE"]),
@@ -286,14 +363,14 @@ impl<'a> MiddlewareConfigProxy<'a> {
     ///
     /// // Finalize a .for_route() chain (empty vec is fine)
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .for_route("/api/*")
     ///     .for_route("/admin/*")
     ///     .for_routes(vec![]);
     ///
     /// // Mix types in same vec (use vec![] for "all methods")
     /// consumer
-    ///     .apply(MyCorsMiddleware::new())
+    ///     .apply_instance(MyCorsMiddleware::new())
     ///     .for_routes(vec![
     ///         ("/api/public/*", vec![]),  // All methods
     ///         ("/api/admin/*", vec!["GET", "POST", "DELETE"]),  // Specific methods
@@ -312,6 +389,41 @@ impl<'a> MiddlewareConfigProxy<'a> {
         self.consumer
     }
 
+    /// Apply middleware to every route of the controller `C`, wherever it is mounted
+    ///
+    /// Returns the proxy so you can chain more controllers, routes or exclusions.
+    ///
+    /// # Example
+    /// ```ignore
+    /// consumer
+    ///     .apply_instance(MyAuthMiddleware::new())
+    ///     .for_controller::<UserController>()
+    ///     .for_controller::<AdminController>()
+    ///     .exclude_route("/users/login")
+    ///     .done();
+    /// ```
+    pub fn for_controller<C: 'static>(self) -> Self {
+        self.consumer.current_controllers.push(TypeId::of::<C>());
+        self
+    }
+
+    /// Apply middleware to every route of the controller `C`
+    ///
+    /// **This method finalizes the middleware configuration** and returns the consumer,
+    /// like `.for_routes()`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// consumer
+    ///     .apply_instance(MyAuthMiddleware::new())
+    ///     .for_controllers::<UserController>();
+    /// ```
+    pub fn for_controllers<C: 'static>(self) -> &'a mut MiddlewareConsumer {
+        let consumer = self.for_controller::<C>().consumer;
+        consumer.finalize_current();
+        consumer
+    }
+
     /// Exclude a single route from middleware
     ///
     /// Returns the proxy, so you can continue chaining exclusions or call `.for_routes()`.
@@ -327,14 +439,14 @@ impl<'a> MiddlewareConfigProxy<'a> {
     /// ```ignore
     /// // Exclude public routes from auth
     /// consumer
-    ///     .apply(MyAuthMiddleware::new())
+    ///     .apply_instance(MyAuthMiddleware::new())
     ///     .exclude_route("/api/public/*")
     ///     .exclude_route("/api/health")
     ///     .for_routes(vec!["/api/*"]);
     ///
     /// // Exclude specific method on a route
     /// consumer
-    ///     .apply(MyRateLimitMiddleware::new(100, 60000))
+    ///     .apply_instance(MyRateLimitMiddleware::new(100, 60000))
     ///     .exclude_route(("/api/health", "GET"))
     ///     .for_routes(vec!["/api/*"]);
     /// ```
@@ -361,13 +473,13 @@ impl<'a> MiddlewareConfigProxy<'a> {
     /// ```ignore
     /// // Exclude multiple public routes from auth
     /// consumer
-    ///     .apply(MyAuthMiddleware::new())
+    ///     .apply_instance(MyAuthMiddleware::new())
     ///     .exclude(vec!["/api/public/*", "/api/health", "/api/status"])
     ///     .for_routes(vec!["/api/*"]);
     ///
     /// // Exclude routes with method arrays (same size)
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .exclude(vec![
     ///         ("/api/health", ["GET", "HEAD"]),
     ///         ("/api/status", ["GET", "HEAD"]),
@@ -376,7 +488,7 @@ impl<'a> MiddlewareConfigProxy<'a> {
     ///
     /// // Different-sized arrays? Use Vec instead
     /// consumer
-    ///     .apply(MyRateLimitMiddleware::new(100, 60000))
+    ///     .apply_instance(MyRateLimitMiddleware::new(100, 60000))
     ///     .exclude(vec![
     ///         ("/api/health", vec!["GET", "HEAD"]),
     ///         ("/api/metrics", vec!["GET"]),
@@ -403,7 +515,7 @@ impl<'a> MiddlewareConfigProxy<'a> {
     /// ```ignore
     /// // Chain routes then finalize
     /// consumer
-    ///     .apply(MyLoggerMiddleware::new())
+    ///     .apply_instance(MyLoggerMiddleware::new())
     ///     .for_route("/api/*")
     ///     .for_route("/admin/*")
     ///     .exclude_route("/api/health")
@@ -411,7 +523,7 @@ impl<'a> MiddlewareConfigProxy<'a> {
     ///
     /// // Then continue with another middleware
     /// consumer
-    ///     .apply(MyAuthMiddleware::new())
+    ///     .apply_instance(MyAuthMiddleware::new())
     ///     .for_route("/admin/*")
     ///     .done();
     /// ```
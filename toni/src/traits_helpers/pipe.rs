@@ -1,5 +1,115 @@
-use crate::injector::Context;
+use std::{any::Any, ops::Range, sync::Arc};
 
-pub trait Pipe: Send + Sync {
-    fn process(&self, data: &mut Context);
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{EnhancerSource, IntoEnhancer};
+use crate::{
+    http_helpers::{HttpException, HttpRequest},
+    injector::Context,
+};
+
+/// Runs before the handler with access to the whole request.
+///
+/// Setting a response and calling `context.abort()` stops the request.
+#[async_trait]
+pub trait Pipe: Any + Send + Sync {
+    async fn process(&self, context: &mut Context);
+}
+
+/// Transforms or validates a single handler argument.
+///
+/// Attached per parameter with `#[pipe(..)]` on a `Path` or `Query`
+/// argument. The raw value is a JSON string (or `null` when missing); each
+/// pipe receives the previous one's output and the last output is
+/// deserialized into the argument type. An `Err` ends the request and goes
+/// through the exception filters.
+///
+/// ```ignore
+/// #[get("/users/{id}")]
+/// async fn find_one(&self, #[pipe(ParseIntPipe)] Path(id): Path<u64>) -> ToniBody { /* ... */ }
+/// ```
+#[async_trait]
+pub trait PipeTransform: Send + Sync {
+    async fn transform(
+        &self,
+        value: Value,
+        metadata: &ArgumentMetadata,
+    ) -> Result<Value, HttpException>;
+}
+
+/// Where a piped argument is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamSource {
+    Path,
+    Query,
+}
+
+/// Describes the argument a `PipeTransform` is applied to
+#[derive(Debug, Clone)]
+pub struct ArgumentMetadata {
+    name: &'static str,
+    source: ParamSource,
+}
+
+impl ArgumentMetadata {
+    pub fn new(name: &'static str, source: ParamSource) -> Self {
+        Self { name, source }
+    }
+
+    /// Name of the path or query parameter
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn source(&self) -> ParamSource {
+        self.source
+    }
+}
+
+/// Where a pipe listed in `#[pipe]` comes from, see [`GuardSource`](super::GuardSource)
+pub type PipeSource = EnhancerSource<dyn PipeTransform>;
+
+impl<T: PipeTransform + 'static> IntoEnhancer<dyn PipeTransform> for T {
+    fn into_enhancer(self: Box<Self>) -> Arc<dyn PipeTransform> {
+        Arc::from(self as Box<dyn PipeTransform>)
+    }
+}
+
+/// The parameter pipes of a handler, in declaration order
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct ParamPipes(pub Arc<[Arc<dyn PipeTransform>]>);
+
+/// Reads an argument, runs its pipes and deserializes the result.
+///
+/// `pipes` indexes the handler's [`ParamPipes`], which the instance wrapper
+/// stores in the request extensions.
+#[doc(hidden)]
+pub async fn apply_param_pipes<T: DeserializeOwned>(
+    req: &HttpRequest,
+    pipes: Range<usize>,
+    metadata: ArgumentMetadata,
+) -> Result<T, HttpException> {
+    let params = match metadata.source {
+        ParamSource::Path => &req.path_params,
+        ParamSource::Query => &req.query_params,
+    };
+    let mut value = params
+        .get(metadata.name)
+        .map_or(Value::Null, |raw| Value::String(raw.clone()));
+
+    if !pipes.is_empty() {
+        let param_pipes = req.extensions.get::<ParamPipes>().ok_or_else(|| {
+            HttpException::internal_server_error("An error occurred while processing the request")
+        })?;
+        for pipe in &param_pipes.0[pipes] {
+            value = pipe.transform(value, &metadata).await?;
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| {
+        HttpException::bad_request(format!("Parameter '{}' is invalid: {}", metadata.name, e))
+    })
 }
@@ -1,7 +1,13 @@
 use std::fmt::Debug;
 
-use validator::ValidationErrors;
+use validator::{Validate, ValidationErrors};
 
 pub trait Validatable: Send + Sync + Debug {
     fn validate_dto(&self) -> Result<(), ValidationErrors>;
 }
+
+impl<T: Validate + Debug + Send + Sync> Validatable for T {
+    fn validate_dto(&self) -> Result<(), ValidationErrors> {
+        self.validate()
+    }
+}
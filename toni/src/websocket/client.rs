@@ -0,0 +1,47 @@
+use serde::Serialize;
+use tokio::sync::mpsc::Sender;
+
+use super::WsMessage;
+
+/// Handle to a single connected WebSocket client.
+///
+/// Cloning is cheap; every clone writes to the same outgoing queue, which the
+/// adapter drains into the underlying socket. The queue is bounded: while it
+/// is full, e.g. because the client reads slower than it is sent to, new
+/// messages are dropped and `send` returns `false`.
+#[derive(Debug, Clone)]
+pub struct WsClient {
+    id: String,
+    sender: Sender<WsMessage>,
+}
+
+impl WsClient {
+    pub fn new(id: String, sender: Sender<WsMessage>) -> Self {
+        Self { id, sender }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Queues a raw frame. Returns `false` if the connection is already gone
+    /// or its queue is full, in which case the frame is dropped.
+    pub fn send(&self, message: WsMessage) -> bool {
+        self.sender.try_send(message).is_ok()
+    }
+
+    /// Sends `{"event": event, "data": data}` to this client
+    pub fn emit<T: Serialize>(&self, event: &str, data: T) -> bool {
+        let data = serde_json::to_value(data).unwrap_or_default();
+        self.send(WsMessage::event(event, data))
+    }
+
+    /// Asks the adapter to close the connection, once it drained the queue
+    pub fn close(&self) {
+        let _ = self.sender.try_send(WsMessage::Close);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        !self.sender.is_closed()
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A single WebSocket frame exchanged between an adapter and a gateway.
+///
+/// Adapters translate their native frame types into `WsMessage` before handing
+/// them to the gateway, and translate outgoing `WsMessage`s back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+    Close,
+}
+
+impl WsMessage {
+    /// Builds a text frame carrying `{"event": ..., "data": ...}`
+    pub fn event(event: &str, data: Value) -> Self {
+        WsMessage::Text(WsEvent::new(event, data).to_string())
+    }
+}
+
+/// Envelope used by gateways on the wire: `{"event": "chat.send", "data": {...}}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WsEvent {
+    pub event: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl WsEvent {
+    pub fn new(event: &str, data: Value) -> Self {
+        Self {
+            event: event.to_string(),
+            data,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+}
+
+impl std::fmt::Display for WsEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
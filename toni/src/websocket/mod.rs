@@ -0,0 +1,8 @@
+mod client;
+pub use self::client::WsClient;
+
+mod message;
+pub use self::message::{WsEvent, WsMessage};
+
+mod server;
+pub use self::server::{OUTGOING_QUEUE_SIZE, WsServer, WsServerManager};
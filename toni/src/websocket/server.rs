@@ -0,0 +1,309 @@
+//! Shared WebSocket server handle.
+//!
+//! `WsServer` tracks every connected client and the rooms they joined. It is
+//! registered by the built-in module as a global singleton, so it can be
+//! injected into gateways, controllers and providers alike:
+//!
+//! ```ignore
+//! #[gateway_struct(pub struct ChatGateway {
+//!     #[inject]
+//!     server: WsServer,
+//! })]
+//! #[websocket_gateway("/ws")]
+//! impl ChatGateway {
+//!     #[subscribe_message("room.join")]
+//!     fn join(&self, client: WsClient, room: String) {
+//!         self.server.join(client.id(), &room);
+//!     }
+//! }
+//! ```
+
+use std::any::Any;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use tokio::sync::mpsc::{self, Receiver};
+
+use super::{WsClient, WsMessage};
+use crate::async_trait;
+use crate::http_helpers::HttpRequest;
+use crate::traits_helpers::{Provider, ProviderTrait};
+
+/// Messages queued for a client before further ones are dropped
+pub const OUTGOING_QUEUE_SIZE: usize = 256;
+
+#[derive(Default)]
+struct WsServerState {
+    next_id: AtomicU64,
+    clients: RwLock<FxHashMap<String, WsClient>>,
+    rooms: RwLock<FxHashMap<String, FxHashSet<String>>>,
+}
+
+/// Injectable handle for rooms and broadcasting.
+///
+/// Cloning is cheap and every clone shares the same connection registry.
+#[derive(Clone, Default)]
+pub struct WsServer {
+    state: Arc<WsServerState>,
+}
+
+impl WsServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new client and returns it with the receiving end of its
+    /// queue, which holds [`OUTGOING_QUEUE_SIZE`] messages
+    pub(crate) fn register(&self) -> (WsClient, Receiver<WsMessage>) {
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (sender, receiver) = mpsc::channel(OUTGOING_QUEUE_SIZE);
+        let client = WsClient::new(format!("ws-{}", id), sender);
+
+        self.state
+            .clients
+            .write()
+            .unwrap()
+            .insert(client.id().to_string(), client.clone());
+
+        (client, receiver)
+    }
+
+    /// Forgets a client and removes it from every room
+    pub(crate) fn unregister(&self, client_id: &str) {
+        self.state.clients.write().unwrap().remove(client_id);
+
+        let mut rooms = self.state.rooms.write().unwrap();
+        for members in rooms.values_mut() {
+            members.remove(client_id);
+        }
+        rooms.retain(|_, members| !members.is_empty());
+    }
+
+    pub fn client(&self, client_id: &str) -> Option<WsClient> {
+        self.state.clients.read().unwrap().get(client_id).cloned()
+    }
+
+    pub fn connected_clients(&self) -> usize {
+        self.state.clients.read().unwrap().len()
+    }
+
+    /// Adds a client to a room. Returns `false` if the client is not connected.
+    pub fn join(&self, client_id: &str, room: &str) -> bool {
+        if self.client(client_id).is_none() {
+            return false;
+        }
+
+        self.state
+            .rooms
+            .write()
+            .unwrap()
+            .entry(room.to_string())
+            .or_default()
+            .insert(client_id.to_string());
+        true
+    }
+
+    pub fn leave(&self, client_id: &str, room: &str) {
+        let mut rooms = self.state.rooms.write().unwrap();
+        if let Some(members) = rooms.get_mut(room) {
+            members.remove(client_id);
+            if members.is_empty() {
+                rooms.remove(room);
+            }
+        }
+    }
+
+    /// Ids of the clients currently in `room`
+    pub fn clients_in(&self, room: &str) -> Vec<String> {
+        self.state
+            .rooms
+            .read()
+            .unwrap()
+            .get(room)
+            .map(|members| members.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Rooms the client currently belongs to
+    pub fn rooms_of(&self, client_id: &str) -> Vec<String> {
+        self.state
+            .rooms
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, members)| members.contains(client_id))
+            .map(|(room, _)| room.clone())
+            .collect()
+    }
+
+    /// Sends an event to a single client
+    pub fn emit_to<T: Serialize>(&self, client_id: &str, event: &str, data: T) -> bool {
+        match self.client(client_id) {
+            Some(client) => client.emit(event, data),
+            None => false,
+        }
+    }
+
+    /// Sends an event to every client in `room`. Returns the number of
+    /// recipients, leaving out clients whose queue is full.
+    pub fn emit_to_room<T: Serialize>(&self, room: &str, event: &str, data: T) -> usize {
+        let message = Self::event_message(event, data);
+        let clients = self.state.clients.read().unwrap();
+
+        self.clients_in(room)
+            .iter()
+            .filter_map(|id| clients.get(id))
+            .filter(|client| client.send(message.clone()))
+            .count()
+    }
+
+    /// Sends an event to every connected client. Returns the number of
+    /// recipients, leaving out clients whose queue is full.
+    pub fn broadcast<T: Serialize>(&self, event: &str, data: T) -> usize {
+        let message = Self::event_message(event, data);
+
+        self.state
+            .clients
+            .read()
+            .unwrap()
+            .values()
+            .filter(|client| client.send(message.clone()))
+            .count()
+    }
+
+    fn event_message<T: Serialize>(event: &str, data: T) -> WsMessage {
+        WsMessage::event(event, serde_json::to_value(data).unwrap_or_default())
+    }
+}
+
+// Manual ProviderTrait implementation (can't use macro inside toni crate)
+#[async_trait]
+impl ProviderTrait for WsServer {
+    fn get_token(&self) -> String {
+        "WsServer".to_string()
+    }
+
+    async fn execute(
+        &self,
+        _params: Vec<Box<dyn Any + Send>>,
+        _req: Option<&HttpRequest>,
+    ) -> Box<dyn Any + Send> {
+        Box::new(self.clone())
+    }
+
+    fn get_token_manager(&self) -> String {
+        "WsServer".to_string()
+    }
+}
+
+pub struct WsServerManager;
+
+#[async_trait]
+impl Provider for WsServerManager {
+    async fn get_all_providers(
+        &self,
+        _dependencies: &FxHashMap<String, Arc<Box<dyn ProviderTrait>>>,
+    ) -> FxHashMap<String, Arc<Box<dyn ProviderTrait>>> {
+        let mut providers = FxHashMap::default();
+        providers.insert(
+            "WsServer".to_string(),
+            Arc::new(Box::new(WsServer::new()) as Box<dyn ProviderTrait>),
+        );
+        providers
+    }
+
+    fn get_name(&self) -> String {
+        "WsServer".to_string()
+    }
+
+    fn get_token(&self) -> String {
+        "WsServer".to_string()
+    }
+
+    fn get_dependencies(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn next_text(receiver: &mut Receiver<WsMessage>) -> Option<String> {
+        match receiver.try_recv() {
+            Ok(WsMessage::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_register_assigns_unique_ids() {
+        let server = WsServer::new();
+        let (a, _ra) = server.register();
+        let (b, _rb) = server.register();
+
+        assert_ne!(a.id(), b.id());
+        assert_eq!(server.connected_clients(), 2);
+    }
+
+    #[test]
+    fn test_emit_to_room_only_reaches_members() {
+        let server = WsServer::new();
+        let (a, mut ra) = server.register();
+        let (_b, mut rb) = server.register();
+
+        assert!(server.join(a.id(), "lobby"));
+        assert_eq!(server.emit_to_room("lobby", "chat", json!("hi")), 1);
+
+        assert_eq!(
+            next_text(&mut ra),
+            Some(r#"{"event":"chat","data":"hi"}"#.to_string())
+        );
+        assert_eq!(next_text(&mut rb), None);
+    }
+
+    #[test]
+    fn test_broadcast_reaches_everyone() {
+        let server = WsServer::new();
+        let (_a, mut ra) = server.register();
+        let (_b, mut rb) = server.register();
+
+        assert_eq!(server.broadcast("tick", 1), 2);
+        assert!(next_text(&mut ra).is_some());
+        assert!(next_text(&mut rb).is_some());
+    }
+
+    #[test]
+    fn test_full_queues_drop_messages() {
+        let server = WsServer::new();
+        let (_slow, mut slow) = server.register();
+        let (_fast, _) = server.register();
+
+        for _ in 0..OUTGOING_QUEUE_SIZE {
+            server.broadcast("tick", 1);
+        }
+        // The slow client's queue is full, the other one is gone
+        assert_eq!(server.broadcast("tick", 2), 0);
+
+        assert!(next_text(&mut slow).is_some());
+        assert_eq!(server.broadcast("tick", 3), 1);
+    }
+
+    #[test]
+    fn test_unregister_leaves_all_rooms() {
+        let server = WsServer::new();
+        let (a, _ra) = server.register();
+
+        server.join(a.id(), "one");
+        server.join(a.id(), "two");
+        assert_eq!(server.rooms_of(a.id()).len(), 2);
+
+        server.unregister(a.id());
+        assert!(server.clients_in("one").is_empty());
+        assert!(server.rooms_of(a.id()).is_empty());
+        assert!(!server.join(a.id(), "one"));
+    }
+}